@@ -1,12 +1,19 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contractmeta, contracttype, symbol_short, Address, Env, Symbol, Vec,
+    contract, contractclient, contractimpl, contractmeta, contracttype, symbol_short, Address,
+    Env, Symbol, Vec,
 };
 
 // Contract metadata
 contractmeta!(key = "version", val = "1.0.0");
 contractmeta!(key = "contract_type", val = "admin_multisig");
 
+/// Entry point a business contract exposes so approved parameter changes reach its config
+#[contractclient(name = "ParamTargetClient")]
+pub trait ParamTarget {
+    fn set_param(env: Env, caller: Address, key: Symbol, value: i128);
+}
+
 /// Action types for admin proposals
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -99,6 +106,17 @@ impl AdminStorage {
             .set(&(symbol_short!("approved"), proposal_id, signer), &true);
     }
 
+    pub fn remove_proposal(env: &Env, proposal: &AdminProposal) {
+        for signer in proposal.approvals.iter() {
+            env.storage()
+                .persistent()
+                .remove(&(symbol_short!("approved"), proposal.proposal_id, signer));
+        }
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("proposal"), proposal.proposal_id));
+    }
+
     pub fn is_emergency_stopped(env: &Env) -> bool {
         env.storage()
             .instance()
@@ -180,6 +198,7 @@ impl AdminMultisig {
         // Validate action-specific parameters
         match action_type {
             AdminActionType::ParameterChange => {
+                assert!(target_contract.is_some(), "Target contract required");
                 assert!(parameter_key.is_some(), "Parameter key required");
                 assert!(parameter_value.is_some(), "Parameter value required");
             }
@@ -336,8 +355,14 @@ impl AdminMultisig {
                 );
             }
             AdminActionType::ParameterChange => {
+                let target = proposal.target_contract.clone().expect("No target contract");
                 let key = proposal.parameter_key.clone().expect("No parameter key");
                 let value = proposal.parameter_value.expect("No parameter value");
+                ParamTargetClient::new(&env, &target).set_param(
+                    &env.current_contract_address(),
+                    &key,
+                    &value,
+                );
                 env.events().publish(
                     (symbol_short!("param"), symbol_short!("changed")),
                     (proposal_id, key, value),
@@ -380,6 +405,35 @@ impl AdminMultisig {
         );
     }
 
+    /// Remove expired, unexecuted proposals and their approval records
+    ///
+    /// # Arguments
+    /// * `caller` - Address requesting the cleanup (must be a signer)
+    /// * `ids` - Proposal IDs to prune; live, executed or unknown IDs are skipped
+    pub fn prune_expired_proposals(env: Env, caller: Address, ids: Vec<u64>) {
+        caller.require_auth();
+
+        let config = AdminStorage::get_multisig_config(&env).expect("Not initialized");
+        assert!(
+            Self::is_signer(&config, &caller),
+            "Not an authorized signer"
+        );
+
+        let now = env.ledger().timestamp();
+        let mut pruned = Vec::new(&env);
+        for proposal_id in ids.iter() {
+            if let Some(proposal) = AdminStorage::get_proposal(&env, proposal_id) {
+                if !proposal.executed && now > proposal.expires_at {
+                    AdminStorage::remove_proposal(&env, &proposal);
+                    pruned.push_back(proposal_id);
+                }
+            }
+        }
+
+        env.events()
+            .publish((symbol_short!("proposal"), symbol_short!("pruned")), pruned);
+    }
+
     /// Add a new signer (internal, called after multi-sig approval)
     fn add_signer_internal(env: Env, new_signer: Address) {
         let mut config = AdminStorage::get_multisig_config(&env).expect("Not initialized");
@@ -464,6 +518,22 @@ impl AdminMultisig {
         AdminStorage::has_approved(&env, proposal_id, &signer)
     }
 
+    /// Every current signer paired with whether they have approved the proposal
+    pub fn approval_status(env: Env, proposal_id: u64) -> Vec<(Address, bool)> {
+        assert!(
+            AdminStorage::get_proposal(&env, proposal_id).is_some(),
+            "Proposal not found"
+        );
+        let config = AdminStorage::get_multisig_config(&env).expect("Not initialized");
+
+        let mut status = Vec::new(&env);
+        for signer in config.signers.iter() {
+            let approved = AdminStorage::has_approved(&env, proposal_id, &signer);
+            status.push_back((signer, approved));
+        }
+        status
+    }
+
     pub fn is_signer_address(env: Env, address: Address) -> bool {
         if let Some(config) = AdminStorage::get_multisig_config(&env) {
             Self::is_signer(&config, &address)
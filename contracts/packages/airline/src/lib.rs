@@ -1,5 +1,5 @@
 #![no_std]
-use access::{AccessControl, Role};
+use access::{AccessControl, EmergencyStop, Role};
 use soroban_sdk::{
     contract, contractimpl, contracttype, symbol_short, vec, Address, Env, Symbol, Vec,
 };
@@ -118,6 +118,10 @@ pub struct PriceHistoryEntry {
 pub struct AirlineRegistry;
 
 const MAX_BATCH_SIZE: u32 = 50;
+// Bookings close this many seconds before departure unless configured otherwise.
+const DEFAULT_BOOKING_CUTOFF_SECS: u64 = 60 * 60;
+// Airlines may oversell a flight by at most 10% of its seats.
+const MAX_OVERBOOK_BPS: u32 = 1_000;
 
 impl AirlineRegistry {
     pub fn get_airline(env: &Env, address: &Address) -> Option<AirlineProfile> {
@@ -144,6 +148,125 @@ impl AirlineRegistry {
             .set(&(symbol_short!("flight"), flight_id), flight);
     }
 
+    pub fn get_booking_cutoff(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("bk_cutoff"))
+            .unwrap_or(DEFAULT_BOOKING_CUTOFF_SECS)
+    }
+
+    pub fn set_booking_cutoff(env: &Env, cutoff_secs: u64) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("bk_cutoff"), &cutoff_secs);
+    }
+
+    // How far past now a departure may be scheduled; unset means no limit
+    pub fn get_max_future_secs(env: &Env) -> Option<u64> {
+        env.storage().instance().get(&symbol_short!("max_fut"))
+    }
+
+    pub fn set_max_future_secs(env: &Env, max_future_secs: u64) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("max_fut"), &max_future_secs);
+    }
+
+    // Booking contract allowed to hold and release seats on passengers' behalf
+    pub fn get_booking_contract(env: &Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("bk_contr"))
+    }
+
+    pub fn set_booking_contract(env: &Env, booking: &Address) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("bk_contr"), booking);
+    }
+
+    pub fn get_airline_flights(env: &Env, airline: &Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("al_flts"), airline))
+            .unwrap_or(vec![env])
+    }
+
+    pub fn add_airline_flight(env: &Env, airline: &Address, flight_id: u64) {
+        let mut flights = Self::get_airline_flights(env, airline);
+        flights.push_back(flight_id);
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("al_flts"), airline), &flights);
+    }
+
+    // Flights on the from -> to route, in creation order
+    pub fn get_route_flights(env: &Env, from: &Symbol, to: &Symbol) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("route"), from, to))
+            .unwrap_or(vec![env])
+    }
+
+    pub fn add_route_flight(env: &Env, from: &Symbol, to: &Symbol, flight_id: u64) {
+        let mut flights = Self::get_route_flights(env, from, to);
+        flights.push_back(flight_id);
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("route"), from, to), &flights);
+    }
+
+    pub fn get_overbook_bps(env: &Env, flight_id: u64) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("ovb_bps"), flight_id))
+            .unwrap_or(0)
+    }
+
+    pub fn set_overbook_bps(env: &Env, flight_id: u64, overbook_bps: u32) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("ovb_bps"), flight_id), &overbook_bps);
+    }
+
+    // Seats sold beyond total_seats once available_seats hit zero
+    pub fn get_oversold(env: &Env, flight_id: u64) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("oversold"), flight_id))
+            .unwrap_or(0)
+    }
+
+    pub fn set_oversold(env: &Env, flight_id: u64, oversold: u32) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("oversold"), flight_id), &oversold);
+    }
+
+    // Seats sold as of the flight's completion, frozen for analytics
+    pub fn get_seats_sold(env: &Env, flight_id: u64) -> Option<u32> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("seats_sld"), flight_id))
+    }
+
+    pub fn set_seats_sold(env: &Env, flight_id: u64, seats_sold: u32) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("seats_sld"), flight_id), &seats_sold);
+    }
+
+    pub fn is_suspended(env: &Env, airline: &Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("suspended"), airline))
+            .unwrap_or(false)
+    }
+
+    pub fn set_suspended(env: &Env, airline: &Address, suspended: bool) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("suspended"), airline), &suspended);
+    }
+
     pub fn next_flight_id(env: &Env) -> u64 {
         let key = symbol_short!("flt_next");
         let next_id = env.storage().instance().get(&key).unwrap_or(1u64);
@@ -165,6 +288,16 @@ impl PricingStorage {
             .set(&symbol_short!("pricing"), config);
     }
 
+    pub fn get_param_authority(env: &Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("prm_auth"))
+    }
+
+    pub fn set_param_authority(env: &Env, authority: &Address) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("prm_auth"), authority);
+    }
+
     pub fn get_last_update(env: &Env, flight_id: u64) -> Option<u64> {
         env.storage()
             .persistent()
@@ -197,6 +330,7 @@ pub struct AirlineContract;
 #[contractimpl]
 impl AirlineContract {
     pub fn initialize(env: Env, owner: Address) {
+        owner.require_auth();
         AccessControl::init_owner(&env, &owner);
         crate::upgrade_timelock::UpgradeTimelock::init_upgrade_owner(&env, &owner);
     }
@@ -207,8 +341,20 @@ impl AirlineContract {
             || *status == symbol_short!("completed")
     }
 
-    fn is_valid_flight_input(input: &FlightInput) -> bool {
-        input.arrival_time > input.departure_time && input.total_seats > 0 && input.price > 0
+    fn is_valid_flight_input(env: &Env, input: &FlightInput) -> bool {
+        input.arrival_time > input.departure_time
+            && input.total_seats > 0
+            && input.price > 0
+            && Self::is_within_schedule_window(env, input.departure_time)
+    }
+
+    fn is_within_schedule_window(env: &Env, departure_time: u64) -> bool {
+        match AirlineRegistry::get_max_future_secs(env) {
+            Some(max_future_secs) => {
+                departure_time <= env.ledger().timestamp().saturating_add(max_future_secs)
+            }
+            None => true,
+        }
     }
 
     pub fn initialize_pricing(
@@ -264,6 +410,56 @@ impl AirlineContract {
         );
     }
 
+    pub fn get_pricing_config(env: Env) -> Option<PricingConfig> {
+        PricingStorage::get_config(&env)
+    }
+
+    // Hand pricing parameter changes to a single authority: the governance contract, or
+    // the admin multisig again. Until one is set, the linked admin multisig holds it.
+    pub fn set_param_authority(env: Env, admin: Address, authority: Address) {
+        AccessControl::require_admin(&env, &admin);
+        PricingStorage::set_param_authority(&env, &authority);
+
+        env.events().publish(
+            (symbol_short!("pricing"), symbol_short!("prm_auth")),
+            (admin, authority),
+        );
+    }
+
+    pub fn get_param_authority(env: Env) -> Option<Address> {
+        PricingStorage::get_param_authority(&env).or(EmergencyStop::get_admin_multisig(&env))
+    }
+
+    // Pricing parameter change from the parameter authority (a passed governance proposal
+    // or an admin multisig ParameterChange). Keys: "max_chg" (max_change_bps), "cooldown"
+    // (cooldown_secs), "max_dmd" (max_demand_multiplier_bps).
+    pub fn set_param(env: Env, caller: Address, key: Symbol, value: i128) {
+        caller.require_auth();
+        let authority =
+            Self::get_param_authority(env.clone()).expect("Param authority not configured");
+        assert!(caller == authority, "Unauthorized");
+
+        let mut cfg = PricingStorage::get_config(&env).expect("Pricing not initialized");
+        if key == symbol_short!("max_chg") {
+            assert!(value > 0 && value <= 2_000, "Invalid max_change_bps");
+            cfg.max_change_bps = value;
+        } else if key == symbol_short!("cooldown") {
+            assert!(value >= 0, "Invalid cooldown_secs");
+            cfg.cooldown_secs = value as u64;
+        } else if key == symbol_short!("max_dmd") {
+            assert!(value >= 0, "Invalid max_demand_multiplier_bps");
+            cfg.max_demand_multiplier_bps = value;
+        } else {
+            panic!("Unknown parameter");
+        }
+        PricingStorage::set_config(&env, &cfg);
+
+        env.events().publish(
+            (symbol_short!("pricing"), symbol_short!("param")),
+            (key, value),
+        );
+    }
+
     // Register new airline
     pub fn register_airline(env: Env, airline: Address, name: Symbol, iata_code: Symbol) -> bool {
         airline.require_auth();
@@ -301,6 +497,38 @@ impl AirlineContract {
         );
     }
 
+    // Block an airline under investigation from listing flights and receiving settlements
+    pub fn suspend_airline(env: Env, admin: Address, airline: Address) {
+        AccessControl::require_admin(&env, &admin);
+        AirlineRegistry::get_airline(&env, &airline).expect("Airline not found");
+
+        AirlineRegistry::set_suspended(&env, &airline, true);
+
+        env.events().publish(
+            (symbol_short!("airline"), symbol_short!("suspended")),
+            airline,
+        );
+    }
+
+    pub fn reinstate_airline(env: Env, admin: Address, airline: Address) {
+        AccessControl::require_admin(&env, &admin);
+        assert!(
+            AirlineRegistry::is_suspended(&env, &airline),
+            "Airline not suspended"
+        );
+
+        AirlineRegistry::set_suspended(&env, &airline, false);
+
+        env.events().publish(
+            (symbol_short!("airline"), symbol_short!("reinstate")),
+            airline,
+        );
+    }
+
+    pub fn is_suspended(env: Env, airline: Address) -> bool {
+        AirlineRegistry::is_suspended(&env, &airline)
+    }
+
     // Create new flight listing
     pub fn create_flight(
         env: Env,
@@ -314,13 +542,23 @@ impl AirlineContract {
         price: i128,
         currency: Symbol,
     ) -> u64 {
+        EmergencyStop::require_not_stopped(&env);
+
         airline.require_auth();
 
         let mut profile =
             AirlineRegistry::get_airline(&env, &airline).expect("Airline not registered");
 
         assert!(profile.is_verified, "Airline not verified");
+        assert!(
+            !AirlineRegistry::is_suspended(&env, &airline),
+            "Airline suspended"
+        );
         assert!(arrival_time > departure_time, "Invalid flight times");
+        assert!(
+            Self::is_within_schedule_window(&env, departure_time),
+            "Departure too far in future"
+        );
         assert!(total_seats > 0, "Invalid seat count");
         assert!(price > 0, "Invalid price");
 
@@ -342,6 +580,8 @@ impl AirlineContract {
         };
 
         AirlineRegistry::set_flight(&env, flight_id, &flight);
+        AirlineRegistry::add_airline_flight(&env, &airline, flight_id);
+        AirlineRegistry::add_route_flight(&env, &flight.from_airport, &flight.to_airport, flight_id);
         profile.total_flights += 1;
         AirlineRegistry::set_airline(&env, &airline, &profile);
 
@@ -357,21 +597,232 @@ impl AirlineContract {
         AirlineRegistry::get_flight(&env, flight_id)
     }
 
+    // Active flights on the route that haven't departed, earliest `limit` (at most
+    // MAX_BATCH_SIZE) by departure. Each flight in the route index is read once and
+    // insertion-sorted into a result that never grows past `limit`.
+    pub fn upcoming_flights(env: Env, from: Symbol, to: Symbol, limit: u32) -> Vec<Flight> {
+        let limit = limit.min(MAX_BATCH_SIZE);
+        let now = env.ledger().timestamp();
+
+        let mut upcoming: Vec<Flight> = vec![&env];
+        for flight_id in AirlineRegistry::get_route_flights(&env, &from, &to).iter() {
+            let flight = match AirlineRegistry::get_flight(&env, flight_id) {
+                Some(flight)
+                    if flight.status == symbol_short!("active") && flight.departure_time > now =>
+                {
+                    flight
+                }
+                _ => continue,
+            };
+
+            let mut pos = upcoming.len();
+            while pos > 0 && upcoming.get_unchecked(pos - 1).departure_time > flight.departure_time {
+                pos -= 1;
+            }
+            if pos < limit {
+                upcoming.insert(pos, flight);
+                if upcoming.len() > limit {
+                    upcoming.pop_back();
+                }
+            }
+        }
+
+        upcoming
+    }
+
     pub fn get_airline(env: Env, address: Address) -> Option<AirlineProfile> {
         AirlineRegistry::get_airline(&env, &address)
     }
 
+    // Minimum time before departure at which a flight stops accepting bookings.
+    pub fn set_booking_cutoff(env: Env, admin: Address, cutoff_secs: u64) {
+        AccessControl::require_admin(&env, &admin);
+        AirlineRegistry::set_booking_cutoff(&env, cutoff_secs);
+
+        env.events().publish(
+            (symbol_short!("flight"), symbol_short!("cutoff")),
+            (admin, cutoff_secs),
+        );
+    }
+
+    pub fn get_booking_cutoff(env: Env) -> u64 {
+        AirlineRegistry::get_booking_cutoff(&env)
+    }
+
+    // Latest departure, relative to now, that a new flight may be scheduled for.
+    pub fn set_max_future_secs(env: Env, admin: Address, max_future_secs: u64) {
+        AccessControl::require_admin(&env, &admin);
+        assert!(max_future_secs > 0, "Invalid window");
+        AirlineRegistry::set_max_future_secs(&env, max_future_secs);
+
+        env.events().publish(
+            (symbol_short!("flight"), symbol_short!("max_fut")),
+            (admin, max_future_secs),
+        );
+    }
+
+    pub fn get_max_future_secs(env: Env) -> Option<u64> {
+        AirlineRegistry::get_max_future_secs(&env)
+    }
+
+    // A flight is bookable when active, not sold out, and departing after the booking cutoff.
+    pub fn is_bookable(env: Env, flight_id: u64) -> bool {
+        let flight = match AirlineRegistry::get_flight(&env, flight_id) {
+            Some(flight) => flight,
+            None => return false,
+        };
+
+        let cutoff = AirlineRegistry::get_booking_cutoff(&env);
+        let now = env.ledger().timestamp();
+
+        flight.status == symbol_short!("active")
+            && Self::remaining_seats(&env, &flight) > 0
+            && flight.departure_time > now.saturating_add(cutoff)
+    }
+
     // Decrement available seats when booking is made
     pub fn reserve_seat(env: Env, airline: Address, flight_id: u64) {
+        EmergencyStop::require_not_stopped(&env);
+
         airline.require_auth();
 
         let mut flight = AirlineRegistry::get_flight(&env, flight_id).expect("Flight not found");
 
         assert!(flight.airline == airline, "Unauthorized");
-        assert!(flight.available_seats > 0, "No seats available");
 
-        flight.available_seats -= 1;
+        Self::take_seats(&env, &mut flight, 1);
+        AirlineRegistry::set_flight(&env, flight_id, &flight);
+    }
+
+    // Let a flight sell up to `overbook_bps` of its seats beyond total_seats
+    pub fn set_overbook_bps(env: Env, airline: Address, flight_id: u64, overbook_bps: u32) {
+        airline.require_auth();
+
+        let flight = AirlineRegistry::get_flight(&env, flight_id).expect("Flight not found");
+        assert!(flight.airline == airline, "Unauthorized");
+        assert!(overbook_bps <= MAX_OVERBOOK_BPS, "Overbooking too high");
+
+        AirlineRegistry::set_overbook_bps(&env, flight_id, overbook_bps);
+
+        env.events().publish(
+            (symbol_short!("flight"), symbol_short!("overbook")),
+            (flight_id, overbook_bps),
+        );
+    }
+
+    pub fn get_overbook_bps(env: Env, flight_id: u64) -> u32 {
+        AirlineRegistry::get_overbook_bps(&env, flight_id)
+    }
+
+    pub fn get_oversold(env: Env, flight_id: u64) -> u32 {
+        AirlineRegistry::get_oversold(&env, flight_id)
+    }
+
+    pub fn set_booking_contract(env: Env, admin: Address, booking: Address) {
+        AccessControl::require_admin(&env, &admin);
+        AirlineRegistry::set_booking_contract(&env, &booking);
+
+        env.events().publish(
+            (symbol_short!("flight"), symbol_short!("booking")),
+            (admin, booking),
+        );
+    }
+
+    // Admin multisig whose emergency stop this contract follows
+    pub fn set_admin_multisig(env: Env, admin: Address, multisig: Address) {
+        AccessControl::require_admin(&env, &admin);
+        EmergencyStop::set_admin_multisig(&env, &multisig);
+
+        env.events().publish(
+            (symbol_short!("airline"), symbol_short!("adm_ms")),
+            (admin, multisig),
+        );
+    }
+
+    pub fn is_emergency_stopped(env: Env) -> bool {
+        EmergencyStop::is_stopped(&env)
+    }
+
+    // Take `seats` seats for a booking being created by the booking contract. Panics when
+    // the flight doesn't have that many left or isn't active, so the booking fails with it.
+    pub fn book_seats(env: Env, caller: Address, flight_id: u64, seats: u32) -> Flight {
+        EmergencyStop::require_not_stopped(&env);
+
+        Self::require_booking_contract(&env, &caller);
+        assert!(seats > 0, "Invalid seat count");
+
+        let mut flight = AirlineRegistry::get_flight(&env, flight_id).expect("Flight not found");
+        assert!(flight.status == symbol_short!("active"), "Flight not active");
+
+        Self::take_seats(&env, &mut flight, seats);
         AirlineRegistry::set_flight(&env, flight_id, &flight);
+        flight
+    }
+
+    // Return seats taken by `book_seats` when their booking is cancelled or refunded
+    pub fn release_seats(env: Env, caller: Address, flight_id: u64, seats: u32) {
+        Self::require_booking_contract(&env, &caller);
+
+        let mut flight = AirlineRegistry::get_flight(&env, flight_id).expect("Flight not found");
+
+        // Oversold seats are given back first
+        let oversold = AirlineRegistry::get_oversold(&env, flight_id);
+        let from_oversold = seats.min(oversold);
+        if from_oversold > 0 {
+            AirlineRegistry::set_oversold(&env, flight_id, oversold - from_oversold);
+        }
+        flight.available_seats =
+            (flight.available_seats + seats - from_oversold).min(flight.total_seats);
+        AirlineRegistry::set_flight(&env, flight_id, &flight);
+    }
+
+    // Seats sold over total seats, in bps. Completed flights report the figure frozen at
+    // completion; anything else reports current utilization. Overbooked flights exceed 10_000.
+    pub fn load_factor_bps(env: Env, flight_id: u64) -> u32 {
+        let flight = AirlineRegistry::get_flight(&env, flight_id).expect("Flight not found");
+        let sold = AirlineRegistry::get_seats_sold(&env, flight_id)
+            .unwrap_or_else(|| Self::seats_sold(&env, &flight));
+        (sold as u64 * 10_000 / flight.total_seats as u64) as u32
+    }
+
+    fn seats_sold(env: &Env, flight: &Flight) -> u32 {
+        flight.total_seats - flight.available_seats
+            + AirlineRegistry::get_oversold(env, flight.flight_id)
+    }
+
+    fn overbook_allowance(env: &Env, flight: &Flight) -> u32 {
+        let bps = AirlineRegistry::get_overbook_bps(env, flight.flight_id);
+        (flight.total_seats as u64 * bps as u64 / 10_000) as u32
+    }
+
+    // Seats still sellable, counting any unused overbooking allowance
+    fn remaining_seats(env: &Env, flight: &Flight) -> u32 {
+        let oversold = AirlineRegistry::get_oversold(env, flight.flight_id);
+        flight.available_seats + Self::overbook_allowance(env, flight).saturating_sub(oversold)
+    }
+
+    // Draw from available_seats first, then from the overbooking allowance
+    fn take_seats(env: &Env, flight: &mut Flight, seats: u32) {
+        assert!(
+            Self::remaining_seats(env, flight) >= seats,
+            "No seats available"
+        );
+
+        let from_available = seats.min(flight.available_seats);
+        flight.available_seats -= from_available;
+
+        let over = seats - from_available;
+        if over > 0 {
+            let oversold = AirlineRegistry::get_oversold(env, flight.flight_id);
+            AirlineRegistry::set_oversold(env, flight.flight_id, oversold + over);
+        }
+    }
+
+    fn require_booking_contract(env: &Env, caller: &Address) {
+        caller.require_auth();
+        let booking =
+            AirlineRegistry::get_booking_contract(env).expect("Booking contract not configured");
+        assert!(*caller == booking, "Unauthorized");
     }
 
     // Cancel flight (airline emergency)
@@ -399,6 +850,8 @@ impl AirlineContract {
         airline: Address,
         flights: Vec<FlightInput>,
     ) -> BatchCreateFlightsResult {
+        EmergencyStop::require_not_stopped(&env);
+
         airline.require_auth();
         assert!(flights.len() > 0, "Empty batch");
         assert!(flights.len() <= MAX_BATCH_SIZE, "Batch too large");
@@ -406,6 +859,10 @@ impl AirlineContract {
         let mut profile =
             AirlineRegistry::get_airline(&env, &airline).expect("Airline not registered");
         assert!(profile.is_verified, "Airline not verified");
+        assert!(
+            !AirlineRegistry::is_suspended(&env, &airline),
+            "Airline suspended"
+        );
 
         let mut created_flight_ids = Vec::new(&env);
         let mut failures = Vec::new(&env);
@@ -413,7 +870,7 @@ impl AirlineContract {
         let mut i: u32 = 0;
         while i < flights.len() {
             let flight_input = flights.get(i).unwrap();
-            if !Self::is_valid_flight_input(&flight_input) {
+            if !Self::is_valid_flight_input(&env, &flight_input) {
                 failures.push_back(BatchFailure {
                     index: i,
                     item_id: 0,
@@ -440,6 +897,8 @@ impl AirlineContract {
             };
 
             AirlineRegistry::set_flight(&env, flight_id, &flight);
+            AirlineRegistry::add_airline_flight(&env, &airline, flight_id);
+            AirlineRegistry::add_route_flight(&env, &flight.from_airport, &flight.to_airport, flight_id);
             created_flight_ids.push_back(flight_id);
 
             env.events().publish(
@@ -510,6 +969,16 @@ impl AirlineContract {
                 continue;
             }
 
+            if update.status == symbol_short!("completed")
+                && AirlineRegistry::get_seats_sold(&env, update.flight_id).is_none()
+            {
+                AirlineRegistry::set_seats_sold(
+                    &env,
+                    update.flight_id,
+                    Self::seats_sold(&env, &flight),
+                );
+            }
+
             flight.status = update.status;
             AirlineRegistry::set_flight(&env, update.flight_id, &flight);
             updated_flight_ids.push_back(update.flight_id);
@@ -621,6 +1090,40 @@ impl AirlineContract {
     pub fn get_current_price(env: Env, flight_id: u64) -> i128 {
         let cfg = PricingStorage::get_config(&env).expect("Pricing not initialized");
         let flight = AirlineRegistry::get_flight(&env, flight_id).expect("Flight not found");
+        Self::current_price(&env, &cfg, &flight)
+    }
+
+    // Surge view over a page of the airline's flights: (flight_id, base, current, multiplier_bps)
+    // for each active flight. A multiplier above 10_000 bps means the flight is in surge.
+    pub fn surge_summary(
+        env: Env,
+        airline: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<(u64, i128, i128, i128)> {
+        let cfg = PricingStorage::get_config(&env).expect("Pricing not initialized");
+        let flight_ids = AirlineRegistry::get_airline_flights(&env, &airline);
+        let end = start.saturating_add(limit.min(MAX_BATCH_SIZE)).min(flight_ids.len());
+
+        let mut summary = Vec::new(&env);
+        let mut i = start;
+        while i < end {
+            let flight_id = flight_ids.get(i).unwrap();
+            i += 1;
+
+            let flight = match AirlineRegistry::get_flight(&env, flight_id) {
+                Some(flight) if flight.status == symbol_short!("active") => flight,
+                _ => continue,
+            };
+            let current = Self::current_price(&env, &cfg, &flight);
+            let multiplier_bps = current * 10_000i128 / flight.price;
+            summary.push_back((flight_id, flight.price, current, multiplier_bps));
+        }
+
+        summary
+    }
+
+    fn current_price(env: &Env, cfg: &PricingConfig, flight: &Flight) -> i128 {
         assert!(flight.price > 0, "Invalid price");
 
         // Demand is derived from seat utilization (sold/total) and time-to-departure.
@@ -1,5 +1,7 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Env, Symbol, Vec, token, String, contractclient};
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, xdr::ToXdr, Address, BytesN, Env, Symbol, Vec, token, String, contractclient};
+use access::{AccessControl, EmergencyStop, Role};
+use storage_version::{Migratable, VersionedStorage, BOOKING_CONTRACT};
 
 #[contractclient(name = "BookingReceiptClient")]
 pub trait BookingReceiptInterface {
@@ -15,6 +17,113 @@ pub trait BookingReceiptInterface {
     ) -> u64;
 }
 
+#[contractclient(name = "LoyaltyClient")]
+pub trait LoyaltyInterface {
+    fn reverse_points(env: Env, caller: Address, user: Address, booking_id: u64) -> i128;
+    fn get_fee_rebate_bps(env: Env, user: Address) -> u32;
+    fn accrue_points(
+        env: Env,
+        caller: Address,
+        passenger: Address,
+        flight_id: Symbol,
+        amount: i128,
+    ) -> i128;
+    fn redeem_points(env: Env, user: Address, points: i128) -> i128;
+}
+
+// Dispute contract whose final verdict settles a disputed booking's escrow
+#[contractclient(name = "DisputeClient")]
+pub trait DisputeInterface {
+    fn get_dispute_parties(env: Env, dispute_id: u64) -> (Address, Address);
+    fn get_dispute_booking(env: Env, dispute_id: u64) -> Option<u64>;
+    fn get_final_verdict(env: Env, dispute_id: u64) -> Option<Symbol>;
+}
+
+// Airline registry consulted before paying settlements to an airline, and holding the
+// seat inventory for bookings made with `book_flight`
+#[contractclient(name = "AirlineRegistryClient")]
+pub trait AirlineRegistryInterface {
+    fn is_suspended(env: Env, airline: Address) -> bool;
+    fn book_seats(env: Env, caller: Address, flight_id: u64, seats: u32) -> Flight;
+    fn release_seats(env: Env, caller: Address, flight_id: u64, seats: u32);
+    fn get_flight(env: Env, flight_id: u64) -> Option<Flight>;
+    fn get_current_price(env: Env, flight_id: u64) -> i128;
+}
+
+// Mirrors the airline contract's flight listing
+#[contracttype]
+#[derive(Clone)]
+pub struct Flight {
+    pub flight_id: u64,
+    pub airline: Address,
+    pub flight_number: Symbol,
+    pub from_airport: Symbol,
+    pub to_airport: Symbol,
+    pub departure_time: u64,
+    pub arrival_time: u64,
+    pub total_seats: u32,
+    pub available_seats: u32,
+    pub price: i128,
+    pub currency: Symbol,
+    pub status: Symbol,
+}
+
+// Refund contract holding each airline's cancellation fee schedule
+#[contractclient(name = "RefundPolicyClient")]
+pub trait RefundPolicyInterface {
+    fn calculate_refund(
+        env: Env,
+        airline: Address,
+        original_price: i128,
+        departure_time: u64,
+    ) -> i128;
+}
+
+// Contracts notified after a booking's escrow is settled to its airline
+#[contractclient(name = "SettlementObserverClient")]
+pub trait SettlementObserverInterface {
+    fn on_settled(env: Env, booking_id: u64, passenger: Address, airline: Address, amount: i128);
+}
+
+// External yield source holding idle escrow. Tokens are transferred to the source before
+// `deposit` is called; `withdraw` sends tokens back to `owner`.
+#[contractclient(name = "YieldSourceClient")]
+pub trait YieldSourceInterface {
+    fn deposit(env: Env, from: Address, amount: i128);
+    fn withdraw(env: Env, owner: Address, amount: i128);
+    fn balance(env: Env, owner: Address) -> i128;
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct YieldConfig {
+    pub admin: Address,
+    pub source: Address,
+    pub token: Address,
+    pub treasury: Address, // receives accrued yield; principal always stays with escrow
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct FeeConfig {
+    pub fee_bps: u32, // protocol fee on top of the fare, basis points (10000 = 100%)
+    pub treasury: Address,
+}
+
+// Cumulative amount of one fee category paid to the treasury in one token
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeTotal {
+    pub category: Symbol,
+    pub token: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeReport {
+    pub totals: Vec<FeeTotal>,
+}
 
 #[contracttype]
 #[derive(Clone)]
@@ -29,10 +138,108 @@ pub struct Booking {
     pub price: i128,
     pub token: Address,
     pub amount_escrowed: i128,
-    pub status: Symbol, // "pending", "confirmed", "completed", "cancelled", "refunded"
+    pub status: BookingStatus,
+    pub created_at: u64,
+    pub points_discount: i128, // fare paid with redeemed loyalty points instead of tokens
+    pub expires_at: u64,       // unpaid bookings can be expired from this time on
+    pub seat_count: u32,       // seats covered; `price` is per seat
+}
+
+// Where a booking stands. A booking ends in one of three terminal statuses: Completed once
+// the airline is paid, Refunded when the passenger cancels or is refunded (in part under an
+// airline fee schedule), and Cancelled when an agreed cancellation splits the escrow or an
+// unpaid booking expires. There are no in-flight statuses (paying, releasing, refunding):
+// each transition runs with its transfers in one atomic invocation, so such a status could
+// only ever be seen by a re-entrant call, which the booking guard already turns away.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BookingStatus {
+    Pending,
+    Confirmed,
+    Disputed,
+    Completed,
+    Cancelled,
+    Refunded,
+}
+
+impl BookingStatus {
+    // Status symbols bookings were stored with before storage v3
+    pub fn from_symbol(status: &Symbol) -> Option<BookingStatus> {
+        if *status == symbol_short!("pending") {
+            Some(BookingStatus::Pending)
+        } else if *status == symbol_short!("confirmed") {
+            Some(BookingStatus::Confirmed)
+        } else if *status == symbol_short!("disputed") {
+            Some(BookingStatus::Disputed)
+        } else if *status == symbol_short!("completed") {
+            Some(BookingStatus::Completed)
+        } else if *status == symbol_short!("cancelled") {
+            Some(BookingStatus::Cancelled)
+        } else if *status == symbol_short!("refunded") {
+            Some(BookingStatus::Refunded)
+        } else {
+            None
+        }
+    }
+}
+
+// Booking layout before storage v3, when the status was a free-form symbol and bookings
+// had no points discount, expiry or seat count
+#[contracttype]
+#[derive(Clone)]
+pub struct LegacyBooking {
+    pub booking_id: u64,
+    pub passenger: Address,
+    pub airline: Address,
+    pub flight_number: Symbol,
+    pub from_airport: Symbol,
+    pub to_airport: Symbol,
+    pub departure_time: u64,
+    pub price: i128,
+    pub token: Address,
+    pub amount_escrowed: i128,
+    pub status: Symbol,
     pub created_at: u64,
 }
 
+impl LegacyBooking {
+    // Old bookings are single-seat and paid in full. A pending one gets a fresh TTL from
+    // the migration instead of being expirable straight away.
+    pub fn upgrade(self, env: &Env) -> Booking {
+        Booking {
+            booking_id: self.booking_id,
+            passenger: self.passenger,
+            airline: self.airline,
+            flight_number: self.flight_number,
+            from_airport: self.from_airport,
+            to_airport: self.to_airport,
+            departure_time: self.departure_time,
+            price: self.price,
+            token: self.token,
+            amount_escrowed: self.amount_escrowed,
+            status: BookingStatus::from_symbol(&self.status).expect("Unknown booking status"),
+            created_at: self.created_at,
+            points_discount: 0,
+            expires_at: env.ledger().timestamp() + BookingStorage::get_booking_ttl(env),
+            seat_count: 1,
+        }
+    }
+}
+
+// Record of an escrow payout to the airline. `digest` is the keccak256 of the XDR-encoded
+// (booking_id, airline, amount, fee, settled_at) tuple, so the fields can be checked
+// against it off-chain.
+#[contracttype]
+#[derive(Clone)]
+pub struct SettlementReceipt {
+    pub booking_id: u64,
+    pub airline: Address,
+    pub amount: i128,
+    pub fee: i128, // protocol fee the passenger paid on the booking
+    pub settled_at: u64,
+    pub digest: BytesN<32>,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct BatchFailure {
@@ -52,6 +259,14 @@ pub struct BatchCompleteBookingsResult {
 pub struct BookingStorage;
 
 const MAX_BATCH_SIZE: u32 = 50;
+// Every observer is called on each settlement, so the list is kept short
+const MAX_OBSERVERS: u32 = 5;
+// Storage v2 keys bookings by the sequential counter instead of the creation timestamp
+const COUNTER_ID_STORAGE_VERSION: u32 = 2;
+// Storage v3 stores `Booking.status` as a `BookingStatus` rather than a symbol
+const STATUS_ENUM_STORAGE_VERSION: u32 = 3;
+// Unpaid bookings expire this long after creation unless configured otherwise
+const DEFAULT_BOOKING_TTL_SECS: u64 = 24 * 60 * 60;
 
 impl BookingStorage {
     pub fn get(env: &Env, booking_id: u64) -> Option<Booking> {
@@ -62,6 +277,95 @@ impl BookingStorage {
         env.storage().persistent().set(&booking_id, booking);
     }
 
+    pub fn remove(env: &Env, booking_id: u64) {
+        env.storage().persistent().remove(&booking_id);
+    }
+
+    pub fn get_legacy(env: &Env, booking_id: u64) -> Option<LegacyBooking> {
+        env.storage().persistent().get(&booking_id)
+    }
+
+    pub fn get_passenger_bookings(env: &Env, passenger: &Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("px_bks"), passenger.clone()))
+            .unwrap_or(Vec::new(env))
+    }
+
+    pub fn add_passenger_booking(env: &Env, passenger: &Address, booking_id: u64) {
+        let mut bookings = Self::get_passenger_bookings(env, passenger);
+        bookings.push_back(booking_id);
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("px_bks"), passenger.clone()), &bookings);
+    }
+
+    pub fn set_passenger_bookings(env: &Env, passenger: &Address, bookings: &Vec<u64>) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("px_bks"), passenger.clone()), bookings);
+    }
+
+    pub fn get_airline_bookings(env: &Env, airline: &Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("al_bks"), airline.clone()))
+            .unwrap_or(Vec::new(env))
+    }
+
+    pub fn add_airline_booking(env: &Env, airline: &Address, booking_id: u64) {
+        let mut bookings = Self::get_airline_bookings(env, airline);
+        bookings.push_back(booking_id);
+        Self::set_airline_bookings(env, airline, &bookings);
+    }
+
+    pub fn set_airline_bookings(env: &Env, airline: &Address, bookings: &Vec<u64>) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("al_bks"), airline.clone()), bookings);
+    }
+
+    // Counter id a migrated timestamp-keyed booking was re-keyed to
+    pub fn get_migrated_id(env: &Env, legacy_id: u64) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("legacy"), legacy_id))
+    }
+
+    pub fn set_migrated_id(env: &Env, legacy_id: u64, booking_id: u64) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("legacy"), legacy_id), &booking_id);
+    }
+
+    pub fn is_status_migrated(env: &Env, booking_id: u64) -> bool {
+        env.storage()
+            .persistent()
+            .has(&(symbol_short!("st_migr"), booking_id))
+    }
+
+    pub fn set_status_migrated(env: &Env, booking_id: u64) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("st_migr"), booking_id), &true);
+    }
+
+    // Ids handed from a migration entrypoint to the `BookingMigration` steps it runs
+    pub fn get_migration_ids(env: &Env) -> Vec<u64> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("mig_ids"))
+            .unwrap_or(Vec::new(env))
+    }
+
+    pub fn set_migration_ids(env: &Env, ids: &Vec<u64>) {
+        env.storage().instance().set(&symbol_short!("mig_ids"), ids);
+    }
+
+    pub fn clear_migration_ids(env: &Env) {
+        env.storage().instance().remove(&symbol_short!("mig_ids"));
+    }
+
     pub fn get_trusted_oracle(env: &Env) -> Option<Address> {
         env.storage().instance().get(&symbol_short!("oracle"))
     }
@@ -70,6 +374,47 @@ impl BookingStorage {
         env.storage().instance().set(&symbol_short!("oracle"), oracle);
     }
 
+    pub fn get_airline_registry(env: &Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("al_reg"))
+    }
+
+    pub fn set_airline_registry(env: &Env, registry: &Address) {
+        env.storage().instance().set(&symbol_short!("al_reg"), registry);
+    }
+
+    // Flight a booking holds a seat on, for bookings made with `book_flight`
+    pub fn get_booked_flight(env: &Env, booking_id: u64) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("bk_flt"), booking_id))
+    }
+
+    pub fn set_booked_flight(env: &Env, booking_id: u64, flight_id: u64) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("bk_flt"), booking_id), &flight_id);
+    }
+
+    pub fn remove_booked_flight(env: &Env, booking_id: u64) {
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("bk_flt"), booking_id));
+    }
+
+    // Settlements owed to a suspended airline, per token
+    pub fn get_held_funds(env: &Env, airline: &Address, token: &Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("held"), airline.clone(), token.clone()))
+            .unwrap_or(0)
+    }
+
+    pub fn set_held_funds(env: &Env, airline: &Address, token: &Address, amount: i128) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("held"), airline.clone(), token.clone()), &amount);
+    }
+
     pub fn get_receipt_contract(env: &Env) -> Option<Address> {
         env.storage().instance().get(&symbol_short!("receipt_c"))
     }
@@ -78,18 +423,304 @@ impl BookingStorage {
         env.storage().instance().set(&symbol_short!("receipt_c"), contract);
     }
 
-    pub fn next_id(env: &Env) -> u64 {
-        let id: u64 = env.storage().instance().get(&symbol_short!("next_id")).unwrap_or(1);
+    pub fn get_loyalty_contract(env: &Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("loyalty_c"))
+    }
+
+    pub fn set_loyalty_contract(env: &Env, contract: &Address) {
+        env.storage().instance().set(&symbol_short!("loyalty_c"), contract);
+    }
+
+    // Loyalty points credited per token unit of a refund taken as points
+    pub fn get_points_refund_rate(env: &Env) -> Option<u32> {
+        env.storage().instance().get(&symbol_short!("pts_rate"))
+    }
+
+    pub fn set_points_refund_rate(env: &Env, rate: u32) {
+        env.storage().instance().set(&symbol_short!("pts_rate"), &rate);
+    }
+
+    pub fn get_booking_ttl(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("bk_ttl"))
+            .unwrap_or(DEFAULT_BOOKING_TTL_SECS)
+    }
+
+    pub fn set_booking_ttl(env: &Env, ttl_secs: u64) {
+        env.storage().instance().set(&symbol_short!("bk_ttl"), &ttl_secs);
+    }
+
+    pub fn get_refund_contract(env: &Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("refund_c"))
+    }
+
+    pub fn set_refund_contract(env: &Env, contract: &Address) {
+        env.storage().instance().set(&symbol_short!("refund_c"), contract);
+    }
+
+    pub fn get_observers(env: &Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("observers"))
+            .unwrap_or(Vec::new(env))
+    }
+
+    pub fn set_observers(env: &Env, observers: &Vec<Address>) {
+        env.storage().instance().set(&symbol_short!("observers"), observers);
+    }
+
+    pub fn get_dispute_contract(env: &Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("dispute_c"))
+    }
+
+    pub fn set_dispute_contract(env: &Env, contract: &Address) {
+        env.storage().instance().set(&symbol_short!("dispute_c"), contract);
+    }
+
+    // Booking whose escrow is held pending `dispute_id`
+    pub fn get_disputed_booking(env: &Env, dispute_id: u64) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("disp_bk"), dispute_id))
+    }
+
+    pub fn set_disputed_booking(env: &Env, dispute_id: u64, booking_id: u64) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("disp_bk"), dispute_id), &booking_id);
+    }
+
+    pub fn remove_disputed_booking(env: &Env, dispute_id: u64) {
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("disp_bk"), dispute_id));
+    }
+
+    pub fn get_fee_config(env: &Env) -> Option<FeeConfig> {
+        env.storage().instance().get(&symbol_short!("fee_cfg"))
+    }
+
+    pub fn set_fee_config(env: &Env, config: &FeeConfig) {
+        env.storage().instance().set(&symbol_short!("fee_cfg"), config);
+    }
+
+    pub fn get_yield_config(env: &Env) -> Option<YieldConfig> {
+        env.storage().instance().get(&symbol_short!("yield_cfg"))
+    }
+
+    pub fn set_yield_config(env: &Env, config: &YieldConfig) {
+        env.storage().instance().set(&symbol_short!("yield_cfg"), config);
+    }
+
+    pub fn get_fee_totals(env: &Env) -> Vec<FeeTotal> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("fee_tot"))
+            .unwrap_or(Vec::new(env))
+    }
+
+    // Add `amount` to the running total for (category, token)
+    pub fn add_fee(env: &Env, category: Symbol, token: &Address, amount: i128) {
+        let mut totals = Self::get_fee_totals(env);
+        let mut found = false;
+        for i in 0..totals.len() {
+            let mut total = totals.get(i).unwrap();
+            if total.category == category && total.token == *token {
+                total.amount += amount;
+                totals.set(i, total);
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            totals.push_back(FeeTotal {
+                category,
+                token: token.clone(),
+                amount,
+            });
+        }
+        env.storage().instance().set(&symbol_short!("fee_tot"), &totals);
+    }
+
+    pub fn get_yield_principal(env: &Env) -> i128 {
+        env.storage().instance().get(&symbol_short!("y_princ")).unwrap_or(0)
+    }
+
+    pub fn set_yield_principal(env: &Env, amount: i128) {
+        env.storage().instance().set(&symbol_short!("y_princ"), &amount);
+    }
+
+    // Total escrow owed to bookings in `token`, whether held here or in the yield source
+    pub fn get_escrow_total(env: &Env, token: &Address) -> i128 {
+        env.storage()
+            .instance()
+            .get(&(symbol_short!("esc_tot"), token.clone()))
+            .unwrap_or(0)
+    }
+
+    pub fn set_escrow_total(env: &Env, token: &Address, amount: i128) {
+        env.storage()
+            .instance()
+            .set(&(symbol_short!("esc_tot"), token.clone()), &amount);
+    }
+
+    // Protocol fees collected across the booking's payments
+    pub fn get_booking_fee(env: &Env, booking_id: u64) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("bk_fee"), booking_id))
+            .unwrap_or(0)
+    }
+
+    pub fn set_booking_fee(env: &Env, booking_id: u64, fee: i128) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("bk_fee"), booking_id), &fee);
+    }
+
+    pub fn get_receipt(env: &Env, booking_id: u64) -> Option<SettlementReceipt> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("st_rcpt"), booking_id))
+    }
+
+    pub fn set_receipt(env: &Env, booking_id: u64, receipt: &SettlementReceipt) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("st_rcpt"), booking_id), receipt);
+    }
+
+    pub fn is_no_show(env: &Env, booking_id: u64) -> bool {
+        env.storage()
+            .persistent()
+            .has(&(symbol_short!("no_show"), booking_id))
+    }
+
+    pub fn set_no_show(env: &Env, booking_id: u64) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("no_show"), booking_id), &true);
+    }
+
+    pub fn clear_no_show(env: &Env, booking_id: u64) {
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("no_show"), booking_id));
+    }
+
+    // Simple reentrancy guard; any panic rolls the flag back with the rest of the transaction.
+    pub fn enter_guard(env: &Env) {
+        let locked: bool = env.storage().instance().get(&symbol_short!("lock")).unwrap_or(false);
+        assert!(!locked, "Reentrancy detected");
+        env.storage().instance().set(&symbol_short!("lock"), &true);
+    }
+
+    pub fn exit_guard(env: &Env) {
+        env.storage().instance().set(&symbol_short!("lock"), &false);
+    }
+
+    pub fn peek_booking_id(env: &Env) -> u64 {
+        env.storage().instance().get(&symbol_short!("next_id")).unwrap_or(1)
+    }
+
+    pub fn next_booking_id(env: &Env) -> u64 {
+        let id = Self::peek_booking_id(env);
         env.storage().instance().set(&symbol_short!("next_id"), &(id + 1));
         id
     }
 }
 
+// Storage steps run through `VersionedStorage::migrate_storage_with`. Each rewrites the
+// bookings whose ids the calling entrypoint stashed and skips any it already rewrote, so
+// running a step again after a rollback changes nothing.
+struct BookingMigration;
+
+impl BookingMigration {
+    fn rekey(env: &Env, legacy_id: u64) {
+        if BookingStorage::get_migrated_id(env, legacy_id).is_some() {
+            return;
+        }
+        // Counter ids sit below the next id; timestamp ids are far above it
+        assert!(legacy_id >= BookingStorage::peek_booking_id(env), "Not a legacy booking");
+        // Timestamp-keyed bookings predate the status enum as well
+        let mut booking = BookingStorage::get_legacy(env, legacy_id)
+            .expect("Booking not found")
+            .upgrade(env);
+
+        let booking_id = BookingStorage::next_booking_id(env);
+        booking.booking_id = booking_id;
+        BookingStorage::set(env, booking_id, &booking);
+        BookingStorage::remove(env, legacy_id);
+        BookingStorage::set_migrated_id(env, legacy_id, booking_id);
+
+        let mut passenger_bookings = BookingStorage::get_passenger_bookings(env, &booking.passenger);
+        match passenger_bookings.first_index_of(legacy_id) {
+            Some(i) => passenger_bookings.set(i, booking_id),
+            None => passenger_bookings.push_back(booking_id),
+        }
+        BookingStorage::set_passenger_bookings(env, &booking.passenger, &passenger_bookings);
+
+        let mut airline_bookings = BookingStorage::get_airline_bookings(env, &booking.airline);
+        match airline_bookings.first_index_of(legacy_id) {
+            Some(i) => airline_bookings.set(i, booking_id),
+            None => airline_bookings.push_back(booking_id),
+        }
+        BookingStorage::set_airline_bookings(env, &booking.airline, &airline_bookings);
+
+        if BookingStorage::is_no_show(env, legacy_id) {
+            BookingStorage::clear_no_show(env, legacy_id);
+            BookingStorage::set_no_show(env, booking_id);
+        }
+
+        BookingStorage::set_status_migrated(env, booking_id);
+    }
+}
+
+impl Migratable for BookingMigration {
+    // v2 keys bookings by the booking counter
+    fn migrate_from_v1_to_v2(env: &Env) -> bool {
+        for legacy_id in BookingStorage::get_migration_ids(env).iter() {
+            Self::rekey(env, legacy_id);
+        }
+        true
+    }
+
+    // v3 stores the status as a `BookingStatus`
+    fn migrate_from_v2_to_v3(env: &Env) -> bool {
+        for booking_id in BookingStorage::get_migration_ids(env).iter() {
+            if BookingStorage::is_status_migrated(env, booking_id) {
+                continue;
+            }
+            let booking = BookingStorage::get_legacy(env, booking_id).expect("Booking not found");
+            BookingStorage::set(env, booking_id, &booking.upgrade(env));
+            BookingStorage::set_status_migrated(env, booking_id);
+        }
+        true
+    }
+
+    fn get_migration_steps(env: &Env, from_version: u32, to_version: u32) -> Vec<Symbol> {
+        let mut steps = Vec::new(env);
+        if from_version < COUNTER_ID_STORAGE_VERSION && to_version >= COUNTER_ID_STORAGE_VERSION {
+            steps.push_back(symbol_short!("rekey"));
+        }
+        if from_version < STATUS_ENUM_STORAGE_VERSION && to_version >= STATUS_ENUM_STORAGE_VERSION {
+            steps.push_back(symbol_short!("status"));
+        }
+        steps
+    }
+}
+
 #[contract]
 pub struct BookingContract;
 
 #[contractimpl]
 impl BookingContract {
+    // Owner who can grant the admins that configure linked contracts, fees and migrations
+    pub fn init_admin(env: Env, owner: Address) {
+        AccessControl::init_owner(&env, &owner);
+    }
+
     // Register the trusted oracle contract address
     pub fn initialize_oracle(env: Env, admin: Address, oracle: Address) {
         admin.require_auth();
@@ -100,44 +731,572 @@ impl BookingContract {
         );
     }
 
-    pub fn set_receipt_contract(env: Env, admin: Address, receipt_contract: Address) {
-        admin.require_auth();
-        BookingStorage::set_receipt_contract(&env, &receipt_contract);
-    }
+    // Re-key bookings created under the timestamp-id scheme onto the booking counter.
+    // Every legacy id must be passed in a single call: the booking storage version is
+    // bumped afterwards and the migration cannot be run again.
+    pub fn migrate_legacy_bookings(env: Env, admin: Address, legacy_ids: Vec<u64>) -> Vec<u64> {
+        AccessControl::require_admin(&env, &admin);
+        assert!(
+            VersionedStorage::needs_migration(&env, &BOOKING_CONTRACT, COUNTER_ID_STORAGE_VERSION),
+            "Already migrated"
+        );
 
-    // Initialize booking - starts in "pending" status until paid
-    pub fn create_booking(        env: Env,
-        passenger: Address,
-        airline: Address,
-        flight_number: Symbol,
-        from_airport: Symbol,
-        to_airport: Symbol,
-        departure_time: u64,
-        price: i128,
-        token: Address,
-    ) -> u64 {
-        passenger.require_auth();
-        
-        let booking_id = BookingStorage::next_id(&env);
-        
-        let booking = Booking {
-            booking_id,
-            passenger,
-            airline,
-            flight_number,
-            from_airport,
-            to_airport,
-            departure_time,
-            price,
-            token,
-            amount_escrowed: 0,
-            status: symbol_short!("pending"),
-            created_at: env.ledger().timestamp(),
-        };
-        
-        BookingStorage::set(&env, booking_id, &booking);
+        BookingStorage::set_migration_ids(&env, &legacy_ids);
+        let current = VersionedStorage::get_storage_version(&env, &BOOKING_CONTRACT);
+        assert!(
+            VersionedStorage::migrate_storage_with::<BookingMigration>(
+                &env,
+                &BOOKING_CONTRACT,
+                current,
+                COUNTER_ID_STORAGE_VERSION,
+                &admin,
+            ),
+            "Migration failed"
+        );
+        BookingStorage::clear_migration_ids(&env);
+
+        let mut new_ids = Vec::new(&env);
+        for legacy_id in legacy_ids.iter() {
+            new_ids.push_back(BookingStorage::get_migrated_id(&env, legacy_id).unwrap());
+        }
 
-        // Standard event schema: (contract, action) -> (actor, timestamp, payload)
+        env.events().publish(
+            (symbol_short!("booking"), symbol_short!("migrated")),
+            (admin, env.ledger().timestamp(), new_ids.len()),
+        );
+
+        new_ids
+    }
+
+    // Rewrite counter-keyed bookings stored before v3 into the current layout (see
+    // `LegacyBooking::upgrade`), at most MAX_BATCH_SIZE per call. Run after
+    // `migrate_legacy_bookings` (whose bookings are converted as they're re-keyed) and page
+    // through the remaining pre-upgrade ids; the call with `last_page` set bumps the version,
+    // after which no more pages are accepted. Ids already rewritten are skipped.
+    pub fn migrate_booking_statuses(
+        env: Env,
+        admin: Address,
+        booking_ids: Vec<u64>,
+        last_page: bool,
+    ) -> u32 {
+        AccessControl::require_admin(&env, &admin);
+        assert!(
+            !VersionedStorage::needs_migration(&env, &BOOKING_CONTRACT, COUNTER_ID_STORAGE_VERSION),
+            "Migrate legacy bookings first"
+        );
+        assert!(
+            VersionedStorage::needs_migration(&env, &BOOKING_CONTRACT, STATUS_ENUM_STORAGE_VERSION),
+            "Already migrated"
+        );
+
+        assert!(booking_ids.len() <= MAX_BATCH_SIZE, "Batch too large");
+
+        BookingStorage::set_migration_ids(&env, &booking_ids);
+        if last_page {
+            assert!(
+                VersionedStorage::migrate_storage_with::<BookingMigration>(
+                    &env,
+                    &BOOKING_CONTRACT,
+                    COUNTER_ID_STORAGE_VERSION,
+                    STATUS_ENUM_STORAGE_VERSION,
+                    &admin,
+                ),
+                "Migration failed"
+            );
+        } else {
+            // Earlier pages run the same step without moving the version
+            BookingMigration::migrate_from_v2_to_v3(&env);
+        }
+        BookingStorage::clear_migration_ids(&env);
+
+        env.events().publish(
+            (symbol_short!("booking"), symbol_short!("st_migr")),
+            (admin, env.ledger().timestamp(), booking_ids.len()),
+        );
+
+        booking_ids.len()
+    }
+
+    // Counter id a legacy timestamp-keyed booking now lives under
+    pub fn get_migrated_booking_id(env: Env, legacy_id: u64) -> Option<u64> {
+        BookingStorage::get_migrated_id(&env, legacy_id)
+    }
+
+    // Airline contract whose suspensions hold settlements back from an airline
+    pub fn set_airline_registry(env: Env, admin: Address, registry: Address) {
+        AccessControl::require_admin(&env, &admin);
+        BookingStorage::set_airline_registry(&env, &registry);
+        env.events().publish(
+            (symbol_short!("booking"), symbol_short!("al_reg")),
+            (admin, env.ledger().timestamp(), registry),
+        );
+    }
+
+    // Admin multisig whose emergency stop this contract follows
+    pub fn set_admin_multisig(env: Env, admin: Address, multisig: Address) {
+        AccessControl::require_admin(&env, &admin);
+        EmergencyStop::set_admin_multisig(&env, &multisig);
+
+        env.events().publish(
+            (symbol_short!("booking"), symbol_short!("adm_ms")),
+            (admin, multisig),
+        );
+    }
+
+    pub fn is_emergency_stopped(env: Env) -> bool {
+        EmergencyStop::is_stopped(&env)
+    }
+
+    // Pay out held settlements once the airline has been reinstated
+    pub fn claim_held_funds(env: Env, airline: Address, token: Address) -> i128 {
+        airline.require_auth();
+        assert!(!Self::airline_suspended(&env, &airline), "Airline suspended");
+
+        let amount = BookingStorage::get_held_funds(&env, &airline, &token);
+        assert!(amount > 0, "No held funds");
+
+        BookingStorage::set_held_funds(&env, &airline, &token, 0);
+        BookingStorage::enter_guard(&env);
+        token::Client::new(&env, &token).transfer(&env.current_contract_address(), &airline, &amount);
+        BookingStorage::exit_guard(&env);
+
+        env.events().publish(
+            (symbol_short!("booking"), symbol_short!("unheld")),
+            (airline, env.ledger().timestamp(), token, amount),
+        );
+        amount
+    }
+
+    pub fn get_held_funds(env: Env, airline: Address, token: Address) -> i128 {
+        BookingStorage::get_held_funds(&env, &airline, &token)
+    }
+
+    fn airline_suspended(env: &Env, airline: &Address) -> bool {
+        match BookingStorage::get_airline_registry(env) {
+            Some(registry) => AirlineRegistryClient::new(env, &registry).is_suspended(airline),
+            None => false,
+        }
+    }
+
+    // Pay an airline from drawn escrow, or hold the amount while the airline is suspended
+    fn pay_airline(env: &Env, booking: &Booking, amount: i128) {
+        if Self::airline_suspended(env, &booking.airline) {
+            let held = BookingStorage::get_held_funds(env, &booking.airline, &booking.token);
+            BookingStorage::set_held_funds(env, &booking.airline, &booking.token, held + amount);
+            env.events().publish(
+                (symbol_short!("booking"), symbol_short!("held")),
+                (booking.airline.clone(), env.ledger().timestamp(), booking.booking_id, amount),
+            );
+            return;
+        }
+
+        token::Client::new(env, &booking.token).transfer(
+            &env.current_contract_address(),
+            &booking.airline,
+            &amount,
+        );
+    }
+
+    pub fn set_receipt_contract(env: Env, admin: Address, receipt_contract: Address) {
+        admin.require_auth();
+        BookingStorage::set_receipt_contract(&env, &receipt_contract);
+    }
+
+    // Optional loyalty contract used to reverse points awarded for no-show bookings
+    pub fn set_loyalty_contract(env: Env, admin: Address, loyalty_contract: Address) {
+        AccessControl::require_admin(&env, &admin);
+        BookingStorage::set_loyalty_contract(&env, &loyalty_contract);
+    }
+
+    // Points per token unit credited by `refund_as_points`. Redemption pays out 100 points
+    // per unit, so a rate above 100 makes taking points the better deal.
+    pub fn set_points_refund_rate(env: Env, admin: Address, points_per_unit: u32) {
+        AccessControl::require_admin(&env, &admin);
+        assert!(points_per_unit > 0, "Invalid points rate");
+        BookingStorage::set_points_refund_rate(&env, points_per_unit);
+        env.events().publish(
+            (symbol_short!("booking"), symbol_short!("pts_rate")),
+            (admin, env.ledger().timestamp(), points_per_unit),
+        );
+    }
+
+    // How long a new booking may stay unpaid before anyone can expire it
+    pub fn set_booking_ttl(env: Env, admin: Address, ttl_secs: u64) {
+        AccessControl::require_admin(&env, &admin);
+        assert!(ttl_secs > 0, "Invalid booking TTL");
+        BookingStorage::set_booking_ttl(&env, ttl_secs);
+        env.events().publish(
+            (symbol_short!("booking"), symbol_short!("ttl")),
+            (admin, env.ledger().timestamp(), ttl_secs),
+        );
+    }
+
+    // Refund contract whose airline policies set the refund on `cancel_booking`
+    pub fn set_refund_contract(env: Env, admin: Address, refund_contract: Address) {
+        AccessControl::require_admin(&env, &admin);
+        BookingStorage::set_refund_contract(&env, &refund_contract);
+    }
+
+    // Dispute contract whose verdicts settle escrow held by `hold_for_dispute`
+    pub fn set_dispute_contract(env: Env, admin: Address, dispute_contract: Address) {
+        AccessControl::require_admin(&env, &admin);
+        BookingStorage::set_dispute_contract(&env, &dispute_contract);
+    }
+
+    // Contract notified of every settlement, up to MAX_OBSERVERS
+    pub fn add_settlement_observer(env: Env, admin: Address, observer: Address) {
+        AccessControl::require_admin(&env, &admin);
+        let mut observers = BookingStorage::get_observers(&env);
+        assert!(!observers.contains(&observer), "Observer already registered");
+        assert!(observers.len() < MAX_OBSERVERS, "Too many observers");
+        observers.push_back(observer.clone());
+        BookingStorage::set_observers(&env, &observers);
+        env.events().publish(
+            (symbol_short!("booking"), symbol_short!("obs_add")),
+            (admin, observer),
+        );
+    }
+
+    pub fn remove_settlement_observer(env: Env, admin: Address, observer: Address) {
+        AccessControl::require_admin(&env, &admin);
+        let mut observers = BookingStorage::get_observers(&env);
+        let index = observers.first_index_of(&observer).expect("Observer not registered");
+        observers.remove(index);
+        BookingStorage::set_observers(&env, &observers);
+        env.events().publish(
+            (symbol_short!("booking"), symbol_short!("obs_rm")),
+            (admin, observer),
+        );
+    }
+
+    fn record_settlement(env: &Env, booking: &Booking, amount: i128) {
+        let fee = BookingStorage::get_booking_fee(env, booking.booking_id);
+        let settled_at = env.ledger().timestamp();
+        let digest = env
+            .crypto()
+            .keccak256(&(booking.booking_id, booking.airline.clone(), amount, fee, settled_at).to_xdr(env))
+            .into();
+        BookingStorage::set_receipt(
+            env,
+            booking.booking_id,
+            &SettlementReceipt {
+                booking_id: booking.booking_id,
+                airline: booking.airline.clone(),
+                amount,
+                fee,
+                settled_at,
+                digest,
+            },
+        );
+    }
+
+    pub fn settlement_receipt(env: Env, booking_id: u64) -> SettlementReceipt {
+        BookingStorage::get_receipt(&env, booking_id).expect("No settlement receipt")
+    }
+
+    pub fn get_settlement_observers(env: Env) -> Vec<Address> {
+        BookingStorage::get_observers(&env)
+    }
+
+    // Best-effort: an observer that fails only has its own changes rolled back, the
+    // settlement still goes through
+    fn notify_settled(env: &Env, booking: &Booking, amount: i128) {
+        for observer in BookingStorage::get_observers(env).iter() {
+            let _ = SettlementObserverClient::new(env, &observer).try_on_settled(
+                &booking.booking_id,
+                &booking.passenger,
+                &booking.airline,
+                &amount,
+            );
+        }
+    }
+
+    // Protocol fee charged on payment, paid to the treasury on top of the escrowed fare
+    pub fn set_protocol_fee(env: Env, admin: Address, fee_bps: u32, treasury: Address) {
+        AccessControl::require_admin(&env, &admin);
+        assert!(fee_bps <= 10_000, "Invalid fee bps");
+        BookingStorage::set_fee_config(&env, &FeeConfig { fee_bps, treasury: treasury.clone() });
+        env.events().publish(
+            (symbol_short!("booking"), symbol_short!("fee_cfg")),
+            (admin, env.ledger().timestamp(), fee_bps, treasury),
+        );
+    }
+
+    // Protocol fee a passenger would pay for a fare, after their loyalty tier rebate
+    pub fn quote_booking_fee(env: Env, passenger: Address, price: i128) -> i128 {
+        Self::booking_fee(&env, &passenger, price)
+    }
+
+    // Checkout total for one seat on a registry flight: the fare `book_flight` would
+    // charge right now plus the passenger's protocol fee
+    pub fn effective_price_for(env: Env, passenger: Address, flight_id: u64) -> i128 {
+        let registry = BookingStorage::get_airline_registry(&env)
+            .expect("Airline registry not configured");
+        let price = Self::flight_fare(&AirlineRegistryClient::new(&env, &registry), flight_id);
+        price + Self::booking_fee(&env, &passenger, price)
+    }
+
+    // Per-seat fare for a registry flight: the airline's live price, or its listed price
+    // when dynamic pricing isn't set up
+    fn flight_fare(client: &AirlineRegistryClient, flight_id: u64) -> i128 {
+        match client.try_get_current_price(&flight_id) {
+            Ok(Ok(price)) => price,
+            _ => client.get_flight(&flight_id).expect("Flight not found").price,
+        }
+    }
+
+    fn booking_fee(env: &Env, passenger: &Address, price: i128) -> i128 {
+        let config = match BookingStorage::get_fee_config(env) {
+            Some(config) => config,
+            None => return 0,
+        };
+
+        let fee = price * config.fee_bps as i128 / 10_000;
+        let rebate_bps = match BookingStorage::get_loyalty_contract(env) {
+            Some(loyalty_contract) => {
+                LoyaltyClient::new(env, &loyalty_contract).get_fee_rebate_bps(passenger)
+            }
+            None => 0,
+        };
+
+        fee - fee * rebate_bps as i128 / 10_000
+    }
+
+    // Configure the yield source for idle escrow of `token`. Only a contract admin can set
+    // it up, and only the same admin can replace it once all principal has been recalled.
+    pub fn set_yield_source(env: Env, admin: Address, source: Address, token: Address, treasury: Address) {
+        AccessControl::require_admin(&env, &admin);
+        if let Some(existing) = BookingStorage::get_yield_config(&env) {
+            assert!(existing.admin == admin, "Not yield admin");
+            assert!(
+                BookingStorage::get_yield_principal(&env) == 0,
+                "Yield principal outstanding"
+            );
+        }
+        BookingStorage::set_yield_config(
+            &env,
+            &YieldConfig {
+                admin: admin.clone(),
+                source: source.clone(),
+                token,
+                treasury,
+            },
+        );
+        env.events().publish(
+            (symbol_short!("yield"), symbol_short!("config")),
+            (admin, env.ledger().timestamp(), source),
+        );
+    }
+
+    // Cumulative amounts this contract has paid to the treasury, by category and token
+    pub fn fee_report(env: Env) -> FeeReport {
+        FeeReport {
+            totals: BookingStorage::get_fee_totals(&env),
+        }
+    }
+
+    pub fn get_yield_config(env: Env) -> Option<YieldConfig> {
+        BookingStorage::get_yield_config(&env)
+    }
+
+    // Escrow principal currently held by the yield source
+    pub fn get_yield_principal(env: Env) -> i128 {
+        BookingStorage::get_yield_principal(&env)
+    }
+
+    // Move idle escrow into the yield source
+    pub fn deposit_escrow_to_yield(env: Env, admin: Address, amount: i128) {
+        let config = Self::require_yield_admin(&env, &admin);
+        assert!(amount > 0, "Invalid amount");
+
+        let principal = BookingStorage::get_yield_principal(&env);
+        let idle = BookingStorage::get_escrow_total(&env, &config.token) - principal;
+        assert!(idle >= amount, "Insufficient idle escrow");
+
+        let contract = env.current_contract_address();
+        token::Client::new(&env, &config.token).transfer(&contract, &config.source, &amount);
+        YieldSourceClient::new(&env, &config.source).deposit(&contract, &amount);
+        BookingStorage::set_yield_principal(&env, principal + amount);
+
+        env.events().publish(
+            (symbol_short!("yield"), symbol_short!("deposit")),
+            (admin, env.ledger().timestamp(), amount),
+        );
+    }
+
+    // Pull principal back from the yield source into escrow
+    pub fn recall_escrow_from_yield(env: Env, admin: Address, amount: i128) {
+        let config = Self::require_yield_admin(&env, &admin);
+        assert!(amount > 0, "Invalid amount");
+        Self::recall_principal(&env, &config, amount);
+    }
+
+    // Send yield accrued above the deposited principal to the treasury
+    pub fn harvest_yield(env: Env, admin: Address) -> i128 {
+        let config = Self::require_yield_admin(&env, &admin);
+        let source = YieldSourceClient::new(&env, &config.source);
+        let contract = env.current_contract_address();
+
+        let accrued = source.balance(&contract) - BookingStorage::get_yield_principal(&env);
+        if accrued <= 0 {
+            return 0;
+        }
+
+        source.withdraw(&contract, &accrued);
+        token::Client::new(&env, &config.token).transfer(&contract, &config.treasury, &accrued);
+        BookingStorage::add_fee(&env, symbol_short!("yield"), &config.token, accrued);
+
+        env.events().publish(
+            (symbol_short!("yield"), symbol_short!("harvest")),
+            (admin, env.ledger().timestamp(), accrued),
+        );
+        accrued
+    }
+
+    fn require_yield_admin(env: &Env, admin: &Address) -> YieldConfig {
+        admin.require_auth();
+        let config = BookingStorage::get_yield_config(env).expect("Yield source not configured");
+        assert!(config.admin == *admin, "Not yield admin");
+        config
+    }
+
+    fn recall_principal(env: &Env, config: &YieldConfig, amount: i128) {
+        let principal = BookingStorage::get_yield_principal(env);
+        assert!(amount <= principal, "Exceeds yield principal");
+
+        // A source that pays back less than requested makes the payout transfer fail,
+        // reverting the whole settlement rather than short-paying it.
+        let contract = env.current_contract_address();
+        YieldSourceClient::new(env, &config.source).withdraw(&contract, &amount);
+        BookingStorage::set_yield_principal(env, principal - amount);
+
+        env.events().publish(
+            (symbol_short!("yield"), symbol_short!("recall")),
+            (contract, env.ledger().timestamp(), amount),
+        );
+    }
+
+    // Take `amount` out of escrow before paying it out, recalling principal from
+    // the yield source when not enough of `token` is held by this contract.
+    fn draw_escrow(env: &Env, token: &Address, amount: i128) {
+        Self::ensure_liquidity(env, token, amount);
+        let total = BookingStorage::get_escrow_total(env, token);
+        BookingStorage::set_escrow_total(env, token, total - amount);
+    }
+
+    fn ensure_liquidity(env: &Env, token: &Address, amount: i128) {
+        if let Some(config) = BookingStorage::get_yield_config(env) {
+            if config.token == *token {
+                let held = BookingStorage::get_escrow_total(env, token)
+                    - BookingStorage::get_yield_principal(env);
+                if held < amount {
+                    Self::recall_principal(env, &config, amount - held);
+                }
+            }
+        }
+    }
+
+    // Pay `amount` of the escrow back to the passenger. A failing token transfer is caught
+    // rather than trapping, so the booking keeps its status and escrow and the refund can
+    // be retried.
+    fn refund_escrow(env: &Env, actor: &Address, booking: &Booking, amount: i128) -> bool {
+        if amount <= 0 {
+            return true;
+        }
+
+        Self::ensure_liquidity(env, &booking.token, amount);
+        let result = token::Client::new(env, &booking.token).try_transfer(
+            &env.current_contract_address(),
+            &booking.passenger,
+            &amount,
+        );
+        if !matches!(result, Ok(Ok(()))) {
+            env.events().publish(
+                (symbol_short!("booking"), symbol_short!("rf_failed")),
+                (actor.clone(), env.ledger().timestamp(), booking.booking_id, amount),
+            );
+            return false;
+        }
+
+        let total = BookingStorage::get_escrow_total(env, &booking.token);
+        BookingStorage::set_escrow_total(env, &booking.token, total - amount);
+        true
+    }
+
+    // Share of the escrow the airline's refund policy gives back on cancellation now. The
+    // whole escrow without a refund contract or a policy for the airline.
+    fn policy_refund(env: &Env, booking: &Booking) -> i128 {
+        let refund_contract = match BookingStorage::get_refund_contract(env) {
+            Some(contract) => contract,
+            None => return booking.amount_escrowed,
+        };
+        match RefundPolicyClient::new(env, &refund_contract).try_calculate_refund(
+            &booking.airline,
+            &booking.amount_escrowed,
+            &booking.departure_time,
+        ) {
+            Ok(Ok(refund)) => refund.min(booking.amount_escrowed),
+            _ => booking.amount_escrowed,
+        }
+    }
+
+    // Initialize booking - starts in Pending status until paid
+    // Free-form booking for a flight that isn't listed with the airline contract. Once an
+    // airline registry is configured, bookings must go through `book_flight` so that they
+    // take seats from the flight's inventory.
+    pub fn create_booking(        env: Env,
+        passenger: Address,
+        airline: Address,
+        flight_number: Symbol,
+        from_airport: Symbol,
+        to_airport: Symbol,
+        departure_time: u64,
+        price: i128,
+        token: Address,
+    ) -> u64 {
+        assert!(
+            BookingStorage::get_airline_registry(&env).is_none(),
+            "Book listed flights with book_flight"
+        );
+
+        Self::open_booking(
+            &env,
+            Booking {
+                booking_id: 0,
+                passenger,
+                airline,
+                flight_number,
+                from_airport,
+                to_airport,
+                departure_time,
+                price,
+                token,
+                amount_escrowed: 0,
+                status: BookingStatus::Pending,
+                created_at: 0,
+                points_discount: 0,
+                expires_at: 0,
+                seat_count: 1,
+            },
+        )
+    }
+
+    // Store a new pending booking under the next id, stamping its creation and expiry times
+    fn open_booking(env: &Env, mut booking: Booking) -> u64 {
+        EmergencyStop::require_not_stopped(env);
+
+        booking.passenger.require_auth();
+        
+        let booking_id = BookingStorage::next_booking_id(env);
+        booking.booking_id = booking_id;
+        booking.created_at = env.ledger().timestamp();
+        booking.expires_at = env.ledger().timestamp() + BookingStorage::get_booking_ttl(env);
+        
+        BookingStorage::set(env, booking_id, &booking);
+        BookingStorage::add_passenger_booking(env, &booking.passenger, booking_id);
+        BookingStorage::add_airline_booking(env, &booking.airline, booking_id);
+
+        // Standard event schema: (contract, action) -> (actor, timestamp, payload)
         env.events().publish(
             (symbol_short!("booking"), symbol_short!("created")),
             (booking.passenger.clone(), env.ledger().timestamp(), booking_id, booking.airline.clone(), booking.flight_number.clone(), booking.price),
@@ -146,83 +1305,310 @@ impl BookingContract {
         booking_id
     }
     
+    // Book a listed flight, taking one of its seats from the airline contract. The whole
+    // booking fails if the flight is full. The seat is given back when the booking is
+    // refunded, cancelled or expires.
+    pub fn book_flight(env: Env, passenger: Address, flight_id: u64, token: Address) -> u64 {
+        Self::book_flight_seats(env, passenger, flight_id, 1, token)
+    }
+
+    // Group booking of `seat_count` seats on one flight, paid and escrowed as one booking.
+    // Each seat is charged the fare `effective_price_for` quotes before the booking.
+    pub fn book_flight_seats(
+        env: Env,
+        passenger: Address,
+        flight_id: u64,
+        seat_count: u32,
+        token: Address,
+    ) -> u64 {
+        EmergencyStop::require_not_stopped(&env);
+        assert!(seat_count > 0, "Invalid seat count");
+
+        let registry = BookingStorage::get_airline_registry(&env)
+            .expect("Airline registry not configured");
+        let client = AirlineRegistryClient::new(&env, &registry);
+        // Priced before the seats are taken, as the fare was quoted
+        let price = Self::flight_fare(&client, flight_id);
+        let flight = client.book_seats(&env.current_contract_address(), &flight_id, &seat_count);
+
+        let booking_id = Self::open_booking(
+            &env,
+            Booking {
+                booking_id: 0,
+                passenger,
+                airline: flight.airline,
+                flight_number: flight.flight_number,
+                from_airport: flight.from_airport,
+                to_airport: flight.to_airport,
+                departure_time: flight.departure_time,
+                price,
+                token,
+                amount_escrowed: 0,
+                status: BookingStatus::Pending,
+                created_at: 0,
+                points_discount: 0,
+                expires_at: 0,
+                seat_count,
+            },
+        );
+        BookingStorage::set_booked_flight(&env, booking_id, flight_id);
+        booking_id
+    }
+
+    fn release_booked_seats(env: &Env, booking_id: u64) {
+        if let Some(booking) = BookingStorage::get(env, booking_id) {
+            Self::release_seats(env, booking_id, booking.seat_count);
+        }
+        BookingStorage::remove_booked_flight(env, booking_id);
+    }
+
+    fn release_seats(env: &Env, booking_id: u64, seats: u32) {
+        if let Some(flight_id) = BookingStorage::get_booked_flight(env, booking_id) {
+            let registry = BookingStorage::get_airline_registry(env)
+                .expect("Airline registry not configured");
+            AirlineRegistryClient::new(env, &registry)
+                .release_seats(&env.current_contract_address(), &flight_id, &seats);
+        }
+    }
+
+    // Fare for every seat on the booking
+    fn total_fare(booking: &Booking) -> i128 {
+        booking.price * booking.seat_count as i128
+    }
+
+    // Create a booking and escrow its payment in one call; the booking is returned confirmed
+    pub fn create_and_pay(
+        env: Env,
+        passenger: Address,
+        airline: Address,
+        flight_number: Symbol,
+        from_airport: Symbol,
+        to_airport: Symbol,
+        departure_time: u64,
+        price: i128,
+        token: Address,
+    ) -> u64 {
+        BookingStorage::enter_guard(&env);
+
+        let booking_id = Self::create_booking(
+            env.clone(),
+            passenger,
+            airline,
+            flight_number,
+            from_airport,
+            to_airport,
+            departure_time,
+            price,
+            token,
+        );
+        Self::pay_for_booking(env.clone(), booking_id);
+
+        BookingStorage::exit_guard(&env);
+        booking_id
+    }
+
     // Accept payment for the booking and hold in escrow
     pub fn pay_for_booking(env: Env, booking_id: u64) {
+        Self::pay(env, booking_id, 0);
+    }
+
+    // Pay for the booking after redeeming `points` with the loyalty contract. The discount
+    // (100 points = 1 token unit) comes off the fare, so less is transferred and escrowed.
+    pub fn pay_for_booking_with_points(env: Env, booking_id: u64, points: i128) {
+        assert!(points > 0, "Invalid points amount");
+        Self::pay(env, booking_id, points);
+    }
+
+    // Pay whatever is left of the fare, net of any points discount, and confirm the booking
+    fn pay(env: Env, booking_id: u64, points: i128) {
+        EmergencyStop::require_not_stopped(&env);
+
         let mut booking = BookingStorage::get(&env, booking_id)
             .expect("Booking not found");
         
-        assert!(booking.status == symbol_short!("pending"), "Already paid or cancelled");
+        assert!(booking.status == BookingStatus::Pending, "Already paid or cancelled");
         
         booking.passenger.require_auth();
-        
-        let token_client = token::Client::new(&env, &booking.token);
-        
-        // Transfer tokens from passenger to this contract
-        token_client.transfer(
-            &booking.passenger,
-            &env.current_contract_address(),
-            &booking.price,
+
+        let outstanding = Self::total_fare(&booking) - booking.amount_escrowed;
+        if points > 0 {
+            let loyalty_contract = BookingStorage::get_loyalty_contract(&env)
+                .expect("Loyalty contract not configured");
+            let discount = LoyaltyClient::new(&env, &loyalty_contract)
+                .redeem_points(&booking.passenger, &points);
+            assert!(discount <= outstanding, "Discount exceeds price");
+            booking.points_discount = discount;
+        }
+        let amount = outstanding - booking.points_discount;
+
+        Self::collect_payment(&env, &mut booking, amount);
+        Self::confirm_booking(&env, &mut booking);
+
+        env.events().publish(
+            (symbol_short!("booking"), symbol_short!("paid")),
+            (booking.passenger.clone(), env.ledger().timestamp(), booking_id, amount),
         );
+    }
+
+    // Pay an installment towards the fare. The booking stays Pending with the
+    // installments in escrow until they cover the price, then it is confirmed.
+    // Cancelling before then refunds whatever has been escrowed so far.
+    pub fn pay_partial(env: Env, booking_id: u64, amount: i128) {
+        EmergencyStop::require_not_stopped(&env);
+
+        let mut booking = BookingStorage::get(&env, booking_id)
+            .expect("Booking not found");
+
+        assert!(booking.status == BookingStatus::Pending, "Already paid or cancelled");
+        booking.passenger.require_auth();
+        assert!(amount > 0, "Invalid amount");
+        assert!(
+            amount <= Self::total_fare(&booking) - booking.amount_escrowed,
+            "Exceeds remaining balance"
+        );
+
+        Self::collect_payment(&env, &mut booking, amount);
+        if booking.amount_escrowed >= Self::total_fare(&booking) {
+            Self::confirm_booking(&env, &mut booking);
+        } else {
+            BookingStorage::set(&env, booking_id, &booking);
+        }
+
+        env.events().publish(
+            (symbol_short!("booking"), symbol_short!("part_paid")),
+            (booking.passenger.clone(), env.ledger().timestamp(), booking_id, amount, booking.amount_escrowed),
+        );
+    }
+
+    // Move `amount` from the passenger into escrow and charge the protocol fee on it
+    fn collect_payment(env: &Env, booking: &mut Booking, amount: i128) {
+        let token_client = token::Client::new(env, &booking.token);
         
-        booking.amount_escrowed = booking.price;
-        booking.status = symbol_short!("confirmed");
+        // Transfer tokens from passenger to this contract
+        if amount > 0 {
+            token_client.transfer(
+                &booking.passenger,
+                &env.current_contract_address(),
+                &amount,
+            );
+        }
+
+        let fee = Self::booking_fee(env, &booking.passenger, amount);
+        if fee > 0 {
+            let config = BookingStorage::get_fee_config(env).expect("Fee not configured");
+            token_client.transfer(&booking.passenger, &config.treasury, &fee);
+            BookingStorage::add_fee(env, symbol_short!("booking"), &booking.token, fee);
+            BookingStorage::set_booking_fee(
+                env,
+                booking.booking_id,
+                BookingStorage::get_booking_fee(env, booking.booking_id) + fee,
+            );
+            env.events().publish(
+                (symbol_short!("booking"), symbol_short!("fee")),
+                (booking.passenger.clone(), env.ledger().timestamp(), booking.booking_id, fee),
+            );
+        }
         
-        BookingStorage::set(&env, booking_id, &booking);
+        BookingStorage::set_escrow_total(
+            env,
+            &booking.token,
+            BookingStorage::get_escrow_total(env, &booking.token) + amount,
+        );
+        booking.amount_escrowed += amount;
+    }
+
+    fn confirm_booking(env: &Env, booking: &mut Booking) {
+        booking.status = BookingStatus::Confirmed;
+        BookingStorage::set(env, booking.booking_id, booking);
 
-        if let Some(receipt_contract) = BookingStorage::get_receipt_contract(&env) {
-            let client = BookingReceiptClient::new(&env, &receipt_contract);
+        if let Some(receipt_contract) = BookingStorage::get_receipt_contract(env) {
+            let client = BookingReceiptClient::new(env, &receipt_contract);
             client.mint_receipt(
                 &booking.passenger,
-                &booking_id,
+                &booking.booking_id,
                 &booking.flight_number,
                 &booking.from_airport,
                 &booking.to_airport,
-                &String::from_str(&env, "TBD"), // Seat is assigned later or TBD initially
-                &booking.price,
+                &String::from_str(env, "TBD"), // Seat is assigned later or TBD initially
+                &Self::total_fare(booking),
             );
         }
+    }
+    
+    // Cancel a booking that is still unpaid past its expiry, refunding any installments
+    // already escrowed. Callable by anyone so abandoned bookings can be swept.
+    pub fn expire_booking(env: Env, booking_id: u64) -> bool {
+        let mut booking = BookingStorage::get(&env, booking_id)
+            .expect("Booking not found");
+
+        assert!(booking.status == BookingStatus::Pending, "Booking not pending");
+        assert!(
+            env.ledger().timestamp() >= booking.expires_at,
+            "Booking not expired"
+        );
+
+        // Cancel before refunding, and put the booking back if the transfer fails
+        let original = booking.clone();
+        let refunded_amount = booking.amount_escrowed;
+        booking.amount_escrowed = 0;
+        booking.status = BookingStatus::Cancelled;
+        BookingStorage::set(&env, booking_id, &booking);
+
+        BookingStorage::enter_guard(&env);
+        let contract = env.current_contract_address();
+        if !Self::refund_escrow(&env, &contract, &original, refunded_amount) {
+            BookingStorage::set(&env, booking_id, &original);
+            BookingStorage::exit_guard(&env);
+            return false;
+        }
+        Self::release_booked_seats(&env, booking_id);
+        BookingStorage::exit_guard(&env);
 
         env.events().publish(
-            (symbol_short!("booking"), symbol_short!("paid")),
-            (booking.passenger.clone(), env.ledger().timestamp(), booking_id, booking.price),
+            (symbol_short!("booking"), symbol_short!("expired")),
+            (booking.passenger.clone(), env.ledger().timestamp(), booking_id, refunded_amount),
         );
+        true
     }
-    
+
     // Release payment to airline - post-flight settlement
     pub fn release_payment_to_airline(env: Env, booking_id: u64) {
+        EmergencyStop::require_not_stopped(&env);
+
         let mut booking = BookingStorage::get(&env, booking_id)
             .expect("Booking not found");
         
         booking.airline.require_auth();
         
         assert!(
-            booking.status == symbol_short!("confirmed"),
+            booking.status == BookingStatus::Confirmed,
             "Invalid booking status"
         );
         assert!(booking.amount_escrowed > 0, "No funds in escrow");
         
-        let token_client = token::Client::new(&env, &booking.token);
-        
-        token_client.transfer(
-            &env.current_contract_address(),
-            &booking.airline,
-            &booking.amount_escrowed,
-        );
-        
         let released_amount = booking.amount_escrowed;
         booking.amount_escrowed = 0;
-        booking.status = symbol_short!("completed");
-        
+        booking.status = BookingStatus::Completed;
         BookingStorage::set(&env, booking_id, &booking);
 
+        // The token and observers are external calls; hold the lock until the booking is settled
+        BookingStorage::enter_guard(&env);
+        Self::draw_escrow(&env, &booking.token, released_amount);
+        Self::pay_airline(&env, &booking, released_amount);
+        Self::record_settlement(&env, &booking, released_amount);
+        Self::notify_settled(&env, &booking, released_amount);
+        BookingStorage::exit_guard(&env);
+
         env.events().publish(
             (symbol_short!("booking"), symbol_short!("released")),
             (booking.airline.clone(), env.ledger().timestamp(), booking_id, released_amount),
         );
     }
     
-    // Refund passenger for cancelled bookings
-    pub fn refund_passenger(env: Env, booking_id: u64) {
+    // Refund passenger for cancelled bookings, per the airline's refund policy when a
+    // refund contract is configured. Returns false, leaving the booking unchanged, if the
+    // token transfer fails.
+    pub fn refund_passenger(env: Env, booking_id: u64) -> bool {
         let mut booking = BookingStorage::get(&env, booking_id)
             .expect("Booking not found");
         
@@ -231,55 +1617,495 @@ impl BookingContract {
         // For simplicity, require passenger auth and check window
         // In a real app, airline could also trigger this
         booking.passenger.require_auth();
+        assert!(
+            !BookingStorage::is_no_show(&env, booking_id),
+            "No-show booking cannot be refunded"
+        );
         assert!(
             current_time < booking.departure_time - 86400,
             "Cancellation window closed"
         );
         
         assert!(
-            booking.status == symbol_short!("confirmed") || booking.status == symbol_short!("pending"),
+            booking.status == BookingStatus::Confirmed || booking.status == BookingStatus::Pending,
             "Booking cannot be refunded"
         );
         
-        if booking.amount_escrowed > 0 {
-            let token_client = token::Client::new(&env, &booking.token);
-            token_client.transfer(
+        // Late cancellations only get the airline policy's share back; the remainder is
+        // the airline's cancellation fee
+        let refunded_amount = Self::policy_refund(&env, &booking);
+        let cancellation_fee = booking.amount_escrowed - refunded_amount;
+
+        // Mark the booking refunded before paying out, and put it back if the transfer fails
+        let original = booking.clone();
+        booking.amount_escrowed = 0;
+        booking.status = BookingStatus::Refunded;
+        BookingStorage::set(&env, booking_id, &booking);
+
+        BookingStorage::enter_guard(&env);
+        if !Self::refund_escrow(&env, &booking.passenger, &original, refunded_amount) {
+            BookingStorage::set(&env, booking_id, &original);
+            BookingStorage::exit_guard(&env);
+            return false;
+        }
+        if cancellation_fee > 0 {
+            Self::draw_escrow(&env, &booking.token, cancellation_fee);
+            Self::pay_airline(&env, &booking, cancellation_fee);
+            env.events().publish(
+                (symbol_short!("booking"), symbol_short!("cxl_fee")),
+                (booking.airline.clone(), env.ledger().timestamp(), booking_id, cancellation_fee),
+            );
+        }
+        Self::release_booked_seats(&env, booking_id);
+        BookingStorage::exit_guard(&env);
+
+        env.events().publish(
+            (symbol_short!("booking"), symbol_short!("refunded")),
+            (booking.passenger.clone(), env.ledger().timestamp(), booking_id, refunded_amount),
+        );
+        true
+    }
+    
+    // Pay out a refund request approved in the refund contract from the booking's escrow.
+    // The request must be the booking passenger's; the refund is capped at the escrow and
+    // whatever it doesn't cover goes to the airline. Returns the amount refunded.
+    pub fn refund_approved_request(
+        env: Env,
+        refund_contract: Address,
+        booking_id: u64,
+        passenger: Address,
+        amount: i128,
+    ) -> i128 {
+        refund_contract.require_auth();
+        let trusted = BookingStorage::get_refund_contract(&env).expect("Refund contract not set");
+        assert!(refund_contract == trusted, "Unauthorized refund contract");
+
+        let mut booking = BookingStorage::get(&env, booking_id)
+            .expect("Booking not found");
+        assert!(booking.passenger == passenger, "Not the booking passenger");
+        Self::assert_cancellable(&env, &booking);
+        assert!(booking.amount_escrowed > 0, "No funds in escrow");
+        assert!(amount > 0, "Invalid refund amount");
+
+        let escrowed = booking.amount_escrowed;
+        let refunded_amount = amount.min(escrowed);
+        booking.amount_escrowed = 0;
+        booking.status = BookingStatus::Refunded;
+        BookingStorage::set(&env, booking_id, &booking);
+
+        BookingStorage::enter_guard(&env);
+        Self::draw_escrow(&env, &booking.token, escrowed);
+        token::Client::new(&env, &booking.token).transfer(
+            &env.current_contract_address(),
+            &booking.passenger,
+            &refunded_amount,
+        );
+        if escrowed > refunded_amount {
+            Self::pay_airline(&env, &booking, escrowed - refunded_amount);
+        }
+        Self::release_booked_seats(&env, booking_id);
+        BookingStorage::exit_guard(&env);
+
+        env.events().publish(
+            (symbol_short!("booking"), symbol_short!("refunded")),
+            (refund_contract, env.ledger().timestamp(), booking_id, refunded_amount),
+        );
+        refunded_amount
+    }
+
+    // Drop `seats` seats from a confirmed group booking, refunding their share of the
+    // escrow per the airline's refund policy and giving the seats back to the flight. Use
+    // `refund_passenger` for all of them.
+    pub fn refund_seats(env: Env, booking_id: u64, seats: u32) -> i128 {
+        let mut booking = BookingStorage::get(&env, booking_id)
+            .expect("Booking not found");
+
+        booking.passenger.require_auth();
+        assert!(
+            !BookingStorage::is_no_show(&env, booking_id),
+            "No-show booking cannot be refunded"
+        );
+        assert!(
+            env.ledger().timestamp() < booking.departure_time - 86400,
+            "Cancellation window closed"
+        );
+        assert!(
+            booking.status == BookingStatus::Confirmed,
+            "Booking cannot be refunded"
+        );
+        assert!(seats > 0 && seats < booking.seat_count, "Invalid seat count");
+
+        // The seats' share of the escrow; the policy's cut of it goes to the airline
+        let share = booking.amount_escrowed * seats as i128 / booking.seat_count as i128;
+        let refund = Self::policy_refund(&env, &booking) * seats as i128 / booking.seat_count as i128;
+        booking.amount_escrowed -= share;
+        booking.seat_count -= seats;
+        BookingStorage::set(&env, booking_id, &booking);
+
+        BookingStorage::enter_guard(&env);
+        Self::release_seats(&env, booking_id, seats);
+        Self::draw_escrow(&env, &booking.token, share);
+        if refund > 0 {
+            token::Client::new(&env, &booking.token).transfer(
                 &env.current_contract_address(),
                 &booking.passenger,
-                &booking.amount_escrowed,
+                &refund,
             );
         }
-        
-        let refunded_amount = booking.amount_escrowed;
+        let cancellation_fee = share - refund;
+        if cancellation_fee > 0 {
+            Self::pay_airline(&env, &booking, cancellation_fee);
+            env.events().publish(
+                (symbol_short!("booking"), symbol_short!("cxl_fee")),
+                (booking.airline.clone(), env.ledger().timestamp(), booking_id, cancellation_fee),
+            );
+        }
+        BookingStorage::exit_guard(&env);
+
+        env.events().publish(
+            (symbol_short!("booking"), symbol_short!("rf_seats")),
+            (booking.passenger.clone(), env.ledger().timestamp(), booking_id, seats, refund),
+        );
+        refund
+    }
+
+    // Refund a cancelled booking partly in tokens and partly in loyalty points.
+    // `token_fraction_bps` of the refund the airline's policy allows goes back to the
+    // passenger (10000 = all tokens); the rest is credited as points at the configured
+    // rate. The escrow not returned in tokens is settled to the airline.
+    pub fn refund_as_points(env: Env, booking_id: u64, token_fraction_bps: u32) -> (i128, i128) {
+        assert!(token_fraction_bps <= 10_000, "Invalid token fraction");
+
+        let mut booking = BookingStorage::get(&env, booking_id)
+            .expect("Booking not found");
+
+        booking.passenger.require_auth();
+        assert!(
+            !BookingStorage::is_no_show(&env, booking_id),
+            "No-show booking cannot be refunded"
+        );
+        assert!(
+            env.ledger().timestamp() < booking.departure_time - 86400,
+            "Cancellation window closed"
+        );
+        assert!(
+            booking.status == BookingStatus::Confirmed,
+            "Booking cannot be refunded"
+        );
+
+        let loyalty_contract =
+            BookingStorage::get_loyalty_contract(&env).expect("Loyalty contract not configured");
+        let rate = BookingStorage::get_points_refund_rate(&env).expect("Points rate not configured");
+
+        let escrowed = booking.amount_escrowed;
+        let refundable = Self::policy_refund(&env, &booking);
+        let token_refund = refundable * (token_fraction_bps as i128) / 10_000;
+        let points_value = refundable - token_refund;
+
         booking.amount_escrowed = 0;
-        booking.status = symbol_short!("refunded");
-        
+        booking.status = BookingStatus::Refunded;
         BookingStorage::set(&env, booking_id, &booking);
 
+        BookingStorage::enter_guard(&env);
+        Self::draw_escrow(&env, &booking.token, escrowed);
+        if token_refund > 0 {
+            token::Client::new(&env, &booking.token).transfer(
+                &env.current_contract_address(),
+                &booking.passenger,
+                &token_refund,
+            );
+        }
+        // Points value plus any cancellation fee the policy keeps back
+        if escrowed > token_refund {
+            Self::pay_airline(&env, &booking, escrowed - token_refund);
+        }
+
+        let mut points = 0i128;
+        if points_value > 0 {
+            points = points_value * rate as i128;
+            LoyaltyClient::new(&env, &loyalty_contract).accrue_points(
+                &env.current_contract_address(),
+                &booking.passenger,
+                &booking.flight_number,
+                &points,
+            );
+        }
+        Self::release_booked_seats(&env, booking_id);
+        BookingStorage::exit_guard(&env);
+
         env.events().publish(
-            (symbol_short!("booking"), symbol_short!("refunded")),
-            (booking.passenger.clone(), env.ledger().timestamp(), booking_id, refunded_amount),
+            (symbol_short!("booking"), symbol_short!("rf_points")),
+            (booking.passenger.clone(), env.ledger().timestamp(), booking_id, token_refund, points),
         );
+        (token_refund, points)
     }
-    
+
+    // Lock a confirmed booking's escrow behind a dispute between its passenger and airline.
+    // While disputed the booking can't be released or refunded; `settle_from_dispute`
+    // pays the escrow to whichever party the verdict favours.
+    pub fn hold_for_dispute(env: Env, caller: Address, booking_id: u64, dispute_id: u64) {
+        caller.require_auth();
+
+        let mut booking = BookingStorage::get(&env, booking_id).expect("Booking not found");
+        assert!(
+            caller == booking.passenger || caller == booking.airline,
+            "Not a booking party"
+        );
+        assert!(
+            booking.status == BookingStatus::Confirmed,
+            "Invalid booking status"
+        );
+        assert!(
+            BookingStorage::get_disputed_booking(&env, dispute_id).is_none(),
+            "Dispute already linked"
+        );
+
+        let dispute_contract =
+            BookingStorage::get_dispute_contract(&env).expect("Dispute contract not configured");
+        let dispute_client = DisputeClient::new(&env, &dispute_contract);
+        assert!(
+            dispute_client.get_dispute_booking(&dispute_id) == Some(booking_id),
+            "Dispute not about this booking"
+        );
+        let (passenger, airline) = dispute_client.get_dispute_parties(&dispute_id);
+        assert!(
+            passenger == booking.passenger && airline == booking.airline,
+            "Dispute parties mismatch"
+        );
+
+        booking.status = BookingStatus::Disputed;
+        BookingStorage::set(&env, booking_id, &booking);
+        BookingStorage::set_disputed_booking(&env, dispute_id, booking_id);
+
+        env.events().publish(
+            (symbol_short!("booking"), symbol_short!("disputed")),
+            (caller, env.ledger().timestamp(), booking_id, dispute_id),
+        );
+    }
+
+    // Settle a disputed booking once the dispute's verdict is final: a passenger verdict or
+    // a tied jury refunds the escrow, while an airline verdict or an accepted settlement
+    // (which already compensated the passenger) releases it. A dispute that expired without
+    // a verdict hands the booking back, escrow intact. Anyone may call.
+    pub fn settle_from_dispute(env: Env, dispute_id: u64) -> Symbol {
+        let booking_id =
+            BookingStorage::get_disputed_booking(&env, dispute_id).expect("No disputed booking");
+        let mut booking = BookingStorage::get(&env, booking_id).expect("Booking not found");
+
+        let dispute_contract =
+            BookingStorage::get_dispute_contract(&env).expect("Dispute contract not configured");
+        let verdict = DisputeClient::new(&env, &dispute_contract)
+            .get_final_verdict(&dispute_id)
+            .expect("Verdict not final");
+
+        if verdict == symbol_short!("expired") {
+            booking.status = BookingStatus::Confirmed;
+            BookingStorage::set(&env, booking_id, &booking);
+            BookingStorage::remove_disputed_booking(&env, dispute_id);
+            env.events().publish(
+                (symbol_short!("booking"), symbol_short!("disp_set")),
+                (env.ledger().timestamp(), booking_id, dispute_id, verdict.clone(), 0i128),
+            );
+            return verdict;
+        }
+
+        let amount = booking.amount_escrowed;
+        let refund = verdict == symbol_short!("passenger") || verdict == symbol_short!("tie");
+        booking.status = if refund {
+            BookingStatus::Refunded
+        } else {
+            BookingStatus::Completed
+        };
+        booking.amount_escrowed = 0;
+        BookingStorage::set(&env, booking_id, &booking);
+        BookingStorage::remove_disputed_booking(&env, dispute_id);
+
+        BookingStorage::enter_guard(&env);
+        Self::draw_escrow(&env, &booking.token, amount);
+        if refund {
+            if amount > 0 {
+                token::Client::new(&env, &booking.token).transfer(
+                    &env.current_contract_address(),
+                    &booking.passenger,
+                    &amount,
+                );
+            }
+        } else {
+            if amount > 0 {
+                Self::pay_airline(&env, &booking, amount);
+            }
+            Self::record_settlement(&env, &booking, amount);
+            Self::notify_settled(&env, &booking, amount);
+        }
+        BookingStorage::exit_guard(&env);
+
+        env.events().publish(
+            (symbol_short!("booking"), symbol_short!("disp_set")),
+            (env.ledger().timestamp(), booking_id, dispute_id, verdict.clone(), amount),
+        );
+        verdict
+    }
+
+    // Airline marks a passenger as no-show after departure. The booking can no longer be
+    // refunded, and points awarded for it are reversed when a loyalty contract is configured.
+    pub fn mark_no_show(env: Env, airline: Address, booking_id: u64) {
+        let booking = BookingStorage::get(&env, booking_id).expect("Booking not found");
+
+        airline.require_auth();
+        assert!(booking.airline == airline, "Not the booking airline");
+        assert!(
+            booking.status == BookingStatus::Confirmed
+                || booking.status == BookingStatus::Completed,
+            "Invalid booking status"
+        );
+        assert!(
+            env.ledger().timestamp() >= booking.departure_time,
+            "Flight has not departed"
+        );
+        assert!(
+            !BookingStorage::is_no_show(&env, booking_id),
+            "Already marked no-show"
+        );
+
+        BookingStorage::set_no_show(&env, booking_id);
+
+        let mut reversed_points = 0i128;
+        if let Some(loyalty_contract) = BookingStorage::get_loyalty_contract(&env) {
+            let client = LoyaltyClient::new(&env, &loyalty_contract);
+            reversed_points = client.reverse_points(
+                &env.current_contract_address(),
+                &booking.passenger,
+                &booking_id,
+            );
+        }
+
+        env.events().publish(
+            (symbol_short!("booking"), symbol_short!("no_show")),
+            (airline, env.ledger().timestamp(), booking_id, reversed_points),
+        );
+    }
+
+    pub fn is_no_show(env: Env, booking_id: u64) -> bool {
+        BookingStorage::is_no_show(&env, booking_id)
+    }
+
+    pub fn set_role(env: Env, caller: Address, target: Address, role: u32, enabled: bool) {
+        let role_enum = match role {
+            1 => Role::Admin,
+            2 => Role::Operator,
+            _ => panic!("Invalid role"),
+        };
+        AccessControl::set_role(&env, &caller, &target, role_enum, enabled);
+    }
+
+    pub fn get_owner(env: Env) -> Address {
+        AccessControl::get_owner(&env)
+    }
+
+    // Total the passenger paid in tokens for their completed bookings; fare covered by
+    // redeemed points is left out. Loyalty can reconcile its lifetime_spent against this.
+    pub fn passenger_total_spend(env: Env, passenger: Address) -> i128 {
+        let mut total = 0i128;
+        for booking_id in BookingStorage::get_passenger_bookings(&env, &passenger).iter() {
+            if let Some(booking) = BookingStorage::get(&env, booking_id) {
+                if booking.status == BookingStatus::Completed {
+                    total += Self::total_fare(&booking) - booking.points_discount;
+                }
+            }
+        }
+        total
+    }
+
     // Helper to get booking details
     pub fn get_booking(env: Env, booking_id: u64) -> Option<Booking> {
         BookingStorage::get(&env, booking_id)
     }
+
+    // Several bookings in one call, None for unknown ids, in the order requested
+    pub fn get_bookings(env: Env, ids: Vec<u64>) -> Vec<Option<Booking>> {
+        assert!(ids.len() <= MAX_BATCH_SIZE, "Batch too large");
+
+        let mut bookings = Vec::new(&env);
+        for booking_id in ids.iter() {
+            bookings.push_back(BookingStorage::get(&env, booking_id));
+        }
+        bookings
+    }
+
+    // The passenger's bookings in creation order, `limit` (at most MAX_BATCH_SIZE) from `start`
+    pub fn get_bookings_for_passenger(env: Env, passenger: Address, start: u32, limit: u32) -> Vec<Booking> {
+        let ids = BookingStorage::get_passenger_bookings(&env, &passenger);
+        Self::page_bookings(&env, &ids, start, limit)
+    }
+
+    pub fn get_bookings_for_airline(env: Env, airline: Address, start: u32, limit: u32) -> Vec<Booking> {
+        let ids = BookingStorage::get_airline_bookings(&env, &airline);
+        Self::page_bookings(&env, &ids, start, limit)
+    }
+
+    fn page_bookings(env: &Env, ids: &Vec<u64>, start: u32, limit: u32) -> Vec<Booking> {
+        assert!(limit <= MAX_BATCH_SIZE, "Batch too large");
+
+        let mut bookings = Vec::new(env);
+        let end = start.saturating_add(limit).min(ids.len());
+        for i in start..end {
+            if let Some(booking) = BookingStorage::get(env, ids.get_unchecked(i)) {
+                bookings.push_back(booking);
+            }
+        }
+        bookings
+    }
+
+    // Token the booking was priced and escrowed in
+    pub fn get_booking_token(env: Env, booking_id: u64) -> Option<Address> {
+        BookingStorage::get(&env, booking_id).map(|b| b.token)
+    }
+
+    pub fn get_booking_airline(env: Env, booking_id: u64) -> Option<Address> {
+        BookingStorage::get(&env, booking_id).map(|b| b.airline)
+    }
+
+    pub fn get_booking_passenger(env: Env, booking_id: u64) -> Option<Address> {
+        BookingStorage::get(&env, booking_id).map(|b| b.passenger)
+    }
     
-    // Original API wrappers for backward compatibility
-    pub fn cancel_booking(env: Env, passenger: Address, booking_id: u64) {
+    // Passenger cancellation. With a refund contract configured the airline's fee schedule
+    // decides how much of the escrow comes back and the rest is paid to the airline;
+    // otherwise the booking is refunded in full. Returns the amount refunded.
+    pub fn cancel_booking(env: Env, passenger: Address, booking_id: u64) -> i128 {
         passenger.require_auth();
-        Self::refund_passenger(env, booking_id);
+
+        if BookingStorage::get_refund_contract(&env).is_none() {
+            let escrowed = BookingStorage::get(&env, booking_id)
+                .expect("Booking not found")
+                .amount_escrowed;
+            return if Self::refund_passenger(env, booking_id) { escrowed } else { 0 };
+        }
+
+        let mut booking = BookingStorage::get(&env, booking_id).expect("Booking not found");
+        assert!(booking.passenger == passenger, "Not authorized to cancel");
+        Self::assert_cancellable(&env, &booking);
+        assert!(
+            env.ledger().timestamp() < booking.departure_time,
+            "Flight already departed"
+        );
+
+        let refund = Self::policy_refund(&env, &booking);
+        // Refunded, as when no refund contract is configured
+        let (passenger_refund, _) =
+            Self::cancel_with_refund(&env, &mut booking, refund, BookingStatus::Refunded);
+        passenger_refund
     }
     
+    // Original API wrapper for backward compatibility
     pub fn complete_booking(env: Env, airline: Address, booking_id: u64) {
         airline.require_auth();
         Self::release_payment_to_airline(env, booking_id);
     }
 
     // Settle cancellation payouts from escrow according to refund basis points.
-    // `passenger_refund_bps` is in basis points (10000 = 100%).
+    // `passenger_refund_bps` is in basis points (10000 = 100%); the passenger's share is
+    // capped at what the airline's refund policy allows.
     pub fn settle_cancellation(
         env: Env,
         booking_id: u64,
@@ -290,33 +2116,55 @@ impl BookingContract {
 
         let mut booking = BookingStorage::get(&env, booking_id).expect("Booking not found");
 
-        assert!(
-            booking.status != symbol_short!("cancelled")
-                && booking.status != symbol_short!("refunded")
-                && booking.status != symbol_short!("completed"),
-            "Booking cannot be cancelled"
-        );
-
+        Self::assert_cancellable(&env, &booking);
         caller.require_auth();
         assert!(
             caller == booking.passenger || caller == booking.airline,
             "Not authorized to cancel"
         );
 
+        let passenger_refund = (booking.amount_escrowed * (passenger_refund_bps as i128) / 10_000)
+            .min(Self::policy_refund(&env, &booking));
+        Self::cancel_with_refund(&env, &mut booking, passenger_refund, BookingStatus::Cancelled)
+    }
+
+    fn assert_cancellable(env: &Env, booking: &Booking) {
+        assert!(
+            !BookingStorage::is_no_show(env, booking.booking_id),
+            "No-show booking cannot be refunded"
+        );
         assert!(
-            booking.status == symbol_short!("confirmed") || booking.status == symbol_short!("pending"),
+            booking.status != BookingStatus::Cancelled
+                && booking.status != BookingStatus::Refunded
+                && booking.status != BookingStatus::Completed,
+            "Booking cannot be cancelled"
+        );
+        assert!(
+            booking.status == BookingStatus::Confirmed || booking.status == BookingStatus::Pending,
             "Invalid booking status"
         );
+    }
 
+    // Cancel the booking, refunding `passenger_refund` of the escrow and paying the rest
+    // to the airline, and leave it in the terminal `status`
+    fn cancel_with_refund(
+        env: &Env,
+        booking: &mut Booking,
+        passenger_refund: i128,
+        status: BookingStatus,
+    ) -> (i128, i128) {
         let escrowed = booking.amount_escrowed;
-        let mut passenger_refund = 0i128;
-        let mut airline_amount = 0i128;
+        let airline_amount = if escrowed > 0 { escrowed - passenger_refund } else { 0 };
 
-        if escrowed > 0 {
-            passenger_refund = escrowed * (passenger_refund_bps as i128) / 10_000;
-            airline_amount = escrowed - passenger_refund;
+        let booking_id = booking.booking_id;
+        booking.amount_escrowed = 0;
+        booking.status = status;
+        BookingStorage::set(env, booking_id, booking);
 
-            let token_client = token::Client::new(&env, &booking.token);
+        BookingStorage::enter_guard(env);
+        if escrowed > 0 {
+            Self::draw_escrow(env, &booking.token, escrowed);
+            let token_client = token::Client::new(env, &booking.token);
 
             if passenger_refund > 0 {
                 token_client.transfer(
@@ -327,17 +2175,11 @@ impl BookingContract {
             }
 
             if airline_amount > 0 {
-                token_client.transfer(
-                    &env.current_contract_address(),
-                    &booking.airline,
-                    &airline_amount,
-                );
+                Self::pay_airline(env, booking, airline_amount);
             }
         }
-
-        booking.amount_escrowed = 0;
-        booking.status = symbol_short!("cancelled");
-        BookingStorage::set(&env, booking_id, &booking);
+        Self::release_booked_seats(env, booking_id);
+        BookingStorage::exit_guard(env);
 
         env.events().publish(
             (symbol_short!("booking"), symbol_short!("cancelled")),
@@ -362,6 +2204,7 @@ impl BookingContract {
         let mut failures = Vec::new(&env);
         let mut total_released: i128 = 0;
 
+        BookingStorage::enter_guard(&env);
         let mut i: u32 = 0;
         while i < booking_ids.len() {
             let booking_id = booking_ids.get(i).unwrap();
@@ -388,7 +2231,7 @@ impl BookingContract {
                 continue;
             }
 
-            if booking.status != symbol_short!("confirmed") {
+            if booking.status != BookingStatus::Confirmed {
                 failures.push_back(BatchFailure {
                     index: i,
                     booking_id,
@@ -408,20 +2251,18 @@ impl BookingContract {
                 continue;
             }
 
-            let token_client = token::Client::new(&env, &booking.token);
-            token_client.transfer(
-                &env.current_contract_address(),
-                &booking.airline,
-                &booking.amount_escrowed,
-            );
-
             let released_amount = booking.amount_escrowed;
             total_released += released_amount;
             booking.amount_escrowed = 0;
-            booking.status = symbol_short!("completed");
+            booking.status = BookingStatus::Completed;
             BookingStorage::set(&env, booking_id, &booking);
             completed_booking_ids.push_back(booking_id);
 
+            Self::draw_escrow(&env, &booking.token, released_amount);
+            Self::pay_airline(&env, &booking, released_amount);
+            Self::record_settlement(&env, &booking, released_amount);
+            Self::notify_settled(&env, &booking, released_amount);
+
             env.events().publish(
                 (symbol_short!("booking"), symbol_short!("released")),
                 (booking.airline.clone(), env.ledger().timestamp(), booking_id, released_amount),
@@ -429,6 +2270,7 @@ impl BookingContract {
 
             i += 1;
         }
+        BookingStorage::exit_guard(&env);
 
         BatchCompleteBookingsResult {
             completed_booking_ids,
@@ -447,31 +2289,33 @@ impl BookingContract {
             .expect("Booking not found");
 
         assert!(
-            booking.status == symbol_short!("confirmed"),
+            booking.status == BookingStatus::Confirmed,
             "Invalid booking status"
         );
         assert!(booking.amount_escrowed > 0, "No funds in escrow");
 
-        let token_client = token::Client::new(&env, &booking.token);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &booking.airline,
-            &booking.amount_escrowed,
-        );
-
         let released_amount = booking.amount_escrowed;
         booking.amount_escrowed = 0;
-        booking.status = symbol_short!("completed");
+        booking.status = BookingStatus::Completed;
         BookingStorage::set(&env, booking_id, &booking);
 
+        // The token and observers are external calls; hold the lock until the booking is settled
+        BookingStorage::enter_guard(&env);
+        Self::draw_escrow(&env, &booking.token, released_amount);
+        Self::pay_airline(&env, &booking, released_amount);
+        Self::record_settlement(&env, &booking, released_amount);
+        Self::notify_settled(&env, &booking, released_amount);
+        BookingStorage::exit_guard(&env);
+
         env.events().publish(
             (symbol_short!("booking"), symbol_short!("released")),
             (oracle, env.ledger().timestamp(), booking_id, released_amount),
         );
     }
 
-    // Oracle-triggered refund: called by the oracle contract after airline cancellation consensus
-    pub fn oracle_refund_airline_cancel(env: Env, oracle: Address, booking_id: u64) {
+    // Oracle-triggered refund: called by the oracle contract after airline cancellation consensus.
+    // Returns false, leaving the booking unchanged, if the token transfer fails.
+    pub fn oracle_refund_airline_cancel(env: Env, oracle: Address, booking_id: u64) -> bool {
         oracle.require_auth();
         let trusted = BookingStorage::get_trusted_oracle(&env).expect("Oracle not configured");
         assert!(oracle == trusted, "Unauthorized oracle");
@@ -480,27 +2324,34 @@ impl BookingContract {
             .expect("Booking not found");
 
         assert!(
-            booking.status == symbol_short!("confirmed") || booking.status == symbol_short!("pending"),
+            !BookingStorage::is_no_show(&env, booking_id),
+            "No-show booking cannot be refunded"
+        );
+        assert!(
+            booking.status == BookingStatus::Confirmed || booking.status == BookingStatus::Pending,
             "Booking cannot be refunded"
         );
 
-        if booking.amount_escrowed > 0 {
-            let token_client = token::Client::new(&env, &booking.token);
-            token_client.transfer(
-                &env.current_contract_address(),
-                &booking.passenger,
-                &booking.amount_escrowed,
-            );
-        }
-
+        // Mark the booking refunded before paying out, and put it back if the transfer fails
+        let original = booking.clone();
         let refunded_amount = booking.amount_escrowed;
         booking.amount_escrowed = 0;
-        booking.status = symbol_short!("refunded");
+        booking.status = BookingStatus::Refunded;
         BookingStorage::set(&env, booking_id, &booking);
 
+        BookingStorage::enter_guard(&env);
+        if !Self::refund_escrow(&env, &oracle, &original, refunded_amount) {
+            BookingStorage::set(&env, booking_id, &original);
+            BookingStorage::exit_guard(&env);
+            return false;
+        }
+        Self::release_booked_seats(&env, booking_id);
+        BookingStorage::exit_guard(&env);
+
         env.events().publish(
             (symbol_short!("booking"), symbol_short!("refunded")),
             (oracle, env.ledger().timestamp(), booking_id, refunded_amount),
         );
+        true
     }
 }
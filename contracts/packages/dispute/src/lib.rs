@@ -1,8 +1,25 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, Address, Bytes, BytesN, Env, Symbol,
+    contract, contractclient, contractimpl, contracttype, symbol_short, token, Address, Bytes,
+    BytesN, Env, Symbol, Vec,
 };
-use access::{AccessControl, Role};
+use access::{AccessControl, EmergencyStop, Role};
+use storage_version::{Migratable, VersionedStorage, DISPUTE_CONTRACT};
+
+// Governance parameter registry; `min_stake` is the protocol-wide stake floor in TRQ.
+#[contractclient(name = "ParamRegistryClient")]
+pub trait ParamRegistryInterface {
+    fn get_protocol_param(env: Env, key: Symbol) -> Option<i128>;
+}
+
+const MAX_PAGE_SIZE: u32 = 50;
+const MAX_JUROR_CANDIDATES: u32 = 100;
+// How long a settlement offer stays open for the passenger to accept
+const SETTLEMENT_OFFER_SECS: u64 = 3 * 86_400;
+// How long past its appeal deadline an undecided dispute sits before anyone can expire it
+const STUCK_DISPUTE_GRACE_SECS: u64 = 7 * 86_400;
+// Storage v2 records the stake token and appeal rounds on disputes and the config
+const STAKE_TOKEN_STORAGE_VERSION: u32 = 2;
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -36,9 +53,12 @@ pub struct Dispute {
     pub votes_for_passenger: u32,
     pub votes_for_airline: u32,
     pub verdict: Option<Symbol>,
-    pub appealed: bool,
+    pub appeal_round: u32, // appeals filed so far
     pub created_at: u64,
     pub finalized_at: Option<u64>,
+    pub no_evidence: bool, // jury selection was forced open without the required evidence
+    pub appeal_period: u64, // snapshotted at filing; longer for high-value disputes
+    pub token: Address,     // stake token at filing; later token rotations don't apply
 }
 
 #[contracttype]
@@ -60,6 +80,15 @@ pub struct JurorSelection {
     pub selected_at: u64,
 }
 
+// Address that has volunteered for a dispute's random jury draw, with the TRQ it locked
+// as draw weight
+#[contracttype]
+#[derive(Clone)]
+pub struct JurorCandidate {
+    pub juror: Address,
+    pub token_balance: i128,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct VoteCommit {
@@ -79,6 +108,65 @@ pub struct VoteReveal {
     pub revealed_at: u64,
 }
 
+// Read-only summary of a jury, for judging how representative a verdict is
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct JuryMetrics {
+    pub jury_size: u32,           // jurors selected so far
+    pub revealed_count: u32,
+    pub total_token_balance: i128, // TRQ balances recorded at selection
+    pub winning_margin: u32,      // difference between the two revealed vote counts
+}
+
+// Cumulative amount of one fee category paid to the treasury in one token
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeTotal {
+    pub category: Symbol,
+    pub token: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeReport {
+    pub totals: Vec<FeeTotal>,
+}
+
+// Partial settlement offered by the airline, escrowed in the contract until accepted
+// or withdrawn after expiry
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SettlementOffer {
+    pub amount: i128,
+    pub expires_at: u64,
+}
+
+// Flat fee a passenger pays the treasury when filing a dispute
+#[contracttype]
+#[derive(Clone)]
+pub struct FilingFee {
+    pub amount: i128,
+    pub treasury: Address,
+}
+
+// Where deposits forfeited by jurors who never revealed end up
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ForfeitDestination {
+    Jurors, // shared by the majority jurors with the reward pool
+    Winner, // added to the winning party's payout
+    Treasury(Address),
+}
+
+// How the jury reward pool is split among the majority jurors
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RewardDistribution {
+    Equal,         // one share per majority vote
+    StakeWeighted, // in proportion to each juror's locked stake
+}
+
 #[contracttype]
 pub struct DisputeConfig {
     pub min_stake_percentage: u32,
@@ -89,6 +177,112 @@ pub struct DisputeConfig {
     pub appeal_period: u64,
     pub appeal_stake_multiplier: u32,
     pub jury_reward_pool_percentage: u32,
+    pub token: Address, // TRQ token stakes are paid in
+    pub min_reveal_quorum: u32, // share of jury_size that must reveal, basis points (0 = any vote)
+    pub max_appeal_rounds: u32,
+    pub appeal_jury_factor: u32, // jury_size is multiplied by this on each appeal
+    pub execution_buffer: u64,   // seconds after appeal_deadline before a verdict can execute
+    pub min_evidence: u32, // evidence items one party must submit before jury selection (0 = none)
+    pub max_open_per_airline: u32, // unresolved disputes allowed against one airline (0 = no cap)
+    pub high_value_amount: i128, // disputes over at least this amount get the longer appeal period (0 = off)
+    pub high_value_appeal_period: u64,
+}
+
+// Dispute layout before storage v2, when an appeal was a single flag and disputes didn't
+// snapshot their stake token or appeal period
+#[contracttype]
+#[derive(Clone)]
+pub struct LegacyDispute {
+    pub dispute_id: u64,
+    pub refund_request_id: u64,
+    pub passenger: Address,
+    pub airline: Address,
+    pub amount: i128,
+    pub passenger_stake: i128,
+    pub airline_stake: i128,
+    pub phase: DisputePhase,
+    pub evidence_deadline: u64,
+    pub voting_deadline: u64,
+    pub reveal_deadline: u64,
+    pub appeal_deadline: u64,
+    pub passenger_evidence_count: u32,
+    pub airline_evidence_count: u32,
+    pub jury_size: u32,
+    pub votes_for_passenger: u32,
+    pub votes_for_airline: u32,
+    pub verdict: Option<Symbol>,
+    pub appealed: bool,
+    pub created_at: u64,
+    pub finalized_at: Option<u64>,
+}
+
+impl LegacyDispute {
+    pub fn upgrade(self, config: &DisputeConfig) -> Dispute {
+        Dispute {
+            dispute_id: self.dispute_id,
+            refund_request_id: self.refund_request_id,
+            passenger: self.passenger,
+            airline: self.airline,
+            amount: self.amount,
+            passenger_stake: self.passenger_stake,
+            airline_stake: self.airline_stake,
+            phase: self.phase,
+            evidence_deadline: self.evidence_deadline,
+            voting_deadline: self.voting_deadline,
+            reveal_deadline: self.reveal_deadline,
+            appeal_deadline: self.appeal_deadline,
+            passenger_evidence_count: self.passenger_evidence_count,
+            airline_evidence_count: self.airline_evidence_count,
+            jury_size: self.jury_size,
+            votes_for_passenger: self.votes_for_passenger,
+            votes_for_airline: self.votes_for_airline,
+            verdict: self.verdict,
+            appeal_round: self.appealed as u32,
+            created_at: self.created_at,
+            finalized_at: self.finalized_at,
+            no_evidence: false,
+            appeal_period: config.appeal_period,
+            token: config.token.clone(),
+        }
+    }
+}
+
+// Config layout before storage v2
+#[contracttype]
+pub struct LegacyDisputeConfig {
+    pub min_stake_percentage: u32,
+    pub jury_size: u32,
+    pub evidence_period: u64,
+    pub voting_period: u64,
+    pub reveal_period: u64,
+    pub appeal_period: u64,
+    pub appeal_stake_multiplier: u32,
+    pub jury_reward_pool_percentage: u32,
+}
+
+impl LegacyDisputeConfig {
+    // New settings take the same defaults as `initialize`
+    pub fn upgrade(self, token: Address) -> DisputeConfig {
+        DisputeConfig {
+            min_stake_percentage: self.min_stake_percentage,
+            jury_size: self.jury_size,
+            evidence_period: self.evidence_period,
+            voting_period: self.voting_period,
+            reveal_period: self.reveal_period,
+            appeal_period: self.appeal_period,
+            appeal_stake_multiplier: self.appeal_stake_multiplier,
+            jury_reward_pool_percentage: self.jury_reward_pool_percentage,
+            token,
+            min_reveal_quorum: 0,
+            max_appeal_rounds: 1,
+            appeal_jury_factor: 2,
+            execution_buffer: 0,
+            min_evidence: 0,
+            max_open_per_airline: 0,
+            high_value_amount: 0,
+            high_value_appeal_period: self.appeal_period,
+        }
+    }
 }
 
 pub struct DisputeStorageKey;
@@ -179,155 +373,588 @@ impl DisputeStorageKey {
             .set(&(symbol_short!("v_reveal"), dispute_id, juror), reveal);
     }
 
+    pub fn clear_vote(env: &Env, dispute_id: u64, juror: &Address) {
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("v_commit"), dispute_id, juror));
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("v_reveal"), dispute_id, juror));
+    }
+
     pub fn get_config(env: &Env) -> Option<DisputeConfig> {
         env.storage().instance().get(&symbol_short!("config"))
     }
 
+    pub fn get_legacy_config(env: &Env) -> Option<LegacyDisputeConfig> {
+        env.storage().instance().get(&symbol_short!("config"))
+    }
+
+    pub fn get_legacy_dispute(env: &Env, dispute_id: u64) -> Option<LegacyDispute> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("dispute"), dispute_id))
+    }
+
     pub fn set_config(env: &Env, config: &DisputeConfig) {
         env.storage()
             .instance()
             .set(&symbol_short!("config"), config);
     }
 
-    pub fn get_stake(env: &Env, dispute_id: u64, party: &Address) -> i128 {
+    pub fn is_config_migrated(env: &Env) -> bool {
+        env.storage().instance().has(&symbol_short!("cfg_migr"))
+    }
+
+    pub fn set_config_migrated(env: &Env) {
+        env.storage().instance().set(&symbol_short!("cfg_migr"), &true);
+    }
+
+    pub fn is_dispute_migrated(env: &Env, dispute_id: u64) -> bool {
         env.storage()
             .persistent()
-            .get(&(symbol_short!("stake"), dispute_id, party))
-            .unwrap_or(0)
+            .has(&(symbol_short!("d_migr"), dispute_id))
     }
 
-    pub fn set_stake(env: &Env, dispute_id: u64, party: &Address, amount: i128) {
+    pub fn set_dispute_migrated(env: &Env, dispute_id: u64) {
         env.storage()
             .persistent()
-            .set(&(symbol_short!("stake"), dispute_id, party), &amount);
+            .set(&(symbol_short!("d_migr"), dispute_id), &true);
     }
-}
 
-#[contract]
-pub struct DisputeContract;
+    // Stake token and dispute ids handed from `migrate_legacy_disputes` to its step
+    pub fn get_migration_input(env: &Env) -> Option<(Address, Vec<u64>)> {
+        env.storage().instance().get(&symbol_short!("mig_in"))
+    }
 
-#[contractimpl]
-impl DisputeContract {
-    pub fn initialize(
-        env: Env,
-        owner: Address,
-        min_stake_percentage: u32,
-        jury_size: u32,
-        evidence_period: u64,
-        voting_period: u64,
-        reveal_period: u64,
-        appeal_period: u64,
-        appeal_stake_multiplier: u32,
-        jury_reward_pool_percentage: u32,
-    ) {
-        assert!(
-            DisputeStorageKey::get_config(&env).is_none(),
-            "Already initialized"
-        );
+    pub fn set_migration_input(env: &Env, token: &Address, dispute_ids: &Vec<u64>) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("mig_in"), &(token.clone(), dispute_ids.clone()));
+    }
 
-        AccessControl::init_owner(&env, &owner);
-        crate::upgrade_timelock::UpgradeTimelock::init_upgrade_owner(&env, &owner);
+    pub fn clear_migration_input(env: &Env) {
+        env.storage().instance().remove(&symbol_short!("mig_in"));
+    }
 
-        let config = DisputeConfig {
-            min_stake_percentage,
-            jury_size,
-            evidence_period,
-            voting_period,
-            reveal_period,
-            appeal_period,
-            appeal_stake_multiplier,
-            jury_reward_pool_percentage,
-        };
+    pub fn get_stake_match_ratio(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("match_bps"))
+            .unwrap_or(0)
+    }
 
-        DisputeStorageKey::set_config(&env, &config);
+    pub fn set_stake_match_ratio(env: &Env, ratio_bps: u32) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("match_bps"), &ratio_bps);
+    }
 
-        env.events()
-            .publish((symbol_short!("dispute"), symbol_short!("init")), jury_size);
+    pub fn has_claimed_reward(env: &Env, dispute_id: u64, juror: &Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&(symbol_short!("claimed"), dispute_id, juror))
     }
 
-    pub fn file_dispute(
-        env: Env,
-        passenger: Address,
-        airline: Address,
-        refund_request_id: u64,
-        amount: i128,
-        passenger_stake: i128,
-    ) -> u64 {
-        passenger.require_auth();
+    pub fn set_claimed_reward(env: &Env, dispute_id: u64, juror: &Address) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("claimed"), dispute_id, juror), &true);
+    }
 
-        let config = DisputeStorageKey::get_config(&env).expect("Contract not initialized");
+    pub fn get_param_registry(env: &Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("registry"))
+    }
 
-        let min_stake = amount * config.min_stake_percentage as i128 / 10000;
-        assert!(passenger_stake >= min_stake, "Insufficient stake");
+    pub fn set_param_registry(env: &Env, registry: &Address) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("registry"), registry);
+    }
 
-        let dispute_count = DisputeStorageKey::get_dispute_count(&env);
-        let dispute_id = dispute_count + 1;
-        DisputeStorageKey::set_dispute_count(&env, dispute_id);
+    pub fn get_candidates(env: &Env, dispute_id: u64) -> Vec<JurorCandidate> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("cands"), dispute_id))
+            .unwrap_or(Vec::new(env))
+    }
 
-        let current_time = env.ledger().timestamp();
+    pub fn set_candidates(env: &Env, dispute_id: u64, candidates: &Vec<JurorCandidate>) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("cands"), dispute_id), candidates);
+    }
 
-        let dispute = Dispute {
-            dispute_id,
-            refund_request_id,
-            passenger: passenger.clone(),
-            airline: airline.clone(),
-            amount,
-            passenger_stake,
-            airline_stake: 0,
-            phase: DisputePhase::Evidence,
-            evidence_deadline: current_time + config.evidence_period,
-            voting_deadline: current_time + config.evidence_period + config.voting_period,
-            reveal_deadline: current_time
-                + config.evidence_period
-                + config.voting_period
-                + config.reveal_period,
-            appeal_deadline: current_time
-                + config.evidence_period
-                + config.voting_period
-                + config.reveal_period
-                + config.appeal_period,
-            passenger_evidence_count: 0,
-            airline_evidence_count: 0,
-            jury_size: config.jury_size,
-            votes_for_passenger: 0,
-            votes_for_airline: 0,
-            verdict: None,
-            appealed: false,
-            created_at: current_time,
-            finalized_at: None,
-        };
+    pub fn is_manual_jury(env: &Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("man_jury"))
+            .unwrap_or(false)
+    }
 
-        DisputeStorageKey::set_dispute(&env, dispute_id, &dispute);
-        DisputeStorageKey::set_stake(&env, dispute_id, &passenger, passenger_stake);
+    pub fn set_manual_jury(env: &Env, enabled: bool) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("man_jury"), &enabled);
+    }
 
-        env.events().publish(
-            (symbol_short!("dispute"), symbol_short!("filed")),
-            (dispute_id, passenger, airline, amount),
-        );
+    pub fn get_juror_deposit_amount(env: &Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("juror_dep"))
+            .unwrap_or(0)
+    }
 
-        dispute_id
+    pub fn set_juror_deposit_amount(env: &Env, amount: i128) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("juror_dep"), &amount);
     }
 
-    pub fn airline_respond(env: Env, airline: Address, dispute_id: u64, airline_stake: i128) {
-        airline.require_auth();
+    // TRQ a self-selected juror locks for the dispute's duration; 0 disables it
+    pub fn get_juror_stake_amount(env: &Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("jur_stake"))
+            .unwrap_or(0)
+    }
 
-        let mut dispute =
-            DisputeStorageKey::get_dispute(&env, dispute_id).expect("Dispute not found");
+    pub fn set_juror_stake_amount(env: &Env, amount: i128) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("jur_stake"), &amount);
+    }
 
-        assert!(dispute.airline == airline, "Not the airline in dispute");
-        assert!(
-            dispute.phase == DisputePhase::Evidence,
-            "Evidence phase ended"
-        );
-        assert!(dispute.airline_stake == 0, "Already responded");
+    pub fn get_juror_stake(env: &Env, dispute_id: u64, juror: &Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("j_stk"), dispute_id, juror))
+            .unwrap_or(0)
+    }
 
-        let config = DisputeStorageKey::get_config(&env).expect("Not initialized");
-        let min_stake = dispute.amount * config.min_stake_percentage as i128 / 10000;
-        assert!(airline_stake >= min_stake, "Insufficient stake");
+    pub fn set_juror_stake(env: &Env, dispute_id: u64, juror: &Address, amount: i128) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("j_stk"), dispute_id, juror), &amount);
+    }
+
+    // Booking a dispute was filed over, for disputes filed with `file_booking_dispute`
+    pub fn get_dispute_booking(env: &Env, dispute_id: u64) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("d_book"), dispute_id))
+    }
+
+    pub fn set_dispute_booking(env: &Env, dispute_id: u64, booking_id: u64) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("d_book"), dispute_id), &booking_id);
+    }
+
+    // TRQ a jury candidate locked as draw weight, held until the draw
+    pub fn get_juror_lock(env: &Env, dispute_id: u64, juror: &Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("j_lock"), dispute_id, juror))
+            .unwrap_or(0)
+    }
+
+    pub fn set_juror_lock(env: &Env, dispute_id: u64, juror: &Address, amount: i128) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("j_lock"), dispute_id, juror), &amount);
+    }
+
+    pub fn get_reward_distribution(env: &Env) -> RewardDistribution {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("rwd_dist"))
+            .unwrap_or(RewardDistribution::Equal)
+    }
+
+    pub fn set_reward_distribution(env: &Env, distribution: &RewardDistribution) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("rwd_dist"), distribution);
+    }
+
+    pub fn get_forfeit_destination(env: &Env) -> ForfeitDestination {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("forf_dst"))
+            .unwrap_or(ForfeitDestination::Jurors)
+    }
+
+    pub fn set_forfeit_destination(env: &Env, destination: &ForfeitDestination) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("forf_dst"), destination);
+    }
+
+    // Set once execute_verdict has paid a dispute's forfeits to the winner or treasury
+    pub fn forfeits_paid_out(env: &Env, dispute_id: u64) -> bool {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("forf_out"), dispute_id))
+            .unwrap_or(false)
+    }
+
+    pub fn set_forfeits_paid_out(env: &Env, dispute_id: u64) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("forf_out"), dispute_id), &true);
+    }
+
+    // Deposit a juror (or jury candidate) posted for a dispute and has not yet reclaimed
+    pub fn get_juror_deposit(env: &Env, dispute_id: u64, juror: &Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("j_dep"), dispute_id, juror))
+            .unwrap_or(0)
+    }
+
+    pub fn set_juror_deposit(env: &Env, dispute_id: u64, juror: &Address, amount: i128) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("j_dep"), dispute_id, juror), &amount);
+    }
+
+    pub fn get_filing_fee(env: &Env) -> Option<FilingFee> {
+        env.storage().instance().get(&symbol_short!("fil_fee"))
+    }
+
+    pub fn set_filing_fee(env: &Env, fee: &FilingFee) {
+        env.storage().instance().set(&symbol_short!("fil_fee"), fee);
+    }
+
+    pub fn get_fee_totals(env: &Env) -> Vec<FeeTotal> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("fee_tot"))
+            .unwrap_or(Vec::new(env))
+    }
+
+    // Add `amount` to the running total for (category, token)
+    pub fn add_fee(env: &Env, category: Symbol, token: &Address, amount: i128) {
+        let mut totals = Self::get_fee_totals(env);
+        let mut found = false;
+        for i in 0..totals.len() {
+            let mut total = totals.get(i).unwrap();
+            if total.category == category && total.token == *token {
+                total.amount += amount;
+                totals.set(i, total);
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            totals.push_back(FeeTotal {
+                category,
+                token: token.clone(),
+                amount,
+            });
+        }
+        env.storage().instance().set(&symbol_short!("fee_tot"), &totals);
+    }
+
+    pub fn get_stake(env: &Env, dispute_id: u64, party: &Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("stake"), dispute_id, party))
+            .unwrap_or(0)
+    }
+
+    pub fn set_stake(env: &Env, dispute_id: u64, party: &Address, amount: i128) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("stake"), dispute_id, party), &amount);
+    }
+
+    pub fn get_settlement(env: &Env, dispute_id: u64) -> Option<SettlementOffer> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("settle"), dispute_id))
+    }
+
+    pub fn set_settlement(env: &Env, dispute_id: u64, offer: &SettlementOffer) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("settle"), dispute_id), offer);
+    }
+
+    pub fn remove_settlement(env: &Env, dispute_id: u64) {
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("settle"), dispute_id));
+    }
+
+    // Disputes filed against the airline that haven't been finalized yet
+    pub fn get_open_disputes(env: &Env, airline: &Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("al_open"), airline))
+            .unwrap_or(0)
+    }
+
+    pub fn set_open_disputes(env: &Env, airline: &Address, count: u32) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("al_open"), airline), &count);
+    }
+}
+
+// Storage steps run through `VersionedStorage::migrate_storage_with`. The v2 step rewrites
+// the config and the disputes stashed by `migrate_legacy_disputes`, skipping any record it
+// already rewrote so that running it again after a rollback changes nothing.
+struct DisputeMigration;
+
+impl Migratable for DisputeMigration {
+    // v2 records the stake token and appeal rounds
+    fn migrate_from_v1_to_v2(env: &Env) -> bool {
+        let (token, dispute_ids) = match DisputeStorageKey::get_migration_input(env) {
+            Some(input) => input,
+            None => return false,
+        };
+
+        if !DisputeStorageKey::is_config_migrated(env) {
+            let config = DisputeStorageKey::get_legacy_config(env)
+                .expect("Not initialized")
+                .upgrade(token);
+            DisputeStorageKey::set_config(env, &config);
+            DisputeStorageKey::set_config_migrated(env);
+        }
+        let config = DisputeStorageKey::get_config(env).unwrap();
+
+        for dispute_id in dispute_ids.iter() {
+            if DisputeStorageKey::is_dispute_migrated(env, dispute_id) {
+                continue;
+            }
+            let dispute =
+                DisputeStorageKey::get_legacy_dispute(env, dispute_id).expect("Dispute not found");
+            DisputeStorageKey::set_dispute(env, dispute_id, &dispute.upgrade(&config));
+            DisputeStorageKey::set_dispute_migrated(env, dispute_id);
+        }
+        true
+    }
+
+    // No layout change past v2
+    fn migrate_from_v2_to_v3(_env: &Env) -> bool {
+        true
+    }
+
+    fn get_migration_steps(env: &Env, from_version: u32, to_version: u32) -> Vec<Symbol> {
+        let mut steps = Vec::new(env);
+        if from_version < STAKE_TOKEN_STORAGE_VERSION && to_version >= STAKE_TOKEN_STORAGE_VERSION {
+            steps.push_back(symbol_short!("stk_token"));
+        }
+        steps
+    }
+}
+
+#[contract]
+pub struct DisputeContract;
+
+#[contractimpl]
+impl DisputeContract {
+    pub fn initialize(
+        env: Env,
+        owner: Address,
+        min_stake_percentage: u32,
+        jury_size: u32,
+        evidence_period: u64,
+        voting_period: u64,
+        reveal_period: u64,
+        appeal_period: u64,
+        appeal_stake_multiplier: u32,
+        jury_reward_pool_percentage: u32,
+        token: Address,
+    ) {
+        assert!(
+            DisputeStorageKey::get_config(&env).is_none(),
+            "Already initialized"
+        );
+
+        AccessControl::init_owner(&env, &owner);
+        crate::upgrade_timelock::UpgradeTimelock::init_upgrade_owner(&env, &owner);
+
+        let config = DisputeConfig {
+            min_stake_percentage,
+            jury_size,
+            evidence_period,
+            voting_period,
+            reveal_period,
+            appeal_period,
+            appeal_stake_multiplier,
+            jury_reward_pool_percentage,
+            token,
+            min_reveal_quorum: 0,
+            max_appeal_rounds: 1,
+            appeal_jury_factor: 2,
+            execution_buffer: 0,
+            min_evidence: 0,
+            max_open_per_airline: 0,
+            high_value_amount: 0,
+            high_value_appeal_period: appeal_period,
+        };
+
+        DisputeStorageKey::set_config(&env, &config);
+        // New deployments start on the current layout
+        VersionedStorage::set_storage_version(&env, &DISPUTE_CONTRACT, STAKE_TOKEN_STORAGE_VERSION);
+
+        env.events()
+            .publish((symbol_short!("dispute"), symbol_short!("init")), jury_size);
+    }
+
+    // Rewrite the config and disputes stored before v2 into the current layout, with
+    // `token` as the stake token. Pass every existing dispute id in a single call: the
+    // storage version is bumped afterwards and the migration cannot be run again.
+    pub fn migrate_legacy_disputes(
+        env: Env,
+        admin: Address,
+        token: Address,
+        dispute_ids: Vec<u64>,
+    ) -> u32 {
+        AccessControl::require_admin(&env, &admin);
+        assert!(
+            VersionedStorage::needs_migration(&env, &DISPUTE_CONTRACT, STAKE_TOKEN_STORAGE_VERSION),
+            "Already migrated"
+        );
+
+        DisputeStorageKey::set_migration_input(&env, &token, &dispute_ids);
+        let current = VersionedStorage::get_storage_version(&env, &DISPUTE_CONTRACT);
+        assert!(
+            VersionedStorage::migrate_storage_with::<DisputeMigration>(
+                &env,
+                &DISPUTE_CONTRACT,
+                current,
+                STAKE_TOKEN_STORAGE_VERSION,
+                &admin,
+            ),
+            "Migration failed"
+        );
+        DisputeStorageKey::clear_migration_input(&env);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("migrated")),
+            (admin, env.ledger().timestamp(), dispute_ids.len()),
+        );
+
+        dispute_ids.len()
+    }
+
+    pub fn file_dispute(
+        env: Env,
+        passenger: Address,
+        airline: Address,
+        refund_request_id: u64,
+        amount: i128,
+        passenger_stake: i128,
+    ) -> u64 {
+        EmergencyStop::require_not_stopped(&env);
+
+        passenger.require_auth();
+
+        let config = DisputeStorageKey::get_config(&env).expect("Contract not initialized");
+        assert!(amount > 0, "Invalid dispute amount");
+
+        let min_stake = Self::min_stake(&env, &config, amount);
+        assert!(passenger_stake >= min_stake, "Insufficient stake");
+
+        let open_disputes = DisputeStorageKey::get_open_disputes(&env, &airline);
+        assert!(
+            config.max_open_per_airline == 0 || open_disputes < config.max_open_per_airline,
+            "Too many open disputes against airline"
+        );
+        DisputeStorageKey::set_open_disputes(&env, &airline, open_disputes + 1);
+
+        let dispute_count = DisputeStorageKey::get_dispute_count(&env);
+        let dispute_id = dispute_count + 1;
+        DisputeStorageKey::set_dispute_count(&env, dispute_id);
+
+        let current_time = env.ledger().timestamp();
+        let appeal_period = Self::appeal_period_for(&config, amount);
+
+        let dispute = Dispute {
+            dispute_id,
+            refund_request_id,
+            passenger: passenger.clone(),
+            airline: airline.clone(),
+            amount,
+            passenger_stake,
+            airline_stake: 0,
+            phase: DisputePhase::Evidence,
+            evidence_deadline: current_time + config.evidence_period,
+            voting_deadline: current_time + config.evidence_period + config.voting_period,
+            reveal_deadline: current_time
+                + config.evidence_period
+                + config.voting_period
+                + config.reveal_period,
+            appeal_deadline: current_time
+                + config.evidence_period
+                + config.voting_period
+                + config.reveal_period
+                + appeal_period,
+            passenger_evidence_count: 0,
+            airline_evidence_count: 0,
+            jury_size: config.jury_size,
+            votes_for_passenger: 0,
+            votes_for_airline: 0,
+            verdict: None,
+            appeal_round: 0,
+            created_at: current_time,
+            finalized_at: None,
+            no_evidence: false,
+            appeal_period,
+            token: config.token.clone(),
+        };
+
+        DisputeStorageKey::set_dispute(&env, dispute_id, &dispute);
+        DisputeStorageKey::set_stake(&env, dispute_id, &passenger, passenger_stake);
+        Self::collect_stake(&env, &dispute.token, &passenger, passenger_stake);
+
+        if let Some(fee) = DisputeStorageKey::get_filing_fee(&env) {
+            if fee.amount > 0 {
+                token::Client::new(&env, &dispute.token).transfer(&passenger, &fee.treasury, &fee.amount);
+                DisputeStorageKey::add_fee(&env, symbol_short!("dispute"), &dispute.token, fee.amount);
+            }
+        }
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("filed")),
+            (dispute_id, passenger, airline, amount),
+        );
+
+        dispute_id
+    }
+
+    pub fn airline_respond(env: Env, airline: Address, dispute_id: u64, airline_stake: i128) {
+        EmergencyStop::require_not_stopped(&env);
+
+        airline.require_auth();
+
+        let mut dispute =
+            DisputeStorageKey::get_dispute(&env, dispute_id).expect("Dispute not found");
+
+        assert!(dispute.airline == airline, "Not the airline in dispute");
+        assert!(
+            dispute.phase == DisputePhase::Evidence,
+            "Evidence phase ended"
+        );
+        assert!(dispute.airline_stake == 0, "Already responded");
+
+        let config = DisputeStorageKey::get_config(&env).expect("Not initialized");
+        let min_stake = Self::min_stake(&env, &config, dispute.amount);
+        assert!(airline_stake >= min_stake, "Insufficient stake");
+
+        // Anti-griefing: the airline must match at least part of the passenger's commitment
+        let match_ratio = DisputeStorageKey::get_stake_match_ratio(&env);
+        let matched_stake = dispute.passenger_stake * match_ratio as i128 / 10000;
+        assert!(airline_stake >= matched_stake, "Airline stake below match ratio");
 
         dispute.airline_stake = airline_stake;
         DisputeStorageKey::set_dispute(&env, dispute_id, &dispute);
         DisputeStorageKey::set_stake(&env, dispute_id, &airline, airline_stake);
+        Self::collect_stake(&env, &dispute.token, &airline, airline_stake);
 
         env.events().publish(
             (symbol_short!("dispute"), symbol_short!("responded")),
@@ -346,10 +973,11 @@ impl DisputeContract {
 
         let mut dispute =
             DisputeStorageKey::get_dispute(&env, dispute_id).expect("Dispute not found");
+        let config = DisputeStorageKey::get_config(&env).expect("Not initialized");
 
         let current_time = env.ledger().timestamp();
         assert!(
-            current_time <= dispute.evidence_deadline,
+            Self::evidence_open(&env, &config, &dispute),
             "Evidence period ended"
         );
         assert!(
@@ -361,13 +989,13 @@ impl DisputeContract {
         let is_airline = submitter == dispute.airline;
         assert!(is_passenger || is_airline, "Not a party to dispute");
 
-        let evidence_index = if is_passenger {
+        // Both parties share one index so evidence is stored in submission order
+        let evidence_index = dispute.passenger_evidence_count + dispute.airline_evidence_count;
+        if is_passenger {
             dispute.passenger_evidence_count += 1;
-            dispute.passenger_evidence_count - 1
         } else {
             dispute.airline_evidence_count += 1;
-            dispute.airline_evidence_count - 1
-        };
+        }
 
         let evidence = Evidence {
             dispute_id,
@@ -381,56 +1009,283 @@ impl DisputeContract {
         DisputeStorageKey::set_dispute(&env, dispute_id, &dispute);
 
         env.events().publish(
-            (symbol_short!("evidence"), symbol_short!("submitted")),
-            (dispute_id, submitter, evidence.evidence_hash.clone()),
+            (symbol_short!("evidence"), symbol_short!("submitted")),
+            (dispute_id, submitter, evidence.evidence_hash.clone()),
+        );
+    }
+
+    // Self-service jury selection, first come first served. Disabled unless the
+    // admin turns on manual selection; disputes otherwise seat jurors via `draw_jury`.
+    pub fn select_as_juror(env: Env, juror: Address, dispute_id: u64, token_balance: i128) {
+        juror.require_auth();
+        assert!(DisputeStorageKey::is_manual_jury(&env), "Manual jury selection disabled");
+
+        let mut dispute =
+            DisputeStorageKey::get_dispute(&env, dispute_id).expect("Dispute not found");
+
+        if Self::close_evidence(&env, &mut dispute) {
+            DisputeStorageKey::set_dispute(&env, dispute_id, &dispute);
+        }
+
+        assert!(
+            dispute.phase == DisputePhase::JurySelection
+                || dispute.phase == DisputePhase::CommitVote,
+            "Not in jury selection phase"
+        );
+        assert!(token_balance > 0, "Must hold TRQ tokens");
+        assert!(
+            !DisputeStorageKey::is_juror(&env, dispute_id, &juror),
+            "Already selected"
+        );
+        assert!(
+            juror != dispute.passenger && juror != dispute.airline,
+            "Parties cannot be jurors"
+        );
+
+        let juror_count = Self::get_juror_count(env.clone(), dispute_id);
+        assert!(juror_count < dispute.jury_size, "Jury full");
+
+        Self::collect_juror_deposit(&env, &dispute, &juror);
+        Self::collect_juror_stake(&env, &dispute, &juror);
+        Self::seat_juror(&env, &mut dispute, juror_count, juror, token_balance);
+    }
+
+    // Volunteer for a dispute's jury draw while evidence is open or before the draw. The
+    // draw weight is `weight` TRQ, locked in the contract until the draw so the same tokens
+    // can't back several candidates. The juror deposit and stake are collected now too and
+    // refunded if the juror isn't drawn.
+    pub fn register_juror_candidate(env: Env, juror: Address, dispute_id: u64, weight: i128) {
+        juror.require_auth();
+
+        let dispute = DisputeStorageKey::get_dispute(&env, dispute_id).expect("Dispute not found");
+        let token_balance = token::Client::new(&env, &dispute.token).balance(&juror);
+        assert!(
+            dispute.phase == DisputePhase::Evidence
+                || dispute.phase == DisputePhase::JurySelection,
+            "Not in jury selection phase"
+        );
+        assert!(token_balance > 0, "Must hold TRQ tokens");
+        assert!(weight > 0 && weight <= token_balance, "Invalid weight");
+        assert!(
+            juror != dispute.passenger && juror != dispute.airline,
+            "Parties cannot be jurors"
+        );
+
+        assert!(
+            !DisputeStorageKey::is_juror(&env, dispute_id, &juror),
+            "Already selected"
+        );
+
+        let mut candidates = DisputeStorageKey::get_candidates(&env, dispute_id);
+        assert!(candidates.len() < MAX_JUROR_CANDIDATES, "Candidate pool full");
+        for c in candidates.iter() {
+            assert!(c.juror != juror, "Already a candidate");
+        }
+        Self::collect_juror_deposit(&env, &dispute, &juror);
+        Self::collect_juror_stake(&env, &dispute, &juror);
+        Self::collect_stake(&env, &dispute.token, &juror, weight);
+        DisputeStorageKey::set_juror_lock(&env, dispute_id, &juror, weight);
+        candidates.push_back(JurorCandidate {
+            juror: juror.clone(),
+            token_balance: weight,
+        });
+        DisputeStorageKey::set_candidates(&env, dispute_id, &candidates);
+
+        env.events().publish(
+            (symbol_short!("juror"), symbol_short!("candidate")),
+            (dispute_id, juror, weight),
         );
     }
 
-    pub fn select_as_juror(env: Env, juror: Address, dispute_id: u64, token_balance: i128) {
-        juror.require_auth();
-
+    // Fill the open jury seats from the candidate pool once evidence closes. Each seat is
+    // drawn with probability proportional to locked weight, without replacement, using the
+    // host PRNG as seeded for this invocation. Every candidate's weight lock is released;
+    // those left out also get their deposit and stake back, and the pool is cleared.
+    pub fn draw_jury(env: Env, dispute_id: u64) {
         let mut dispute =
             DisputeStorageKey::get_dispute(&env, dispute_id).expect("Dispute not found");
 
-        let current_time = env.ledger().timestamp();
-
-        if current_time > dispute.evidence_deadline && dispute.phase == DisputePhase::Evidence {
-            dispute.phase = DisputePhase::JurySelection;
-            DisputeStorageKey::set_dispute(&env, dispute_id, &dispute);
-        }
-
+        Self::close_evidence(&env, &mut dispute);
         assert!(
-            dispute.phase == DisputePhase::JurySelection
-                || dispute.phase == DisputePhase::CommitVote,
+            dispute.phase == DisputePhase::JurySelection,
             "Not in jury selection phase"
         );
-        assert!(token_balance > 0, "Must hold TRQ tokens");
+
+        let mut seated = Self::get_juror_count(env.clone(), dispute_id);
+        let pool = DisputeStorageKey::get_candidates(&env, dispute_id);
+        let mut candidates = pool.clone();
         assert!(
-            !DisputeStorageKey::is_juror(&env, dispute_id, &juror),
-            "Already selected"
+            candidates.len() >= dispute.jury_size - seated,
+            "Not enough candidates"
         );
-        assert!(
-            juror != dispute.passenger && juror != dispute.airline,
-            "Parties cannot be jurors"
+
+        while seated < dispute.jury_size {
+            // Locked weights are all held by this contract, so their sum fits in u128
+            let mut total = 0u128;
+            for c in candidates.iter() {
+                total += Self::draw_weight(&c);
+            }
+
+            let mut target = Self::draw_below(&env, total);
+            let mut chosen = 0u32;
+            for (i, c) in candidates.iter().enumerate() {
+                let weight = Self::draw_weight(&c);
+                if target < weight {
+                    chosen = i as u32;
+                    break;
+                }
+                target -= weight;
+            }
+
+            let candidate = candidates.get(chosen).unwrap();
+            candidates.remove(chosen);
+            Self::seat_juror(&env, &mut dispute, seated, candidate.juror, candidate.token_balance);
+            seated += 1;
+        }
+
+        DisputeStorageKey::set_candidates(&env, dispute_id, &Vec::new(&env));
+        for c in candidates.iter() {
+            Self::refund_candidate(&env, &dispute, &c.juror);
+        }
+        for c in pool.iter() {
+            Self::release_juror_lock(&env, &dispute, &c.juror);
+        }
+    }
+
+    fn release_juror_lock(env: &Env, dispute: &Dispute, juror: &Address) {
+        let lock = DisputeStorageKey::get_juror_lock(env, dispute.dispute_id, juror);
+        if lock > 0 {
+            DisputeStorageKey::set_juror_lock(env, dispute.dispute_id, juror, 0);
+            token::Client::new(env, &dispute.token).transfer(
+                &env.current_contract_address(),
+                juror,
+                &lock,
+            );
+        }
+    }
+
+    // Return the deposit and stake an undrawn candidate posted at registration
+    fn refund_candidate(env: &Env, dispute: &Dispute, juror: &Address) {
+        let dispute_id = dispute.dispute_id;
+        let refund = DisputeStorageKey::get_juror_deposit(env, dispute_id, juror)
+            + DisputeStorageKey::get_juror_stake(env, dispute_id, juror);
+        DisputeStorageKey::set_juror_deposit(env, dispute_id, juror, 0);
+        DisputeStorageKey::set_juror_stake(env, dispute_id, juror, 0);
+        if refund > 0 {
+            token::Client::new(env, &dispute.token).transfer(
+                &env.current_contract_address(),
+                juror,
+                &refund,
+            );
+        }
+
+        env.events().publish(
+            (symbol_short!("juror"), symbol_short!("not_drawn")),
+            (dispute_id, juror.clone(), refund),
         );
+    }
 
-        let juror_count = Self::get_juror_count(env.clone(), dispute_id);
-        assert!(juror_count < dispute.jury_size, "Jury full");
+    // Capped so the summed weights of a full candidate pool cannot overflow
+    fn draw_weight(candidate: &JurorCandidate) -> u128 {
+        candidate.token_balance as u128
+    }
+
+    // Uniform draw in [0, bound). The PRNG only samples u64, so two draws make a u128 and
+    // the uneven tail above the last multiple of `bound` is rejected.
+    fn draw_below(env: &Env, bound: u128) -> u128 {
+        let zone = u128::MAX - u128::MAX % bound;
+        loop {
+            let high = env.prng().gen::<u64>() as u128;
+            let low = env.prng().gen::<u64>() as u128;
+            let value = (high << 64) | low;
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+
+    // Enable the first-come-first-served `select_as_juror` path (tests and local setups)
+    pub fn set_manual_jury_selection(env: Env, admin: Address, enabled: bool) {
+        AccessControl::require_admin(&env, &admin);
+        DisputeStorageKey::set_manual_jury(&env, enabled);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("man_jury")),
+            (admin, enabled),
+        );
+    }
+
+    pub fn get_juror_candidates(env: Env, dispute_id: u64) -> Vec<JurorCandidate> {
+        DisputeStorageKey::get_candidates(&env, dispute_id)
+    }
+
+    // Evidence stays open until its deadline. With a `min_evidence` requirement unmet by
+    // both parties it stays open (and jury selection blocked) for one more evidence period.
+    fn evidence_open(env: &Env, config: &DisputeConfig, dispute: &Dispute) -> bool {
+        let now = env.ledger().timestamp();
+        if now <= dispute.evidence_deadline {
+            return true;
+        }
+        config.min_evidence > 0
+            && !Self::has_min_evidence(config, dispute)
+            && now <= dispute.evidence_deadline + config.evidence_period
+    }
+
+    fn has_min_evidence(config: &DisputeConfig, dispute: &Dispute) -> bool {
+        dispute.passenger_evidence_count >= config.min_evidence
+            || dispute.airline_evidence_count >= config.min_evidence
+    }
+
+    // Move a dispute out of evidence into jury selection once evidence has closed. Returns
+    // whether the dispute changed. With an evidence requirement, later deadlines are pushed
+    // back by however long evidence ran over, and a dispute forced on without the required
+    // evidence is flagged `no_evidence`.
+    fn close_evidence(env: &Env, dispute: &mut Dispute) -> bool {
+        let config = DisputeStorageKey::get_config(env).expect("Not initialized");
+        if dispute.phase != DisputePhase::Evidence || Self::evidence_open(env, &config, dispute) {
+            return false;
+        }
+
+        dispute.phase = DisputePhase::JurySelection;
+        if config.min_evidence > 0 {
+            let overrun = env.ledger().timestamp() - dispute.evidence_deadline;
+            dispute.voting_deadline += overrun;
+            dispute.reveal_deadline += overrun;
+            dispute.appeal_deadline += overrun;
+
+            if !Self::has_min_evidence(&config, dispute) {
+                dispute.no_evidence = true;
+                env.events().publish(
+                    (symbol_short!("dispute"), symbol_short!("no_evid")),
+                    (dispute.dispute_id, config.min_evidence),
+                );
+            }
+        }
+        true
+    }
 
+    fn seat_juror(
+        env: &Env,
+        dispute: &mut Dispute,
+        index: u32,
+        juror: Address,
+        token_balance: i128,
+    ) {
+        let dispute_id = dispute.dispute_id;
         let selection = JurorSelection {
             dispute_id,
             juror: juror.clone(),
             token_balance,
-            selected_at: current_time,
+            selected_at: env.ledger().timestamp(),
         };
 
-        DisputeStorageKey::set_juror(&env, dispute_id, juror_count, &selection);
-        DisputeStorageKey::mark_as_juror(&env, dispute_id, &juror);
+        DisputeStorageKey::set_juror(env, dispute_id, index, &selection);
+        DisputeStorageKey::mark_as_juror(env, dispute_id, &juror);
 
-        if juror_count + 1 >= dispute.jury_size {
+        if index + 1 >= dispute.jury_size {
             dispute.phase = DisputePhase::CommitVote;
-            DisputeStorageKey::set_dispute(&env, dispute_id, &dispute);
         }
+        DisputeStorageKey::set_dispute(env, dispute_id, dispute);
 
         env.events().publish(
             (symbol_short!("juror"), symbol_short!("selected")),
@@ -516,6 +1371,17 @@ impl DisputeContract {
             current_time <= dispute.reveal_deadline,
             "Reveal period ended"
         );
+
+        // Open the reveal phase without waiting for an advance_to_reveal call
+        if current_time > dispute.voting_deadline && dispute.phase == DisputePhase::CommitVote {
+            dispute.phase = DisputePhase::RevealVote;
+            DisputeStorageKey::set_dispute(&env, dispute_id, &dispute);
+            env.events().publish(
+                (symbol_short!("phase"), symbol_short!("reveal")),
+                dispute_id,
+            );
+        }
+
         assert!(
             dispute.phase == DisputePhase::RevealVote,
             "Not in reveal phase"
@@ -578,6 +1444,11 @@ impl DisputeContract {
 
         let total_votes = dispute.votes_for_passenger + dispute.votes_for_airline;
         assert!(total_votes > 0, "No votes revealed");
+        let config = DisputeStorageKey::get_config(&env).expect("Not initialized");
+        assert!(
+            total_votes >= Self::reveal_quorum(&config, dispute.jury_size),
+            "Reveal quorum not met"
+        );
 
         let verdict = if dispute.votes_for_passenger > dispute.votes_for_airline {
             symbol_short!("passenger")
@@ -591,64 +1462,444 @@ impl DisputeContract {
         dispute.phase = DisputePhase::Appeal;
         dispute.finalized_at = Some(current_time);
 
-        DisputeStorageKey::set_dispute(&env, dispute_id, &dispute);
+        DisputeStorageKey::set_dispute(&env, dispute_id, &dispute);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("finalized")),
+            (dispute_id, verdict),
+        );
+    }
+
+    // Jury reveals needed for a verdict, rounded up
+    fn reveal_quorum(config: &DisputeConfig, jury_size: u32) -> u32 {
+        (jury_size * config.min_reveal_quorum).div_ceil(10000)
+    }
+
+    pub fn set_min_reveal_quorum(env: Env, admin: Address, quorum_bps: u32) {
+        AccessControl::require_admin(&env, &admin);
+        assert!(quorum_bps <= 10000, "Invalid reveal quorum");
+
+        let mut config = DisputeStorageKey::get_config(&env).expect("Not initialized");
+        config.min_reveal_quorum = quorum_bps;
+        DisputeStorageKey::set_config(&env, &config);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("quorum")),
+            (admin, quorum_bps),
+        );
+    }
+
+    // Re-run commit/reveal with the same jury when too few jurors revealed to finalize
+    pub fn restart_vote(env: Env, executor: Address, dispute_id: u64) {
+        AccessControl::require_operator(&env, &executor);
+        let mut dispute =
+            DisputeStorageKey::get_dispute(&env, dispute_id).expect("Dispute not found");
+
+        let current_time = env.ledger().timestamp();
+        assert!(
+            current_time > dispute.reveal_deadline,
+            "Reveal period not ended"
+        );
+        assert!(
+            dispute.phase == DisputePhase::RevealVote,
+            "Not in reveal phase"
+        );
+
+        let config = DisputeStorageKey::get_config(&env).expect("Not initialized");
+        let total_votes = dispute.votes_for_passenger + dispute.votes_for_airline;
+        assert!(
+            total_votes == 0 || total_votes < Self::reveal_quorum(&config, dispute.jury_size),
+            "Reveal quorum met"
+        );
+
+        Self::clear_jury_votes(&env, dispute_id);
+
+        dispute.phase = DisputePhase::CommitVote;
+        dispute.voting_deadline = current_time + config.voting_period;
+        dispute.reveal_deadline = dispute.voting_deadline + config.reveal_period;
+        dispute.appeal_deadline = dispute.reveal_deadline + dispute.appeal_period;
+        dispute.votes_for_passenger = 0;
+        dispute.votes_for_airline = 0;
+
+        DisputeStorageKey::set_dispute(&env, dispute_id, &dispute);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("revote")),
+            dispute_id,
+        );
+    }
+
+    fn clear_jury_votes(env: &Env, dispute_id: u64) {
+        let mut i = 0u32;
+        while let Some(selection) = DisputeStorageKey::get_juror(env, dispute_id, i) {
+            DisputeStorageKey::clear_vote(env, dispute_id, &selection.juror);
+            i += 1;
+        }
+    }
+
+    pub fn file_appeal(env: Env, appellant: Address, dispute_id: u64, appeal_stake: i128) {
+        appellant.require_auth();
+
+        let mut dispute =
+            DisputeStorageKey::get_dispute(&env, dispute_id).expect("Dispute not found");
+
+        let current_time = env.ledger().timestamp();
+        assert!(
+            current_time <= dispute.appeal_deadline,
+            "Appeal period ended"
+        );
+        assert!(dispute.phase == DisputePhase::Appeal, "Not in appeal phase");
+
+        let config = DisputeStorageKey::get_config(&env).expect("Not initialized");
+        assert!(dispute.appeal_round < config.max_appeal_rounds, "Max appeal rounds reached");
+
+        let verdict = dispute.verdict.clone().expect("No verdict");
+        let is_losing_party = (verdict == symbol_short!("airline")
+            && appellant == dispute.passenger)
+            || (verdict == symbol_short!("passenger") && appellant == dispute.airline);
+
+        assert!(is_losing_party, "Only losing party can appeal");
+
+        let round = dispute.appeal_round + 1;
+        let required_stake =
+            Self::stake_floor(&env, Self::appeal_stake_required(&config, dispute.amount, round));
+        assert!(appeal_stake >= required_stake, "Insufficient appeal stake");
+
+        // The sitting jury stays seated and votes again alongside the new seats
+        Self::clear_jury_votes(&env, dispute_id);
+        dispute.appeal_round = round;
+        dispute.jury_size *= config.appeal_jury_factor;
+        dispute.phase = DisputePhase::Evidence;
+
+        let new_evidence_deadline = current_time + config.evidence_period;
+        dispute.evidence_deadline = new_evidence_deadline;
+        dispute.voting_deadline = new_evidence_deadline + config.voting_period;
+        dispute.reveal_deadline =
+            new_evidence_deadline + config.voting_period + config.reveal_period;
+        dispute.appeal_deadline = new_evidence_deadline
+            + config.voting_period
+            + config.reveal_period
+            + dispute.appeal_period;
+
+        dispute.votes_for_passenger = 0;
+        dispute.votes_for_airline = 0;
+        dispute.verdict = None;
+
+        DisputeStorageKey::set_dispute(&env, dispute_id, &dispute);
+
+        let current_stake = DisputeStorageKey::get_stake(&env, dispute_id, &appellant);
+        DisputeStorageKey::set_stake(&env, dispute_id, &appellant, current_stake + appeal_stake);
+        Self::collect_stake(&env, &dispute.token, &appellant, appeal_stake);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("appealed")),
+            (dispute_id, appellant, appeal_stake),
+        );
+    }
+
+    // Stake for appeal `round`: amount * (appeal_stake_multiplier / 10000) ^ round
+    fn appeal_stake_required(config: &DisputeConfig, amount: i128, round: u32) -> i128 {
+        let mut required = amount;
+        for _ in 0..round {
+            required = required * config.appeal_stake_multiplier as i128 / 10000;
+        }
+        required
+    }
+
+    // Filing fee charged to the passenger on top of their stake; 0 disables it, which is
+    // only allowed while there is no open-dispute cap to exhaust
+    pub fn set_filing_fee(env: Env, admin: Address, amount: i128, treasury: Address) {
+        AccessControl::require_admin(&env, &admin);
+        assert!(amount >= 0, "Invalid filing fee");
+        let config = DisputeStorageKey::get_config(&env).expect("Not initialized");
+        assert!(
+            amount > 0 || config.max_open_per_airline == 0,
+            "Open dispute cap requires a filing fee"
+        );
+        DisputeStorageKey::set_filing_fee(&env, &FilingFee { amount, treasury: treasury.clone() });
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("fil_fee")),
+            (admin, amount, treasury),
+        );
+    }
+
+    // Airline offers to pay `amount` to end the dispute before a verdict. The amount is
+    // escrowed now; a new offer replaces (and refunds) the previous one.
+    pub fn offer_settlement(env: Env, airline: Address, dispute_id: u64, amount: i128) {
+        airline.require_auth();
+
+        let dispute = DisputeStorageKey::get_dispute(&env, dispute_id).expect("Dispute not found");
+        assert!(airline == dispute.airline, "Not the dispute airline");
+        Self::assert_unresolved(&dispute);
+        assert!(amount > 0 && amount <= dispute.amount, "Invalid settlement amount");
+
+        let token_client = token::Client::new(&env, &dispute.token);
+        if let Some(previous) = DisputeStorageKey::get_settlement(&env, dispute_id) {
+            token_client.transfer(&env.current_contract_address(), &airline, &previous.amount);
+        }
+        token_client.transfer(&airline, &env.current_contract_address(), &amount);
+
+        let expires_at = env.ledger().timestamp() + SETTLEMENT_OFFER_SECS;
+        DisputeStorageKey::set_settlement(&env, dispute_id, &SettlementOffer { amount, expires_at });
+
+        env.events().publish(
+            (symbol_short!("settle"), symbol_short!("offered")),
+            (dispute_id, airline, amount, expires_at),
+        );
+    }
+
+    // Passenger accepts the open offer: they receive the offered amount, both stakes are
+    // returned, and the dispute is finalized as `settled` without a jury verdict.
+    pub fn accept_settlement(env: Env, passenger: Address, dispute_id: u64) {
+        passenger.require_auth();
+
+        let mut dispute =
+            DisputeStorageKey::get_dispute(&env, dispute_id).expect("Dispute not found");
+        assert!(passenger == dispute.passenger, "Not the dispute passenger");
+        Self::assert_unresolved(&dispute);
+
+        let offer =
+            DisputeStorageKey::get_settlement(&env, dispute_id).expect("No settlement offer");
+        let now = env.ledger().timestamp();
+        assert!(now <= offer.expires_at, "Settlement offer expired");
+
+        let token_client = token::Client::new(&env, &dispute.token);
+        let contract = env.current_contract_address();
+        token_client.transfer(&contract, &passenger, &offer.amount);
+        for party in [dispute.passenger.clone(), dispute.airline.clone()] {
+            let stake = DisputeStorageKey::get_stake(&env, dispute_id, &party);
+            if stake > 0 {
+                DisputeStorageKey::set_stake(&env, dispute_id, &party, 0);
+                token_client.transfer(&contract, &party, &stake);
+            }
+        }
+
+        dispute.phase = DisputePhase::Finalized;
+        dispute.verdict = Some(symbol_short!("settled"));
+        dispute.finalized_at = Some(now);
+        DisputeStorageKey::set_dispute(&env, dispute_id, &dispute);
+        Self::close_for_airline(&env, &dispute.airline);
+        DisputeStorageKey::remove_settlement(&env, dispute_id);
+
+        env.events().publish(
+            (symbol_short!("settle"), symbol_short!("accepted")),
+            (dispute_id, passenger, offer.amount),
+        );
+    }
+
+    // Return an expired, unaccepted offer to the airline
+    pub fn withdraw_settlement(env: Env, airline: Address, dispute_id: u64) -> i128 {
+        airline.require_auth();
+
+        let dispute = DisputeStorageKey::get_dispute(&env, dispute_id).expect("Dispute not found");
+        assert!(airline == dispute.airline, "Not the dispute airline");
+        let offer =
+            DisputeStorageKey::get_settlement(&env, dispute_id).expect("No settlement offer");
+        assert!(env.ledger().timestamp() > offer.expires_at, "Settlement offer still open");
+
+        DisputeStorageKey::remove_settlement(&env, dispute_id);
+        token::Client::new(&env, &dispute.token).transfer(
+            &env.current_contract_address(),
+            &airline,
+            &offer.amount,
+        );
+
+        env.events().publish(
+            (symbol_short!("settle"), symbol_short!("withdrawn")),
+            (dispute_id, airline, offer.amount),
+        );
+        offer.amount
+    }
+
+    pub fn get_settlement_offer(env: Env, dispute_id: u64) -> Option<SettlementOffer> {
+        DisputeStorageKey::get_settlement(&env, dispute_id)
+    }
+
+    // Close a dispute that never reached a verdict (jury never filled, reveal quorum
+    // missed) once it is well past its appeal deadline. Both stakes and any settlement
+    // offer are returned, remaining candidates are refunded, and the airline's open
+    // dispute slot is freed. Anyone may call.
+    pub fn expire_dispute(env: Env, dispute_id: u64) {
+        let mut dispute =
+            DisputeStorageKey::get_dispute(&env, dispute_id).expect("Dispute not found");
+        Self::assert_unresolved(&dispute);
+        let now = env.ledger().timestamp();
+        assert!(
+            now > dispute.appeal_deadline + STUCK_DISPUTE_GRACE_SECS,
+            "Dispute not stuck"
+        );
+
+        dispute.phase = DisputePhase::Finalized;
+        dispute.verdict = Some(symbol_short!("expired"));
+        dispute.finalized_at = Some(now);
+        DisputeStorageKey::set_dispute(&env, dispute_id, &dispute);
+        Self::close_for_airline(&env, &dispute.airline);
+
+        let token_client = token::Client::new(&env, &dispute.token);
+        let contract = env.current_contract_address();
+        for party in [dispute.passenger.clone(), dispute.airline.clone()] {
+            let stake = DisputeStorageKey::get_stake(&env, dispute_id, &party);
+            if stake > 0 {
+                DisputeStorageKey::set_stake(&env, dispute_id, &party, 0);
+                token_client.transfer(&contract, &party, &stake);
+            }
+        }
+        if let Some(offer) = DisputeStorageKey::get_settlement(&env, dispute_id) {
+            DisputeStorageKey::remove_settlement(&env, dispute_id);
+            token_client.transfer(&contract, &dispute.airline, &offer.amount);
+        }
+        let candidates = DisputeStorageKey::get_candidates(&env, dispute_id);
+        DisputeStorageKey::set_candidates(&env, dispute_id, &Vec::new(&env));
+        for c in candidates.iter() {
+            Self::refund_candidate(&env, &dispute, &c.juror);
+            Self::release_juror_lock(&env, &dispute, &c.juror);
+        }
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("expired")),
+            (dispute_id, now),
+        );
+    }
+
+    fn close_for_airline(env: &Env, airline: &Address) {
+        let open_disputes = DisputeStorageKey::get_open_disputes(env, airline);
+        DisputeStorageKey::set_open_disputes(env, airline, open_disputes.saturating_sub(1));
+    }
+
+    pub fn airline_open_disputes(env: Env, airline: Address) -> u32 {
+        DisputeStorageKey::get_open_disputes(&env, &airline)
+    }
+
+    // Cap on unresolved disputes against one airline, so its outstanding stakes stay
+    // bounded. Filings beyond it are rejected until earlier disputes finalize. A cap needs
+    // a filing fee, which isn't refunded, so filling it to block others isn't free.
+    pub fn set_max_open_disputes(env: Env, admin: Address, max_open: u32) {
+        AccessControl::require_admin(&env, &admin);
+        let fee = DisputeStorageKey::get_filing_fee(&env).map(|fee| fee.amount).unwrap_or(0);
+        assert!(max_open == 0 || fee > 0, "Open dispute cap requires a filing fee");
+
+        let mut config = DisputeStorageKey::get_config(&env).expect("Not initialized");
+        config.max_open_per_airline = max_open;
+        DisputeStorageKey::set_config(&env, &config);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("max_open")),
+            (admin, max_open),
+        );
+    }
+
+    // Token for disputes filed from now on; open disputes keep settling in their own token
+    pub fn set_dispute_token(env: Env, admin: Address, token: Address) {
+        AccessControl::require_admin(&env, &admin);
+
+        let mut config = DisputeStorageKey::get_config(&env).expect("Not initialized");
+        config.token = token.clone();
+        DisputeStorageKey::set_config(&env, &config);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("token")),
+            (admin, token),
+        );
+    }
+
+    // Admin multisig whose emergency stop this contract follows
+    pub fn set_admin_multisig(env: Env, admin: Address, multisig: Address) {
+        AccessControl::require_admin(&env, &admin);
+        EmergencyStop::set_admin_multisig(&env, &multisig);
 
         env.events().publish(
-            (symbol_short!("dispute"), symbol_short!("finalized")),
-            (dispute_id, verdict),
+            (symbol_short!("dispute"), symbol_short!("adm_ms")),
+            (admin, multisig),
         );
     }
 
-    pub fn file_appeal(env: Env, appellant: Address, dispute_id: u64, appeal_stake: i128) {
-        appellant.require_auth();
-
-        let mut dispute =
-            DisputeStorageKey::get_dispute(&env, dispute_id).expect("Dispute not found");
+    pub fn is_emergency_stopped(env: Env) -> bool {
+        EmergencyStop::is_stopped(&env)
+    }
 
-        let current_time = env.ledger().timestamp();
+    // Settlement is only possible before the jury's verdict
+    fn assert_unresolved(dispute: &Dispute) {
         assert!(
-            current_time <= dispute.appeal_deadline,
-            "Appeal period ended"
+            dispute.phase != DisputePhase::Appeal && dispute.phase != DisputePhase::Finalized,
+            "Dispute already decided"
         );
-        assert!(dispute.phase == DisputePhase::Appeal, "Not in appeal phase");
-        assert!(!dispute.appealed, "Already appealed");
+    }
 
-        let verdict = dispute.verdict.clone().expect("No verdict");
-        let is_losing_party = (verdict == symbol_short!("airline")
-            && appellant == dispute.passenger)
-            || (verdict == symbol_short!("passenger") && appellant == dispute.airline);
+    // Cumulative amounts this contract has paid to the treasury, by category and token
+    pub fn fee_report(env: Env) -> FeeReport {
+        FeeReport {
+            totals: DisputeStorageKey::get_fee_totals(&env),
+        }
+    }
 
-        assert!(is_losing_party, "Only losing party can appeal");
+    // Evidence items one party must submit before jurors can be seated
+    pub fn set_min_evidence(env: Env, admin: Address, min_evidence: u32) {
+        AccessControl::require_admin(&env, &admin);
 
-        let config = DisputeStorageKey::get_config(&env).expect("Not initialized");
-        let required_stake = dispute.amount * config.appeal_stake_multiplier as i128 / 10000;
-        assert!(appeal_stake >= required_stake, "Insufficient appeal stake");
+        let mut config = DisputeStorageKey::get_config(&env).expect("Not initialized");
+        config.min_evidence = min_evidence;
+        DisputeStorageKey::set_config(&env, &config);
 
-        dispute.appealed = true;
-        dispute.phase = DisputePhase::Evidence;
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("min_evid")),
+            (admin, min_evidence),
+        );
+    }
 
-        let new_evidence_deadline = current_time + config.evidence_period;
-        dispute.evidence_deadline = new_evidence_deadline;
-        dispute.voting_deadline = new_evidence_deadline + config.voting_period;
-        dispute.reveal_deadline =
-            new_evidence_deadline + config.voting_period + config.reveal_period;
-        dispute.appeal_deadline = new_evidence_deadline
-            + config.voting_period
-            + config.reveal_period
-            + config.appeal_period;
+    // Disputes over at least `min_amount` get `appeal_period` instead of the default, so
+    // the losing party has longer to weigh an appeal. Applies to disputes filed afterwards;
+    // a `min_amount` of 0 turns it off.
+    pub fn set_high_value_appeal(env: Env, admin: Address, min_amount: i128, appeal_period: u64) {
+        AccessControl::require_admin(&env, &admin);
+
+        let mut config = DisputeStorageKey::get_config(&env).expect("Not initialized");
+        assert!(min_amount >= 0, "Invalid amount");
+        assert!(appeal_period >= config.appeal_period, "Appeal period below default");
+        config.high_value_amount = min_amount;
+        config.high_value_appeal_period = appeal_period;
+        DisputeStorageKey::set_config(&env, &config);
 
-        dispute.votes_for_passenger = 0;
-        dispute.votes_for_airline = 0;
-        dispute.verdict = None;
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("hv_appeal")),
+            (admin, min_amount, appeal_period),
+        );
+    }
 
-        DisputeStorageKey::set_dispute(&env, dispute_id, &dispute);
+    fn appeal_period_for(config: &DisputeConfig, amount: i128) -> u64 {
+        if config.high_value_amount > 0 && amount >= config.high_value_amount {
+            config.high_value_appeal_period
+        } else {
+            config.appeal_period
+        }
+    }
 
-        let current_stake = DisputeStorageKey::get_stake(&env, dispute_id, &appellant);
-        DisputeStorageKey::set_stake(&env, dispute_id, &appellant, current_stake + appeal_stake);
+    // Grace period after the appeal deadline so late appeals settle before execution
+    pub fn set_execution_buffer(env: Env, admin: Address, buffer_secs: u64) {
+        AccessControl::require_admin(&env, &admin);
+
+        let mut config = DisputeStorageKey::get_config(&env).expect("Not initialized");
+        config.execution_buffer = buffer_secs;
+        DisputeStorageKey::set_config(&env, &config);
 
         env.events().publish(
-            (symbol_short!("dispute"), symbol_short!("appealed")),
-            (dispute_id, appellant, appeal_stake),
+            (symbol_short!("dispute"), symbol_short!("exec_buf")),
+            (admin, buffer_secs),
+        );
+    }
+
+    pub fn set_appeal_rounds(env: Env, admin: Address, max_rounds: u32, jury_factor: u32) {
+        AccessControl::require_admin(&env, &admin);
+        assert!(jury_factor >= 2, "Jury must grow each round");
+
+        let mut config = DisputeStorageKey::get_config(&env).expect("Not initialized");
+        config.max_appeal_rounds = max_rounds;
+        config.appeal_jury_factor = jury_factor;
+        DisputeStorageKey::set_config(&env, &config);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("appeals")),
+            (admin, max_rounds, jury_factor),
         );
     }
 
@@ -658,25 +1909,30 @@ impl DisputeContract {
             DisputeStorageKey::get_dispute(&env, dispute_id).expect("Dispute not found");
 
         let current_time = env.ledger().timestamp();
+        let config = DisputeStorageKey::get_config(&env).expect("Not initialized");
 
-        if dispute.phase == DisputePhase::Appeal {
-            assert!(
-                current_time > dispute.appeal_deadline,
-                "Appeal period not ended"
-            );
-        }
-
-        let verdict = dispute.verdict.clone().expect("No verdict");
         assert!(
-            verdict != symbol_short!("tie"),
-            "Cannot execute tie verdict"
+            dispute.phase != DisputePhase::Finalized,
+            "Verdict already executed"
+        );
+        // An appeal filed before the deadline moves the dispute out of the appeal phase
+        assert!(dispute.phase == DisputePhase::Appeal, "Appeal in progress");
+        assert!(
+            current_time > dispute.appeal_deadline + config.execution_buffer,
+            "Appeal period not ended"
         );
 
+        let verdict = dispute.verdict.clone().expect("No verdict");
+
         dispute.phase = DisputePhase::Finalized;
         DisputeStorageKey::set_dispute(&env, dispute_id, &dispute);
+        Self::close_for_airline(&env, &dispute.airline);
+        if verdict == symbol_short!("tie") {
+            Self::execute_tie(&env, &dispute);
+            return;
+        }
 
-        let config = DisputeStorageKey::get_config(&env).expect("Not initialized");
-        let total_stake_pool = dispute.passenger_stake + dispute.airline_stake;
+        let total_stake_pool = Self::stake_pool(&env, &dispute);
         let jury_reward_pool =
             total_stake_pool * config.jury_reward_pool_percentage as i128 / 10000;
 
@@ -686,12 +1942,89 @@ impl DisputeContract {
             (dispute.airline.clone(), dispute.passenger.clone())
         };
 
+        // Winner takes both stakes back less the jury's share, which stays in the
+        // contract until jurors claim it
+        let mut winner_payout = total_stake_pool - jury_reward_pool;
+
+        let forfeited = Self::forfeited_deposits(&env, dispute_id);
+        if forfeited > 0 {
+            match DisputeStorageKey::get_forfeit_destination(&env) {
+                ForfeitDestination::Jurors => {}
+                ForfeitDestination::Winner => {
+                    winner_payout += forfeited;
+                    DisputeStorageKey::set_forfeits_paid_out(&env, dispute_id);
+                }
+                ForfeitDestination::Treasury(treasury) => {
+                    token::Client::new(&env, &dispute.token).transfer(
+                        &env.current_contract_address(),
+                        &treasury,
+                        &forfeited,
+                    );
+                    DisputeStorageKey::add_fee(
+                        &env,
+                        symbol_short!("forfeit"),
+                        &dispute.token,
+                        forfeited,
+                    );
+                    DisputeStorageKey::set_forfeits_paid_out(&env, dispute_id);
+                }
+            }
+        }
+
+        if winner_payout > 0 {
+            token::Client::new(&env, &dispute.token).transfer(
+                &env.current_contract_address(),
+                &winner,
+                &winner_payout,
+            );
+        }
+
         env.events().publish(
             (symbol_short!("verdict"), symbol_short!("executed")),
             (dispute_id, winner, loser, dispute.amount, jury_reward_pool),
         );
     }
 
+    // No majority: each party takes its own stake back and no jury reward is paid. What
+    // non-revealing jurors forfeited goes to the treasury when that is the forfeit
+    // destination, and is otherwise split between the parties.
+    fn execute_tie(env: &Env, dispute: &Dispute) {
+        let dispute_id = dispute.dispute_id;
+        let token_client = token::Client::new(env, &dispute.token);
+        let contract = env.current_contract_address();
+
+        let mut forfeited = Self::forfeited_deposits(env, dispute_id)
+            + Self::unrevealed_juror_stakes(env, dispute_id);
+        DisputeStorageKey::set_forfeits_paid_out(env, dispute_id);
+        if let ForfeitDestination::Treasury(treasury) =
+            DisputeStorageKey::get_forfeit_destination(env)
+        {
+            if forfeited > 0 {
+                token_client.transfer(&contract, &treasury, &forfeited);
+                DisputeStorageKey::add_fee(env, symbol_short!("forfeit"), &dispute.token, forfeited);
+            }
+            forfeited = 0;
+        }
+
+        let passenger_share = forfeited / 2;
+        let shares = [
+            (dispute.passenger.clone(), passenger_share),
+            (dispute.airline.clone(), forfeited - passenger_share),
+        ];
+        for (party, share) in shares {
+            let payout = DisputeStorageKey::get_stake(env, dispute_id, &party) + share;
+            DisputeStorageKey::set_stake(env, dispute_id, &party, 0);
+            if payout > 0 {
+                token_client.transfer(&contract, &party, &payout);
+            }
+        }
+
+        env.events().publish(
+            (symbol_short!("verdict"), symbol_short!("tie")),
+            (dispute_id, dispute.amount),
+        );
+    }
+
     pub fn claim_juror_reward(env: Env, juror: Address, dispute_id: u64) -> i128 {
         juror.require_auth();
 
@@ -711,26 +2044,331 @@ impl DisputeContract {
             || (verdict == symbol_short!("airline") && !reveal.vote_for_passenger);
 
         assert!(voted_correctly, "Did not vote with majority");
+        assert!(
+            !DisputeStorageKey::has_claimed_reward(&env, dispute_id, &juror),
+            "Reward already claimed"
+        );
 
-        let config = DisputeStorageKey::get_config(&env).expect("Not initialized");
-        let total_stake_pool = dispute.passenger_stake + dispute.airline_stake;
-        let jury_reward_pool =
+        let reward = Self::juror_reward(&env, &dispute, &verdict, &juror);
+
+        // A majority juror's own stake comes back alongside the reward. The stake record is
+        // kept so later claims still see every majority stake when weighting by stake.
+        let stake = DisputeStorageKey::get_juror_stake(&env, dispute_id, &juror);
+
+        DisputeStorageKey::set_claimed_reward(&env, dispute_id, &juror);
+        if reward + stake > 0 {
+            token::Client::new(&env, &dispute.token).transfer(
+                &env.current_contract_address(),
+                &juror,
+                &(reward + stake),
+            );
+        }
+
+        env.events().publish(
+            (symbol_short!("reward"), symbol_short!("claimed")),
+            (dispute_id, juror.clone(), reward),
+        );
+
+        reward
+    }
+
+    // Deposit jurors post so that committing without revealing has a cost
+    pub fn set_juror_deposit(env: Env, admin: Address, amount: i128) {
+        AccessControl::require_admin(&env, &admin);
+        assert!(amount >= 0, "Invalid juror deposit");
+        DisputeStorageKey::set_juror_deposit_amount(&env, amount);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("juror_dep")),
+            (admin, amount),
+        );
+    }
+
+    // Expected reward for `juror` under the current distribution; 0 until there is a
+    // verdict the juror revealed a vote for. Claims do not change other jurors' previews.
+    pub fn juror_reward_preview(env: Env, dispute_id: u64, juror: Address) -> i128 {
+        let dispute = DisputeStorageKey::get_dispute(&env, dispute_id).expect("Dispute not found");
+        let verdict = match dispute.verdict.clone() {
+            Some(v) if v == symbol_short!("passenger") || v == symbol_short!("airline") => v,
+            _ => return 0,
+        };
+        let for_passenger = verdict == symbol_short!("passenger");
+        match DisputeStorageKey::get_vote_reveal(&env, dispute_id, &juror) {
+            Some(reveal) if reveal.vote_for_passenger == for_passenger => {
+                Self::juror_reward(&env, &dispute, &verdict, &juror)
+            }
+            _ => 0,
+        }
+    }
+
+    pub fn set_reward_distribution(env: Env, admin: Address, distribution: RewardDistribution) {
+        AccessControl::require_admin(&env, &admin);
+        DisputeStorageKey::set_reward_distribution(&env, &distribution);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("rwd_dist")),
+            (admin, distribution),
+        );
+    }
+
+    pub fn get_reward_distribution(env: Env) -> RewardDistribution {
+        DisputeStorageKey::get_reward_distribution(&env)
+    }
+
+    // Stake jurors lock when they are seated or register for the draw, until they claim;
+    // non-revealing and minority jurors forfeit it to the majority jurors' rewards
+    pub fn set_juror_stake(env: Env, admin: Address, amount: i128) {
+        AccessControl::require_admin(&env, &admin);
+        assert!(amount >= 0, "Invalid juror stake");
+        DisputeStorageKey::set_juror_stake_amount(&env, amount);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("jur_stake")),
+            (admin, amount),
+        );
+    }
+
+    pub fn get_juror_stake(env: Env, dispute_id: u64, juror: Address) -> i128 {
+        DisputeStorageKey::get_juror_stake(&env, dispute_id, &juror)
+    }
+
+    // Where non-revealing jurors' deposits go when a verdict is executed
+    pub fn set_forfeit_destination(env: Env, admin: Address, destination: ForfeitDestination) {
+        AccessControl::require_admin(&env, &admin);
+        DisputeStorageKey::set_forfeit_destination(&env, &destination);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("forf_dst")),
+            (admin, destination),
+        );
+    }
+
+    pub fn get_forfeit_destination(env: Env) -> ForfeitDestination {
+        DisputeStorageKey::get_forfeit_destination(&env)
+    }
+
+    pub fn get_juror_deposit(env: Env, dispute_id: u64, juror: Address) -> i128 {
+        DisputeStorageKey::get_juror_deposit(&env, dispute_id, &juror)
+    }
+
+    // Return a juror's deposit once the dispute is finalized. Seated jurors who never revealed
+    // forfeit theirs to the jury reward pool; everyone gets theirs back once the parties
+    // settle. Candidates left out of the draw are refunded by `draw_jury`.
+    pub fn claim_juror_deposit(env: Env, juror: Address, dispute_id: u64) -> i128 {
+        juror.require_auth();
+
+        let dispute = DisputeStorageKey::get_dispute(&env, dispute_id).expect("Dispute not found");
+        // Nobody is at fault for a settlement or an expired dispute, so every juror is refunded
+        let settled = dispute.verdict == Some(symbol_short!("settled"))
+            || dispute.verdict == Some(symbol_short!("expired"));
+        // Without a majority there is no one to forfeit the stake to, so a settlement or a
+        // tie also hands it back
+        let no_majority = settled || dispute.verdict == Some(symbol_short!("tie"));
+        let stake = if no_majority {
+            DisputeStorageKey::get_juror_stake(&env, dispute_id, &juror)
+        } else {
+            0
+        };
+        let deposit = DisputeStorageKey::get_juror_deposit(&env, dispute_id, &juror) + stake;
+        assert!(deposit > 0, "No deposit");
+
+        if DisputeStorageKey::is_juror(&env, dispute_id, &juror) && !settled {
+            // An appeal seats the jury again, so the deposit stays in until the verdict is final
+            assert!(dispute.phase == DisputePhase::Finalized, "Dispute not finalized");
+            assert!(
+                DisputeStorageKey::get_vote_reveal(&env, dispute_id, &juror).is_some(),
+                "Deposit forfeited"
+            );
+        } else {
+            assert!(
+                dispute.phase != DisputePhase::Evidence
+                    && dispute.phase != DisputePhase::JurySelection,
+                "Jury not selected"
+            );
+        }
+
+        DisputeStorageKey::set_juror_deposit(&env, dispute_id, &juror, 0);
+        // Otherwise the stake stays put for the verdict payout
+        if no_majority {
+            DisputeStorageKey::set_juror_stake(&env, dispute_id, &juror, 0);
+        }
+        token::Client::new(&env, &dispute.token).transfer(
+            &env.current_contract_address(),
+            &juror,
+            &deposit,
+        );
+
+        env.events().publish(
+            (symbol_short!("juror"), symbol_short!("deposit")),
+            (dispute_id, juror, deposit),
+        );
+        deposit
+    }
+
+    fn collect_juror_deposit(env: &Env, dispute: &Dispute, juror: &Address) {
+        let amount = DisputeStorageKey::get_juror_deposit_amount(env);
+        if amount > 0 {
+            Self::collect_stake(env, &dispute.token, juror, amount);
+            DisputeStorageKey::set_juror_deposit(env, dispute.dispute_id, juror, amount);
+        }
+    }
+
+    fn collect_juror_stake(env: &Env, dispute: &Dispute, juror: &Address) {
+        let amount = DisputeStorageKey::get_juror_stake_amount(env);
+        if amount > 0 {
+            Self::collect_stake(env, &dispute.token, juror, amount);
+            DisputeStorageKey::set_juror_stake(env, dispute.dispute_id, juror, amount);
+        }
+    }
+
+    // Deposits of seated jurors who did not reveal, added to the jury reward pool
+    fn forfeited_deposits(env: &Env, dispute_id: u64) -> i128 {
+        let mut total = 0i128;
+        let mut i = 0u32;
+        while let Some(selection) = DisputeStorageKey::get_juror(env, dispute_id, i) {
+            if DisputeStorageKey::get_vote_reveal(env, dispute_id, &selection.juror).is_none() {
+                total += DisputeStorageKey::get_juror_deposit(env, dispute_id, &selection.juror);
+            }
+            i += 1;
+        }
+        total
+    }
+
+    // Share of the jury reward pool owed to majority juror `juror`. Stake weighting falls
+    // back to equal shares when no majority juror locked a stake.
+    fn juror_reward(env: &Env, dispute: &Dispute, verdict: &Symbol, juror: &Address) -> i128 {
+        let dispute_id = dispute.dispute_id;
+        let config = DisputeStorageKey::get_config(env).expect("Not initialized");
+        let total_stake_pool = Self::stake_pool(env, dispute);
+        let mut jury_reward_pool =
             total_stake_pool * config.jury_reward_pool_percentage as i128 / 10000;
+        if !DisputeStorageKey::forfeits_paid_out(env, dispute_id) {
+            jury_reward_pool += Self::forfeited_deposits(env, dispute_id);
+        }
+        jury_reward_pool += Self::forfeited_juror_stakes(env, dispute_id, verdict);
+
+        if DisputeStorageKey::get_reward_distribution(env) == RewardDistribution::StakeWeighted {
+            let majority_stake = Self::majority_juror_stakes(env, dispute_id, verdict);
+            if majority_stake > 0 {
+                let stake = DisputeStorageKey::get_juror_stake(env, dispute_id, juror);
+                return jury_reward_pool * stake / majority_stake;
+            }
+        }
 
-        let winning_votes = if verdict == symbol_short!("passenger") {
+        let winning_votes = if *verdict == symbol_short!("passenger") {
             dispute.votes_for_passenger
         } else {
             dispute.votes_for_airline
         };
+        jury_reward_pool / winning_votes as i128
+    }
+
+    // Stakes locked by seated jurors who revealed a vote for `verdict`
+    fn majority_juror_stakes(env: &Env, dispute_id: u64, verdict: &Symbol) -> i128 {
+        let for_passenger = *verdict == symbol_short!("passenger");
+        let mut total = 0i128;
+        let mut i = 0u32;
+        while let Some(selection) = DisputeStorageKey::get_juror(env, dispute_id, i) {
+            let reveal = DisputeStorageKey::get_vote_reveal(env, dispute_id, &selection.juror);
+            if reveal.is_some_and(|r| r.vote_for_passenger == for_passenger) {
+                total += DisputeStorageKey::get_juror_stake(env, dispute_id, &selection.juror);
+            }
+            i += 1;
+        }
+        total
+    }
+
+    // Stakes of seated jurors who did not reveal or voted against `verdict`
+    fn forfeited_juror_stakes(env: &Env, dispute_id: u64, verdict: &Symbol) -> i128 {
+        let for_passenger = *verdict == symbol_short!("passenger");
+        let mut total = 0i128;
+        let mut i = 0u32;
+        while let Some(selection) = DisputeStorageKey::get_juror(env, dispute_id, i) {
+            let with_majority = DisputeStorageKey::get_vote_reveal(env, dispute_id, &selection.juror)
+                .map(|reveal| reveal.vote_for_passenger == for_passenger)
+                .unwrap_or(false);
+            if !with_majority {
+                total += DisputeStorageKey::get_juror_stake(env, dispute_id, &selection.juror);
+            }
+            i += 1;
+        }
+        total
+    }
+
+    // Stakes of seated jurors who never revealed, forfeited when the vote ties
+    fn unrevealed_juror_stakes(env: &Env, dispute_id: u64) -> i128 {
+        let mut total = 0i128;
+        let mut i = 0u32;
+        while let Some(selection) = DisputeStorageKey::get_juror(env, dispute_id, i) {
+            if DisputeStorageKey::get_vote_reveal(env, dispute_id, &selection.juror).is_none() {
+                total += DisputeStorageKey::get_juror_stake(env, dispute_id, &selection.juror);
+            }
+            i += 1;
+        }
+        total
+    }
+
+    fn collect_stake(env: &Env, token: &Address, from: &Address, amount: i128) {
+        token::Client::new(env, token).transfer(from, &env.current_contract_address(), &amount);
+    }
+
+    // Stakes held for a dispute, including any appeal stake
+    fn stake_pool(env: &Env, dispute: &Dispute) -> i128 {
+        DisputeStorageKey::get_stake(env, dispute.dispute_id, &dispute.passenger)
+            + DisputeStorageKey::get_stake(env, dispute.dispute_id, &dispute.airline)
+    }
+
+    pub fn has_claimed_reward(env: Env, dispute_id: u64, juror: Address) -> bool {
+        DisputeStorageKey::has_claimed_reward(&env, dispute_id, &juror)
+    }
+
+    // Minimum airline stake as a fraction of the passenger stake, in basis points (0 disables)
+    pub fn set_stake_match_ratio(env: Env, admin: Address, ratio_bps: u32) {
+        AccessControl::require_admin(&env, &admin);
+        assert!(ratio_bps <= 10000, "Invalid match ratio");
 
-        let reward = jury_reward_pool / winning_votes as i128;
+        DisputeStorageKey::set_stake_match_ratio(&env, ratio_bps);
 
         env.events().publish(
-            (symbol_short!("reward"), symbol_short!("claimed")),
-            (dispute_id, juror.clone(), reward),
+            (symbol_short!("dispute"), symbol_short!("match")),
+            (admin, ratio_bps),
         );
+    }
 
-        reward
+    pub fn get_stake_match_ratio(env: Env) -> u32 {
+        DisputeStorageKey::get_stake_match_ratio(&env)
+    }
+
+    // Governance registry providing the protocol-wide minimum stake
+    pub fn set_param_registry(env: Env, admin: Address, registry: Address) {
+        AccessControl::require_admin(&env, &admin);
+        DisputeStorageKey::set_param_registry(&env, &registry);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("registry")),
+            (admin, registry),
+        );
+    }
+
+    // Minimum stake to file a dispute over `amount`; the airline's response needs the same
+    pub fn required_stake(env: Env, amount: i128) -> i128 {
+        let config = DisputeStorageKey::get_config(&env).expect("Not initialized");
+        Self::min_stake(&env, &config, amount)
+    }
+
+    fn min_stake(env: &Env, config: &DisputeConfig, amount: i128) -> i128 {
+        Self::stake_floor(env, amount * config.min_stake_percentage as i128 / 10000)
+    }
+
+    // The protocol minimum stake applies beneath this contract's own minimum
+    fn stake_floor(env: &Env, required: i128) -> i128 {
+        let floor = match DisputeStorageKey::get_param_registry(env) {
+            Some(registry) => ParamRegistryClient::new(env, &registry)
+                .get_protocol_param(&symbol_short!("min_stake"))
+                .unwrap_or(0),
+            None => 0,
+        };
+        required.max(floor)
     }
 
     // Role management functions
@@ -766,10 +2404,101 @@ impl DisputeContract {
         DisputeStorageKey::get_dispute(&env, dispute_id)
     }
 
+    // (passenger, airline) of a dispute, for contracts linking their records to it
+    // File a dispute over a booking escrow. The booking contract only holds escrow for a
+    // dispute bound to that booking.
+    pub fn file_booking_dispute(
+        env: Env,
+        passenger: Address,
+        airline: Address,
+        booking_id: u64,
+        amount: i128,
+        passenger_stake: i128,
+    ) -> u64 {
+        let dispute_id =
+            Self::file_dispute(env.clone(), passenger, airline, 0, amount, passenger_stake);
+        DisputeStorageKey::set_dispute_booking(&env, dispute_id, booking_id);
+        dispute_id
+    }
+
+    pub fn get_dispute_booking(env: Env, dispute_id: u64) -> Option<u64> {
+        DisputeStorageKey::get_dispute_booking(&env, dispute_id)
+    }
+
+    pub fn get_dispute_parties(env: Env, dispute_id: u64) -> (Address, Address) {
+        let dispute = DisputeStorageKey::get_dispute(&env, dispute_id).expect("Dispute not found");
+        (dispute.passenger, dispute.airline)
+    }
+
+    // Verdict once it has been executed and can no longer be appealed
+    pub fn get_final_verdict(env: Env, dispute_id: u64) -> Option<Symbol> {
+        let dispute = DisputeStorageKey::get_dispute(&env, dispute_id).expect("Dispute not found");
+        if dispute.phase == DisputePhase::Finalized {
+            dispute.verdict
+        } else {
+            None
+        }
+    }
+
+    // Phase a dispute is effectively in once elapsed deadlines are taken into account,
+    // even if no transition call has been made yet.
+    fn effective_phase(env: &Env, dispute: &Dispute) -> DisputePhase {
+        let now = env.ledger().timestamp();
+        let config = DisputeStorageKey::get_config(env).expect("Not initialized");
+        match dispute.phase {
+            DisputePhase::Evidence if !Self::evidence_open(env, &config, dispute) => {
+                DisputePhase::JurySelection
+            }
+            DisputePhase::CommitVote if now > dispute.voting_deadline => DisputePhase::RevealVote,
+            _ => dispute.phase.clone(),
+        }
+    }
+
+    // Paginated list of dispute ids whose effective phase matches `phase`.
+    // `start` skips that many matching disputes, so jurors can page through results.
+    pub fn disputes_in_phase(env: Env, phase: DisputePhase, start: u32, limit: u32) -> Vec<u64> {
+        let limit = if limit > MAX_PAGE_SIZE { MAX_PAGE_SIZE } else { limit };
+        let count = DisputeStorageKey::get_dispute_count(&env);
+
+        let mut result = Vec::new(&env);
+        let mut skipped = 0u32;
+        let mut dispute_id = 1u64;
+        while dispute_id <= count && result.len() < limit {
+            if let Some(dispute) = DisputeStorageKey::get_dispute(&env, dispute_id) {
+                if Self::effective_phase(&env, &dispute) == phase {
+                    if skipped < start {
+                        skipped += 1;
+                    } else {
+                        result.push_back(dispute_id);
+                    }
+                }
+            }
+            dispute_id += 1;
+        }
+        result
+    }
+
     pub fn get_evidence(env: Env, dispute_id: u64, index: u32) -> Option<Evidence> {
         DisputeStorageKey::get_evidence(&env, dispute_id, index)
     }
 
+    // Evidence from both parties in submission order, `limit` capped at MAX_PAGE_SIZE
+    pub fn get_all_evidence(env: Env, dispute_id: u64, start: u32, limit: u32) -> Vec<Evidence> {
+        let dispute = DisputeStorageKey::get_dispute(&env, dispute_id).expect("Dispute not found");
+        let limit = if limit > MAX_PAGE_SIZE { MAX_PAGE_SIZE } else { limit };
+        let total = dispute.passenger_evidence_count + dispute.airline_evidence_count;
+
+        let mut result = Vec::new(&env);
+        let mut index = start;
+        while index < total && result.len() < limit {
+            if let Some(evidence) = DisputeStorageKey::get_evidence(&env, dispute_id, index) {
+                result.push_back(evidence);
+            }
+            index += 1;
+        }
+        result
+    }
+
     pub fn get_juror(env: Env, dispute_id: u64, index: u32) -> Option<JurorSelection> {
         DisputeStorageKey::get_juror(&env, dispute_id, index)
     }
@@ -787,6 +2516,47 @@ impl DisputeContract {
         count
     }
 
+    pub fn jury_metrics(env: Env, dispute_id: u64) -> JuryMetrics {
+        let dispute = DisputeStorageKey::get_dispute(&env, dispute_id).expect("Dispute not found");
+
+        let mut jury_size = 0u32;
+        let mut revealed_count = 0u32;
+        let mut total_token_balance = 0i128;
+        while jury_size < dispute.jury_size {
+            let selection = match DisputeStorageKey::get_juror(&env, dispute_id, jury_size) {
+                Some(s) => s,
+                None => break,
+            };
+            total_token_balance += selection.token_balance;
+            if DisputeStorageKey::get_vote_reveal(&env, dispute_id, &selection.juror).is_some() {
+                revealed_count += 1;
+            }
+            jury_size += 1;
+        }
+
+        JuryMetrics {
+            jury_size,
+            revealed_count,
+            total_token_balance,
+            winning_margin: dispute.votes_for_passenger.abs_diff(dispute.votes_for_airline),
+        }
+    }
+
+    // Seated jurors who committed a vote but have not revealed it yet
+    pub fn pending_revealers(env: Env, dispute_id: u64) -> Vec<Address> {
+        let mut pending = Vec::new(&env);
+        let mut i = 0u32;
+        while let Some(selection) = DisputeStorageKey::get_juror(&env, dispute_id, i) {
+            if DisputeStorageKey::get_vote_commit(&env, dispute_id, &selection.juror).is_some()
+                && DisputeStorageKey::get_vote_reveal(&env, dispute_id, &selection.juror).is_none()
+            {
+                pending.push_back(selection.juror);
+            }
+            i += 1;
+        }
+        pending
+    }
+
     pub fn is_juror(env: Env, dispute_id: u64, address: Address) -> bool {
         DisputeStorageKey::is_juror(&env, dispute_id, &address)
     }
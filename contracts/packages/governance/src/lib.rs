@@ -1,8 +1,48 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Env, Symbol};
+use soroban_sdk::{
+    contract, contractclient, contractimpl, contracttype, symbol_short, Address, BytesN, Env,
+    Symbol, Vec,
+};
 use access::{AccessControl, Role};
+use storage_version::{VersionedStorage, GOVERNANCE_CONTRACT};
+
+const MAX_PARAM_UPDATES: u32 = 20;
+const MAX_PAGE_SIZE: u32 = 50;
+/// `cast_vote` reads one balance per delegator, so the list per delegatee is kept short.
+const MAX_DELEGATORS: u32 = 50;
+/// Storage v2 adds execution, timelock and quorum fields to proposals and the config.
+const EXECUTION_STORAGE_VERSION: u32 = 2;
+
+/// Interface a contract must expose to accept parameter changes from governance.
+/// The target is expected to panic if it rejects the update, and must recognise this
+/// contract as its parameter authority (for the airline, via `set_param_authority`).
+#[contractclient(name = "ParamTargetClient")]
+pub trait ParamTargetInterface {
+    fn set_param(env: Env, caller: Address, key: Symbol, value: i128);
+}
+
+/// Upgrade entry point of the proxy contract. Governance must be one of its multisig
+/// signers; the forwarded upgrade still needs the proxy's approval threshold.
+#[contractclient(name = "ProxyClient")]
+pub trait ProxyInterface {
+    fn propose_upgrade(
+        env: Env,
+        proposer: Address,
+        new_implementation: BytesN<32>,
+        new_storage_version: Option<u32>,
+    ) -> u64;
+}
+
+/// TRQ token whose balances weight votes once configured.
+#[contractclient(name = "VoteTokenClient")]
+pub trait VoteTokenInterface {
+    fn balance_of(env: Env, account: Address) -> i128;
+    fn snapshot(env: Env, admin: Address) -> u64;
+    fn balance_of_at(env: Env, account: Address, snapshot_id: u64) -> i128;
+}
 
-/// On-chain governance proposal: one vote per address per proposal (1 token-holder = 1 vote).
+/// On-chain governance proposal: one vote per address per proposal. Votes are weighted by
+/// TRQ balance (plus delegated balances) when a vote token is configured, else 1 each.
 #[contracttype]
 #[derive(Clone)]
 pub struct Proposal {
@@ -13,12 +53,102 @@ pub struct Proposal {
     pub yes_votes: u64,
     pub no_votes: u64,
     pub status: Symbol,
+    pub executed: bool, // set once a passed proposal's effects have been applied
+    pub eta: u64,       // earliest execution time, recorded when the proposal passes
+    pub quorum: u64,    // minimum total votes, copied from the config at creation
+    pub min_voters: u32, // minimum distinct voters, copied from the config at creation
+    pub voters: u32,     // addresses that cast a vote themselves; delegators are not counted
+}
+
+/// A recorded vote and the weight it was cast with.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Vote {
+    pub support: bool,
+    pub power: u64,
+}
+
+/// Implementation upgrade forwarded to `proxy` when a proposal executes.
+#[contracttype]
+#[derive(Clone)]
+pub struct UpgradeAction {
+    pub proxy: Address,
+    pub new_implementation: BytesN<32>,
+}
+
+/// A single parameter change applied to `target` when a proposal executes.
+#[contracttype]
+#[derive(Clone)]
+pub struct ParamUpdate {
+    pub target: Address,
+    pub key: Symbol,
+    pub value: i128,
 }
 
 #[contracttype]
 pub struct GovernanceConfig {
     /// Length of the voting window for new proposals (seconds).
     pub voting_period_secs: u64,
+    /// Delay between a proposal passing and its earliest execution (seconds).
+    pub execution_delay: u64,
+    /// TRQ token votes are weighted by; `None` means one vote per address.
+    pub token: Option<Address>,
+    /// Minimum yes + no votes for a proposal to pass; 0 disables the check.
+    pub quorum: u64,
+    /// Minimum distinct addresses voting for a proposal to pass; 0 disables the check.
+    pub min_voters: u32,
+}
+
+/// Proposal layout before storage v2, when passing a proposal had no effects to execute.
+#[contracttype]
+pub struct LegacyProposal {
+    pub id: u32,
+    pub creator: Address,
+    pub description: Symbol,
+    pub vote_deadline: u64,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    pub status: Symbol,
+}
+
+impl LegacyProposal {
+    /// Legacy proposals were created without a quorum and carry no actions, so they
+    /// keep their tally and have nothing to execute.
+    pub fn upgrade(self) -> Proposal {
+        Proposal {
+            id: self.id,
+            creator: self.creator,
+            description: self.description,
+            vote_deadline: self.vote_deadline,
+            yes_votes: self.yes_votes,
+            no_votes: self.no_votes,
+            status: self.status,
+            executed: false,
+            eta: 0,
+            quorum: 0,
+            min_voters: 0,
+            voters: 0,
+        }
+    }
+}
+
+/// Config layout before storage v2.
+#[contracttype]
+pub struct LegacyGovernanceConfig {
+    pub voting_period_secs: u64,
+}
+
+impl LegacyGovernanceConfig {
+    /// New settings take the same defaults as `init_governance`.
+    pub fn upgrade(self) -> GovernanceConfig {
+        GovernanceConfig {
+            voting_period_secs: self.voting_period_secs,
+            execution_delay: 0,
+            token: None,
+            quorum: 0,
+            min_voters: 0,
+        }
+    }
 }
 
 pub struct GovernanceStorageKey;
@@ -36,22 +166,147 @@ impl GovernanceStorageKey {
             .set(&(symbol_short!("proposal"), proposal_id), proposal);
     }
 
+    pub fn get_legacy_proposal(env: &Env, proposal_id: u32) -> Option<LegacyProposal> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("proposal"), proposal_id))
+    }
+
     pub fn has_voted(env: &Env, voter: &Address, proposal_id: u32) -> bool {
         env.storage()
             .persistent()
             .has(&(symbol_short!("vote"), voter, proposal_id))
     }
 
-    pub fn record_vote(env: &Env, voter: &Address, proposal_id: u32) {
+    pub fn get_vote(env: &Env, voter: &Address, proposal_id: u32) -> Option<Vote> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("vote"), voter, proposal_id))
+    }
+
+    pub fn record_vote(env: &Env, voter: &Address, proposal_id: u32, vote: &Vote) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("vote"), voter, proposal_id), vote);
+    }
+
+    pub fn get_delegate(env: &Env, delegator: &Address) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("deleg"), delegator))
+    }
+
+    pub fn set_delegate(env: &Env, delegator: &Address, delegatee: &Address) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("deleg"), delegator), delegatee);
+    }
+
+    pub fn remove_delegate(env: &Env, delegator: &Address) {
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("deleg"), delegator));
+    }
+
+    /// Addresses that have delegated their voting power to `delegatee`.
+    pub fn get_delegators(env: &Env, delegatee: &Address) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("delegs"), delegatee))
+            .unwrap_or(Vec::new(env))
+    }
+
+    pub fn set_delegators(env: &Env, delegatee: &Address, delegators: &Vec<Address>) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("delegs"), delegatee), delegators);
+    }
+
+    pub fn get_param_updates(env: &Env, proposal_id: u32) -> Option<Vec<ParamUpdate>> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("p_upd"), proposal_id))
+    }
+
+    pub fn set_param_updates(env: &Env, proposal_id: u32, updates: &Vec<ParamUpdate>) {
         env.storage()
             .persistent()
-            .set(&(symbol_short!("vote"), voter, proposal_id), &true);
+            .set(&(symbol_short!("p_upd"), proposal_id), updates);
+    }
+
+    pub fn get_upgrade_action(env: &Env, proposal_id: u32) -> Option<UpgradeAction> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("p_upg"), proposal_id))
+    }
+
+    pub fn set_upgrade_action(env: &Env, proposal_id: u32, action: &UpgradeAction) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("p_upg"), proposal_id), action);
+    }
+
+    /// Vote token snapshot taken when the proposal was created
+    pub fn get_proposal_snapshot(env: &Env, proposal_id: u32) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("p_snap"), proposal_id))
+    }
+
+    pub fn set_proposal_snapshot(env: &Env, proposal_id: u32, snapshot_id: u64) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("p_snap"), proposal_id), &snapshot_id);
+    }
+
+    /// Vote token the proposal's snapshot was taken on. Kept apart from the snapshot id
+    /// so snapshots recorded before it still decode.
+    pub fn get_snapshot_token(env: &Env, proposal_id: u32) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("p_snap_tk"), proposal_id))
+    }
+
+    pub fn set_snapshot_token(env: &Env, proposal_id: u32, token: &Address) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("p_snap_tk"), proposal_id), token);
+    }
+
+    /// Vote token balance needed to create a proposal; 0 lets anyone propose.
+    pub fn get_proposal_threshold(env: &Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("p_thresh"))
+            .unwrap_or(0)
+    }
+
+    pub fn set_proposal_threshold(env: &Env, threshold: i128) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("p_thresh"), &threshold);
+    }
+
+    pub fn get_protocol_param(env: &Env, key: &Symbol) -> Option<i128> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("param"), key.clone()))
+    }
+
+    pub fn set_protocol_param(env: &Env, key: &Symbol, value: i128) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("param"), key.clone()), &value);
     }
 
     pub fn get_config(env: &Env) -> Option<GovernanceConfig> {
         env.storage().instance().get(&symbol_short!("config"))
     }
 
+    pub fn get_legacy_config(env: &Env) -> Option<LegacyGovernanceConfig> {
+        env.storage().instance().get(&symbol_short!("config"))
+    }
+
     pub fn set_config(env: &Env, config: &GovernanceConfig) {
         env.storage()
             .instance()
@@ -70,6 +325,20 @@ impl GovernanceStorageKey {
             .instance()
             .set(&symbol_short!("p_count"), &count);
     }
+
+    // Ids of proposals still open for voting, in creation order
+    pub fn get_active_proposals(env: &Env) -> Vec<u32> {
+        env.storage()
+            .persistent()
+            .get(&symbol_short!("active"))
+            .unwrap_or(Vec::new(env))
+    }
+
+    pub fn set_active_proposals(env: &Env, ids: &Vec<u32>) {
+        env.storage()
+            .persistent()
+            .set(&symbol_short!("active"), ids);
+    }
 }
 
 #[contract]
@@ -92,15 +361,167 @@ impl GovernanceContract {
             &env,
             &GovernanceConfig {
                 voting_period_secs,
+                execution_delay: 0,
+                token: None,
+                quorum: 0,
+                min_voters: 0,
             },
         );
+        // New deployments start on the current layout
+        VersionedStorage::set_storage_version(
+            &env,
+            &GOVERNANCE_CONTRACT,
+            EXECUTION_STORAGE_VERSION,
+        );
+    }
+
+    /// Rewrite the config and proposals stored before v2 into the current layout, and
+    /// list the ones still open for voting. Pass every existing proposal id in a single
+    /// call: the storage version is bumped afterwards and the migration cannot be run again.
+    pub fn migrate_legacy_proposals(env: Env, admin: Address, proposal_ids: Vec<u32>) -> u32 {
+        AccessControl::require_admin(&env, &admin);
+        assert!(
+            VersionedStorage::needs_migration(
+                &env,
+                &GOVERNANCE_CONTRACT,
+                EXECUTION_STORAGE_VERSION
+            ),
+            "Already migrated"
+        );
+
+        let config = GovernanceStorageKey::get_legacy_config(&env).expect("Not initialized");
+        GovernanceStorageKey::set_config(&env, &config.upgrade());
+
+        let now = env.ledger().timestamp();
+        let mut active = GovernanceStorageKey::get_active_proposals(&env);
+        for proposal_id in proposal_ids.iter() {
+            let proposal = GovernanceStorageKey::get_legacy_proposal(&env, proposal_id)
+                .expect("Proposal not found")
+                .upgrade();
+            if now <= proposal.vote_deadline && !active.contains(proposal_id) {
+                active.push_back(proposal_id);
+            }
+            GovernanceStorageKey::set_proposal(&env, proposal_id, &proposal);
+        }
+        GovernanceStorageKey::set_active_proposals(&env, &active);
+
+        let current = VersionedStorage::get_storage_version(&env, &GOVERNANCE_CONTRACT);
+        VersionedStorage::migrate_storage(
+            &env,
+            &GOVERNANCE_CONTRACT,
+            current,
+            EXECUTION_STORAGE_VERSION,
+            &admin,
+        );
+
+        env.events().publish(
+            (symbol_short!("gov"), symbol_short!("migrated")),
+            (admin, now, proposal_ids.len()),
+        );
+
+        proposal_ids.len()
+    }
+
+    /// Weight votes by balances of `token` (TRQ) instead of one vote per address.
+    /// This contract must hold the token's admin role, as it snapshots balances for
+    /// every new proposal.
+    pub fn set_vote_token(env: Env, admin: Address, token: Address) {
+        AccessControl::require_admin(&env, &admin);
+
+        let mut config = GovernanceStorageKey::get_config(&env).expect("Not initialized");
+        config.token = Some(token.clone());
+        GovernanceStorageKey::set_config(&env, &config);
+
+        env.events().publish(
+            (symbol_short!("gov"), symbol_short!("vote_tok")),
+            (admin, token),
+        );
+    }
+
+    /// Set the quorum for proposals created from now on.
+    pub fn set_quorum(env: Env, admin: Address, quorum: u64) {
+        AccessControl::require_admin(&env, &admin);
+
+        let mut config = GovernanceStorageKey::get_config(&env).expect("Not initialized");
+        config.quorum = quorum;
+        GovernanceStorageKey::set_config(&env, &config);
+
+        env.events().publish(
+            (symbol_short!("gov"), symbol_short!("quorum")),
+            (admin, quorum),
+        );
+    }
+
+    /// Set the participation quorum for proposals created from now on, so a few large
+    /// holders cannot pass a proposal on their own however much power they hold.
+    pub fn set_min_voters(env: Env, admin: Address, min_voters: u32) {
+        AccessControl::require_admin(&env, &admin);
+
+        let mut config = GovernanceStorageKey::get_config(&env).expect("Not initialized");
+        config.min_voters = min_voters;
+        GovernanceStorageKey::set_config(&env, &config);
+
+        env.events().publish(
+            (symbol_short!("gov"), symbol_short!("min_voter")),
+            (admin, min_voters),
+        );
+    }
+
+    /// Set the vote token balance a creator must hold to open a proposal, so proposals
+    /// (each of which takes a token snapshot and joins the open list) can't be spammed.
+    /// A threshold needs a vote token to measure it against.
+    pub fn set_proposal_threshold(env: Env, admin: Address, threshold: i128) {
+        AccessControl::require_admin(&env, &admin);
+        assert!(threshold >= 0, "Invalid threshold");
+        let config = GovernanceStorageKey::get_config(&env).expect("Not initialized");
+        assert!(threshold == 0 || config.token.is_some(), "Vote token not set");
+
+        GovernanceStorageKey::set_proposal_threshold(&env, threshold);
+
+        env.events().publish(
+            (symbol_short!("gov"), symbol_short!("threshold")),
+            (admin, threshold),
+        );
+    }
+
+    pub fn get_proposal_threshold(env: Env) -> i128 {
+        GovernanceStorageKey::get_proposal_threshold(&env)
+    }
+
+    pub fn get_min_voters(env: Env) -> u32 {
+        GovernanceStorageKey::get_config(&env)
+            .expect("Not initialized")
+            .min_voters
+    }
+
+    /// Set the timelock applied to proposals that pass from now on.
+    pub fn set_execution_delay(env: Env, admin: Address, execution_delay: u64) {
+        AccessControl::require_admin(&env, &admin);
+
+        let mut config = GovernanceStorageKey::get_config(&env).expect("Not initialized");
+        config.execution_delay = execution_delay;
+        GovernanceStorageKey::set_config(&env, &config);
+
+        env.events().publish(
+            (symbol_short!("gov"), symbol_short!("delay")),
+            (admin, execution_delay),
+        );
     }
 
     /// Create a proposal; voting runs until `vote_deadline` (now + configured period).
+    /// The creator must hold at least the proposal threshold of the vote token.
     pub fn create_proposal(env: Env, creator: Address, description: Symbol) -> u32 {
         creator.require_auth();
 
         let config = GovernanceStorageKey::get_config(&env).expect("Not initialized");
+        let threshold = GovernanceStorageKey::get_proposal_threshold(&env);
+        if threshold > 0 {
+            let token = config.token.as_ref().expect("Vote token not set");
+            assert!(
+                VoteTokenClient::new(&env, token).balance_of(&creator) >= threshold,
+                "Below proposal threshold"
+            );
+        }
 
         let count = GovernanceStorageKey::get_proposal_count(&env);
         let id = count
@@ -119,9 +540,32 @@ impl GovernanceContract {
             yes_votes: 0,
             no_votes: 0,
             status: Symbol::new(&env, "open"),
+            executed: false,
+            eta: 0,
+            quorum: config.quorum,
+            min_voters: config.min_voters,
+            voters: 0,
         };
 
         GovernanceStorageKey::set_proposal(&env, id, &proposal);
+        if let Some(token) = &config.token {
+            let snapshot_id =
+                VoteTokenClient::new(&env, token).snapshot(&env.current_contract_address());
+            GovernanceStorageKey::set_proposal_snapshot(&env, id, snapshot_id);
+            GovernanceStorageKey::set_snapshot_token(&env, id, token);
+        }
+
+        // Drop proposals whose voting has ended so the list stays bounded even when
+        // nobody finalizes them
+        let mut active = Vec::new(&env);
+        for open_id in GovernanceStorageKey::get_active_proposals(&env).iter() {
+            let open = GovernanceStorageKey::get_proposal(&env, open_id).expect("Proposal not found");
+            if now <= open.vote_deadline {
+                active.push_back(open_id);
+            }
+        }
+        active.push_back(id);
+        GovernanceStorageKey::set_active_proposals(&env, &active);
 
         env.events()
             .publish((symbol_short!("proposal"), symbol_short!("created")), id);
@@ -129,7 +573,51 @@ impl GovernanceContract {
         id
     }
 
+    /// Create a proposal that, once passed, applies `updates` to their target contracts
+    /// atomically in `execute_proposal`. If any target rejects its update, the whole
+    /// execution reverts and no parameter is changed.
+    pub fn create_param_proposal(
+        env: Env,
+        creator: Address,
+        description: Symbol,
+        updates: Vec<ParamUpdate>,
+    ) -> u32 {
+        assert!(!updates.is_empty(), "No parameter updates");
+        assert!(updates.len() <= MAX_PARAM_UPDATES, "Too many parameter updates");
+
+        let id = Self::create_proposal(env.clone(), creator, description);
+        GovernanceStorageKey::set_param_updates(&env, id, &updates);
+
+        id
+    }
+
+    /// Create a proposal that, once passed, forwards `new_implementation` to `proxy` as an
+    /// upgrade proposal in `execute_proposal`.
+    pub fn create_upgrade_proposal(
+        env: Env,
+        creator: Address,
+        description: Symbol,
+        proxy: Address,
+        new_implementation: BytesN<32>,
+    ) -> u32 {
+        let id = Self::create_proposal(env.clone(), creator, description);
+        GovernanceStorageKey::set_upgrade_action(
+            &env,
+            id,
+            &UpgradeAction {
+                proxy,
+                new_implementation,
+            },
+        );
+
+        id
+    }
+
     /// Cast a single vote (yes/no). Each address may vote at most once per proposal.
+    /// The vote carries the voter's balance plus the balances delegated to it, as of the
+    /// snapshot taken when the proposal was created, on the token it was taken on (current
+    /// balances for proposals made before a vote token was set); delegators counted this way
+    /// are marked as having voted.
     pub fn cast_vote(env: Env, voter: Address, proposal_id: u32, support: bool) {
         voter.require_auth();
 
@@ -137,6 +625,10 @@ impl GovernanceContract {
             !GovernanceStorageKey::has_voted(&env, &voter, proposal_id),
             "Already voted"
         );
+        assert!(
+            GovernanceStorageKey::get_delegate(&env, &voter).is_none(),
+            "Voting power delegated"
+        );
 
         let mut proposal =
             GovernanceStorageKey::get_proposal(&env, proposal_id).expect("Proposal not found");
@@ -149,25 +641,108 @@ impl GovernanceContract {
         let now = env.ledger().timestamp();
         assert!(now <= proposal.vote_deadline, "Voting period ended");
 
+        let config = GovernanceStorageKey::get_config(&env).expect("Not initialized");
+        // A snapshot id only means something on the token it was taken on, which stays in
+        // force for the proposal even if `set_vote_token` switches tokens afterwards
+        let token = GovernanceStorageKey::get_snapshot_token(&env, proposal_id).or(config.token);
+        let power = match &token {
+            Some(token) => {
+                let client = VoteTokenClient::new(&env, token);
+                let snapshot = GovernanceStorageKey::get_proposal_snapshot(&env, proposal_id);
+                let balance = |account: &Address| match snapshot {
+                    Some(snapshot_id) => client.balance_of_at(account, &snapshot_id),
+                    None => client.balance_of(account),
+                };
+                let mut power = Self::balance_power(balance(&voter));
+                for delegator in GovernanceStorageKey::get_delegators(&env, &voter).iter() {
+                    if GovernanceStorageKey::has_voted(&env, &delegator, proposal_id) {
+                        continue;
+                    }
+                    power = power.saturating_add(Self::balance_power(balance(&delegator)));
+                    GovernanceStorageKey::record_vote(
+                        &env,
+                        &delegator,
+                        proposal_id,
+                        &Vote { support, power: 0 },
+                    );
+                }
+                power
+            }
+            None => 1,
+        };
+        assert!(power > 0, "No voting power");
+
         if support {
-            proposal.yes_votes = proposal.yes_votes.saturating_add(1);
+            proposal.yes_votes = proposal.yes_votes.saturating_add(power);
         } else {
-            proposal.no_votes = proposal.no_votes.saturating_add(1);
+            proposal.no_votes = proposal.no_votes.saturating_add(power);
         }
+        proposal.voters = proposal.voters.saturating_add(1);
 
         GovernanceStorageKey::set_proposal(&env, proposal_id, &proposal);
-        GovernanceStorageKey::record_vote(&env, &voter, proposal_id);
+        GovernanceStorageKey::record_vote(&env, &voter, proposal_id, &Vote { support, power });
 
         env.events().publish(
             (symbol_short!("vote"), symbol_short!("cast")),
-            (proposal_id, voter, support),
+            (proposal_id, voter, support, power),
         );
     }
 
-    /// Close voting after the deadline and record outcome.
-    /// Only an admin can execute the outcome.
-    pub fn execute_proposal(env: Env, executor: Address, proposal_id: u32) {
-        AccessControl::require_admin(&env, &executor);
+    fn balance_power(balance: i128) -> u64 {
+        balance.clamp(0, u64::MAX as i128) as u64
+    }
+
+    /// Delegate voting power to `delegatee`, replacing any earlier delegation. A delegatee
+    /// accepts at most `MAX_DELEGATORS` delegators.
+    pub fn delegate_votes(env: Env, delegator: Address, delegatee: Address) {
+        delegator.require_auth();
+        assert!(delegator != delegatee, "Cannot delegate to self");
+
+        Self::clear_delegation(&env, &delegator);
+        GovernanceStorageKey::set_delegate(&env, &delegator, &delegatee);
+        let mut delegators = GovernanceStorageKey::get_delegators(&env, &delegatee);
+        assert!(delegators.len() < MAX_DELEGATORS, "Too many delegators");
+        delegators.push_back(delegator.clone());
+        GovernanceStorageKey::set_delegators(&env, &delegatee, &delegators);
+
+        env.events().publish(
+            (symbol_short!("vote"), symbol_short!("delegate")),
+            (delegator, delegatee),
+        );
+    }
+
+    /// Take back delegated voting power.
+    pub fn undelegate_votes(env: Env, delegator: Address) {
+        delegator.require_auth();
+        assert!(
+            GovernanceStorageKey::get_delegate(&env, &delegator).is_some(),
+            "Not delegated"
+        );
+        Self::clear_delegation(&env, &delegator);
+
+        env.events().publish(
+            (symbol_short!("vote"), symbol_short!("undeleg")),
+            delegator,
+        );
+    }
+
+    fn clear_delegation(env: &Env, delegator: &Address) {
+        if let Some(previous) = GovernanceStorageKey::get_delegate(env, delegator) {
+            let mut delegators = GovernanceStorageKey::get_delegators(env, &previous);
+            if let Some(i) = delegators.first_index_of(delegator) {
+                delegators.remove(i);
+            }
+            GovernanceStorageKey::set_delegators(env, &previous, &delegators);
+            GovernanceStorageKey::remove_delegate(env, delegator);
+        }
+    }
+
+    /// Switch an existing vote to the other side while voting is still open. The old vote
+    /// is removed from its tally so only the final choice counts.
+    pub fn change_vote(env: Env, voter: Address, proposal_id: u32, new_support: bool) {
+        voter.require_auth();
+
+        let vote = GovernanceStorageKey::get_vote(&env, &voter, proposal_id).expect("Not voted");
 
         let mut proposal =
             GovernanceStorageKey::get_proposal(&env, proposal_id).expect("Proposal not found");
@@ -177,14 +752,129 @@ impl GovernanceContract {
             "Proposal not open"
         );
 
+        let now = env.ledger().timestamp();
+        assert!(now <= proposal.vote_deadline, "Voting period ended");
+        assert!(vote.support != new_support, "Vote unchanged");
+
+        // The vote keeps the weight it was cast with
+        if new_support {
+            proposal.no_votes -= vote.power;
+            proposal.yes_votes = proposal.yes_votes.saturating_add(vote.power);
+        } else {
+            proposal.yes_votes -= vote.power;
+            proposal.no_votes = proposal.no_votes.saturating_add(vote.power);
+        }
+
+        GovernanceStorageKey::set_proposal(&env, proposal_id, &proposal);
+        GovernanceStorageKey::record_vote(
+            &env,
+            &voter,
+            proposal_id,
+            &Vote {
+                support: new_support,
+                power: vote.power,
+            },
+        );
+
+        env.events().publish(
+            (symbol_short!("vote"), symbol_short!("changed")),
+            (proposal_id, voter, new_support),
+        );
+    }
+
+    /// Close voting after the deadline and record the outcome. A passed proposal gets an
+    /// `eta` of now + the configured execution delay. Anyone may finalize.
+    pub fn finalize_proposal(env: Env, proposal_id: u32) -> Symbol {
+        let mut proposal =
+            GovernanceStorageKey::get_proposal(&env, proposal_id).expect("Proposal not found");
+        Self::close_voting(&env, &mut proposal);
+        GovernanceStorageKey::set_proposal(&env, proposal_id, &proposal);
+
+        env.events().publish(
+            (symbol_short!("proposal"), symbol_short!("finalized")),
+            (proposal_id, proposal.status.clone(), proposal.eta),
+        );
+
+        proposal.status
+    }
+
+    fn close_voting(env: &Env, proposal: &mut Proposal) {
+        assert!(
+            proposal.status == Symbol::new(env, "open"),
+            "Proposal not open"
+        );
+
         let now = env.ledger().timestamp();
         assert!(now > proposal.vote_deadline, "Voting still active");
 
-        proposal.status = if proposal.yes_votes > proposal.no_votes {
-            Symbol::new(&env, "passed")
+        if Self::standing(proposal) == symbol_short!("passing") {
+            let config = GovernanceStorageKey::get_config(env).expect("Not initialized");
+            proposal.status = Symbol::new(env, "passed");
+            proposal.eta = now.saturating_add(config.execution_delay);
         } else {
-            Symbol::new(&env, "rejected")
-        };
+            proposal.status = Symbol::new(env, "rejected");
+        }
+
+        let mut active = GovernanceStorageKey::get_active_proposals(env);
+        if let Some(index) = active.first_index_of(proposal.id) {
+            active.remove(index);
+            GovernanceStorageKey::set_active_proposals(env, &active);
+        }
+    }
+
+    /// Provisional outcome from the live tallies: `passing`, `failing` (majority against
+    /// or tied), `no_quorum` or `few_voter` (too few distinct voters). Quorum is checked
+    /// first, then participation.
+    pub fn proposal_standing(env: Env, proposal_id: u32) -> Symbol {
+        let proposal =
+            GovernanceStorageKey::get_proposal(&env, proposal_id).expect("Proposal not found");
+        Self::standing(&proposal)
+    }
+
+    fn standing(proposal: &Proposal) -> Symbol {
+        let total = proposal.yes_votes.saturating_add(proposal.no_votes);
+        if total < proposal.quorum {
+            symbol_short!("no_quorum")
+        } else if proposal.voters < proposal.min_voters {
+            symbol_short!("few_voter")
+        } else if proposal.yes_votes > proposal.no_votes {
+            symbol_short!("passing")
+        } else {
+            symbol_short!("failing")
+        }
+    }
+
+    /// Apply a passed proposal's parameter updates or upgrade and mark it executed, once
+    /// its timelock has elapsed. A proposal still open is finalized first, so with no
+    /// execution delay voting can be closed and executed in one call.
+    /// Only an admin can execute the outcome, and only once.
+    pub fn execute_proposal(env: Env, executor: Address, proposal_id: u32) {
+        AccessControl::require_admin(&env, &executor);
+
+        let mut proposal =
+            GovernanceStorageKey::get_proposal(&env, proposal_id).expect("Proposal not found");
+
+        assert!(!proposal.executed, "Proposal already executed");
+        if proposal.status == Symbol::new(&env, "open") {
+            Self::close_voting(&env, &mut proposal);
+        } else {
+            assert!(
+                proposal.status == Symbol::new(&env, "passed"),
+                "Proposal not passed"
+            );
+        }
+
+        if proposal.status == Symbol::new(&env, "passed") {
+            assert!(env.ledger().timestamp() >= proposal.eta, "Timelock active");
+
+            if let Some(updates) = GovernanceStorageKey::get_param_updates(&env, proposal_id) {
+                Self::apply_param_updates(&env, &updates);
+            }
+            if let Some(action) = GovernanceStorageKey::get_upgrade_action(&env, proposal_id) {
+                Self::forward_upgrade(&env, &action);
+            }
+            proposal.executed = true;
+        }
 
         GovernanceStorageKey::set_proposal(&env, proposal_id, &proposal);
 
@@ -194,6 +884,41 @@ impl GovernanceContract {
         );
     }
 
+    // A panicking target aborts the transaction, so either every update lands or none do.
+    // Updates targeting this contract are protocol-wide parameters kept in the registry.
+    fn apply_param_updates(env: &Env, updates: &Vec<ParamUpdate>) {
+        let self_addr = env.current_contract_address();
+        for update in updates.iter() {
+            if update.target == self_addr {
+                GovernanceStorageKey::set_protocol_param(env, &update.key, update.value);
+            } else {
+                ParamTargetClient::new(env, &update.target).set_param(
+                    &self_addr,
+                    &update.key,
+                    &update.value,
+                );
+            }
+
+            env.events().publish(
+                (symbol_short!("param"), symbol_short!("applied")),
+                (update.target, update.key, update.value),
+            );
+        }
+    }
+
+    fn forward_upgrade(env: &Env, action: &UpgradeAction) {
+        let proxy_proposal_id = ProxyClient::new(env, &action.proxy).propose_upgrade(
+            &env.current_contract_address(),
+            &action.new_implementation,
+            &None,
+        );
+
+        env.events().publish(
+            (symbol_short!("upgrade"), symbol_short!("forwarded")),
+            (action.proxy.clone(), proxy_proposal_id, action.new_implementation.clone()),
+        );
+    }
+
     // Role management functions
 
     pub fn set_role(env: Env, caller: Address, target: Address, role: u32, enabled: bool) {
@@ -231,7 +956,54 @@ impl GovernanceContract {
         GovernanceStorageKey::has_voted(&env, &voter, proposal_id)
     }
 
+    pub fn get_vote(env: Env, voter: Address, proposal_id: u32) -> Option<Vote> {
+        GovernanceStorageKey::get_vote(&env, &voter, proposal_id)
+    }
+
+    pub fn get_delegate(env: Env, delegator: Address) -> Option<Address> {
+        GovernanceStorageKey::get_delegate(&env, &delegator)
+    }
+
     pub fn get_proposal_count(env: Env) -> u32 {
         GovernanceStorageKey::get_proposal_count(&env)
     }
+
+    /// Proposals still accepting votes paired with the seconds left until their deadline,
+    /// in creation order. `start` skips that many open proposals; `limit` is capped at 50.
+    pub fn open_proposals(env: Env, start: u32, limit: u32) -> Vec<(u32, u64)> {
+        let limit = if limit > MAX_PAGE_SIZE { MAX_PAGE_SIZE } else { limit };
+        let now = env.ledger().timestamp();
+
+        let mut result = Vec::new(&env);
+        let mut skipped = 0u32;
+        for id in GovernanceStorageKey::get_active_proposals(&env).iter() {
+            if result.len() >= limit {
+                break;
+            }
+            let proposal = GovernanceStorageKey::get_proposal(&env, id).expect("Proposal not found");
+            // Past the deadline but not yet finalized: voting is over, so it is not open
+            if now > proposal.vote_deadline {
+                continue;
+            }
+            if skipped < start {
+                skipped += 1;
+            } else {
+                result.push_back((id, proposal.vote_deadline - now));
+            }
+        }
+        result
+    }
+
+    /// Protocol-wide parameter set by a passed proposal, read by other contracts as a shared value.
+    pub fn get_protocol_param(env: Env, key: Symbol) -> Option<i128> {
+        GovernanceStorageKey::get_protocol_param(&env, &key)
+    }
+
+    pub fn get_upgrade_action(env: Env, proposal_id: u32) -> Option<UpgradeAction> {
+        GovernanceStorageKey::get_upgrade_action(&env, proposal_id)
+    }
+
+    pub fn get_param_updates(env: Env, proposal_id: u32) -> Vec<ParamUpdate> {
+        GovernanceStorageKey::get_param_updates(&env, proposal_id).unwrap_or(Vec::new(&env))
+    }
 }
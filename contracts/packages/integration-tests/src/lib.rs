@@ -1,4 +1,7 @@
-use soroban_sdk::{testutils::Address as _, Address, Env, String, Symbol};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env, String, Symbol,
+};
 use airline::{AirlineContract, AirlineContractClient};
 use booking::{BookingContract, BookingContractClient};
 use governance::{GovernanceContract, GovernanceContractClient};
@@ -20,6 +23,9 @@ pub struct Contracts<'a> {
     pub refund_automation: RefundAutomationContractClient<'a>,
 }
 
+// Ledger time for tests that list flights relative to "now"
+pub const NOW: u64 = 2_000_000_000;
+
 pub struct Actors {
     pub admin: Address,
     pub passenger: Address,
@@ -82,3 +88,35 @@ pub fn register_and_verify_airline(
     );
     airline_client.verify_airline(owner, airline);
 }
+
+// Contracts at ledger time NOW with booking and airline admins set and a verified airline
+pub fn setup_airline(env: &Env) -> (Contracts<'_>, Actors) {
+    env.ledger().set_timestamp(NOW);
+    let actors = generate_actors(env);
+    let contracts = register_contracts(env);
+    contracts.booking.init_admin(&actors.admin);
+    contracts.airline.initialize(&actors.admin);
+    register_and_verify_airline(env, &contracts.airline, &actors.admin, &actors.airline);
+    (contracts, actors)
+}
+
+// Six-hour JFK-LAX flight listed by the actors' airline at a fare of 1_000
+pub fn create_flight(
+    env: &Env,
+    contracts: &Contracts,
+    actors: &Actors,
+    departure: u64,
+    seats: u32,
+) -> u64 {
+    contracts.airline.create_flight(
+        &actors.airline,
+        &Symbol::new(env, "TQ100"),
+        &Symbol::new(env, "JFK"),
+        &Symbol::new(env, "LAX"),
+        &departure,
+        &(departure + 6 * 3600),
+        &seats,
+        &1_000i128,
+        &Symbol::new(env, "USD"),
+    )
+}
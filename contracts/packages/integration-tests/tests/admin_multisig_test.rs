@@ -0,0 +1,275 @@
+use admin::{AdminActionType, AdminMultisig, AdminMultisigClient};
+use dispute::{DisputeContract, DisputeContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, Symbol,
+};
+
+use integration_tests::{generate_actors, initialize_token, new_env, register_contracts};
+
+#[test]
+fn test_approval_status_maps_each_signer() {
+    let env = new_env();
+    let multisig = AdminMultisigClient::new(&env, &env.register(AdminMultisig, ()));
+    let (a, b, c) = (
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+    multisig.initialize(&vec![&env, a.clone(), b.clone(), c.clone()], &2, &86_400);
+
+    // Proposing counts as the proposer's approval
+    let proposal_id = multisig.propose_admin_action(
+        &b,
+        &AdminActionType::EmergencyStop,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+    );
+
+    assert_eq!(
+        multisig.approval_status(&proposal_id),
+        vec![&env, (a, false), (b, true), (c, false)]
+    );
+}
+
+fn setup_two_of_two(env: &Env) -> (AdminMultisigClient<'_>, (Address, Address)) {
+    let (a, b) = (Address::generate(env), Address::generate(env));
+    let multisig = AdminMultisigClient::new(env, &env.register(AdminMultisig, ()));
+    multisig.initialize(&vec![env, a.clone(), b.clone()], &2, &86_400);
+    (multisig, (a, b))
+}
+
+fn run_action(multisig: &AdminMultisigClient, signers: &(Address, Address), action: AdminActionType) {
+    let (a, b) = signers;
+    let id = multisig.propose_admin_action(a, &action, &None, &None, &None, &None, &None);
+    multisig.approve_admin_action(b, &id);
+    multisig.execute_admin_action(a, &id);
+}
+
+#[test]
+fn test_emergency_stop_reported_by_every_linked_contract() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.airline.initialize(&actors.admin);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    let dispute = DisputeContractClient::new(&env, &env.register(DisputeContract, ()));
+    dispute.initialize(
+        &actors.admin,
+        &2000,
+        &3,
+        &86_400,
+        &86_400,
+        &86_400,
+        &86_400,
+        &5000,
+        &2000,
+        &contracts.token.address,
+    );
+
+    let (multisig, signers) = setup_two_of_two(&env);
+    let run = |action: AdminActionType| run_action(&multisig, &signers, action);
+
+    contracts.booking.init_admin(&actors.admin);
+    contracts.booking.set_admin_multisig(&actors.admin, &multisig.address);
+    contracts.airline.set_admin_multisig(&actors.admin, &multisig.address);
+    dispute.set_admin_multisig(&actors.admin, &multisig.address);
+    contracts.token.set_admin_multisig(&actors.admin, &multisig.address);
+
+    let stopped = || {
+        [
+            contracts.booking.is_emergency_stopped(),
+            contracts.airline.is_emergency_stopped(),
+            dispute.is_emergency_stopped(),
+            contracts.token.is_emergency_stopped(),
+        ]
+    };
+    assert_eq!(stopped(), [false; 4]);
+
+    run(AdminActionType::EmergencyStop);
+    assert_eq!(stopped(), [true; 4]);
+
+    run(AdminActionType::EmergencyResume);
+    assert_eq!(stopped(), [false; 4]);
+}
+
+#[test]
+#[should_panic(expected = "System paused")]
+fn test_emergency_stop_blocks_pay_for_booking() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    let (multisig, signers) = setup_two_of_two(&env);
+    contracts.booking.init_admin(&actors.admin);
+    contracts.booking.set_admin_multisig(&actors.admin, &multisig.address);
+
+    let price = 1_000i128;
+    let booking_id = contracts.booking.create_booking(
+        &actors.passenger,
+        &actors.airline,
+        &Symbol::new(&env, "FL123"),
+        &Symbol::new(&env, "JFK"),
+        &Symbol::new(&env, "LAX"),
+        &1_705_000_000,
+        &price,
+        &contracts.token.address,
+    );
+    contracts.token.mint(&actors.admin, &actors.passenger, &price);
+
+    run_action(&multisig, &signers, AdminActionType::EmergencyStop);
+    contracts.booking.pay_for_booking(&booking_id);
+}
+
+#[test]
+#[should_panic(expected = "Not an admin")]
+fn test_non_admin_cannot_relink_booking_multisig() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+
+    // Pointing booking at a multisig the caller controls would let them stop or resume it
+    let (multisig, _) = setup_two_of_two(&env);
+    contracts
+        .booking
+        .set_admin_multisig(&actors.passenger, &multisig.address);
+}
+
+#[test]
+fn test_emergency_resume_reopens_operations() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.airline.initialize(&actors.admin);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    let (multisig, signers) = setup_two_of_two(&env);
+    contracts.airline.set_admin_multisig(&actors.admin, &multisig.address);
+    contracts.token.set_admin_multisig(&actors.admin, &multisig.address);
+    contracts.token.mint(&actors.admin, &actors.passenger, &1_000);
+
+    run_action(&multisig, &signers, AdminActionType::EmergencyStop);
+    assert!(contracts
+        .token
+        .try_transfer(&actors.passenger, &actors.airline, &100)
+        .is_err());
+    // Entry points that don't move funds or inventory stay open
+    assert!(contracts
+        .airline
+        .try_register_airline(
+            &actors.airline,
+            &Symbol::new(&env, "TraqoraAir"),
+            &Symbol::new(&env, "TQ"),
+        )
+        .is_ok());
+
+    run_action(&multisig, &signers, AdminActionType::EmergencyResume);
+    contracts.token.transfer(&actors.passenger, &actors.airline, &100);
+    assert_eq!(contracts.token.balance_of(&actors.airline), 100);
+}
+
+#[test]
+fn test_parameter_change_updates_airline_pricing_config() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.airline.initialize(&actors.admin);
+    contracts
+        .airline
+        .initialize_pricing(&actors.admin, &Address::generate(&env), &3_600, &1_000, &5_000);
+
+    let (multisig, (a, b)) = setup_two_of_two(&env);
+    contracts.airline.set_admin_multisig(&actors.admin, &multisig.address);
+
+    let id = multisig.propose_admin_action(
+        &a,
+        &AdminActionType::ParameterChange,
+        &Some(contracts.airline.address.clone()),
+        &Some(Symbol::new(&env, "max_chg")),
+        &Some(1_500),
+        &None,
+        &None,
+    );
+    multisig.approve_admin_action(&b, &id);
+    multisig.execute_admin_action(&a, &id);
+
+    let cfg = contracts.airline.get_pricing_config().unwrap();
+    assert_eq!(cfg.max_change_bps, 1_500);
+    assert_eq!(cfg.cooldown_secs, 3_600);
+}
+
+#[test]
+fn test_parameter_change_rejected_without_linked_multisig() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.airline.initialize(&actors.admin);
+    contracts
+        .airline
+        .initialize_pricing(&actors.admin, &Address::generate(&env), &3_600, &1_000, &5_000);
+
+    // Only the parameter authority, by default the linked multisig, may set parameters
+    assert!(contracts
+        .airline
+        .try_set_param(&actors.admin, &Symbol::new(&env, "max_chg"), &1_500)
+        .is_err());
+
+    let (multisig, (a, b)) = setup_two_of_two(&env);
+    let id = multisig.propose_admin_action(
+        &a,
+        &AdminActionType::ParameterChange,
+        &Some(contracts.airline.address.clone()),
+        &Some(Symbol::new(&env, "cooldown")),
+        &Some(60),
+        &None,
+        &None,
+    );
+    multisig.approve_admin_action(&b, &id);
+    assert!(multisig.try_execute_admin_action(&a, &id).is_err());
+    assert_eq!(contracts.airline.get_pricing_config().unwrap().cooldown_secs, 3_600);
+}
+
+#[test]
+fn test_prune_removes_only_expired_unexecuted_proposals() {
+    let env = new_env();
+    let (multisig, signers) = setup_two_of_two(&env);
+    let (a, b) = &signers;
+
+    run_action(&multisig, &signers, AdminActionType::EmergencyStop);
+    let executed = multisig.get_proposal_count();
+    let expired = multisig.propose_admin_action(
+        a,
+        &AdminActionType::EmergencyResume,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+    );
+    multisig.approve_admin_action(b, &expired);
+
+    env.ledger().with_mut(|l| l.timestamp += 86_401);
+    let live = multisig.propose_admin_action(
+        a,
+        &AdminActionType::EmergencyResume,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+    );
+
+    multisig.prune_expired_proposals(b, &vec![&env, executed, expired, live]);
+
+    assert!(multisig.get_proposal(&expired).is_none());
+    assert!(!multisig.has_approved(&expired, a));
+    assert!(!multisig.has_approved(&expired, b));
+    assert!(multisig.get_proposal(&executed).unwrap().executed);
+    assert!(multisig.get_proposal(&live).is_some());
+    assert!(multisig.has_approved(&live, a));
+}
@@ -128,6 +128,7 @@ proptest! {
         register_and_verify_airline(&env, &contracts.airline, &actors.admin, &actors.airline);
 
         contracts.loyalty.init_loyalty();
+        contracts.loyalty.init_admin(&actors.admin);
 
         let total_mint = if price1 > price2 { price1 } else { price2 } * 2;
         contracts
@@ -145,7 +146,9 @@ proptest! {
             &contracts.token.address,
         );
         contracts.booking.pay_for_booking(&booking1);
-        let points1 = contracts.loyalty.award_points(&actors.passenger, &price1, &booking1);
+        let points1 = contracts
+            .loyalty
+            .award_points(&actors.admin, &actors.passenger, &price1, &booking1);
 
         let booking2 = contracts.booking.create_booking(
             &actors.passenger,
@@ -158,7 +161,9 @@ proptest! {
             &contracts.token.address,
         );
         contracts.booking.pay_for_booking(&booking2);
-        let points2 = contracts.loyalty.award_points(&actors.passenger, &price2, &booking2);
+        let points2 = contracts
+            .loyalty
+            .award_points(&actors.admin, &actors.passenger, &price2, &booking2);
 
         // Points should scale with price (higher price → higher points)
         if price1 > price2 {
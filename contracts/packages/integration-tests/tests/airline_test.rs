@@ -1,22 +1,22 @@
-use soroban_sdk::{testutils::Address as _, Address, Env, Symbol, Vec};
-use airline::{
-    AirlineContract,
-    AirlineContractClient,
-    BatchCreateFlightsResult,
-    BatchUpdateFlightStatusResult,
-    Flight,
-    FlightInput,
-    FlightStatusUpdate,
+use airline::{FlightInput, FlightStatusUpdate};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env, Symbol, Vec,
 };
 
+use integration_tests::{
+    create_flight, generate_actors, initialize_token, new_env, register_and_verify_airline,
+    register_contracts, setup_airline, Actors, Contracts, NOW,
+};
 
-use integration_tests::{new_env, generate_actors, register_contracts, register_and_verify_airline};
+const DAY: u64 = 86_400;
 
 #[test]
 fn test_register_and_verify_airline() {
     let env = new_env();
     let actors = generate_actors(&env);
     let contracts = register_contracts(&env);
+    contracts.airline.initialize(&actors.admin);
 
     contracts.airline.register_airline(
         &actors.airline,
@@ -38,7 +38,8 @@ fn test_create_flight_requires_verified_airline_and_reserve_seat() {
     let env = new_env();
     let actors = generate_actors(&env);
     let contracts = register_contracts(&env);
-    register_and_verify_airline(&env, &contracts.airline, &actors.airline);
+    contracts.airline.initialize(&actors.admin);
+    register_and_verify_airline(&env, &contracts.airline, &actors.admin, &actors.airline);
 
     let flight_id = contracts.airline.create_flight(
         &actors.airline,
@@ -66,7 +67,8 @@ fn test_cancel_flight_and_unauthorized_changes() {
     let env = new_env();
     let actors = generate_actors(&env);
     let contracts = register_contracts(&env);
-    register_and_verify_airline(&env, &contracts.airline, &actors.airline);
+    contracts.airline.initialize(&actors.admin);
+    register_and_verify_airline(&env, &contracts.airline, &actors.admin, &actors.airline);
 
     let flight_id = contracts.airline.create_flight(
         &actors.airline,
@@ -91,7 +93,8 @@ fn test_batch_create_flights_partial_failure() {
     let env = new_env();
     let actors = generate_actors(&env);
     let contracts = register_contracts(&env);
-    register_and_verify_airline(&env, &contracts.airline, &actors.airline);
+    contracts.airline.initialize(&actors.admin);
+    register_and_verify_airline(&env, &contracts.airline, &actors.admin, &actors.airline);
 
     let mut batch = Vec::new(&env);
     batch.push_back(FlightInput {
@@ -144,10 +147,11 @@ fn test_batch_update_flight_status_partial_failure() {
     let env = new_env();
     let actors = generate_actors(&env);
     let contracts = register_contracts(&env);
-    register_and_verify_airline(&env, &contracts.airline, &actors.airline);
+    contracts.airline.initialize(&actors.admin);
+    register_and_verify_airline(&env, &contracts.airline, &actors.admin, &actors.airline);
 
     let other_airline = Address::generate(&env);
-    register_and_verify_airline(&env, &contracts.airline, &other_airline);
+    register_and_verify_airline(&env, &contracts.airline, &actors.admin, &other_airline);
 
     let my_flight = contracts.airline.create_flight(
         &actors.airline,
@@ -203,7 +207,8 @@ fn test_batch_create_flights_enforces_max_batch_size() {
     let env = new_env();
     let actors = generate_actors(&env);
     let contracts = register_contracts(&env);
-    register_and_verify_airline(&env, &contracts.airline, &actors.airline);
+    contracts.airline.initialize(&actors.admin);
+    register_and_verify_airline(&env, &contracts.airline, &actors.admin, &actors.airline);
 
     let mut batch = Vec::new(&env);
     let mut i = 0;
@@ -223,3 +228,583 @@ fn test_batch_create_flights_enforces_max_batch_size() {
 
     contracts.airline.batch_create_flights(&actors.airline, &batch);
 }
+
+#[test]
+#[should_panic(expected = "Not an admin")]
+fn test_unauthorized_address_cannot_verify_airline() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.airline.initialize(&actors.admin);
+
+    contracts.airline.register_airline(
+        &actors.airline,
+        &Symbol::new(&env, "Traqora"),
+        &Symbol::new(&env, "TQ"),
+    );
+    let attacker = Address::generate(&env);
+    contracts.airline.verify_airline(&attacker, &actors.airline);
+}
+
+#[test]
+fn test_is_bookable_for_active_future_flight() {
+    let env = new_env();
+    let (contracts, actors) = setup_airline(&env);
+
+    let flight_id = create_flight(&env, &contracts, &actors, NOW + 86_400, 10);
+    assert!(contracts.airline.is_bookable(&flight_id));
+}
+
+#[test]
+fn test_is_not_bookable_when_sold_out() {
+    let env = new_env();
+    let (contracts, actors) = setup_airline(&env);
+
+    let flight_id = create_flight(&env, &contracts, &actors, NOW + 86_400, 1);
+    contracts.airline.reserve_seat(&actors.airline, &flight_id);
+    assert!(!contracts.airline.is_bookable(&flight_id));
+}
+
+#[test]
+fn test_is_not_bookable_when_cancelled() {
+    let env = new_env();
+    let (contracts, actors) = setup_airline(&env);
+
+    let flight_id = create_flight(&env, &contracts, &actors, NOW + 86_400, 10);
+    contracts.airline.cancel_flight(&actors.airline, &flight_id);
+    assert!(!contracts.airline.is_bookable(&flight_id));
+}
+
+#[test]
+fn test_is_not_bookable_after_departure_or_inside_cutoff() {
+    let env = new_env();
+    let (contracts, actors) = setup_airline(&env);
+
+    let flight_id = create_flight(&env, &contracts, &actors, NOW + 1_800, 10);
+    // Default cutoff is one hour, so a flight 30 minutes out is closed
+    assert!(!contracts.airline.is_bookable(&flight_id));
+
+    contracts.airline.set_booking_cutoff(&actors.admin, &600);
+    assert!(contracts.airline.is_bookable(&flight_id));
+
+    env.ledger().set_timestamp(NOW + 3_600);
+    assert!(!contracts.airline.is_bookable(&flight_id));
+}
+
+#[test]
+#[should_panic(expected = "Departure too far in future")]
+fn test_create_flight_beyond_max_future_window_should_panic() {
+    let env = new_env();
+    let (contracts, actors) = setup_airline(&env);
+    contracts
+        .airline
+        .set_max_future_secs(&actors.admin, &(365 * 86_400));
+
+    // A year-3000 departure is almost certainly a typo
+    create_flight(&env, &contracts, &actors, 32_503_680_000, 10);
+}
+
+#[test]
+fn test_create_flight_within_max_future_window() {
+    let env = new_env();
+    let (contracts, actors) = setup_airline(&env);
+    contracts
+        .airline
+        .set_max_future_secs(&actors.admin, &(365 * 86_400));
+
+    let flight_id = create_flight(&env, &contracts, &actors, NOW + 365 * 86_400, 10);
+    assert!(contracts.airline.is_bookable(&flight_id));
+}
+
+fn link_booking(contracts: &Contracts, actors: &Actors) {
+    contracts
+        .airline
+        .set_booking_contract(&actors.admin, &contracts.booking.address);
+    contracts
+        .booking
+        .set_airline_registry(&actors.admin, &contracts.airline.address);
+}
+
+#[test]
+#[should_panic(expected = "No seats available")]
+fn test_booking_full_flight_should_panic() {
+    let env = new_env();
+    let (contracts, actors) = setup_airline(&env);
+    link_booking(&contracts, &actors);
+
+    let flight_id = create_flight(&env, &contracts, &actors, NOW + 10 * 86_400, 1);
+    contracts
+        .booking
+        .book_flight(&actors.passenger, &flight_id, &contracts.token.address);
+    assert!(!contracts.airline.is_bookable(&flight_id));
+
+    contracts
+        .booking
+        .book_flight(&actors.passenger, &flight_id, &contracts.token.address);
+}
+
+#[test]
+#[should_panic(expected = "Book listed flights with book_flight")]
+fn test_free_form_booking_cannot_bypass_inventory() {
+    let env = new_env();
+    let (contracts, actors) = setup_airline(&env);
+    link_booking(&contracts, &actors);
+
+    // The only seat is taken; a hand-built booking for the same flight must not get around that
+    let flight_id = create_flight(&env, &contracts, &actors, NOW + 10 * 86_400, 1);
+    contracts
+        .booking
+        .book_flight(&actors.passenger, &flight_id, &contracts.token.address);
+    contracts.booking.create_booking(
+        &actors.passenger,
+        &actors.airline,
+        &Symbol::new(&env, "TQ100"),
+        &Symbol::new(&env, "JFK"),
+        &Symbol::new(&env, "LAX"),
+        &(NOW + 10 * 86_400),
+        &1_000i128,
+        &contracts.token.address,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Invalid seat count")]
+fn test_booking_zero_seats_should_panic() {
+    let env = new_env();
+    let (contracts, actors) = setup_airline(&env);
+    link_booking(&contracts, &actors);
+
+    let flight_id = create_flight(&env, &contracts, &actors, NOW + 10 * 86_400, 10);
+    contracts
+        .booking
+        .book_flight_seats(&actors.passenger, &flight_id, &0, &contracts.token.address);
+}
+
+#[test]
+fn test_refunded_booking_releases_its_seat() {
+    let env = new_env();
+    let (contracts, actors) = setup_airline(&env);
+    link_booking(&contracts, &actors);
+
+    let flight_id = create_flight(&env, &contracts, &actors, NOW + 10 * 86_400, 1);
+    let booking_id = contracts
+        .booking
+        .book_flight(&actors.passenger, &flight_id, &contracts.token.address);
+    let booking = contracts.booking.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.flight_number, Symbol::new(&env, "TQ100"));
+    assert_eq!(booking.price, 1_000);
+
+    contracts.booking.refund_passenger(&booking_id);
+    assert_eq!(
+        contracts.airline.get_flight(&flight_id).unwrap().available_seats,
+        1
+    );
+    assert!(contracts.airline.is_bookable(&flight_id));
+}
+
+#[test]
+fn test_group_booking_escrows_and_holds_every_seat() {
+    let env = new_env();
+    let (contracts, actors) = setup_airline(&env);
+    link_booking(&contracts, &actors);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    let flight_id = create_flight(&env, &contracts, &actors, NOW + 10 * 86_400, 5);
+    let booking_id = contracts.booking.book_flight_seats(
+        &actors.passenger,
+        &flight_id,
+        &3,
+        &contracts.token.address,
+    );
+    contracts
+        .token
+        .mint(&actors.admin, &actors.passenger, &3_000);
+    contracts.booking.pay_for_booking(&booking_id);
+
+    let booking = contracts.booking.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.seat_count, 3);
+    assert_eq!(booking.amount_escrowed, 3_000);
+    assert_eq!(
+        contracts.airline.get_flight(&flight_id).unwrap().available_seats,
+        2
+    );
+
+    // One traveller drops out and gets their share back
+    assert_eq!(contracts.booking.refund_seats(&booking_id, &1), 1_000);
+    let booking = contracts.booking.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.seat_count, 2);
+    assert_eq!(booking.amount_escrowed, 2_000);
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 1_000);
+    assert_eq!(
+        contracts.airline.get_flight(&flight_id).unwrap().available_seats,
+        3
+    );
+}
+
+#[test]
+fn test_group_seat_refund_follows_refund_policy() {
+    let env = new_env();
+    let (contracts, actors) = setup_airline(&env);
+    link_booking(&contracts, &actors);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    // Full refund from 72h out, half from 24h out
+    contracts
+        .refund
+        .set_refund_policy(&actors.airline, &259_200, &10_000, &5_000, &86_400);
+    contracts
+        .booking
+        .set_refund_contract(&actors.admin, &contracts.refund.address);
+
+    let departure = NOW + 10 * 86_400;
+    let flight_id = create_flight(&env, &contracts, &actors, departure, 5);
+    let booking_id = contracts.booking.book_flight_seats(
+        &actors.passenger,
+        &flight_id,
+        &3,
+        &contracts.token.address,
+    );
+    contracts
+        .token
+        .mint(&actors.admin, &actors.passenger, &3_000);
+    contracts.booking.pay_for_booking(&booking_id);
+    env.ledger().set_timestamp(departure - 2 * 86_400);
+
+    // Half of the seat's 1000 share comes back; the airline keeps the rest as its fee
+    assert_eq!(contracts.booking.refund_seats(&booking_id, &1), 500);
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 500);
+    assert_eq!(contracts.token.balance_of(&actors.airline), 500);
+    let booking = contracts.booking.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.seat_count, 2);
+    assert_eq!(booking.amount_escrowed, 2_000);
+}
+
+#[test]
+#[should_panic(expected = "No seats available")]
+fn test_group_booking_larger_than_availability_should_panic() {
+    let env = new_env();
+    let (contracts, actors) = setup_airline(&env);
+    link_booking(&contracts, &actors);
+
+    let flight_id = create_flight(&env, &contracts, &actors, NOW + 10 * 86_400, 2);
+    contracts.booking.book_flight_seats(
+        &actors.passenger,
+        &flight_id,
+        &3,
+        &contracts.token.address,
+    );
+}
+
+#[test]
+fn test_upcoming_flights_sorted_by_departure() {
+    let env = new_env();
+    let (contracts, actors) = setup_airline(&env);
+
+    let third = create_flight(&env, &contracts, &actors, NOW + 3 * 86_400, 10);
+    let first = create_flight(&env, &contracts, &actors, NOW + 86_400, 10);
+    let cancelled = create_flight(&env, &contracts, &actors, NOW + 43_200, 10);
+    contracts.airline.cancel_flight(&actors.airline, &cancelled);
+    let second = create_flight(&env, &contracts, &actors, NOW + 2 * 86_400, 10);
+    contracts.airline.create_flight(
+        &actors.airline,
+        &Symbol::new(&env, "TQ101"),
+        &Symbol::new(&env, "LAX"),
+        &Symbol::new(&env, "JFK"),
+        &(NOW + 3_600 * 2),
+        &(NOW + 3_600 * 8),
+        &10,
+        &1_000i128,
+        &Symbol::new(&env, "USD"),
+    );
+
+    let jfk = Symbol::new(&env, "JFK");
+    let lax = Symbol::new(&env, "LAX");
+    let ids = |limit: u32| {
+        let mut ids = soroban_sdk::Vec::new(&env);
+        for flight in contracts.airline.upcoming_flights(&jfk, &lax, &limit).iter() {
+            ids.push_back(flight.flight_id);
+        }
+        ids
+    };
+    assert_eq!(ids(10), soroban_sdk::vec![&env, first, second, third]);
+    assert_eq!(ids(2), soroban_sdk::vec![&env, first, second]);
+
+    // Departed flights drop out
+    env.ledger().set_timestamp(NOW + 86_400);
+    assert_eq!(ids(10), soroban_sdk::vec![&env, second, third]);
+}
+
+#[test]
+fn test_overbooking_allows_reserving_past_total_seats() {
+    let env = new_env();
+    let (contracts, actors) = setup_airline(&env);
+
+    // 10% of 20 seats lets two extra seats be sold
+    let flight_id = create_flight(&env, &contracts, &actors, NOW + 86_400, 20);
+    contracts.airline.set_overbook_bps(&actors.airline, &flight_id, &1_000);
+
+    for _ in 0..22 {
+        contracts.airline.reserve_seat(&actors.airline, &flight_id);
+    }
+    assert_eq!(contracts.airline.get_flight(&flight_id).unwrap().available_seats, 0);
+    assert_eq!(contracts.airline.get_oversold(&flight_id), 2);
+    assert!(!contracts.airline.is_bookable(&flight_id));
+
+    let res = contracts.airline.try_reserve_seat(&actors.airline, &flight_id);
+    assert!(res.is_err());
+    assert_eq!(contracts.airline.get_oversold(&flight_id), 2);
+}
+
+#[test]
+fn test_released_seats_reduce_oversold_first() {
+    let env = new_env();
+    let (contracts, actors) = setup_airline(&env);
+    link_booking(&contracts, &actors);
+
+    let flight_id = create_flight(&env, &contracts, &actors, NOW + 10 * 86_400, 10);
+    contracts.airline.set_overbook_bps(&actors.airline, &flight_id, &1_000);
+    for _ in 0..10 {
+        contracts.airline.reserve_seat(&actors.airline, &flight_id);
+    }
+
+    let booking_id = contracts
+        .booking
+        .book_flight(&actors.passenger, &flight_id, &contracts.token.address);
+    assert_eq!(contracts.airline.get_oversold(&flight_id), 1);
+
+    contracts.booking.refund_passenger(&booking_id);
+    assert_eq!(contracts.airline.get_oversold(&flight_id), 0);
+    assert_eq!(contracts.airline.get_flight(&flight_id).unwrap().available_seats, 0);
+    assert!(contracts.airline.is_bookable(&flight_id));
+}
+
+#[test]
+#[should_panic(expected = "Overbooking too high")]
+fn test_overbook_bps_above_cap_should_panic() {
+    let env = new_env();
+    let (contracts, actors) = setup_airline(&env);
+
+    let flight_id = create_flight(&env, &contracts, &actors, NOW + 86_400, 20);
+    contracts.airline.set_overbook_bps(&actors.airline, &flight_id, &1_001);
+}
+
+#[test]
+fn test_load_factor_frozen_when_flight_completes() {
+    let env = new_env();
+    let (contracts, actors) = setup_airline(&env);
+
+    let flight_id = create_flight(&env, &contracts, &actors, NOW + 86_400, 8);
+    assert_eq!(contracts.airline.load_factor_bps(&flight_id), 0);
+
+    for _ in 0..6 {
+        contracts.airline.reserve_seat(&actors.airline, &flight_id);
+    }
+    assert_eq!(contracts.airline.load_factor_bps(&flight_id), 7_500);
+
+    contracts.airline.batch_update_flight_status(
+        &actors.airline,
+        &vec![
+            &env,
+            FlightStatusUpdate {
+                flight_id,
+                status: Symbol::new(&env, "completed"),
+            },
+        ],
+    );
+    assert_eq!(contracts.airline.load_factor_bps(&flight_id), 7_500);
+
+    // Seat changes after completion don't move the snapshot
+    contracts.airline.reserve_seat(&actors.airline, &flight_id);
+    assert_eq!(contracts.airline.load_factor_bps(&flight_id), 7_500);
+}
+
+fn setup_pricing(env: &Env) -> (Contracts<'_>, Actors) {
+    let (contracts, actors) = setup_airline(env);
+    contracts.airline.initialize_pricing(
+        &actors.admin,
+        &Address::generate(env),
+        &3600,
+        &2_000,
+        &5_000,
+    );
+    (contracts, actors)
+}
+
+#[test]
+fn test_surge_summary_flags_flights_near_departure() {
+    let env = new_env();
+    let (contracts, actors) = setup_pricing(&env);
+
+    let far = create_flight(&env, &contracts, &actors, NOW + 10 * DAY, 100);
+    let near = create_flight(&env, &contracts, &actors, NOW + DAY, 100);
+    let cancelled = create_flight(&env, &contracts, &actors, NOW + DAY, 100);
+    contracts.airline.cancel_flight(&actors.airline, &cancelled);
+
+    let summary = contracts.airline.surge_summary(&actors.airline, &0, &10);
+    assert_eq!(summary.len(), 2);
+
+    let (far_id, far_base, far_current, far_multiplier) = summary.get(0).unwrap();
+    assert_eq!(far_id, far);
+    assert_eq!(far_base, 1_000);
+    assert_eq!(far_current, 1_000);
+    assert_eq!(far_multiplier, 10_000);
+
+    let (near_id, near_base, near_current, near_multiplier) = summary.get(1).unwrap();
+    assert_eq!(near_id, near);
+    assert_eq!(near_base, 1_000);
+    assert_eq!(near_current, contracts.airline.get_current_price(&near));
+    assert!(near_multiplier > 10_000);
+}
+
+#[test]
+fn test_surge_summary_is_paginated_per_airline() {
+    let env = new_env();
+    let (contracts, actors) = setup_pricing(&env);
+
+    let first = create_flight(&env, &contracts, &actors, NOW + 10 * DAY, 100);
+    let second = create_flight(&env, &contracts, &actors, NOW + 10 * DAY, 100);
+
+    let page = contracts.airline.surge_summary(&actors.airline, &1, &5);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().0, second);
+    assert_eq!(contracts.airline.surge_summary(&actors.airline, &0, &1).get(0).unwrap().0, first);
+
+    let other = Address::generate(&env);
+    assert_eq!(contracts.airline.surge_summary(&other, &0, &10).len(), 0);
+}
+
+#[test]
+fn test_effective_price_combines_surge_and_tier_fee_rebate() {
+    let env = new_env();
+    let (contracts, actors) = setup_pricing(&env);
+    contracts
+        .booking
+        .set_airline_registry(&actors.admin, &contracts.airline.address);
+    contracts.loyalty.init_loyalty();
+    contracts.loyalty.init_admin(&actors.admin);
+    contracts
+        .booking
+        .set_loyalty_contract(&actors.admin, &contracts.loyalty.address);
+    contracts
+        .booking
+        .set_protocol_fee(&actors.admin, &500, &Address::generate(&env)); // 5%
+
+    // Gold requires 5000 points and 20 bookings
+    let gold_passenger = Address::generate(&env);
+    for i in 0..20 {
+        contracts.loyalty.award_points(&actors.admin, &gold_passenger, &1_000, &i);
+    }
+
+    let flight_id = create_flight(&env, &contracts, &actors, NOW + DAY, 100);
+    let current = contracts.airline.get_current_price(&flight_id);
+    assert!(current > 1_000);
+
+    let bronze = contracts.booking.effective_price_for(&actors.passenger, &flight_id);
+    let gold = contracts.booking.effective_price_for(&gold_passenger, &flight_id);
+    let fee = current * 500 / 10_000;
+    assert_eq!(bronze, current + fee);
+    assert_eq!(gold, current + fee - fee / 10); // 10% gold rebate on the fee
+}
+
+#[test]
+fn test_booking_charges_the_quoted_effective_price() {
+    let env = new_env();
+    let (contracts, actors) = setup_pricing(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts
+        .airline
+        .set_booking_contract(&actors.admin, &contracts.booking.address);
+    contracts
+        .booking
+        .set_airline_registry(&actors.admin, &contracts.airline.address);
+    let treasury = Address::generate(&env);
+    contracts.booking.set_protocol_fee(&actors.admin, &500, &treasury);
+
+    // Near departure the live price is above the listed 1_000
+    let flight_id = create_flight(&env, &contracts, &actors, NOW + DAY, 100);
+    let quoted = contracts.booking.effective_price_for(&actors.passenger, &flight_id);
+    let current = contracts.airline.get_current_price(&flight_id);
+    assert!(current > 1_000);
+
+    contracts.token.mint(&actors.admin, &actors.passenger, &quoted);
+    let booking_id = contracts
+        .booking
+        .book_flight(&actors.passenger, &flight_id, &contracts.token.address);
+    assert_eq!(contracts.booking.get_booking(&booking_id).unwrap().price, current);
+
+    contracts.booking.pay_for_booking(&booking_id);
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 0);
+    assert_eq!(contracts.token.balance_of(&treasury), quoted - current);
+}
+
+// Listed flight booked through the airline's inventory and paid into escrow
+fn create_paid_booking(env: &Env, contracts: &Contracts, actors: &Actors) -> u64 {
+    initialize_token(env, &contracts.token, &actors.admin);
+    link_booking(contracts, actors);
+    let flight_id = create_flight(env, contracts, actors, NOW + 10 * DAY, 100);
+    let booking_id = contracts
+        .booking
+        .book_flight(&actors.passenger, &flight_id, &contracts.token.address);
+    contracts
+        .token
+        .mint(&actors.admin, &actors.passenger, &1_000);
+    contracts.booking.pay_for_booking(&booking_id);
+    booking_id
+}
+
+#[test]
+#[should_panic(expected = "Airline suspended")]
+fn test_suspended_airline_cannot_create_flights() {
+    let env = new_env();
+    let (contracts, actors) = setup_airline(&env);
+
+    contracts
+        .airline
+        .suspend_airline(&actors.admin, &actors.airline);
+    assert!(contracts.airline.is_suspended(&actors.airline));
+
+    create_flight(&env, &contracts, &actors, NOW + DAY, 100);
+}
+
+#[test]
+fn test_settlement_to_suspended_airline_is_held() {
+    let env = new_env();
+    let (contracts, actors) = setup_airline(&env);
+    let price = 1_000i128;
+    let booking_id = create_paid_booking(&env, &contracts, &actors);
+
+    contracts
+        .airline
+        .suspend_airline(&actors.admin, &actors.airline);
+    contracts.booking.release_payment_to_airline(&booking_id);
+
+    assert_eq!(contracts.token.balance_of(&actors.airline), 0);
+    assert_eq!(contracts.token.balance_of(&contracts.booking.address), price);
+    assert_eq!(
+        contracts
+            .booking
+            .get_held_funds(&actors.airline, &contracts.token.address),
+        price
+    );
+    assert!(contracts
+        .booking
+        .try_claim_held_funds(&actors.airline, &contracts.token.address)
+        .is_err());
+
+    // Held funds are paid out after reinstatement
+    contracts
+        .airline
+        .reinstate_airline(&actors.admin, &actors.airline);
+    assert_eq!(
+        contracts
+            .booking
+            .claim_held_funds(&actors.airline, &contracts.token.address),
+        price
+    );
+    assert_eq!(contracts.token.balance_of(&actors.airline), price);
+    assert_eq!(
+        contracts
+            .booking
+            .get_held_funds(&actors.airline, &contracts.token.address),
+        0
+    );
+}
@@ -1,5 +1,5 @@
 use soroban_sdk::{testutils::Address as _, Address, Env, Symbol, Vec};
-use booking::{BookingContract, BookingContractClient};
+use booking::{BookingContract, BookingContractClient, BookingStatus};
 use token::{TRQTokenContract, TRQTokenContractClient};
 
 
@@ -116,7 +116,7 @@ fn test_release_payment_success() {
         .booking
         .release_payment_to_airline(&booking_id);
     let booking = contracts.booking.get_booking(&booking_id).unwrap();
-    assert_eq!(booking.status, Symbol::new(&env, "completed"));
+    assert_eq!(booking.status, BookingStatus::Completed);
     assert_eq!(booking.amount_escrowed, 0);
 }
 
@@ -144,7 +144,7 @@ fn test_refund_passenger_window_and_status_checks() {
     // Pending -> refundable, but amount_escrowed = 0
     contracts.booking.refund_passenger(&booking_id);
     let booking = contracts.booking.get_booking(&booking_id).unwrap();
-    assert_eq!(booking.status, Symbol::new(&env, "refunded"));
+    assert_eq!(booking.status, BookingStatus::Refunded);
     assert_eq!(booking.amount_escrowed, 0);
 
     // Reset to confirmed with escrow to test transfer
@@ -210,7 +210,7 @@ fn test_cancel_and_complete_wrappers() {
     // Cancel wrapper (pending -> refunded)
     contracts.booking.cancel_booking(&actors.passenger, &booking_id);
     let b = contracts.booking.get_booking(&booking_id).unwrap();
-    assert_eq!(b.status, Symbol::new(&env, "refunded"));
+    assert_eq!(b.status, BookingStatus::Refunded);
 
     // Complete wrapper path
     let booking_id2 = contracts.booking.create_booking(
@@ -227,7 +227,7 @@ fn test_cancel_and_complete_wrappers() {
     contracts.booking.pay_for_booking(&booking_id2);
     contracts.booking.complete_booking(&actors.airline, &booking_id2);
     let b2 = contracts.booking.get_booking(&booking_id2).unwrap();
-    assert_eq!(b2.status, Symbol::new(&env, "completed"));
+    assert_eq!(b2.status, BookingStatus::Completed);
 }
 
 #[test]
@@ -290,13 +290,13 @@ fn test_batch_complete_bookings_partial_failure() {
     assert_eq!(result.total_released, price_ok);
 
     let completed = contracts.booking.get_booking(&booking_ok).unwrap();
-    assert_eq!(completed.status, Symbol::new(&env, "completed"));
+    assert_eq!(completed.status, BookingStatus::Completed);
 
     let pending = contracts.booking.get_booking(&booking_pending).unwrap();
-    assert_eq!(pending.status, Symbol::new(&env, "pending"));
+    assert_eq!(pending.status, BookingStatus::Pending);
 
     let untouched_other = contracts.booking.get_booking(&other_booking).unwrap();
-    assert_eq!(untouched_other.status, Symbol::new(&env, "confirmed"));
+    assert_eq!(untouched_other.status, BookingStatus::Confirmed);
 }
 
 #[test]
@@ -1,5 +1,139 @@
+use booking::{BookingStatus, LegacyBooking};
 use integration_tests::{generate_actors, initialize_token, new_env, register_contracts};
-use soroban_sdk::{testutils::Ledger, Symbol};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Ledger},
+    token, vec,
+    xdr::{FromXdr, ToXdr},
+    Address, Env, Symbol,
+};
+use storage_version::{VersionedStorage, BOOKING_CONTRACT};
+
+// Minimal yield source: tracks deposits per owner and pays withdrawals from its own balance.
+#[contract]
+pub struct MockYieldSource;
+
+#[contractimpl]
+impl MockYieldSource {
+    pub fn init(env: Env, token: Address) {
+        env.storage().instance().set(&symbol_short!("token"), &token);
+    }
+
+    pub fn deposit(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        let held = Self::balance(env.clone(), from.clone());
+        env.storage().instance().set(&from, &(held + amount));
+    }
+
+    pub fn withdraw(env: Env, owner: Address, amount: i128) {
+        owner.require_auth();
+        let held = Self::balance(env.clone(), owner.clone());
+        assert!(held >= amount, "Insufficient deposit");
+        env.storage().instance().set(&owner, &(held - amount));
+        let token: Address = env.storage().instance().get(&symbol_short!("token")).unwrap();
+        token::Client::new(&env, &token).transfer(&env.current_contract_address(), &owner, &amount);
+    }
+
+    pub fn balance(env: Env, owner: Address) -> i128 {
+        env.storage().instance().get(&owner).unwrap_or(0)
+    }
+
+    // Test hook: credit interest that has already been funded to this contract
+    pub fn accrue(env: Env, owner: Address, amount: i128) {
+        let held = Self::balance(env.clone(), owner.clone());
+        env.storage().instance().set(&owner, &(held + amount));
+    }
+}
+
+// Token whose transfers can be switched to fail, to exercise refund failure handling.
+// Kept in its own module so its exported fns don't clash with the yield source's.
+mod failing_token {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env};
+
+    #[contract]
+    pub struct MockFailingToken;
+
+    #[contractimpl]
+    impl MockFailingToken {
+        pub fn mint(env: Env, to: Address, amount: i128) {
+            let held = Self::balance(env.clone(), to.clone());
+            env.storage().instance().set(&to, &(held + amount));
+        }
+
+        pub fn set_fail(env: Env, fail: bool) {
+            env.storage().instance().set(&symbol_short!("fail"), &fail);
+        }
+
+        pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+            from.require_auth();
+            let fail: bool = env
+                .storage()
+                .instance()
+                .get(&symbol_short!("fail"))
+                .unwrap_or(false);
+            assert!(!fail, "Transfer failed");
+            let held = Self::balance(env.clone(), from.clone());
+            assert!(held >= amount, "Insufficient balance");
+            env.storage().instance().set(&from, &(held - amount));
+            let received = Self::balance(env.clone(), to.clone());
+            env.storage().instance().set(&to, &(received + amount));
+        }
+
+        pub fn balance(env: Env, owner: Address) -> i128 {
+            env.storage().instance().get(&owner).unwrap_or(0)
+        }
+    }
+}
+use failing_token::{MockFailingToken, MockFailingTokenClient};
+
+// Token that, once armed, calls back into the booking contract's oracle settlement from
+// inside its transfer
+mod reentrant_token {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env};
+
+    #[contract]
+    pub struct MockReentrantToken;
+
+    #[contractimpl]
+    impl MockReentrantToken {
+        pub fn mint(env: Env, to: Address, amount: i128) {
+            let held = Self::balance(env.clone(), to.clone());
+            env.storage().instance().set(&to, &(held + amount));
+        }
+
+        pub fn arm(env: Env, booking: Address, oracle: Address, booking_id: u64) {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("target"), &(booking, oracle, booking_id));
+        }
+
+        pub fn disarm(env: Env) {
+            env.storage().instance().remove(&symbol_short!("target"));
+        }
+
+        pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+            from.require_auth();
+            if let Some((booking, oracle, booking_id)) = env
+                .storage()
+                .instance()
+                .get::<_, (Address, Address, u64)>(&symbol_short!("target"))
+            {
+                booking::BookingContractClient::new(&env, &booking)
+                    .oracle_release_payment(&oracle, &booking_id);
+            }
+            let held = Self::balance(env.clone(), from.clone());
+            assert!(held >= amount, "Insufficient balance");
+            env.storage().instance().set(&from, &(held - amount));
+            let received = Self::balance(env.clone(), to.clone());
+            env.storage().instance().set(&to, &(received + amount));
+        }
+
+        pub fn balance(env: Env, owner: Address) -> i128 {
+            env.storage().instance().get(&owner).unwrap_or(0)
+        }
+    }
+}
+use reentrant_token::{MockReentrantToken, MockReentrantTokenClient};
 
 #[test]
 fn test_payment_escrow_flow() {
@@ -23,7 +157,7 @@ fn test_payment_escrow_flow() {
     );
 
     let booking = contracts.booking.get_booking(&booking_id).unwrap();
-    assert_eq!(booking.status, Symbol::new(&env, "pending"));
+    assert_eq!(booking.status, BookingStatus::Pending);
     assert_eq!(booking.amount_escrowed, 0);
 
     // 2. Mint tokens to passenger and Pay
@@ -65,6 +199,1308 @@ fn test_refund_flow() {
     contracts.booking.refund_passenger(&booking_id);
 
     let booking = contracts.booking.get_booking(&booking_id).unwrap();
-    assert_eq!(booking.status, Symbol::new(&env, "refunded"));
+    assert_eq!(booking.status, BookingStatus::Refunded);
+    assert_eq!(booking.amount_escrowed, 0);
+}
+
+// Settlement observer recording each notification. The always-failing one lives in its
+// own module since both export `on_settled`.
+mod observer {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env, Vec};
+
+    #[contract]
+    pub struct MockObserver;
+
+    #[contractimpl]
+    impl MockObserver {
+        pub fn on_settled(env: Env, booking_id: u64, _passenger: Address, _airline: Address, amount: i128) {
+            let mut calls: Vec<(u64, i128)> =
+                env.storage().instance().get(&symbol_short!("calls")).unwrap_or(Vec::new(&env));
+            calls.push_back((booking_id, amount));
+            env.storage().instance().set(&symbol_short!("calls"), &calls);
+        }
+
+        pub fn calls(env: Env) -> Vec<(u64, i128)> {
+            env.storage().instance().get(&symbol_short!("calls")).unwrap_or(Vec::new(&env))
+        }
+    }
+}
+
+mod failing_observer {
+    use soroban_sdk::{contract, contractimpl, Address, Env};
+
+    #[contract]
+    pub struct MockFailingObserver;
+
+    #[contractimpl]
+    impl MockFailingObserver {
+        pub fn on_settled(_env: Env, _booking_id: u64, _passenger: Address, _airline: Address, _amount: i128) {
+            panic!("Observer failed");
+        }
+    }
+}
+
+fn create_paid_booking(
+    env: &soroban_sdk::Env,
+    actors: &integration_tests::Actors,
+    contracts: &integration_tests::Contracts,
+    departure_time: u64,
+    price: i128,
+) -> u64 {
+    let booking_id = contracts.booking.create_booking(
+        &actors.passenger,
+        &actors.airline,
+        &Symbol::new(env, "FL123"),
+        &Symbol::new(env, "JFK"),
+        &Symbol::new(env, "LAX"),
+        &departure_time,
+        &price,
+        &contracts.token.address,
+    );
+    contracts
+        .token
+        .mint(&actors.admin, &actors.passenger, &price);
+    contracts.booking.pay_for_booking(&booking_id);
+    booking_id
+}
+
+#[test]
+#[should_panic(expected = "No-show booking cannot be refunded")]
+fn test_no_show_booking_cannot_be_refunded() {
+    let env = new_env();
+    env.ledger().set_timestamp(1700000000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    let booking_id = create_paid_booking(&env, &actors, &contracts, 1700003600, 1_000);
+
+    env.ledger().set_timestamp(1700003600);
+    contracts.booking.mark_no_show(&actors.airline, &booking_id);
+    assert!(contracts.booking.is_no_show(&booking_id));
+
+    contracts
+        .booking
+        .settle_cancellation(&booking_id, &actors.passenger, &10_000);
+}
+
+#[test]
+fn test_no_show_reverses_loyalty_points() {
+    let env = new_env();
+    env.ledger().set_timestamp(1700000000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts.loyalty.init_loyalty();
+    contracts
+        .booking
+        .set_loyalty_contract(&actors.admin, &contracts.loyalty.address);
+    contracts.loyalty.init_admin(&actors.admin);
+    contracts
+        .loyalty
+        .set_booking_contract(&actors.admin, &contracts.booking.address);
+
+    let booking_id = create_paid_booking(&env, &actors, &contracts, 1700003600, 1_000);
+    contracts
+        .loyalty
+        .award_points(&actors.admin, &actors.passenger, &1_000, &booking_id);
+    // Only the linked booking contract or an admin may reverse points
+    assert!(contracts
+        .loyalty
+        .try_reverse_points(&actors.passenger, &actors.passenger, &booking_id)
+        .is_err());
+    assert_eq!(
+        contracts.loyalty.get_account(&actors.passenger).unwrap().total_points,
+        1_000
+    );
+
+    env.ledger().set_timestamp(1700003600);
+    contracts.booking.mark_no_show(&actors.airline, &booking_id);
+
+    assert_eq!(
+        contracts.loyalty.get_account(&actors.passenger).unwrap().total_points,
+        0
+    );
+}
+
+#[test]
+#[should_panic(expected = "Not an admin")]
+fn test_non_admin_cannot_set_loyalty_contract() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+
+    contracts
+        .booking
+        .set_loyalty_contract(&actors.passenger, &contracts.loyalty.address);
+}
+
+#[test]
+fn test_refund_split_between_tokens_and_points() {
+    let env = new_env();
+    env.ledger().set_timestamp(1700000000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts.loyalty.init_loyalty();
+    contracts.loyalty.init_admin(&actors.admin);
+    contracts
+        .booking
+        .set_loyalty_contract(&actors.admin, &contracts.loyalty.address);
+    contracts
+        .loyalty
+        .set_booking_contract(&actors.admin, &contracts.booking.address);
+    // Only an admin sets the rate; a passenger could otherwise inflate their own points
+    assert!(contracts
+        .booking
+        .try_set_points_refund_rate(&actors.passenger, &10_000)
+        .is_err());
+    contracts.booking.set_points_refund_rate(&actors.admin, &120);
+
+    let booking_id = create_paid_booking(&env, &actors, &contracts, 1705000000, 1_000);
+    assert!(contracts.booking.try_refund_as_points(&booking_id, &10_001).is_err());
+
+    // 60% back in tokens, 40% as points at 120 per unit
+    let (tokens, points) = contracts.booking.refund_as_points(&booking_id, &6_000);
+    assert_eq!(tokens, 600);
+    assert_eq!(points, 48_000);
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 600);
+    assert_eq!(contracts.token.balance_of(&actors.airline), 400);
+    assert_eq!(
+        contracts.loyalty.get_account(&actors.passenger).unwrap().total_points,
+        48_000
+    );
+
+    let booking = contracts.booking.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.status, BookingStatus::Refunded);
+    assert_eq!(booking.amount_escrowed, 0);
+}
+
+#[test]
+fn test_gold_tier_passenger_pays_reduced_fee() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts.loyalty.init_loyalty();
+    contracts.loyalty.init_admin(&actors.admin);
+    contracts
+        .booking
+        .set_loyalty_contract(&actors.admin, &contracts.loyalty.address);
+
+    let treasury = Address::generate(&env);
+    contracts
+        .booking
+        .set_protocol_fee(&actors.admin, &500, &treasury); // 5%
+
+    // Gold requires 5000 points and 20 bookings
+    let gold_passenger = Address::generate(&env);
+    for i in 0..20 {
+        contracts.loyalty.award_points(&actors.admin, &gold_passenger, &1_000, &i);
+    }
+    assert_eq!(
+        contracts.loyalty.get_account(&gold_passenger).unwrap().tier,
+        Symbol::new(&env, "gold")
+    );
+
+    let price = 1_000i128;
+    let bronze_fee = contracts.booking.quote_booking_fee(&actors.passenger, &price);
+    let gold_fee = contracts.booking.quote_booking_fee(&gold_passenger, &price);
+    assert_eq!(bronze_fee, 50);
+    assert_eq!(gold_fee, 45); // 10% gold rebate on the fee
+
+    for passenger in [actors.passenger.clone(), gold_passenger.clone()] {
+        let booking_id = contracts.booking.create_booking(
+            &passenger,
+            &actors.airline,
+            &Symbol::new(&env, "FL123"),
+            &Symbol::new(&env, "JFK"),
+            &Symbol::new(&env, "LAX"),
+            &1704067200,
+            &price,
+            &contracts.token.address,
+        );
+        contracts.token.mint(&actors.admin, &passenger, &(price + 50));
+        contracts.booking.pay_for_booking(&booking_id);
+    }
+
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 0);
+    assert_eq!(contracts.token.balance_of(&gold_passenger), 5);
+    assert_eq!(contracts.token.balance_of(&treasury), 95);
+    assert_eq!(contracts.token.balance_of(&contracts.booking.address), 2 * price);
+}
+
+#[test]
+fn test_create_and_pay_confirms_with_escrow() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    let price = 100_0000000i128;
+    contracts
+        .token
+        .mint(&actors.admin, &actors.passenger, &price);
+
+    let booking_id = contracts.booking.create_and_pay(
+        &actors.passenger,
+        &actors.airline,
+        &Symbol::new(&env, "FL123"),
+        &Symbol::new(&env, "JFK"),
+        &Symbol::new(&env, "LAX"),
+        &1704067200,
+        &price,
+        &contracts.token.address,
+    );
+
+    let booking = contracts.booking.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.status, BookingStatus::Confirmed);
+    assert_eq!(booking.amount_escrowed, price);
+    assert_eq!(contracts.token.balance_of(&contracts.booking.address), price);
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 0);
+}
+
+#[test]
+fn test_bookings_in_same_ledger_get_distinct_ids() {
+    let env = new_env();
+    env.ledger().set_timestamp(1700000000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    let other_passenger = Address::generate(&env);
+    let mut ids = [0u64; 2];
+    for (i, passenger) in [actors.passenger.clone(), other_passenger.clone()].iter().enumerate() {
+        ids[i] = contracts.booking.create_booking(
+            passenger,
+            &actors.airline,
+            &Symbol::new(&env, "FL123"),
+            &Symbol::new(&env, "JFK"),
+            &Symbol::new(&env, "LAX"),
+            &1705000000,
+            &1_000,
+            &contracts.token.address,
+        );
+    }
+
+    assert_ne!(ids[0], ids[1]);
+    let first = contracts.booking.get_booking(&ids[0]).unwrap();
+    let second = contracts.booking.get_booking(&ids[1]).unwrap();
+    assert_eq!(first.passenger, actors.passenger);
+    assert_eq!(second.passenger, other_passenger);
+    assert_eq!(first.created_at, 1700000000);
+    assert_eq!(second.created_at, 1700000000);
+}
+
+#[test]
+#[should_panic(expected = "Not an admin")]
+fn test_non_admin_cannot_set_protocol_fee() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+
+    contracts
+        .booking
+        .set_protocol_fee(&actors.passenger, &10_000, &actors.passenger);
+}
+
+#[test]
+#[should_panic(expected = "Not an admin")]
+fn test_non_admin_cannot_set_dispute_contract() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+
+    // A contract reporting its own verdicts could pay out any held escrow
+    contracts
+        .booking
+        .set_dispute_contract(&actors.passenger, &actors.passenger);
+}
+
+#[test]
+#[should_panic(expected = "Not an admin")]
+fn test_non_admin_cannot_replace_airline_registry() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+
+    // A registry that never reports suspensions would release held settlements
+    contracts
+        .booking
+        .set_airline_registry(&actors.airline, &actors.airline);
+}
+
+#[test]
+fn test_escrow_fully_refundable_after_yield_cycle() {
+    let env = new_env();
+    env.ledger().set_timestamp(1700000000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    let source_id = env.register(MockYieldSource, ());
+    let source = MockYieldSourceClient::new(&env, &source_id);
+    source.init(&contracts.token.address);
+    let treasury = Address::generate(&env);
+    contracts.booking.set_yield_source(
+        &actors.admin,
+        &source_id,
+        &contracts.token.address,
+        &treasury,
+    );
+
+    let price = 100_0000000i128;
+    let booking_id = create_paid_booking(&env, &actors, &contracts, 1705000000, price);
+
+    contracts.booking.deposit_escrow_to_yield(&actors.admin, &price);
+    assert_eq!(contracts.token.balance_of(&contracts.booking.address), 0);
+    assert_eq!(contracts.booking.get_yield_principal(), price);
+
+    // Interest accrues in the source and is harvested to the treasury
+    let interest = 5_0000000i128;
+    contracts.token.mint(&actors.admin, &source_id, &interest);
+    source.accrue(&contracts.booking.address, &interest);
+    assert_eq!(contracts.booking.harvest_yield(&actors.admin), interest);
+    assert_eq!(contracts.token.balance_of(&treasury), interest);
+    assert_eq!(contracts.booking.get_yield_principal(), price);
+
+    // Refund recalls the principal from the source automatically
+    contracts.booking.refund_passenger(&booking_id);
+    assert_eq!(contracts.token.balance_of(&actors.passenger), price);
+    assert_eq!(contracts.booking.get_yield_principal(), 0);
+    assert_eq!(source.balance(&contracts.booking.address), 0);
+    assert_eq!(
+        contracts.booking.get_booking(&booking_id).unwrap().status,
+        BookingStatus::Refunded
+    );
+}
+
+#[test]
+#[should_panic(expected = "Not an admin")]
+fn test_first_yield_source_requires_contract_admin() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+
+    // Being first to configure the source does not make the caller yield admin
+    let attacker = Address::generate(&env);
+    contracts.booking.set_yield_source(
+        &attacker,
+        &Address::generate(&env),
+        &contracts.token.address,
+        &attacker,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Insufficient idle escrow")]
+fn test_yield_deposit_limited_to_held_escrow() {
+    let env = new_env();
+    env.ledger().set_timestamp(1700000000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    let source_id = env.register(MockYieldSource, ());
+    MockYieldSourceClient::new(&env, &source_id).init(&contracts.token.address);
+    contracts.booking.set_yield_source(
+        &actors.admin,
+        &source_id,
+        &contracts.token.address,
+        &Address::generate(&env),
+    );
+
+    let price = 100_0000000i128;
+    create_paid_booking(&env, &actors, &contracts, 1705000000, price);
+    contracts.booking.deposit_escrow_to_yield(&actors.admin, &(price + 1));
+}
+
+#[test]
+fn test_passenger_total_spend_sums_completed_bookings() {
+    let env = new_env();
+    env.ledger().set_timestamp(1700000000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    let first = create_paid_booking(&env, &actors, &contracts, 1705000000, 300);
+    let second = create_paid_booking(&env, &actors, &contracts, 1705000000, 450);
+    // Still in escrow, so not counted
+    create_paid_booking(&env, &actors, &contracts, 1705000000, 1_000);
+
+    contracts.booking.release_payment_to_airline(&first);
+    contracts.booking.release_payment_to_airline(&second);
+
+    assert_eq!(contracts.booking.passenger_total_spend(&actors.passenger), 750);
+    assert_eq!(
+        contracts.booking.passenger_total_spend(&Address::generate(&env)),
+        0
+    );
+}
+
+#[test]
+fn test_failed_refund_transfer_leaves_booking_recoverable() {
+    let env = new_env();
+    env.ledger().set_timestamp(1700000000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+
+    let token_id = env.register(MockFailingToken, ());
+    let token = MockFailingTokenClient::new(&env, &token_id);
+    let price = 1_000i128;
+    token.mint(&actors.passenger, &price);
+
+    let booking_id = contracts.booking.create_booking(
+        &actors.passenger,
+        &actors.airline,
+        &Symbol::new(&env, "FL123"),
+        &Symbol::new(&env, "JFK"),
+        &Symbol::new(&env, "LAX"),
+        &1705000000,
+        &price,
+        &token_id,
+    );
+    contracts.booking.pay_for_booking(&booking_id);
+
+    // The transfer fails: nothing changes and the refund reports failure
+    token.set_fail(&true);
+    assert!(!contracts.booking.refund_passenger(&booking_id));
+    let booking = contracts.booking.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.status, BookingStatus::Confirmed);
+    assert_eq!(booking.amount_escrowed, price);
+    assert_eq!(token.balance(&contracts.booking.address), price);
+
+    // Once the token recovers the same booking can be refunded
+    token.set_fail(&false);
+    assert!(contracts.booking.refund_passenger(&booking_id));
+    let booking = contracts.booking.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.status, BookingStatus::Refunded);
     assert_eq!(booking.amount_escrowed, 0);
+    assert_eq!(token.balance(&actors.passenger), price);
+}
+
+#[test]
+fn test_legacy_timestamp_booking_migrates_to_counter_id() {
+    let env = new_env();
+    env.ledger().set_timestamp(1700000000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    // Seed a booking the way the timestamp-id scheme stored it
+    let legacy_id = 1699990000u64;
+    let legacy = LegacyBooking {
+        booking_id: legacy_id,
+        passenger: actors.passenger.clone(),
+        airline: actors.airline.clone(),
+        flight_number: Symbol::new(&env, "FL123"),
+        from_airport: Symbol::new(&env, "JFK"),
+        to_airport: Symbol::new(&env, "LAX"),
+        departure_time: 1705000000,
+        price: 1_000,
+        token: contracts.token.address.clone(),
+        amount_escrowed: 0,
+        status: Symbol::new(&env, "pending"),
+        created_at: legacy_id,
+    };
+    env.as_contract(&contracts.booking.address, || {
+        env.storage().persistent().set(&legacy_id, &legacy);
+        env.storage().persistent().set(
+            &(symbol_short!("px_bks"), actors.passenger.clone()),
+            &vec![&env, legacy_id],
+        );
+    });
+
+    assert!(contracts
+        .booking
+        .try_migrate_legacy_bookings(&actors.passenger, &vec![&env, legacy_id])
+        .is_err());
+    let new_ids = contracts
+        .booking
+        .migrate_legacy_bookings(&actors.admin, &vec![&env, legacy_id]);
+    assert_eq!(new_ids.len(), 1);
+    let booking_id = new_ids.get(0).unwrap();
+
+    let migrated = contracts.booking.get_booking(&booking_id).unwrap();
+    assert_eq!(migrated.booking_id, booking_id);
+    assert_eq!(migrated.passenger, actors.passenger);
+    assert_eq!(migrated.price, 1_000);
+    assert_eq!(migrated.created_at, legacy_id);
+    assert_eq!(migrated.status, BookingStatus::Pending);
+    assert_eq!(migrated.seat_count, 1);
+    assert_eq!(migrated.points_discount, 0);
+    // The pending booking gets a full TTL from the migration rather than expiring at once
+    assert_eq!(migrated.expires_at, 1700000000 + 86_400);
+    assert!(contracts.booking.try_expire_booking(&booking_id).is_err());
+    assert!(contracts.booking.get_booking(&legacy_id).is_none());
+    assert_eq!(
+        contracts.booking.get_migrated_booking_id(&legacy_id),
+        Some(booking_id)
+    );
+
+    // Counter ids keep advancing past the migrated booking
+    let next_id = contracts.booking.create_booking(
+        &actors.passenger,
+        &actors.airline,
+        &Symbol::new(&env, "FL123"),
+        &Symbol::new(&env, "JFK"),
+        &Symbol::new(&env, "LAX"),
+        &1705000000,
+        &1_000,
+        &contracts.token.address,
+    );
+    assert!(next_id > booking_id);
+
+    // Migration runs once
+    assert!(contracts
+        .booking
+        .try_migrate_legacy_bookings(&actors.admin, &vec![&env])
+        .is_err());
+}
+
+// A counter-keyed booking as stored before statuses became `BookingStatus`
+fn seed_symbol_status_booking(
+    env: &Env,
+    actors: &integration_tests::Actors,
+    contracts: &integration_tests::Contracts,
+    booking_id: u64,
+    status: &str,
+) {
+    let legacy = LegacyBooking {
+        booking_id,
+        passenger: actors.passenger.clone(),
+        airline: actors.airline.clone(),
+        flight_number: Symbol::new(env, "FL123"),
+        from_airport: Symbol::new(env, "JFK"),
+        to_airport: Symbol::new(env, "LAX"),
+        departure_time: 1705000000,
+        price: 1_000,
+        token: contracts.token.address.clone(),
+        amount_escrowed: 0,
+        status: Symbol::new(env, status),
+        created_at: 1700000000,
+    };
+    env.as_contract(&contracts.booking.address, || {
+        env.storage().persistent().set(&booking_id, &legacy);
+    });
+}
+
+#[test]
+fn test_symbol_statuses_migrate_to_enum() {
+    let env = new_env();
+    env.ledger().set_timestamp(1700000000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    seed_symbol_status_booking(&env, &actors, &contracts, 1, "confirmed");
+    seed_symbol_status_booking(&env, &actors, &contracts, 2, "refunded");
+
+    // Statuses migrate after the id re-keying, here with no legacy ids left to move
+    assert!(contracts
+        .booking
+        .try_migrate_booking_statuses(&actors.admin, &vec![&env, 1, 2], &true)
+        .is_err());
+    contracts.booking.migrate_legacy_bookings(&actors.admin, &vec![&env]);
+
+    assert!(contracts
+        .booking
+        .try_migrate_booking_statuses(&actors.passenger, &vec![&env, 1, 2], &true)
+        .is_err());
+    assert_eq!(
+        contracts.booking.migrate_booking_statuses(&actors.admin, &vec![&env, 1, 2], &true),
+        2
+    );
+    assert_eq!(
+        contracts.booking.get_booking(&1).unwrap().status,
+        BookingStatus::Confirmed
+    );
+    assert_eq!(
+        contracts.booking.get_booking(&2).unwrap().status,
+        BookingStatus::Refunded
+    );
+    // The rewrite ran as the v2 -> v3 step of a tracked storage migration
+    env.as_contract(&contracts.booking.address, || {
+        let progress =
+            VersionedStorage::get_migration_progress(&env, &BOOKING_CONTRACT).unwrap();
+        assert!(progress.completed);
+        assert_eq!((progress.from_version, progress.to_version), (2, 3));
+    });
+    assert!(contracts
+        .booking
+        .try_migrate_booking_statuses(&actors.admin, &vec![&env], &true)
+        .is_err());
+}
+
+#[test]
+fn test_symbol_statuses_migrate_in_pages() {
+    let env = new_env();
+    env.ledger().set_timestamp(1700000000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    for booking_id in 1..=3u64 {
+        seed_symbol_status_booking(&env, &actors, &contracts, booking_id, "confirmed");
+    }
+    contracts.booking.migrate_legacy_bookings(&actors.admin, &vec![&env]);
+
+    let mut too_many = vec![&env];
+    for booking_id in 0..51u64 {
+        too_many.push_back(booking_id);
+    }
+    assert!(contracts
+        .booking
+        .try_migrate_booking_statuses(&actors.admin, &too_many, &false)
+        .is_err());
+
+    // Earlier pages leave the version alone; a repeated id is skipped
+    contracts.booking.migrate_booking_statuses(&actors.admin, &vec![&env, 1, 2], &false);
+    env.as_contract(&contracts.booking.address, || {
+        assert_eq!(VersionedStorage::get_storage_version(&env, &BOOKING_CONTRACT), 2);
+    });
+    contracts.booking.migrate_booking_statuses(&actors.admin, &vec![&env, 2, 3], &true);
+    env.as_contract(&contracts.booking.address, || {
+        assert_eq!(VersionedStorage::get_storage_version(&env, &BOOKING_CONTRACT), 3);
+    });
+    for booking_id in 1..=3u64 {
+        assert_eq!(
+            contracts.booking.get_booking(&booking_id).unwrap().status,
+            BookingStatus::Confirmed
+        );
+    }
+    assert!(contracts
+        .booking
+        .try_migrate_booking_statuses(&actors.admin, &vec![&env], &false)
+        .is_err());
+}
+
+#[test]
+fn test_booking_status_round_trips_and_follows_transitions() {
+    let env = new_env();
+    env.ledger().set_timestamp(1700000000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    for status in [
+        BookingStatus::Pending,
+        BookingStatus::Confirmed,
+        BookingStatus::Disputed,
+        BookingStatus::Completed,
+        BookingStatus::Cancelled,
+        BookingStatus::Refunded,
+    ] {
+        let encoded = status.to_xdr(&env);
+        assert_eq!(BookingStatus::from_xdr(&env, &encoded).unwrap(), status);
+    }
+
+    let booking_id = contracts.booking.create_booking(
+        &actors.passenger,
+        &actors.airline,
+        &Symbol::new(&env, "FL123"),
+        &Symbol::new(&env, "JFK"),
+        &Symbol::new(&env, "LAX"),
+        &1705000000,
+        &1_000,
+        &contracts.token.address,
+    );
+    let status = || contracts.booking.get_booking(&booking_id).unwrap().status;
+    assert_eq!(status(), BookingStatus::Pending);
+
+    contracts.token.mint(&actors.admin, &actors.passenger, &1_000);
+    contracts.booking.pay_for_booking(&booking_id);
+    assert_eq!(status(), BookingStatus::Confirmed);
+    assert!(contracts.booking.try_pay_for_booking(&booking_id).is_err());
+
+    contracts.booking.release_payment_to_airline(&booking_id);
+    assert_eq!(status(), BookingStatus::Completed);
+    assert!(contracts.booking.try_release_payment_to_airline(&booking_id).is_err());
+}
+
+#[test]
+fn test_get_bookings_returns_none_for_unknown_ids() {
+    let env = new_env();
+    env.ledger().set_timestamp(1700000000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    let first = create_paid_booking(&env, &actors, &contracts, 1705000000, 1_000);
+    let second = create_paid_booking(&env, &actors, &contracts, 1705000000, 2_000);
+
+    let bookings = contracts
+        .booking
+        .get_bookings(&vec![&env, second, 999, first]);
+    assert_eq!(bookings.len(), 3);
+    assert_eq!(bookings.get(0).unwrap().unwrap().price, 2_000);
+    assert!(bookings.get(1).unwrap().is_none());
+    assert_eq!(bookings.get(2).unwrap().unwrap().booking_id, first);
+}
+
+#[test]
+fn test_points_redeemed_against_booking_reduce_escrow() {
+    let env = new_env();
+    env.ledger().set_timestamp(1700000000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts.loyalty.init_loyalty();
+    contracts.loyalty.init_admin(&actors.admin);
+    contracts
+        .booking
+        .set_loyalty_contract(&actors.admin, &contracts.loyalty.address);
+    contracts.loyalty.award_points(&actors.admin, &actors.passenger, &5_000, &0);
+
+    let booking_id = contracts.booking.create_booking(
+        &actors.passenger,
+        &actors.airline,
+        &Symbol::new(&env, "FL123"),
+        &Symbol::new(&env, "JFK"),
+        &Symbol::new(&env, "LAX"),
+        &1705000000,
+        &1_000,
+        &contracts.token.address,
+    );
+    contracts.token.mint(&actors.admin, &actors.passenger, &1_000);
+
+    // 3000 points redeem for a discount of 30
+    contracts
+        .booking
+        .pay_for_booking_with_points(&booking_id, &3_000);
+
+    let booking = contracts.booking.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.points_discount, 30);
+    assert_eq!(booking.amount_escrowed, 970);
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 30);
+    assert_eq!(
+        contracts.loyalty.get_account(&actors.passenger).unwrap().total_points,
+        2_000
+    );
+
+    // Spend counts the tokens paid, not the part of the fare covered by points
+    contracts.booking.release_payment_to_airline(&booking_id);
+    assert_eq!(contracts.booking.passenger_total_spend(&actors.passenger), 970);
+}
+
+#[test]
+fn test_installments_confirm_only_on_final_payment() {
+    let env = new_env();
+    env.ledger().set_timestamp(1_700_000_000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    let booking_id = contracts.booking.create_booking(
+        &actors.passenger,
+        &actors.airline,
+        &Symbol::new(&env, "FL900"),
+        &Symbol::new(&env, "JFK"),
+        &Symbol::new(&env, "SYD"),
+        &1_705_000_000,
+        &1_000,
+        &contracts.token.address,
+    );
+    contracts
+        .token
+        .mint(&actors.admin, &actors.passenger, &1_000);
+
+    // Deposit first; the booking isn't confirmed until the balance is in
+    contracts.booking.pay_partial(&booking_id, &300);
+    let booking = contracts.booking.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.status, BookingStatus::Pending);
+    assert_eq!(booking.amount_escrowed, 300);
+    assert!(contracts.booking.try_pay_partial(&booking_id, &701).is_err());
+
+    contracts.booking.pay_partial(&booking_id, &700);
+    let booking = contracts.booking.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.status, BookingStatus::Confirmed);
+    assert_eq!(booking.amount_escrowed, 1_000);
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 0);
+}
+
+#[test]
+fn test_cancelling_part_paid_booking_refunds_deposit() {
+    let env = new_env();
+    env.ledger().set_timestamp(1_700_000_000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    let booking_id = contracts.booking.create_booking(
+        &actors.passenger,
+        &actors.airline,
+        &Symbol::new(&env, "FL900"),
+        &Symbol::new(&env, "JFK"),
+        &Symbol::new(&env, "SYD"),
+        &1_705_000_000,
+        &1_000,
+        &contracts.token.address,
+    );
+    contracts
+        .token
+        .mint(&actors.admin, &actors.passenger, &1_000);
+    contracts.booking.pay_partial(&booking_id, &400);
+
+    contracts.booking.refund_passenger(&booking_id);
+
+    let booking = contracts.booking.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.status, BookingStatus::Refunded);
+    assert_eq!(booking.amount_escrowed, 0);
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 1_000);
+}
+
+#[test]
+fn test_unpaid_booking_expires_after_ttl() {
+    let env = new_env();
+    env.ledger().set_timestamp(1_700_000_000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    assert!(contracts
+        .booking
+        .try_set_booking_ttl(&actors.passenger, &1)
+        .is_err());
+    contracts.booking.set_booking_ttl(&actors.admin, &3_600);
+
+    let booking_id = contracts.booking.create_booking(
+        &actors.passenger,
+        &actors.airline,
+        &Symbol::new(&env, "FL123"),
+        &Symbol::new(&env, "JFK"),
+        &Symbol::new(&env, "LAX"),
+        &1_705_000_000,
+        &1_000,
+        &contracts.token.address,
+    );
+    contracts
+        .token
+        .mint(&actors.admin, &actors.passenger, &1_000);
+    contracts.booking.pay_partial(&booking_id, &250);
+    assert!(contracts.booking.try_expire_booking(&booking_id).is_err());
+
+    env.ledger().set_timestamp(1_700_000_000 + 3_600);
+    assert!(contracts.booking.expire_booking(&booking_id));
+
+    let booking = contracts.booking.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.status, BookingStatus::Cancelled);
+    assert_eq!(booking.amount_escrowed, 0);
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Booking not pending")]
+fn test_confirmed_booking_cannot_be_expired() {
+    let env = new_env();
+    env.ledger().set_timestamp(1_700_000_000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    let booking_id = create_paid_booking(&env, &actors, &contracts, 1_705_000_000, 1_000);
+    env.ledger().set_timestamp(1_700_000_000 + 2 * 86_400);
+    contracts.booking.expire_booking(&booking_id);
+}
+
+// Airline schedule: full refund from 24h out, half from 1h out, nothing after that
+fn cancel_under_fee_schedule(secs_before_departure: u64) -> (i128, i128) {
+    let env = new_env();
+    env.ledger().set_timestamp(1_700_000_000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts
+        .refund
+        .set_refund_policy(&actors.airline, &86_400, &10_000, &5_000, &3_600);
+    contracts
+        .booking
+        .set_refund_contract(&actors.admin, &contracts.refund.address);
+
+    let departure = 1_700_000_000 + 10 * 86_400;
+    let booking_id = create_paid_booking(&env, &actors, &contracts, departure, 1_000);
+    env.ledger().set_timestamp(departure - secs_before_departure);
+
+    let refunded = contracts.booking.cancel_booking(&actors.passenger, &booking_id);
+    // A passenger cancellation ends Refunded whether or not a fee schedule applies
+    assert_eq!(
+        contracts.booking.get_booking(&booking_id).unwrap().status,
+        BookingStatus::Refunded
+    );
+    assert_eq!(contracts.token.balance_of(&actors.passenger), refunded);
+    (refunded, contracts.token.balance_of(&actors.airline))
+}
+
+#[test]
+fn test_cancel_booking_follows_airline_fee_schedule() {
+    assert_eq!(cancel_under_fee_schedule(2 * 86_400), (1_000, 0));
+    assert_eq!(cancel_under_fee_schedule(12 * 3_600), (500, 500));
+    assert_eq!(cancel_under_fee_schedule(1_800), (0, 1_000));
+}
+
+#[test]
+fn test_get_bookings_for_passenger_pages_in_creation_order() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    let mut ids = vec![&env];
+    for price in [100i128, 200, 300, 400, 500] {
+        ids.push_back(contracts.booking.create_booking(
+            &actors.passenger,
+            &actors.airline,
+            &Symbol::new(&env, "FL123"),
+            &Symbol::new(&env, "JFK"),
+            &Symbol::new(&env, "LAX"),
+            &1_705_000_000,
+            &price,
+            &contracts.token.address,
+        ));
+    }
+    let other_passenger = Address::generate(&env);
+    contracts.booking.create_booking(
+        &other_passenger,
+        &actors.airline,
+        &Symbol::new(&env, "FL456"),
+        &Symbol::new(&env, "LAX"),
+        &Symbol::new(&env, "JFK"),
+        &1_705_000_000,
+        &900,
+        &contracts.token.address,
+    );
+
+    let all = contracts.booking.get_bookings_for_passenger(&actors.passenger, &0, &10);
+    assert_eq!(all.len(), 5);
+    for (i, booking) in all.iter().enumerate() {
+        assert_eq!(booking.booking_id, ids.get(i as u32).unwrap());
+        assert_eq!(booking.passenger, actors.passenger);
+    }
+
+    let page = contracts.booking.get_bookings_for_passenger(&actors.passenger, &3, &10);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().price, 400);
+    assert!(contracts
+        .booking
+        .get_bookings_for_passenger(&actors.passenger, &5, &10)
+        .is_empty());
+
+    assert_eq!(
+        contracts.booking.get_bookings_for_airline(&actors.airline, &0, &10).len(),
+        6
+    );
+}
+
+#[test]
+fn test_settlement_notifies_observers_best_effort() {
+    let env = new_env();
+    env.ledger().set_timestamp(1_700_000_000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    let failing = env.register(failing_observer::MockFailingObserver, ());
+    let observer = observer::MockObserverClient::new(&env, &env.register(observer::MockObserver, ()));
+    contracts.booking.add_settlement_observer(&actors.admin, &failing);
+    contracts.booking.add_settlement_observer(&actors.admin, &observer.address);
+    assert!(contracts
+        .booking
+        .try_add_settlement_observer(&actors.admin, &failing)
+        .is_err());
+    assert!(contracts
+        .booking
+        .try_add_settlement_observer(&actors.passenger, &actors.passenger)
+        .is_err());
+    assert!(contracts
+        .booking
+        .try_remove_settlement_observer(&actors.passenger, &observer.address)
+        .is_err());
+
+    let booking_id = create_paid_booking(&env, &actors, &contracts, 1_705_000_000, 1_000);
+    contracts.booking.release_payment_to_airline(&booking_id);
+
+    // The failing observer didn't stop the release or the other observer
+    assert_eq!(contracts.token.balance_of(&actors.airline), 1_000);
+    assert_eq!(observer.calls(), vec![&env, (booking_id, 1_000i128)]);
+
+    contracts.booking.remove_settlement_observer(&actors.admin, &observer.address);
+    let second = create_paid_booking(&env, &actors, &contracts, 1_705_000_000, 500);
+    contracts.booking.release_payment_to_airline(&second);
+    assert_eq!(observer.calls().len(), 1);
+    assert_eq!(contracts.booking.get_settlement_observers(), vec![&env, failing]);
+}
+
+#[test]
+fn test_refund_passenger_splits_escrow_per_refund_policy() {
+    let env = new_env();
+    env.ledger().set_timestamp(1_700_000_000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    // Full refund from 72h out, half from 24h out
+    contracts
+        .refund
+        .set_refund_policy(&actors.airline, &259_200, &10_000, &5_000, &86_400);
+    contracts
+        .booking
+        .set_refund_contract(&actors.admin, &contracts.refund.address);
+    // A refund contract under a passenger's control could dictate their own refunds
+    assert!(contracts
+        .booking
+        .try_set_refund_contract(&actors.passenger, &actors.passenger)
+        .is_err());
+
+    let departure = 1_700_000_000 + 10 * 86_400;
+    let booking_id = create_paid_booking(&env, &actors, &contracts, departure, 1_000);
+    env.ledger().set_timestamp(departure - 2 * 86_400);
+
+    assert!(contracts.booking.refund_passenger(&booking_id));
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 500);
+    assert_eq!(contracts.token.balance_of(&actors.airline), 500);
+    assert_eq!(contracts.token.balance_of(&contracts.booking.address), 0);
+    let booking = contracts.booking.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.status, BookingStatus::Refunded);
+    assert_eq!(booking.amount_escrowed, 0);
+}
+
+#[test]
+fn test_every_refund_path_respects_refund_policy() {
+    let env = new_env();
+    env.ledger().set_timestamp(1_700_000_000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts.loyalty.init_loyalty();
+    contracts.loyalty.init_admin(&actors.admin);
+    contracts
+        .booking
+        .set_loyalty_contract(&actors.admin, &contracts.loyalty.address);
+    contracts
+        .loyalty
+        .set_booking_contract(&actors.admin, &contracts.booking.address);
+    contracts.booking.set_points_refund_rate(&actors.admin, &120);
+    // Full refund from 72h out, half from 24h out
+    contracts
+        .refund
+        .set_refund_policy(&actors.airline, &259_200, &10_000, &5_000, &86_400);
+    contracts
+        .booking
+        .set_refund_contract(&actors.admin, &contracts.refund.address);
+
+    let departure = 1_700_000_000 + 10 * 86_400;
+    let as_points = create_paid_booking(&env, &actors, &contracts, departure, 1_000);
+    let settled = create_paid_booking(&env, &actors, &contracts, departure, 1_000);
+    env.ledger().set_timestamp(departure - 2 * 86_400);
+
+    // Asking for everything in tokens still only returns the policy's half
+    assert_eq!(contracts.booking.refund_as_points(&as_points, &10_000), (500, 0));
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 500);
+    assert_eq!(contracts.token.balance_of(&actors.airline), 500);
+
+    // A passenger-set split is capped the same way
+    assert_eq!(
+        contracts
+            .booking
+            .settle_cancellation(&settled, &actors.passenger, &10_000),
+        (500, 500)
+    );
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 1_000);
+    assert_eq!(contracts.token.balance_of(&actors.airline), 1_000);
+    assert_eq!(contracts.token.balance_of(&contracts.booking.address), 0);
+}
+
+#[test]
+fn test_oracle_settlement_rejects_token_reentry() {
+    let env = new_env();
+    env.ledger().set_timestamp(1700000000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    let oracle = Address::generate(&env);
+    contracts.booking.initialize_oracle(&actors.admin, &oracle);
+
+    let token_id = env.register(MockReentrantToken, ());
+    let token = MockReentrantTokenClient::new(&env, &token_id);
+    let price = 1_000i128;
+    token.mint(&actors.passenger, &price);
+    let booking_id = contracts.booking.create_booking(
+        &actors.passenger,
+        &actors.airline,
+        &Symbol::new(&env, "FL123"),
+        &Symbol::new(&env, "JFK"),
+        &Symbol::new(&env, "LAX"),
+        &1705000000,
+        &price,
+        &token_id,
+    );
+    contracts.booking.pay_for_booking(&booking_id);
+
+    // Re-entering mid-payout is rejected and the settlement rolls back
+    token.arm(&contracts.booking.address, &oracle, &booking_id);
+    assert!(contracts
+        .booking
+        .try_oracle_release_payment(&oracle, &booking_id)
+        .is_err());
+    assert!(!contracts.booking.oracle_refund_airline_cancel(&oracle, &booking_id));
+    let booking = contracts.booking.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.status, BookingStatus::Confirmed);
+    assert_eq!(booking.amount_escrowed, price);
+    assert_eq!(token.balance(&contracts.booking.address), price);
+
+    // The failed refund released its lock, so the booking still settles normally
+    token.disarm();
+    assert!(contracts.booking.oracle_refund_airline_cancel(&oracle, &booking_id));
+    assert_eq!(token.balance(&actors.passenger), price);
+}
+
+#[test]
+fn test_airline_release_and_passenger_refund_reject_reentry() {
+    let env = new_env();
+    env.ledger().set_timestamp(1700000000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    let oracle = Address::generate(&env);
+    contracts.booking.initialize_oracle(&actors.admin, &oracle);
+
+    let token_id = env.register(MockReentrantToken, ());
+    let token = MockReentrantTokenClient::new(&env, &token_id);
+    let price = 1_000i128;
+    token.mint(&actors.passenger, &(2 * price));
+    let mut booking_ids = std::vec::Vec::new();
+    for _ in 0..2 {
+        let booking_id = contracts.booking.create_booking(
+            &actors.passenger,
+            &actors.airline,
+            &Symbol::new(&env, "FL123"),
+            &Symbol::new(&env, "JFK"),
+            &Symbol::new(&env, "LAX"),
+            &1705000000,
+            &price,
+            &token_id,
+        );
+        contracts.booking.pay_for_booking(&booking_id);
+        booking_ids.push(booking_id);
+    }
+
+    // Each payout tries to settle the other booking from inside the token transfer
+    token.arm(&contracts.booking.address, &oracle, &booking_ids[1]);
+    assert!(contracts
+        .booking
+        .try_release_payment_to_airline(&booking_ids[0])
+        .is_err());
+    assert!(!contracts.booking.refund_passenger(&booking_ids[0]));
+    for booking_id in &booking_ids {
+        let booking = contracts.booking.get_booking(booking_id).unwrap();
+        assert_eq!(booking.status, BookingStatus::Confirmed);
+        assert_eq!(booking.amount_escrowed, price);
+    }
+    assert_eq!(token.balance(&contracts.booking.address), 2 * price);
+
+    token.disarm();
+    contracts.booking.release_payment_to_airline(&booking_ids[0]);
+    assert_eq!(token.balance(&actors.airline), price);
+}
+
+#[test]
+fn test_batch_settlement_rejects_token_reentry() {
+    let env = new_env();
+    env.ledger().set_timestamp(1700000000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    let oracle = Address::generate(&env);
+    contracts.booking.initialize_oracle(&actors.admin, &oracle);
+
+    let token_id = env.register(MockReentrantToken, ());
+    let token = MockReentrantTokenClient::new(&env, &token_id);
+    let price = 1_000i128;
+    token.mint(&actors.passenger, &(2 * price));
+    let mut booking_ids = soroban_sdk::Vec::new(&env);
+    for _ in 0..2 {
+        let booking_id = contracts.booking.create_booking(
+            &actors.passenger,
+            &actors.airline,
+            &Symbol::new(&env, "FL123"),
+            &Symbol::new(&env, "JFK"),
+            &Symbol::new(&env, "LAX"),
+            &1705000000,
+            &price,
+            &token_id,
+        );
+        contracts.booking.pay_for_booking(&booking_id);
+        booking_ids.push_back(booking_id);
+    }
+
+    // Paying out the first booking tries to settle the second through the oracle
+    token.arm(&contracts.booking.address, &oracle, &booking_ids.get(1).unwrap());
+    assert!(contracts
+        .booking
+        .try_batch_complete_bookings(&actors.airline, &booking_ids)
+        .is_err());
+    for booking_id in booking_ids.iter() {
+        let booking = contracts.booking.get_booking(&booking_id).unwrap();
+        assert_eq!(booking.status, BookingStatus::Confirmed);
+        assert_eq!(booking.amount_escrowed, price);
+    }
+
+    token.disarm();
+    let result = contracts.booking.batch_complete_bookings(&actors.airline, &booking_ids);
+    assert_eq!(result.total_released, 2 * price);
+    assert_eq!(token.balance(&actors.airline), 2 * price);
+}
+
+#[test]
+fn test_settlement_receipt_digest_matches_fields() {
+    let env = new_env();
+    env.ledger().set_timestamp(1700000000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts
+        .booking
+        .set_protocol_fee(&actors.admin, &500, &Address::generate(&env)); // 5%
+
+    contracts.token.mint(&actors.admin, &actors.passenger, &50);
+    let booking_id = create_paid_booking(&env, &actors, &contracts, 1705000000, 1_000);
+    assert!(contracts.booking.try_settlement_receipt(&booking_id).is_err());
+
+    env.ledger().set_timestamp(1705100000);
+    contracts.booking.release_payment_to_airline(&booking_id);
+
+    let receipt = contracts.booking.settlement_receipt(&booking_id);
+    assert_eq!(receipt.booking_id, booking_id);
+    assert_eq!(receipt.airline, actors.airline);
+    assert_eq!(receipt.amount, 1_000);
+    assert_eq!(receipt.fee, 50);
+    assert_eq!(receipt.settled_at, 1705100000);
+
+    let fields = (
+        receipt.booking_id,
+        receipt.airline.clone(),
+        receipt.amount,
+        receipt.fee,
+        receipt.settled_at,
+    );
+    let digest: soroban_sdk::BytesN<32> = env.crypto().keccak256(&fields.to_xdr(&env)).into();
+    assert_eq!(receipt.digest, digest);
+
+    // Any altered field no longer matches
+    let tampered = (receipt.booking_id, receipt.airline, receipt.amount + 1, receipt.fee, receipt.settled_at);
+    let tampered: soroban_sdk::BytesN<32> = env.crypto().keccak256(&tampered.to_xdr(&env)).into();
+    assert_ne!(receipt.digest, tampered);
 }
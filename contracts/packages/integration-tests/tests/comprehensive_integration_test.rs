@@ -127,6 +127,7 @@ fn test_loyalty_points_across_multiple_bookings() {
     register_and_verify_airline(&env, &contracts.airline, &actors.admin, &actors.airline);
 
     contracts.loyalty.init_loyalty();
+    contracts.loyalty.init_admin(&actors.admin);
 
     let base_price = 500_0000000i128;
     contracts
@@ -147,7 +148,7 @@ fn test_loyalty_points_across_multiple_bookings() {
     contracts.booking.pay_for_booking(&booking1);
     let points1 = contracts
         .loyalty
-        .award_points(&actors.passenger, &base_price, &booking1);
+        .award_points(&actors.admin, &actors.passenger, &base_price, &booking1);
 
     // Second booking
     let booking2 = contracts.booking.create_booking(
@@ -163,7 +164,7 @@ fn test_loyalty_points_across_multiple_bookings() {
     contracts.booking.pay_for_booking(&booking2);
     let points2 = contracts
         .loyalty
-        .award_points(&actors.passenger, &base_price, &booking2);
+        .award_points(&actors.admin, &actors.passenger, &base_price, &booking2);
 
     // Verify points accumulation
     assert!(points1 > 0);
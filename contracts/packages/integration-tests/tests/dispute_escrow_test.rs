@@ -0,0 +1,225 @@
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, Bytes, BytesN, Env, Symbol,
+};
+use booking::BookingStatus;
+use dispute::{DisputeContract, DisputeContractClient};
+use integration_tests::{generate_actors, initialize_token, new_env, register_contracts, Actors, Contracts};
+
+fn compute_commit_hash(env: &Env, vote_for_passenger: bool, salt: &BytesN<32>) -> BytesN<32> {
+    let mut hash_bytes = Bytes::new(env);
+    hash_bytes.push_back(if vote_for_passenger { 1u8 } else { 0u8 });
+    for byte in salt.to_array().iter() {
+        hash_bytes.push_back(*byte);
+    }
+    env.crypto().keccak256(&hash_bytes).into()
+}
+
+fn advance_ledger(env: &Env, seconds: u64) {
+    env.ledger().set_timestamp(env.ledger().timestamp() + seconds);
+}
+
+// Books and pays a 1_000 fare, then files a dispute over it and links the two.
+fn disputed_booking<'a>(
+    env: &'a Env,
+    actors: &Actors,
+    contracts: &Contracts<'a>,
+) -> (DisputeContractClient<'a>, u64, u64) {
+    initialize_token(env, &contracts.token, &actors.admin);
+
+    let booking_id = contracts.booking.create_booking(
+        &actors.passenger,
+        &actors.airline,
+        &Symbol::new(env, "FL123"),
+        &Symbol::new(env, "JFK"),
+        &Symbol::new(env, "LAX"),
+        &1_705_000_000,
+        &1_000,
+        &contracts.token.address,
+    );
+    contracts.token.mint(&actors.admin, &actors.passenger, &1_000);
+    contracts.booking.pay_for_booking(&booking_id);
+
+    let dispute = DisputeContractClient::new(env, &env.register(DisputeContract, ()));
+    let stake_token = env.register_stellar_asset_contract_v2(actors.admin.clone()).address();
+    dispute.initialize(
+        &actors.admin, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &stake_token,
+    );
+    let asset = token::StellarAssetClient::new(env, &stake_token);
+    asset.mint(&actors.passenger, &10_000);
+    asset.mint(&actors.airline, &10_000);
+
+    let dispute_id =
+        dispute.file_booking_dispute(&actors.passenger, &actors.airline, &booking_id, &1_000, &200);
+    dispute.airline_respond(&actors.airline, &dispute_id, &200);
+
+    contracts.booking.init_admin(&actors.admin);
+    contracts
+        .booking
+        .set_dispute_contract(&actors.admin, &dispute.address);
+    contracts
+        .booking
+        .hold_for_dispute(&actors.passenger, &booking_id, &dispute_id);
+
+    (dispute, booking_id, dispute_id)
+}
+
+// Seats three jurors voting `votes`; a `None` juror commits but never reveals
+fn run_to_executed_verdict(
+    env: &Env,
+    dispute: &DisputeContractClient,
+    owner: &Address,
+    dispute_id: u64,
+    votes: [Option<bool>; 3],
+) {
+    dispute.set_manual_jury_selection(owner, &true);
+    advance_ledger(env, 86401);
+
+    // The jury must be full before voting opens
+    let mut jurors = soroban_sdk::Vec::new(env);
+    for i in 0..3u8 {
+        let juror = Address::generate(env);
+        dispute.select_as_juror(&juror, &dispute_id, &1000);
+        jurors.push_back((juror, BytesN::from_array(env, &[i + 1; 32])));
+    }
+    for (i, (juror, salt)) in jurors.iter().enumerate() {
+        let vote = votes[i].unwrap_or(true);
+        dispute.commit_vote(&juror, &dispute_id, &compute_commit_hash(env, vote, &salt));
+    }
+
+    advance_ledger(env, 86401);
+    dispute.advance_to_reveal(&dispute_id);
+    for (i, (juror, salt)) in jurors.iter().enumerate() {
+        if let Some(vote) = votes[i] {
+            dispute.reveal_vote(&juror, &dispute_id, &vote, &salt);
+        }
+    }
+
+    advance_ledger(env, 86401);
+    dispute.finalize_dispute(owner, &dispute_id);
+    advance_ledger(env, 86401);
+    dispute.execute_verdict(owner, &dispute_id);
+}
+
+#[test]
+fn test_passenger_verdict_refunds_held_escrow() {
+    let env = new_env();
+    env.ledger().set_timestamp(1_700_000_000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    let (dispute, booking_id, dispute_id) = disputed_booking(&env, &actors, &contracts);
+
+    // Held escrow can't be released while the dispute is open
+    assert!(contracts
+        .booking
+        .try_release_payment_to_airline(&booking_id)
+        .is_err());
+    assert!(contracts.booking.try_settle_from_dispute(&dispute_id).is_err());
+
+    run_to_executed_verdict(&env, &dispute, &actors.admin, dispute_id, [Some(true); 3]);
+    let verdict = contracts.booking.settle_from_dispute(&dispute_id);
+
+    assert_eq!(verdict, Symbol::new(&env, "passenger"));
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 1_000);
+    let booking = contracts.booking.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.status, BookingStatus::Refunded);
+    assert_eq!(booking.amount_escrowed, 0);
+}
+
+#[test]
+fn test_airline_verdict_releases_held_escrow() {
+    let env = new_env();
+    env.ledger().set_timestamp(1_700_000_000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    let (dispute, booking_id, dispute_id) = disputed_booking(&env, &actors, &contracts);
+
+    run_to_executed_verdict(&env, &dispute, &actors.admin, dispute_id, [Some(false); 3]);
+    contracts.booking.settle_from_dispute(&dispute_id);
+
+    assert_eq!(contracts.token.balance_of(&actors.airline), 1_000);
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 0);
+    assert_eq!(
+        contracts.booking.get_booking(&booking_id).unwrap().status,
+        BookingStatus::Completed
+    );
+    // The link is consumed, so the escrow can't be paid out twice
+    assert!(contracts.booking.try_settle_from_dispute(&dispute_id).is_err());
+}
+
+#[test]
+fn test_tied_verdict_refunds_held_escrow() {
+    let env = new_env();
+    env.ledger().set_timestamp(1_700_000_000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    let (dispute, booking_id, dispute_id) = disputed_booking(&env, &actors, &contracts);
+
+    // One vote each way and a no-show: no majority, but the escrow still settles
+    run_to_executed_verdict(
+        &env,
+        &dispute,
+        &actors.admin,
+        dispute_id,
+        [Some(true), Some(false), None],
+    );
+    assert_eq!(dispute.get_final_verdict(&dispute_id), Some(Symbol::new(&env, "tie")));
+    assert_eq!(dispute.airline_open_disputes(&actors.airline), 0);
+    contracts.booking.settle_from_dispute(&dispute_id);
+
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 1_000);
+    let booking = contracts.booking.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.status, BookingStatus::Refunded);
+    assert_eq!(booking.amount_escrowed, 0);
+}
+
+#[test]
+fn test_expired_dispute_returns_booking_with_escrow() {
+    let env = new_env();
+    env.ledger().set_timestamp(1_700_000_000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    let (dispute, booking_id, dispute_id) = disputed_booking(&env, &actors, &contracts);
+
+    // No jury ever forms, so the dispute is expired instead of decided
+    let appeal_deadline = dispute.get_dispute(&dispute_id).unwrap().appeal_deadline;
+    env.ledger().set_timestamp(appeal_deadline + 7 * 86400 + 1);
+    dispute.expire_dispute(&dispute_id);
+    contracts.booking.settle_from_dispute(&dispute_id);
+
+    let booking = contracts.booking.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.status, BookingStatus::Confirmed);
+    assert_eq!(booking.amount_escrowed, 1_000);
+    assert_eq!(contracts.token.balance_of(&actors.airline), 0);
+    assert!(contracts.booking.try_settle_from_dispute(&dispute_id).is_err());
+}
+
+#[test]
+#[should_panic(expected = "Dispute not about this booking")]
+fn test_dispute_over_another_booking_cannot_hold_escrow() {
+    let env = new_env();
+    env.ledger().set_timestamp(1_700_000_000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    let (dispute, booking_id, _) = disputed_booking(&env, &actors, &contracts);
+
+    // Same parties, but filed over a different booking
+    let other_id = contracts.booking.create_booking(
+        &actors.passenger,
+        &actors.airline,
+        &Symbol::new(&env, "FL124"),
+        &Symbol::new(&env, "JFK"),
+        &Symbol::new(&env, "LAX"),
+        &1_705_000_000,
+        &1_000,
+        &contracts.token.address,
+    );
+    contracts.token.mint(&actors.admin, &actors.passenger, &1_000);
+    contracts.booking.pay_for_booking(&other_id);
+    let dispute_id =
+        dispute.file_booking_dispute(&actors.passenger, &actors.airline, &booking_id, &1_000, &200);
+
+    contracts
+        .booking
+        .hold_for_dispute(&actors.passenger, &other_id, &dispute_id);
+}
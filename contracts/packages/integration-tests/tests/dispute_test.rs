@@ -2,9 +2,13 @@
 
 use soroban_sdk::{
     testutils::{Address as _, Ledger, LedgerInfo},
-    Address, Bytes, BytesN, Env, Symbol,
+    token, Address, Bytes, BytesN, Env, Symbol,
 };
-use dispute::{DisputeContract, DisputeContractClient};
+use dispute::{
+    DisputeContract, DisputeContractClient, DisputePhase, ForfeitDestination, JuryMetrics,
+    LegacyDispute, LegacyDisputeConfig, RewardDistribution,
+};
+use storage_version::{VersionedStorage, DISPUTE_CONTRACT};
 
 fn compute_commit_hash(env: &Env, vote_for_passenger: bool, salt: &BytesN<32>) -> BytesN<32> {
     let mut hash_bytes = Bytes::new(env);
@@ -20,6 +24,16 @@ fn create_dispute_contract(env: &Env) -> Address {
     env.register(DisputeContract, ())
 }
 
+fn create_stake_token(env: &Env, admin: &Address) -> Address {
+    env.register_stellar_asset_contract_v2(admin.clone()).address()
+}
+
+fn fund_parties(env: &Env, token: &Address, passenger: &Address, airline: &Address) {
+    let asset = token::StellarAssetClient::new(env, token);
+    asset.mint(passenger, &100_000);
+    asset.mint(airline, &100_000);
+}
+
 fn advance_ledger(env: &Env, seconds: u64) {
     env.ledger().set(LedgerInfo {
         timestamp: env.ledger().timestamp() + seconds,
@@ -51,6 +65,7 @@ fn test_initialize() {
         &86400, // appeal_period (1 day)
         &5000,  // appeal_stake_multiplier (50%)
         &2000,  // jury_reward_pool_percentage (20%)
+        &create_stake_token(&env, &owner),
     );
 
     let config = client.get_config();
@@ -67,10 +82,12 @@ fn test_multiple_disputes() {
     let client = DisputeContractClient::new(&env, &contract_id);
 
     let owner = Address::generate(&env);
-    client.initialize(&owner, &2000, &5, &86400, &86400, &86400, &86400, &5000, &2000);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &5, &86400, &86400, &86400, &86400, &5000, &2000, &token);
 
     let passenger = Address::generate(&env);
     let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
 
     let dispute_id1 = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
     let dispute_id2 = client.file_dispute(&passenger, &airline, &2, &10000, &2000);
@@ -87,10 +104,12 @@ fn test_file_dispute() {
     let contract_id = create_dispute_contract(&env);
     let client = DisputeContractClient::new(&env, &contract_id);
     let owner = Address::generate(&env);
-    client.initialize(&owner, &2000, &5, &86400, &86400, &86400, &86400, &5000, &2000);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &5, &86400, &86400, &86400, &86400, &5000, &2000, &token);
 
     let passenger = Address::generate(&env);
     let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
 
     let dispute_id = client.file_dispute(
         &passenger, &airline, &1,     // refund_request_id
@@ -119,10 +138,12 @@ fn test_file_dispute_insufficient_stake() {
     let contract_id = create_dispute_contract(&env);
     let client = DisputeContractClient::new(&env, &contract_id);
     let owner = Address::generate(&env);
-    client.initialize(&owner, &2000, &5, &86400, &86400, &86400, &86400, &5000, &2000);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &5, &86400, &86400, &86400, &86400, &5000, &2000, &token);
 
     let passenger = Address::generate(&env);
     let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
 
     client.file_dispute(
         &passenger, &airline, &1, &10000, &1000, // Only 10%, need 20%
@@ -137,10 +158,12 @@ fn test_airline_respond() {
     let contract_id = create_dispute_contract(&env);
     let client = DisputeContractClient::new(&env, &contract_id);
     let owner = Address::generate(&env);
-    client.initialize(&owner, &2000, &5, &86400, &86400, &86400, &86400, &5000, &2000);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &5, &86400, &86400, &86400, &86400, &5000, &2000, &token);
 
     let passenger = Address::generate(&env);
     let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
 
     let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
 
@@ -158,10 +181,12 @@ fn test_submit_evidence() {
     let contract_id = create_dispute_contract(&env);
     let client = DisputeContractClient::new(&env, &contract_id);
     let owner = Address::generate(&env);
-    client.initialize(&owner, &2000, &5, &86400, &86400, &86400, &86400, &5000, &2000);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &5, &86400, &86400, &86400, &86400, &5000, &2000, &token);
 
     let passenger = Address::generate(&env);
     let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
 
     let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
     client.airline_respond(&airline, &dispute_id, &2000);
@@ -187,10 +212,13 @@ fn test_jury_selection() {
     let contract_id = create_dispute_contract(&env);
     let client = DisputeContractClient::new(&env, &contract_id);
     let owner = Address::generate(&env);
-    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
 
     let passenger = Address::generate(&env);
     let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
 
     let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
     client.airline_respond(&airline, &dispute_id, &2000);
@@ -222,10 +250,13 @@ fn test_party_cannot_be_juror() {
     let contract_id = create_dispute_contract(&env);
     let client = DisputeContractClient::new(&env, &contract_id);
     let owner = Address::generate(&env);
-    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
 
     let passenger = Address::generate(&env);
     let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
 
     let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
 
@@ -242,10 +273,13 @@ fn test_commit_reveal_voting() {
     let contract_id = create_dispute_contract(&env);
     let client = DisputeContractClient::new(&env, &contract_id);
     let owner = Address::generate(&env);
-    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
 
     let passenger = Address::generate(&env);
     let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
 
     let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
     client.airline_respond(&airline, &dispute_id, &2000);
@@ -293,10 +327,13 @@ fn test_finalize_dispute() {
     let contract_id = create_dispute_contract(&env);
     let client = DisputeContractClient::new(&env, &contract_id);
     let owner = Address::generate(&env);
-    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
 
     let passenger = Address::generate(&env);
     let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
 
     let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
     client.airline_respond(&airline, &dispute_id, &2000);
@@ -346,10 +383,13 @@ fn test_appeal_mechanism() {
     let contract_id = create_dispute_contract(&env);
     let client = DisputeContractClient::new(&env, &contract_id);
     let owner = Address::generate(&env);
-    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
 
     let passenger = Address::generate(&env);
     let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
 
     let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
     client.airline_respond(&airline, &dispute_id, &2000);
@@ -394,7 +434,7 @@ fn test_appeal_mechanism() {
     client.file_appeal(&passenger, &dispute_id, &5000);
 
     let dispute_after_appeal = client.get_dispute(&dispute_id).unwrap();
-    assert!(dispute_after_appeal.appealed);
+    assert_eq!(dispute_after_appeal.appeal_round, 1);
     assert!(dispute_after_appeal.verdict.is_none());
 }
 
@@ -406,10 +446,13 @@ fn test_execute_verdict() {
     let contract_id = create_dispute_contract(&env);
     let client = DisputeContractClient::new(&env, &contract_id);
     let owner = Address::generate(&env);
-    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
 
     let passenger = Address::generate(&env);
     let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
 
     let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
     client.airline_respond(&airline, &dispute_id, &2000);
@@ -461,10 +504,13 @@ fn test_claim_juror_reward() {
     let contract_id = create_dispute_contract(&env);
     let client = DisputeContractClient::new(&env, &contract_id);
     let owner = Address::generate(&env);
-    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
 
     let passenger = Address::generate(&env);
     let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
 
     let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
     client.airline_respond(&airline, &dispute_id, &2000);
@@ -524,10 +570,13 @@ fn test_claim_juror_reward_wrong_vote() {
     let contract_id = create_dispute_contract(&env);
     let client = DisputeContractClient::new(&env, &contract_id);
     let owner = Address::generate(&env);
-    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
 
     let passenger = Address::generate(&env);
     let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
 
     let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
     client.airline_respond(&airline, &dispute_id, &2000);
@@ -578,10 +627,13 @@ fn test_complete_dispute_lifecycle() {
     let contract_id = create_dispute_contract(&env);
     let client = DisputeContractClient::new(&env, &contract_id);
     let owner = Address::generate(&env);
-    client.initialize(&owner, &2000, &5, &86400, &86400, &86400, &86400, &5000, &2000);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &5, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
 
     let passenger = Address::generate(&env);
     let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
 
     let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
     assert_eq!(dispute_id, 1);
@@ -648,3 +700,1640 @@ fn test_complete_dispute_lifecycle() {
         }
     }
 }
+
+#[test]
+#[should_panic(expected = "Airline stake below match ratio")]
+fn test_airline_respond_under_matched_stake_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &5, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_stake_match_ratio(&owner, &5000);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+
+    // Passenger stakes 6000; airline must put up at least 50% of that
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &6000);
+    client.airline_respond(&airline, &dispute_id, &2000);
+}
+
+#[test]
+fn test_airline_respond_matched_stake_accepted() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &5, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_stake_match_ratio(&owner, &5000);
+    assert_eq!(client.get_stake_match_ratio(), 5000);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &6000);
+    client.airline_respond(&airline, &dispute_id, &3000);
+
+    let dispute = client.get_dispute(&dispute_id).unwrap();
+    assert_eq!(dispute.airline_stake, 3000);
+}
+
+#[test]
+fn test_disputes_in_phase_uses_effective_phase() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &5, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+
+    let first = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    advance_ledger(&env, 43200);
+    let second = client.file_dispute(&passenger, &airline, &2, &10000, &2000);
+
+    // First dispute's evidence deadline has passed, second's has not
+    advance_ledger(&env, 43201);
+
+    let selecting = client.disputes_in_phase(&DisputePhase::JurySelection, &0, &10);
+    assert_eq!(selecting.len(), 1);
+    assert_eq!(selecting.get(0).unwrap(), first);
+
+    let evidence = client.disputes_in_phase(&DisputePhase::Evidence, &0, &10);
+    assert_eq!(evidence.len(), 1);
+    assert_eq!(evidence.get(0).unwrap(), second);
+
+    // Stored phase is unchanged; only the query derives it
+    assert_eq!(client.get_dispute(&first).unwrap().phase, DisputePhase::Evidence);
+    assert_eq!(client.disputes_in_phase(&DisputePhase::JurySelection, &1, &10).len(), 0);
+}
+
+fn run_to_passenger_verdict(
+    env: &Env,
+    client: &DisputeContractClient,
+    owner: &Address,
+    dispute_id: u64,
+) -> (Address, Address, Address) {
+    client.set_manual_jury_selection(owner, &true);
+    advance_ledger(env, 86401);
+
+    let juror1 = Address::generate(env);
+    let juror2 = Address::generate(env);
+    let juror3 = Address::generate(env);
+    client.select_as_juror(&juror1, &dispute_id, &1000);
+    client.select_as_juror(&juror2, &dispute_id, &1500);
+    client.select_as_juror(&juror3, &dispute_id, &2000);
+
+    let salt1 = BytesN::from_array(env, &[1u8; 32]);
+    let salt2 = BytesN::from_array(env, &[2u8; 32]);
+    let salt3 = BytesN::from_array(env, &[3u8; 32]);
+    client.commit_vote(&juror1, &dispute_id, &compute_commit_hash(env, true, &salt1));
+    client.commit_vote(&juror2, &dispute_id, &compute_commit_hash(env, true, &salt2));
+    client.commit_vote(&juror3, &dispute_id, &compute_commit_hash(env, false, &salt3));
+
+    advance_ledger(env, 86401);
+    client.advance_to_reveal(&dispute_id);
+    client.reveal_vote(&juror1, &dispute_id, &true, &salt1);
+    client.reveal_vote(&juror2, &dispute_id, &true, &salt2);
+    client.reveal_vote(&juror3, &dispute_id, &false, &salt3);
+
+    advance_ledger(env, 86401);
+    client.finalize_dispute(owner, &dispute_id);
+    advance_ledger(env, 86401);
+
+    (juror1, juror2, juror3)
+}
+
+#[test]
+fn test_stakes_and_rewards_move_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    let balances = token::Client::new(&env, &token);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    client.airline_respond(&airline, &dispute_id, &3000);
+    assert_eq!(balances.balance(&passenger), 98_000);
+    assert_eq!(balances.balance(&airline), 97_000);
+    assert_eq!(balances.balance(&contract_id), 5_000);
+
+    let (juror1, juror2, _juror3) = run_to_passenger_verdict(&env, &client, &owner, dispute_id);
+    client.execute_verdict(&owner, &dispute_id);
+
+    // Pool of 5000, 20% (1000) reserved for jurors, rest to the winning passenger
+    assert_eq!(balances.balance(&passenger), 102_000);
+    assert_eq!(balances.balance(&airline), 97_000);
+    assert_eq!(balances.balance(&contract_id), 1_000);
+
+    assert_eq!(client.claim_juror_reward(&juror1, &dispute_id), 500);
+    assert_eq!(client.claim_juror_reward(&juror2, &dispute_id), 500);
+    assert_eq!(balances.balance(&juror1), 500);
+    assert_eq!(balances.balance(&juror2), 500);
+    assert_eq!(balances.balance(&contract_id), 0);
+    assert!(client.has_claimed_reward(&dispute_id, &juror1));
+}
+
+#[test]
+fn test_in_flight_dispute_settles_in_old_token_after_rotation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let old_token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &old_token);
+    let old_balances = token::Client::new(&env, &old_token);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &old_token, &passenger, &airline);
+
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    client.airline_respond(&airline, &dispute_id, &3000);
+
+    let new_token = create_stake_token(&env, &owner);
+    client.set_dispute_token(&owner, &new_token);
+    assert_eq!(client.get_config().unwrap().token, new_token);
+    assert_eq!(client.get_dispute(&dispute_id).unwrap().token, old_token);
+
+    let (juror1, _juror2, _juror3) = run_to_passenger_verdict(&env, &client, &owner, dispute_id);
+    client.execute_verdict(&owner, &dispute_id);
+    assert_eq!(old_balances.balance(&passenger), 102_000);
+    assert_eq!(client.claim_juror_reward(&juror1, &dispute_id), 500);
+    assert_eq!(old_balances.balance(&juror1), 500);
+
+    // New filings stake the rotated token
+    let new_balances = token::Client::new(&env, &new_token);
+    fund_parties(&env, &new_token, &passenger, &airline);
+    let second = client.file_dispute(&passenger, &airline, &2, &10000, &2000);
+    assert_eq!(client.get_dispute(&second).unwrap().token, new_token);
+    assert_eq!(new_balances.balance(&contract_id), 2_000);
+}
+
+#[test]
+#[should_panic(expected = "Reward already claimed")]
+fn test_juror_reward_cannot_be_claimed_twice() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    client.airline_respond(&airline, &dispute_id, &2000);
+
+    let (juror1, _juror2, _juror3) = run_to_passenger_verdict(&env, &client, &owner, dispute_id);
+    client.execute_verdict(&owner, &dispute_id);
+
+    client.claim_juror_reward(&juror1, &dispute_id);
+    client.claim_juror_reward(&juror1, &dispute_id);
+}
+
+#[test]
+fn test_reveal_auto_advances_after_voting_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &1, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    client.airline_respond(&airline, &dispute_id, &2000);
+
+    advance_ledger(&env, 86401);
+
+    let juror = Address::generate(&env);
+    client.select_as_juror(&juror, &dispute_id, &1000);
+    let salt = BytesN::from_array(&env, &[1u8; 32]);
+    client.commit_vote(&juror, &dispute_id, &compute_commit_hash(&env, true, &salt));
+
+    advance_ledger(&env, 86401);
+
+    // No advance_to_reveal call
+    client.reveal_vote(&juror, &dispute_id, &true, &salt);
+
+    let dispute = client.get_dispute(&dispute_id).unwrap();
+    assert_eq!(dispute.phase, DisputePhase::RevealVote);
+    assert_eq!(dispute.votes_for_passenger, 1);
+}
+
+#[test]
+fn test_jury_metrics_for_partially_revealed_jury() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    client.airline_respond(&airline, &dispute_id, &2000);
+
+    advance_ledger(&env, 86401);
+
+    let juror1 = Address::generate(&env);
+    let juror2 = Address::generate(&env);
+    let juror3 = Address::generate(&env);
+    client.select_as_juror(&juror1, &dispute_id, &1000);
+    client.select_as_juror(&juror2, &dispute_id, &1500);
+    client.select_as_juror(&juror3, &dispute_id, &2000);
+
+    let salt1 = BytesN::from_array(&env, &[1u8; 32]);
+    let salt2 = BytesN::from_array(&env, &[2u8; 32]);
+    let salt3 = BytesN::from_array(&env, &[3u8; 32]);
+    client.commit_vote(&juror1, &dispute_id, &compute_commit_hash(&env, true, &salt1));
+    client.commit_vote(&juror2, &dispute_id, &compute_commit_hash(&env, true, &salt2));
+    client.commit_vote(&juror3, &dispute_id, &compute_commit_hash(&env, false, &salt3));
+
+    advance_ledger(&env, 86401);
+    client.advance_to_reveal(&dispute_id);
+
+    // juror3 never reveals
+    client.reveal_vote(&juror1, &dispute_id, &true, &salt1);
+    client.reveal_vote(&juror2, &dispute_id, &true, &salt2);
+
+    assert_eq!(
+        client.jury_metrics(&dispute_id),
+        JuryMetrics {
+            jury_size: 3,
+            revealed_count: 2,
+            total_token_balance: 4500,
+            winning_margin: 2,
+        }
+    );
+}
+
+#[test]
+#[should_panic(expected = "Manual jury selection disabled")]
+fn test_manual_jury_selection_disabled_by_default() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+
+    advance_ledger(&env, 86401);
+    client.select_as_juror(&Address::generate(&env), &dispute_id, &1000);
+}
+
+#[test]
+fn test_draw_jury_seats_candidates() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+
+    client.set_juror_deposit(&owner, &100);
+    client.set_juror_stake(&owner, &50);
+
+    let asset = token::StellarAssetClient::new(&env, &token);
+    let balances = token::Client::new(&env, &token);
+    let mut candidates = std::vec::Vec::new();
+    for _ in 0..5 {
+        let candidate = Address::generate(&env);
+        asset.mint(&candidate, &1000);
+        client.register_juror_candidate(&candidate, &dispute_id, &500);
+        assert_eq!(client.get_juror_stake(&dispute_id, &candidate), 50);
+        // Deposit, stake and the 500 weight lock all move into the contract
+        assert_eq!(balances.balance(&candidate), 350);
+        candidates.push(candidate);
+    }
+    // Too early: evidence is still open
+    assert!(client.try_draw_jury(&dispute_id).is_err());
+
+    advance_ledger(&env, 86401);
+    client.draw_jury(&dispute_id);
+
+    assert_eq!(client.get_juror_count(&dispute_id), 3);
+    assert_eq!(client.get_juror_candidates(&dispute_id).len(), 0);
+    let dispute = client.get_dispute(&dispute_id).unwrap();
+    assert_eq!(dispute.phase, DisputePhase::CommitVote);
+    for i in 0..3 {
+        let selection = client.get_juror(&dispute_id, &i).unwrap();
+        assert!(client.is_juror(&dispute_id, &selection.juror));
+    }
+
+    // Weight locks come back to everyone; seated jurors keep their deposit and stake in,
+    // the two left out are refunded in full
+    let mut refunded = 0;
+    for candidate in candidates.iter() {
+        if client.is_juror(&dispute_id, candidate) {
+            assert_eq!(client.get_juror_stake(&dispute_id, candidate), 50);
+            assert_eq!(balances.balance(candidate), 850);
+        } else {
+            assert_eq!(client.get_juror_stake(&dispute_id, candidate), 0);
+            assert_eq!(client.get_juror_deposit(&dispute_id, candidate), 0);
+            assert_eq!(balances.balance(candidate), 1000);
+            refunded += 1;
+        }
+    }
+    assert_eq!(refunded, 2);
+}
+
+#[test]
+fn test_draw_jury_favors_higher_balances() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.cost_estimate().budget().reset_unlimited();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &1, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+
+    // A candidate holding 9x the balance of the other, in 100 single-seat draws
+    let heavy = Address::generate(&env);
+    let light = Address::generate(&env);
+    let asset = token::StellarAssetClient::new(&env, &token);
+    asset.mint(&heavy, &9000);
+    asset.mint(&light, &1000);
+    let draws = 100u32;
+    let mut disputes = std::vec::Vec::new();
+    for i in 0..draws {
+        disputes.push(client.file_dispute(&passenger, &airline, &(i as u64), &1000, &200));
+    }
+
+    // Each draw returns the locks, so the same balances back every registration
+    advance_ledger(&env, 86401);
+    let mut heavy_seats = 0u32;
+    for dispute_id in disputes {
+        client.register_juror_candidate(&heavy, &dispute_id, &9000);
+        client.register_juror_candidate(&light, &dispute_id, &1000);
+        client.draw_jury(&dispute_id);
+        if client.get_juror(&dispute_id, &0).unwrap().juror == heavy {
+            heavy_seats += 1;
+        }
+    }
+
+    // Expected ~90; a fair coin would give ~50
+    assert!(heavy_seats >= 75, "heavy candidate seated {} of {}", heavy_seats, draws);
+    assert!(heavy_seats < draws);
+}
+
+#[test]
+#[should_panic(expected = "Must hold TRQ tokens")]
+fn test_candidate_without_trq_balance_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+
+    // The weight comes from the token, so an empty wallet can't claim one
+    client.register_juror_candidate(&Address::generate(&env), &dispute_id, &1000);
+}
+
+#[test]
+fn test_candidate_weight_is_locked_until_draw() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &1, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+
+    let asset = token::StellarAssetClient::new(&env, &token);
+    let balances = token::Client::new(&env, &token);
+    let candidate = Address::generate(&env);
+    let other = Address::generate(&env);
+    asset.mint(&candidate, &1000);
+    client.register_juror_candidate(&candidate, &dispute_id, &1000);
+    assert_eq!(client.get_juror_candidates(&dispute_id).get(0).unwrap().token_balance, 1000);
+
+    // The locked balance can't be passed on to back a second candidate
+    assert_eq!(balances.balance(&candidate), 0);
+    assert!(balances.try_transfer(&candidate, &other, &1000).is_err());
+
+    advance_ledger(&env, 86401);
+    client.draw_jury(&dispute_id);
+    assert!(client.is_juror(&dispute_id, &candidate));
+    assert_eq!(balances.balance(&candidate), 1000);
+}
+
+#[test]
+#[should_panic(expected = "Already selected")]
+fn test_seated_juror_cannot_register_again() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+
+    advance_ledger(&env, 86401);
+    let juror = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token).mint(&juror, &1000);
+    client.select_as_juror(&juror, &dispute_id, &1000);
+
+    // A second seat in the same dispute would double the juror's vote
+    client.register_juror_candidate(&juror, &dispute_id, &1000);
+}
+
+#[test]
+#[should_panic(expected = "Invalid weight")]
+fn test_candidate_cannot_claim_more_weight_than_held() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+
+    let candidate = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token).mint(&candidate, &1000);
+    client.register_juror_candidate(&candidate, &dispute_id, &5000);
+}
+
+// Five jurors commit but only the first two reveal
+fn run_to_partial_reveal(env: &Env, client: &DisputeContractClient, owner: &Address) -> u64 {
+    let passenger = Address::generate(env);
+    let airline = Address::generate(env);
+    let token = client.get_config().unwrap().token;
+    fund_parties(env, &token, &passenger, &airline);
+
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    client.airline_respond(&airline, &dispute_id, &2000);
+    client.set_manual_jury_selection(owner, &true);
+
+    advance_ledger(env, 86401);
+
+    let mut jurors = std::vec::Vec::new();
+    for i in 0..5u8 {
+        let juror = Address::generate(env);
+        let salt = BytesN::from_array(env, &[i + 1; 32]);
+        client.select_as_juror(&juror, &dispute_id, &1000);
+        jurors.push((juror, salt));
+    }
+    for (juror, salt) in jurors.iter() {
+        client.commit_vote(juror, &dispute_id, &compute_commit_hash(env, true, salt));
+    }
+
+    advance_ledger(env, 86401);
+    client.advance_to_reveal(&dispute_id);
+    for (juror, salt) in jurors.iter().take(2) {
+        client.reveal_vote(juror, &dispute_id, &true, salt);
+    }
+
+    advance_ledger(env, 86401);
+    dispute_id
+}
+
+#[test]
+#[should_panic(expected = "Reveal quorum not met")]
+fn test_finalize_requires_reveal_quorum() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &5, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    // 3 of 5 jurors must reveal
+    client.set_min_reveal_quorum(&owner, &6000);
+
+    let dispute_id = run_to_partial_reveal(&env, &client, &owner);
+    client.finalize_dispute(&owner, &dispute_id);
+}
+
+#[test]
+fn test_restart_vote_after_missed_quorum() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &5, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_min_reveal_quorum(&owner, &6000);
+
+    let dispute_id = run_to_partial_reveal(&env, &client, &owner);
+    client.restart_vote(&owner, &dispute_id);
+
+    let dispute = client.get_dispute(&dispute_id).unwrap();
+    assert_eq!(dispute.phase, DisputePhase::CommitVote);
+    assert_eq!(dispute.votes_for_passenger, 0);
+    assert!(dispute.voting_deadline > env.ledger().timestamp());
+    let first = client.get_juror(&dispute_id, &0).unwrap().juror;
+    assert!(client.get_vote_commit(&dispute_id, &first).is_none());
+    assert!(client.get_vote_reveal(&dispute_id, &first).is_none());
+}
+
+#[test]
+fn test_no_show_juror_forfeits_deposit() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
+    client.set_juror_deposit(&owner, &100);
+    let balances = token::Client::new(&env, &token);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    client.airline_respond(&airline, &dispute_id, &3000);
+
+    advance_ledger(&env, 86401);
+
+    let revealer1 = Address::generate(&env);
+    let revealer2 = Address::generate(&env);
+    let no_show = Address::generate(&env);
+    let jurors = [&revealer1, &revealer2, &no_show];
+    for juror in jurors {
+        token::StellarAssetClient::new(&env, &token).mint(juror, &100);
+        client.select_as_juror(juror, &dispute_id, &1000);
+    }
+    let mut salts = std::vec::Vec::new();
+    for (i, juror) in jurors.into_iter().enumerate() {
+        let salt = BytesN::from_array(&env, &[i as u8 + 1; 32]);
+        client.commit_vote(juror, &dispute_id, &compute_commit_hash(&env, true, &salt));
+        salts.push(salt);
+    }
+    assert_eq!(balances.balance(&no_show), 0);
+    assert_eq!(client.get_juror_deposit(&dispute_id, &no_show), 100);
+
+    advance_ledger(&env, 86401);
+    client.advance_to_reveal(&dispute_id);
+    client.reveal_vote(&revealer1, &dispute_id, &true, &salts[0]);
+    client.reveal_vote(&revealer2, &dispute_id, &true, &salts[1]);
+
+    advance_ledger(&env, 86401);
+    client.finalize_dispute(&owner, &dispute_id);
+
+    // Deposits stay in during the appeal window, when the jury could be seated again
+    assert!(client.try_claim_juror_deposit(&revealer1, &dispute_id).is_err());
+
+    advance_ledger(&env, 86401);
+    client.execute_verdict(&owner, &dispute_id);
+
+    // The revealer gets its deposit back; the no-show's is forfeited
+    assert_eq!(client.claim_juror_deposit(&revealer1, &dispute_id), 100);
+    assert_eq!(balances.balance(&revealer1), 100);
+    assert!(client.try_claim_juror_deposit(&no_show, &dispute_id).is_err());
+    assert!(client.try_claim_juror_deposit(&revealer1, &dispute_id).is_err());
+
+    // Forfeited deposit is shared by the winning jurors on top of the 1000 stake share
+    assert_eq!(client.claim_juror_reward(&revealer1, &dispute_id), 550);
+    assert_eq!(client.claim_juror_reward(&revealer2, &dispute_id), 550);
+    assert_eq!(client.claim_juror_deposit(&revealer2, &dispute_id), 100);
+    assert_eq!(balances.balance(&no_show), 0);
+    assert_eq!(balances.balance(&contract_id), 0);
+}
+
+#[test]
+#[should_panic(expected = "Deposit forfeited")]
+fn test_no_show_juror_cannot_reclaim_deposit() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &2, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
+    client.set_juror_deposit(&owner, &100);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+
+    advance_ledger(&env, 86401);
+    let revealer = Address::generate(&env);
+    let no_show = Address::generate(&env);
+    for juror in [&revealer, &no_show] {
+        token::StellarAssetClient::new(&env, &token).mint(juror, &100);
+        client.select_as_juror(juror, &dispute_id, &1000);
+    }
+    let salt = BytesN::from_array(&env, &[1u8; 32]);
+    client.commit_vote(&revealer, &dispute_id, &compute_commit_hash(&env, true, &salt));
+    client.commit_vote(&no_show, &dispute_id, &compute_commit_hash(&env, true, &salt));
+
+    advance_ledger(&env, 86401);
+    client.advance_to_reveal(&dispute_id);
+    client.reveal_vote(&revealer, &dispute_id, &true, &salt);
+    advance_ledger(&env, 86401);
+    client.finalize_dispute(&owner, &dispute_id);
+    advance_ledger(&env, 86401);
+    client.execute_verdict(&owner, &dispute_id);
+
+    client.claim_juror_deposit(&no_show, &dispute_id);
+}
+
+#[test]
+fn test_tied_verdict_returns_stakes_and_splits_forfeits() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
+    client.set_juror_deposit(&owner, &100);
+    client.set_juror_stake(&owner, &50);
+    let balances = token::Client::new(&env, &token);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    client.airline_respond(&airline, &dispute_id, &3000);
+    advance_ledger(&env, 86401);
+
+    let for_passenger = Address::generate(&env);
+    let for_airline = Address::generate(&env);
+    let no_show = Address::generate(&env);
+    let jurors = [(&for_passenger, true), (&for_airline, false), (&no_show, true)];
+    for (juror, _) in jurors {
+        token::StellarAssetClient::new(&env, &token).mint(juror, &150);
+        client.select_as_juror(juror, &dispute_id, &1000);
+    }
+    let mut salts = std::vec::Vec::new();
+    for (i, (juror, vote)) in jurors.into_iter().enumerate() {
+        let salt = BytesN::from_array(&env, &[i as u8 + 1; 32]);
+        client.commit_vote(juror, &dispute_id, &compute_commit_hash(&env, vote, &salt));
+        salts.push(salt);
+    }
+
+    advance_ledger(&env, 86401);
+    client.advance_to_reveal(&dispute_id);
+    client.reveal_vote(&for_passenger, &dispute_id, &true, &salts[0]);
+    client.reveal_vote(&for_airline, &dispute_id, &false, &salts[1]);
+    advance_ledger(&env, 86401);
+    client.finalize_dispute(&owner, &dispute_id);
+    advance_ledger(&env, 86401);
+    client.execute_verdict(&owner, &dispute_id);
+
+    // Each party gets its own stake back plus half the no-show's 150 deposit and stake
+    let dispute = client.get_dispute(&dispute_id).unwrap();
+    assert_eq!(dispute.phase, DisputePhase::Finalized);
+    assert_eq!(balances.balance(&passenger), 100_000 + 75);
+    assert_eq!(balances.balance(&airline), 100_000 + 75);
+    assert_eq!(client.airline_open_disputes(&airline), 0);
+
+    // No majority to reward, so the revealers take back deposit and stake
+    assert!(client.try_claim_juror_reward(&for_passenger, &dispute_id).is_err());
+    assert_eq!(client.claim_juror_deposit(&for_passenger, &dispute_id), 150);
+    assert_eq!(client.claim_juror_deposit(&for_airline, &dispute_id), 150);
+    assert!(client.try_claim_juror_deposit(&no_show, &dispute_id).is_err());
+    assert_eq!(balances.balance(&contract_id), 0);
+}
+
+// Passenger wins 2-0 with a third juror never revealing, forfeiting its 100 deposit.
+// Returns the dispute and the two revealing jurors, ready for execute_verdict.
+fn run_with_no_show_juror(
+    env: &Env,
+    client: &DisputeContractClient,
+    owner: &Address,
+    token: &Address,
+) -> (u64, Address, Address) {
+    client.set_manual_jury_selection(owner, &true);
+    client.set_juror_deposit(owner, &100);
+
+    let passenger = Address::generate(env);
+    let airline = Address::generate(env);
+    fund_parties(env, token, &passenger, &airline);
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    client.airline_respond(&airline, &dispute_id, &3000);
+    advance_ledger(env, 86401);
+
+    let revealer1 = Address::generate(env);
+    let revealer2 = Address::generate(env);
+    let no_show = Address::generate(env);
+    let jurors = [&revealer1, &revealer2, &no_show];
+    for juror in jurors {
+        token::StellarAssetClient::new(env, token).mint(juror, &100);
+        client.select_as_juror(juror, &dispute_id, &1000);
+    }
+    let mut salts = std::vec::Vec::new();
+    for (i, juror) in jurors.into_iter().enumerate() {
+        let salt = BytesN::from_array(env, &[i as u8 + 1; 32]);
+        client.commit_vote(juror, &dispute_id, &compute_commit_hash(env, true, &salt));
+        salts.push(salt);
+    }
+
+    advance_ledger(env, 86401);
+    client.advance_to_reveal(&dispute_id);
+    client.reveal_vote(&revealer1, &dispute_id, &true, &salts[0]);
+    client.reveal_vote(&revealer2, &dispute_id, &true, &salts[1]);
+    advance_ledger(env, 86401);
+    client.finalize_dispute(owner, &dispute_id);
+    advance_ledger(env, 86401);
+
+    (dispute_id, revealer1, revealer2)
+}
+
+#[test]
+fn test_forfeits_to_jurors_share_reward_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    assert_eq!(client.get_forfeit_destination(), ForfeitDestination::Jurors);
+    let balances = token::Client::new(&env, &token);
+
+    let (dispute_id, revealer1, revealer2) = run_with_no_show_juror(&env, &client, &owner, &token);
+    let passenger = client.get_dispute(&dispute_id).unwrap().passenger;
+    client.execute_verdict(&owner, &dispute_id);
+
+    assert_eq!(balances.balance(&passenger), 102_000);
+    assert_eq!(client.claim_juror_reward(&revealer1, &dispute_id), 550);
+    assert_eq!(client.claim_juror_reward(&revealer2, &dispute_id), 550);
+}
+
+#[test]
+fn test_juror_stake_locked_on_selection_and_returned_with_majority_reward() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
+    client.set_juror_stake(&owner, &500);
+    let balances = token::Client::new(&env, &token);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    client.airline_respond(&airline, &dispute_id, &3000);
+    advance_ledger(&env, 86401);
+
+    let majority1 = Address::generate(&env);
+    let majority2 = Address::generate(&env);
+    let minority = Address::generate(&env);
+    let jurors = [(&majority1, true), (&majority2, true), (&minority, false)];
+    for (juror, _) in jurors {
+        token::StellarAssetClient::new(&env, &token).mint(juror, &500);
+        client.select_as_juror(juror, &dispute_id, &1000);
+        assert_eq!(balances.balance(juror), 0);
+        assert_eq!(client.get_juror_stake(&dispute_id, juror), 500);
+    }
+    let mut salts = std::vec::Vec::new();
+    for (i, (juror, vote)) in jurors.into_iter().enumerate() {
+        let salt = BytesN::from_array(&env, &[i as u8 + 1; 32]);
+        client.commit_vote(juror, &dispute_id, &compute_commit_hash(&env, vote, &salt));
+        salts.push(salt);
+    }
+
+    advance_ledger(&env, 86401);
+    client.advance_to_reveal(&dispute_id);
+    for (i, (juror, vote)) in jurors.into_iter().enumerate() {
+        client.reveal_vote(juror, &dispute_id, &vote, &salts[i]);
+    }
+    advance_ledger(&env, 86401);
+    client.finalize_dispute(&owner, &dispute_id);
+    advance_ledger(&env, 86401);
+    client.execute_verdict(&owner, &dispute_id);
+
+    // 1000 jury share plus the minority juror's 500 stake, split two ways, plus own stake
+    assert_eq!(client.claim_juror_reward(&majority1, &dispute_id), 750);
+    assert_eq!(balances.balance(&majority1), 1250);
+    assert!(client.try_claim_juror_reward(&majority1, &dispute_id).is_err());
+    assert!(client.try_claim_juror_reward(&minority, &dispute_id).is_err());
+    assert_eq!(balances.balance(&minority), 0);
+}
+
+#[test]
+fn test_claiming_deposit_first_keeps_stake_for_reward() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
+    client.set_juror_stake(&owner, &500);
+    client.set_juror_deposit(&owner, &100);
+    let balances = token::Client::new(&env, &token);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    client.airline_respond(&airline, &dispute_id, &3000);
+    advance_ledger(&env, 86401);
+
+    let majority1 = Address::generate(&env);
+    let majority2 = Address::generate(&env);
+    let minority = Address::generate(&env);
+    let jurors = [(&majority1, true), (&majority2, true), (&minority, false)];
+    for (juror, _) in jurors {
+        token::StellarAssetClient::new(&env, &token).mint(juror, &600);
+        client.select_as_juror(juror, &dispute_id, &1000);
+    }
+    let mut salts = std::vec::Vec::new();
+    for (i, (juror, vote)) in jurors.into_iter().enumerate() {
+        let salt = BytesN::from_array(&env, &[i as u8 + 1; 32]);
+        client.commit_vote(juror, &dispute_id, &compute_commit_hash(&env, vote, &salt));
+        salts.push(salt);
+    }
+
+    advance_ledger(&env, 86401);
+    client.advance_to_reveal(&dispute_id);
+    for (i, (juror, vote)) in jurors.into_iter().enumerate() {
+        client.reveal_vote(juror, &dispute_id, &vote, &salts[i]);
+    }
+    advance_ledger(&env, 86401);
+    client.finalize_dispute(&owner, &dispute_id);
+
+    advance_ledger(&env, 86401);
+    client.execute_verdict(&owner, &dispute_id);
+
+    // Taking the deposit back before the reward leaves the locked stake alone
+    for (juror, _) in jurors {
+        assert_eq!(client.claim_juror_deposit(juror, &dispute_id), 100);
+        assert_eq!(client.get_juror_stake(&dispute_id, juror), 500);
+    }
+    assert_eq!(client.claim_juror_reward(&majority1, &dispute_id), 750);
+    assert_eq!(client.claim_juror_reward(&majority2, &dispute_id), 750);
+    assert_eq!(balances.balance(&majority1), 100 + 500 + 750);
+    assert_eq!(balances.balance(&minority), 100);
+}
+
+#[test]
+fn test_stake_weighted_reward_preview_follows_locked_stake() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
+    client.set_reward_distribution(&owner, &RewardDistribution::StakeWeighted);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    client.airline_respond(&airline, &dispute_id, &3000);
+    advance_ledger(&env, 86401);
+
+    // The stake is fixed at selection, so raising it between jurors gives them different stakes
+    let small = Address::generate(&env);
+    let large = Address::generate(&env);
+    let minority = Address::generate(&env);
+    let jurors = [(&small, 500, true), (&large, 1000, true), (&minority, 500, false)];
+    for (juror, stake, _) in jurors {
+        client.set_juror_stake(&owner, &stake);
+        token::StellarAssetClient::new(&env, &token).mint(juror, &stake);
+        client.select_as_juror(juror, &dispute_id, &1000);
+    }
+    let mut salts = std::vec::Vec::new();
+    for (i, (juror, _, vote)) in jurors.into_iter().enumerate() {
+        let salt = BytesN::from_array(&env, &[i as u8 + 1; 32]);
+        client.commit_vote(juror, &dispute_id, &compute_commit_hash(&env, vote, &salt));
+        salts.push(salt);
+    }
+
+    advance_ledger(&env, 86401);
+    client.advance_to_reveal(&dispute_id);
+    for (i, (juror, _, vote)) in jurors.into_iter().enumerate() {
+        client.reveal_vote(juror, &dispute_id, &vote, &salts[i]);
+    }
+    assert_eq!(client.juror_reward_preview(&dispute_id, &small), 0);
+    advance_ledger(&env, 86401);
+    client.finalize_dispute(&owner, &dispute_id);
+
+    // 1000 jury share plus the minority's 500 stake, split 1:2 by locked stake
+    assert_eq!(client.juror_reward_preview(&dispute_id, &small), 500);
+    assert_eq!(client.juror_reward_preview(&dispute_id, &large), 1000);
+    assert_eq!(client.juror_reward_preview(&dispute_id, &minority), 0);
+
+    client.set_reward_distribution(&owner, &RewardDistribution::Equal);
+    assert_eq!(client.juror_reward_preview(&dispute_id, &small), 750);
+    assert_eq!(client.juror_reward_preview(&dispute_id, &large), 750);
+    client.set_reward_distribution(&owner, &RewardDistribution::StakeWeighted);
+
+    advance_ledger(&env, 86401);
+    client.execute_verdict(&owner, &dispute_id);
+    assert_eq!(client.claim_juror_reward(&large, &dispute_id), 1000);
+    assert_eq!(client.juror_reward_preview(&dispute_id, &small), 500);
+    assert_eq!(client.claim_juror_reward(&small, &dispute_id), 500);
+}
+
+#[test]
+fn test_forfeits_to_winner_added_to_payout() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_forfeit_destination(&owner, &ForfeitDestination::Winner);
+    let balances = token::Client::new(&env, &token);
+
+    let (dispute_id, revealer1, revealer2) = run_with_no_show_juror(&env, &client, &owner, &token);
+    let passenger = client.get_dispute(&dispute_id).unwrap().passenger;
+    client.execute_verdict(&owner, &dispute_id);
+
+    // 4000 of the stake pool plus the 100 forfeited deposit
+    assert_eq!(balances.balance(&passenger), 102_100);
+    assert_eq!(client.claim_juror_reward(&revealer1, &dispute_id), 500);
+    assert_eq!(client.claim_juror_reward(&revealer2, &dispute_id), 500);
+    client.claim_juror_deposit(&revealer1, &dispute_id);
+    client.claim_juror_deposit(&revealer2, &dispute_id);
+    assert_eq!(balances.balance(&contract_id), 0);
+}
+
+#[test]
+fn test_forfeits_to_treasury_recorded_as_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    let treasury = Address::generate(&env);
+    client.set_forfeit_destination(&owner, &ForfeitDestination::Treasury(treasury.clone()));
+    let balances = token::Client::new(&env, &token);
+
+    let (dispute_id, revealer1, revealer2) = run_with_no_show_juror(&env, &client, &owner, &token);
+    let passenger = client.get_dispute(&dispute_id).unwrap().passenger;
+    client.execute_verdict(&owner, &dispute_id);
+
+    assert_eq!(balances.balance(&treasury), 100);
+    assert_eq!(balances.balance(&passenger), 102_000);
+    assert_eq!(client.claim_juror_reward(&revealer1, &dispute_id), 500);
+    assert_eq!(client.claim_juror_reward(&revealer2, &dispute_id), 500);
+
+    let fees = client.fee_report().totals;
+    assert_eq!(fees.len(), 1);
+    assert_eq!(fees.get(0).unwrap().category, Symbol::new(&env, "forfeit"));
+    assert_eq!(fees.get(0).unwrap().amount, 100);
+}
+
+// Seat any open jury seats, then run one commit/reveal round where every juror votes the same way
+fn run_unanimous_round(
+    env: &Env,
+    client: &DisputeContractClient,
+    owner: &Address,
+    dispute_id: u64,
+    jurors: &mut std::vec::Vec<Address>,
+    vote_for_passenger: bool,
+) {
+    advance_ledger(env, 86401);
+    let jury_size = client.get_dispute(&dispute_id).unwrap().jury_size;
+    while (jurors.len() as u32) < jury_size {
+        let juror = Address::generate(env);
+        client.select_as_juror(&juror, &dispute_id, &1000);
+        jurors.push(juror);
+    }
+
+    let salt = BytesN::from_array(env, &[7u8; 32]);
+    for juror in jurors.iter() {
+        client.commit_vote(juror, &dispute_id, &compute_commit_hash(env, vote_for_passenger, &salt));
+    }
+    advance_ledger(env, 86401);
+    for juror in jurors.iter() {
+        client.reveal_vote(juror, &dispute_id, &vote_for_passenger, &salt);
+    }
+    advance_ledger(env, 86401);
+    client.finalize_dispute(owner, &dispute_id);
+}
+
+#[test]
+fn test_two_appeal_rounds_grow_jury_and_stake() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    // 1 juror, appeal stake doubles each round
+    client.initialize(&owner, &2000, &1, &86400, &86400, &86400, &86400, &20000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
+    client.set_appeal_rounds(&owner, &2, &2);
+    let balances = token::Client::new(&env, &token);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    client.airline_respond(&airline, &dispute_id, &2000);
+
+    let mut jurors = std::vec::Vec::new();
+    run_unanimous_round(&env, &client, &owner, dispute_id, &mut jurors, false);
+    assert_eq!(
+        client.get_dispute(&dispute_id).unwrap().verdict,
+        Some(Symbol::new(&env, "airline"))
+    );
+
+    // Round 1: 2x the amount, jury doubles
+    assert!(client.try_file_appeal(&passenger, &dispute_id, &19_999).is_err());
+    client.file_appeal(&passenger, &dispute_id, &20_000);
+    let dispute = client.get_dispute(&dispute_id).unwrap();
+    assert_eq!(dispute.appeal_round, 1);
+    assert_eq!(dispute.jury_size, 2);
+
+    run_unanimous_round(&env, &client, &owner, dispute_id, &mut jurors, true);
+    assert_eq!(
+        client.get_dispute(&dispute_id).unwrap().verdict,
+        Some(Symbol::new(&env, "passenger"))
+    );
+
+    // Round 2: 4x the amount, jury doubles again
+    assert!(client.try_file_appeal(&airline, &dispute_id, &39_999).is_err());
+    client.file_appeal(&airline, &dispute_id, &40_000);
+    let dispute = client.get_dispute(&dispute_id).unwrap();
+    assert_eq!(dispute.appeal_round, 2);
+    assert_eq!(dispute.jury_size, 4);
+
+    run_unanimous_round(&env, &client, &owner, dispute_id, &mut jurors, true);
+    let dispute = client.get_dispute(&dispute_id).unwrap();
+    assert_eq!(dispute.verdict, Some(Symbol::new(&env, "passenger")));
+    assert_eq!(dispute.votes_for_passenger, 4);
+
+    // No rounds left; the verdict stands
+    assert!(client.try_file_appeal(&airline, &dispute_id, &80_000).is_err());
+    advance_ledger(&env, 86401);
+    client.execute_verdict(&owner, &dispute_id);
+
+    // Pool of 64000, 20% (12800) reserved for jurors, rest to the winning passenger
+    assert_eq!(client.get_dispute(&dispute_id).unwrap().phase, DisputePhase::Finalized);
+    assert_eq!(balances.balance(&passenger), 100_000 - 22_000 + 51_200);
+    assert_eq!(client.claim_juror_reward(&jurors[0], &dispute_id), 3_200);
+}
+
+#[test]
+fn test_pending_revealers_lists_committed_non_revealers() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    client.airline_respond(&airline, &dispute_id, &2000);
+
+    advance_ledger(&env, 86401);
+    let jurors = [
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    for juror in jurors.iter() {
+        client.select_as_juror(juror, &dispute_id, &1000);
+    }
+    let salt = BytesN::from_array(&env, &[1u8; 32]);
+    for juror in jurors.iter() {
+        client.commit_vote(juror, &dispute_id, &compute_commit_hash(&env, true, &salt));
+    }
+    assert_eq!(client.pending_revealers(&dispute_id).len(), 3);
+
+    advance_ledger(&env, 86401);
+    client.reveal_vote(&jurors[0], &dispute_id, &true, &salt);
+    client.reveal_vote(&jurors[1], &dispute_id, &true, &salt);
+
+    let pending = client.pending_revealers(&dispute_id);
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending.get(0).unwrap(), jurors[2]);
+}
+
+#[test]
+fn test_get_all_evidence_pages_in_submission_order() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &5, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    client.airline_respond(&airline, &dispute_id, &2000);
+
+    // Alternate submitters: passenger, airline, passenger, airline, passenger
+    let submitters = [&passenger, &airline, &passenger, &airline, &passenger];
+    for (i, submitter) in submitters.iter().enumerate() {
+        let hash = BytesN::from_array(&env, &[i as u8 + 1; 32]);
+        client.submit_evidence(submitter, &dispute_id, &hash, &Symbol::new(&env, "doc"));
+    }
+
+    let all = client.get_all_evidence(&dispute_id, &0, &10);
+    assert_eq!(all.len(), 5);
+    for (i, evidence) in all.iter().enumerate() {
+        assert_eq!(&evidence.submitter, submitters[i]);
+        assert_eq!(evidence.evidence_hash, BytesN::from_array(&env, &[i as u8 + 1; 32]));
+    }
+
+    let page = client.get_all_evidence(&dispute_id, &1, &2);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().submitter, airline);
+    assert_eq!(page.get(1).unwrap().evidence_hash, BytesN::from_array(&env, &[3u8; 32]));
+
+    assert_eq!(client.get_all_evidence(&dispute_id, &5, &10).len(), 0);
+}
+
+#[test]
+fn test_execute_verdict_waits_for_execution_buffer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_execution_buffer(&owner, &600);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    client.airline_respond(&airline, &dispute_id, &2000);
+
+    // Leaves the ledger just past the appeal deadline, inside the buffer
+    run_to_passenger_verdict(&env, &client, &owner, dispute_id);
+    let appeal_deadline = client.get_dispute(&dispute_id).unwrap().appeal_deadline;
+    let now = env.ledger().timestamp();
+    assert!(now > appeal_deadline && now <= appeal_deadline + 600);
+    assert!(client.try_execute_verdict(&owner, &dispute_id).is_err());
+
+    advance_ledger(&env, appeal_deadline + 601 - now);
+    client.execute_verdict(&owner, &dispute_id);
+    assert_eq!(
+        client.get_dispute(&dispute_id).unwrap().phase,
+        DisputePhase::Finalized
+    );
+}
+
+fn dispute_requiring_evidence(env: &Env) -> (DisputeContractClient<'_>, Address, u64) {
+    let contract_id = create_dispute_contract(env);
+    let client = DisputeContractClient::new(env, &contract_id);
+    let owner = Address::generate(env);
+    let token = create_stake_token(env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_manual_jury_selection(&owner, &true);
+    client.set_min_evidence(&owner, &1);
+
+    let passenger = Address::generate(env);
+    let airline = Address::generate(env);
+    fund_parties(env, &token, &passenger, &airline);
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    client.airline_respond(&airline, &dispute_id, &2000);
+    (client, passenger, dispute_id)
+}
+
+#[test]
+fn test_jury_selection_waits_for_required_evidence() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, passenger, dispute_id) = dispute_requiring_evidence(&env);
+
+    advance_ledger(&env, 86401);
+    let juror = Address::generate(&env);
+    assert!(client.try_select_as_juror(&juror, &dispute_id, &1000).is_err());
+    assert_eq!(
+        client.disputes_in_phase(&DisputePhase::Evidence, &0, &10).len(),
+        1
+    );
+
+    // Evidence is still accepted past the deadline while the requirement is unmet
+    client.submit_evidence(
+        &passenger,
+        &dispute_id,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &Symbol::new(&env, "receipt"),
+    );
+    client.select_as_juror(&juror, &dispute_id, &1000);
+
+    let dispute = client.get_dispute(&dispute_id).unwrap();
+    assert_eq!(dispute.phase, DisputePhase::JurySelection);
+    assert!(!dispute.no_evidence);
+}
+
+#[test]
+fn test_jury_selection_forced_without_evidence_after_grace() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _passenger, dispute_id) = dispute_requiring_evidence(&env);
+    let filed = client.get_dispute(&dispute_id).unwrap();
+
+    advance_ledger(&env, 2 * 86400 + 1);
+    client.select_as_juror(&Address::generate(&env), &dispute_id, &1000);
+
+    let dispute = client.get_dispute(&dispute_id).unwrap();
+    assert!(dispute.no_evidence);
+    // Voting keeps its full window after the evidence overrun
+    assert_eq!(dispute.voting_deadline, filed.voting_deadline + 86401);
+}
+
+fn responded_dispute(env: &Env) -> (DisputeContractClient<'_>, Address, Address, Address, u64) {
+    let contract_id = create_dispute_contract(env);
+    let client = DisputeContractClient::new(env, &contract_id);
+    let owner = Address::generate(env);
+    let token = create_stake_token(env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+
+    let passenger = Address::generate(env);
+    let airline = Address::generate(env);
+    fund_parties(env, &token, &passenger, &airline);
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    client.airline_respond(&airline, &dispute_id, &3000);
+    (client, token, passenger, airline, dispute_id)
+}
+
+#[test]
+fn test_accepted_settlement_pays_passenger_and_returns_stakes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, passenger, airline, dispute_id) = responded_dispute(&env);
+    let balances = token::Client::new(&env, &token);
+
+    client.offer_settlement(&airline, &dispute_id, &4000);
+    assert_eq!(balances.balance(&airline), 93_000);
+    assert_eq!(client.get_settlement_offer(&dispute_id).unwrap().amount, 4000);
+
+    client.accept_settlement(&passenger, &dispute_id);
+
+    assert_eq!(balances.balance(&passenger), 104_000);
+    assert_eq!(balances.balance(&airline), 96_000);
+    assert_eq!(balances.balance(&client.address), 0);
+    let dispute = client.get_dispute(&dispute_id).unwrap();
+    assert_eq!(dispute.phase, DisputePhase::Finalized);
+    assert_eq!(dispute.verdict, Some(Symbol::new(&env, "settled")));
+    assert!(client.try_offer_settlement(&airline, &dispute_id, &1000).is_err());
+}
+
+#[test]
+fn test_unaccepted_settlement_expires_and_is_withdrawn() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, passenger, airline, dispute_id) = responded_dispute(&env);
+    let balances = token::Client::new(&env, &token);
+
+    client.offer_settlement(&airline, &dispute_id, &4000);
+    assert!(client.try_withdraw_settlement(&airline, &dispute_id).is_err());
+
+    advance_ledger(&env, 3 * 86400 + 1);
+    assert!(client.try_accept_settlement(&passenger, &dispute_id).is_err());
+    assert_eq!(client.withdraw_settlement(&airline, &dispute_id), 4000);
+
+    assert_eq!(balances.balance(&airline), 97_000);
+    assert!(client.get_settlement_offer(&dispute_id).is_none());
+    assert_eq!(client.get_dispute(&dispute_id).unwrap().verdict, None);
+}
+
+#[test]
+fn test_open_dispute_cap_tracks_resolution() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = DisputeContractClient::new(&env, &create_dispute_contract(&env));
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+
+    client.set_filing_fee(&owner, &500, &Address::generate(&env));
+    client.set_max_open_disputes(&owner, &2);
+    let settled_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    let verdict_id = client.file_dispute(&passenger, &airline, &2, &10000, &2000);
+    assert_eq!(client.airline_open_disputes(&airline), 2);
+    assert!(client.try_file_dispute(&passenger, &airline, &3, &10000, &2000).is_err());
+
+    // Another airline isn't affected by the first one's backlog
+    let other_airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &other_airline);
+    client.file_dispute(&passenger, &other_airline, &4, &10000, &2000);
+    assert_eq!(client.airline_open_disputes(&other_airline), 1);
+
+    client.airline_respond(&airline, &settled_id, &3000);
+    client.offer_settlement(&airline, &settled_id, &4000);
+    client.accept_settlement(&passenger, &settled_id);
+    assert_eq!(client.airline_open_disputes(&airline), 1);
+    client.file_dispute(&passenger, &airline, &3, &10000, &2000);
+    assert_eq!(client.airline_open_disputes(&airline), 2);
+
+    client.airline_respond(&airline, &verdict_id, &3000);
+    run_to_passenger_verdict(&env, &client, &owner, verdict_id);
+    client.execute_verdict(&owner, &verdict_id);
+    assert_eq!(client.airline_open_disputes(&airline), 1);
+}
+
+#[test]
+fn test_open_dispute_cap_requires_filing_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = DisputeContractClient::new(&env, &create_dispute_contract(&env));
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    let treasury = Address::generate(&env);
+
+    // A free cap could be filled with throwaway disputes to lock out real claims
+    assert!(client.try_set_max_open_disputes(&owner, &2).is_err());
+    client.set_filing_fee(&owner, &500, &treasury);
+    client.set_max_open_disputes(&owner, &2);
+    assert!(client.try_set_filing_fee(&owner, &0, &treasury).is_err());
+
+    client.set_max_open_disputes(&owner, &0);
+    client.set_filing_fee(&owner, &0, &treasury);
+}
+
+#[test]
+fn test_stuck_dispute_expires_and_frees_slot() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_filing_fee(&owner, &500, &Address::generate(&env));
+    client.set_max_open_disputes(&owner, &1);
+    let balances = token::Client::new(&env, &token);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+    let dispute_id = client.file_dispute(&passenger, &airline, &1, &10000, &2000);
+    client.airline_respond(&airline, &dispute_id, &3000);
+
+    // A lone candidate can never fill the three seats, so the jury is never drawn
+    let candidate = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token).mint(&candidate, &1000);
+    client.register_juror_candidate(&candidate, &dispute_id, &1000);
+    assert!(client.try_file_dispute(&passenger, &airline, &2, &10000, &2000).is_err());
+
+    let appeal_deadline = client.get_dispute(&dispute_id).unwrap().appeal_deadline;
+    advance_ledger(&env, appeal_deadline - env.ledger().timestamp() + 1);
+    assert!(client.try_expire_dispute(&dispute_id).is_err());
+
+    advance_ledger(&env, 7 * 86400);
+    client.expire_dispute(&dispute_id);
+
+    let dispute = client.get_dispute(&dispute_id).unwrap();
+    assert_eq!(dispute.phase, DisputePhase::Finalized);
+    assert_eq!(dispute.verdict, Some(Symbol::new(&env, "expired")));
+    assert_eq!(client.airline_open_disputes(&airline), 0);
+    // Stakes come back; only the filing fee is spent
+    assert_eq!(balances.balance(&passenger), 100_000 - 500);
+    assert_eq!(balances.balance(&airline), 100_000);
+    assert_eq!(balances.balance(&candidate), 1000);
+    assert_eq!(balances.balance(&contract_id), 0);
+    assert!(client.try_expire_dispute(&dispute_id).is_err());
+
+    client.file_dispute(&passenger, &airline, &2, &10000, &2000);
+}
+
+#[test]
+fn test_high_value_dispute_gets_longer_appeal_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = DisputeContractClient::new(&env, &create_dispute_contract(&env));
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    fund_parties(&env, &token, &passenger, &airline);
+
+    assert!(client.try_set_high_value_appeal(&owner, &50_000, &3_600).is_err());
+    client.set_high_value_appeal(&owner, &50_000, &(3 * 86400));
+
+    let small = client.file_dispute(&passenger, &airline, &1, &10_000, &2_000);
+    let large = client.file_dispute(&passenger, &airline, &2, &50_000, &10_000);
+    let small = client.get_dispute(&small).unwrap();
+    let large = client.get_dispute(&large).unwrap();
+
+    assert_eq!(small.appeal_deadline - small.reveal_deadline, 86400);
+    assert_eq!(large.appeal_deadline - large.reveal_deadline, 3 * 86400);
+    assert!(large.appeal_deadline > small.appeal_deadline);
+}
+
+#[test]
+fn test_legacy_dispute_and_config_migrate_to_current_layout() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = create_dispute_contract(&env);
+    let client = DisputeContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = create_stake_token(&env, &owner);
+    client.initialize(&owner, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    // Fresh deployments are already on the current layout
+    assert!(client
+        .try_migrate_legacy_disputes(&owner, &token, &soroban_sdk::vec![&env])
+        .is_err());
+
+    // Put back the config and an appealed dispute as stored before v2
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    let legacy = LegacyDispute {
+        dispute_id: 1,
+        refund_request_id: 7,
+        passenger: passenger.clone(),
+        airline: airline.clone(),
+        amount: 10_000,
+        passenger_stake: 2_000,
+        airline_stake: 2_000,
+        phase: DisputePhase::Appeal,
+        evidence_deadline: 86_400,
+        voting_deadline: 172_800,
+        reveal_deadline: 259_200,
+        appeal_deadline: 345_600,
+        passenger_evidence_count: 1,
+        airline_evidence_count: 0,
+        jury_size: 6,
+        votes_for_passenger: 2,
+        votes_for_airline: 1,
+        verdict: Some(Symbol::new(&env, "passenger")),
+        appealed: true,
+        created_at: 0,
+        finalized_at: None,
+    };
+    env.as_contract(&contract_id, || {
+        env.storage().instance().set(
+            &soroban_sdk::symbol_short!("config"),
+            &LegacyDisputeConfig {
+                min_stake_percentage: 2000,
+                jury_size: 3,
+                evidence_period: 86400,
+                voting_period: 86400,
+                reveal_period: 86400,
+                appeal_period: 43200,
+                appeal_stake_multiplier: 5000,
+                jury_reward_pool_percentage: 2000,
+            },
+        );
+        env.storage()
+            .persistent()
+            .set(&(soroban_sdk::symbol_short!("dispute"), 1u64), &legacy);
+        VersionedStorage::set_storage_version(&env, &DISPUTE_CONTRACT, 1);
+    });
+
+    assert!(client
+        .try_migrate_legacy_disputes(&passenger, &token, &soroban_sdk::vec![&env, 1])
+        .is_err());
+    assert_eq!(
+        client.migrate_legacy_disputes(&owner, &token, &soroban_sdk::vec![&env, 1]),
+        1
+    );
+
+    let config = client.get_config().unwrap();
+    assert_eq!(config.token, token);
+    assert_eq!(config.appeal_period, 43200);
+    assert_eq!(config.max_appeal_rounds, 1);
+    assert_eq!(config.high_value_appeal_period, 43200);
+
+    let dispute = client.get_dispute(&1).unwrap();
+    assert_eq!(dispute.appeal_round, 1);
+    assert!(!dispute.no_evidence);
+    assert_eq!(dispute.appeal_period, 43200);
+    assert_eq!(dispute.token, token);
+    assert_eq!(dispute.jury_size, 6);
+    assert_eq!(dispute.passenger, passenger);
+
+    // The step skips records it already rewrote, so a retry after a rollback is harmless
+    env.as_contract(&contract_id, || {
+        VersionedStorage::rollback_migration(&env, &DISPUTE_CONTRACT, 1, &owner);
+    });
+    client.migrate_legacy_disputes(&owner, &token, &soroban_sdk::vec![&env, 1]);
+    let dispute = client.get_dispute(&1).unwrap();
+    assert_eq!((dispute.appeal_round, dispute.appeal_period), (1, 43200));
+    assert_eq!(client.get_config().unwrap().token, token);
+
+    // Migration runs once
+    assert!(client
+        .try_migrate_legacy_disputes(&owner, &token, &soroban_sdk::vec![&env])
+        .is_err());
+}
@@ -0,0 +1,76 @@
+use soroban_sdk::{symbol_short, testutils::Address as _, token, vec, Address, Symbol};
+
+use booking::{FeeReport as BookingFeeReport, FeeTotal as BookingFeeTotal};
+use dispute::{DisputeContract, DisputeContractClient, FeeReport, FeeTotal};
+use integration_tests::{generate_actors, initialize_token, new_env, register_contracts};
+
+#[test]
+fn test_fee_reports_track_booking_and_dispute_fees() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.booking.init_admin(&actors.admin);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    let treasury = Address::generate(&env);
+
+    // Booking: 2% protocol fee on a 10_000 fare
+    contracts
+        .booking
+        .set_protocol_fee(&actors.admin, &200, &treasury);
+    let booking_id = contracts.booking.create_booking(
+        &actors.passenger,
+        &actors.airline,
+        &Symbol::new(&env, "FL123"),
+        &Symbol::new(&env, "JFK"),
+        &Symbol::new(&env, "LAX"),
+        &(env.ledger().timestamp() + 200_000),
+        &10_000,
+        &contracts.token.address,
+    );
+    contracts
+        .token
+        .mint(&actors.admin, &actors.passenger, &10_200);
+    contracts.booking.pay_for_booking(&booking_id);
+
+    assert_eq!(
+        contracts.booking.fee_report(),
+        BookingFeeReport {
+            totals: vec![
+                &env,
+                BookingFeeTotal {
+                    category: symbol_short!("booking"),
+                    token: contracts.token.address.clone(),
+                    amount: 200,
+                },
+            ],
+        }
+    );
+
+    // Dispute: flat filing fee of 50, charged on each filing
+    let dispute_id = env.register(DisputeContract, ());
+    let dispute = DisputeContractClient::new(&env, &dispute_id);
+    let stake_token = env
+        .register_stellar_asset_contract_v2(actors.admin.clone())
+        .address();
+    dispute.initialize(&actors.admin, &2000, &3, &86400, &86400, &86400, &86400, &5000, &2000, &stake_token);
+    dispute.set_filing_fee(&actors.admin, &50, &treasury);
+    token::StellarAssetClient::new(&env, &stake_token).mint(&actors.passenger, &10_000);
+
+    dispute.file_dispute(&actors.passenger, &actors.airline, &1, &10_000, &2_000);
+    dispute.file_dispute(&actors.passenger, &actors.airline, &2, &10_000, &2_000);
+
+    assert_eq!(token::Client::new(&env, &stake_token).balance(&treasury), 100);
+    assert_eq!(
+        dispute.fee_report(),
+        FeeReport {
+            totals: vec![
+                &env,
+                FeeTotal {
+                    category: symbol_short!("dispute"),
+                    token: stake_token.clone(),
+                    amount: 100,
+                },
+            ],
+        }
+    );
+}
@@ -1,10 +1,21 @@
-use soroban_sdk::{testutils::Address as _, Address, Env, Symbol, Vec};
-use governance::{GovernanceContract, GovernanceContractClient, Proposal};
+use airline::{AirlineContract, AirlineContractClient};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Ledger},
+    vec, Address, BytesN, Env, String, Symbol, Vec,
+};
+use governance::{
+    GovernanceContract, GovernanceContractClient, LegacyGovernanceConfig, LegacyProposal,
+    ParamUpdate,
+};
+use storage_version::{VersionedStorage, GOVERNANCE_CONTRACT};
+use proxy::{ContractProxy, ContractProxyClient};
+use token::{TRQTokenContract, TRQTokenContractClient};
 
 use integration_tests::{new_env, register_contracts};
 
-fn setup_test(env: &Env) -> (GovernanceContractClient, Address, Address, Address) {
-    let contract_id = env.register_contract(None, GovernanceContract);
+fn setup_test(env: &Env) -> (GovernanceContractClient<'_>, Address, Address, Address) {
+    let contract_id = env.register(GovernanceContract, ());
     let client = GovernanceContractClient::new(env, &contract_id);
     let admin = Address::generate(env);
     let voter1 = Address::generate(env);
@@ -21,14 +32,14 @@ fn test_create_proposal() {
 
     let (client, _admin, voter1, _voter2) = setup_test(&env);
 
-    let description = Symbol::new(&env, "Test proposal");
+    let description = Symbol::new(&env, "test_proposal");
     let proposal_id = client.create_proposal(&voter1, &description);
 
     assert_eq!(proposal_id, 1);
 
     let proposal = client.get_proposal(&1).unwrap();
     assert_eq!(proposal.description, description);
-    assert_eq!(proposal.proposer, voter1);
+    assert_eq!(proposal.creator, voter1);
     assert!(!proposal.executed);
 }
 
@@ -42,13 +53,13 @@ fn test_vote_on_proposal() {
     let proposal_id = client.create_proposal(&voter1, &Symbol::new(&env, "desc"));
 
     // Vote YES
-    client.vote(&voter1, &proposal_id, &true);
+    client.cast_vote(&voter1, &proposal_id, &true);
     // Vote NO
-    client.vote(&voter2, &proposal_id, &false);
+    client.cast_vote(&voter2, &proposal_id, &false);
 
     let proposal = client.get_proposal(&proposal_id).unwrap();
-    assert_eq!(proposal.votes_for, 1);
-    assert_eq!(proposal.votes_against, 1);
+    assert_eq!(proposal.yes_votes, 1);
+    assert_eq!(proposal.no_votes, 1);
 }
 
 #[test]
@@ -61,8 +72,8 @@ fn test_double_vote_should_panic() {
 
     let proposal_id = client.create_proposal(&voter1, &Symbol::new(&env, "desc"));
 
-    client.vote(&voter1, &proposal_id, &true);
-    client.vote(&voter1, &proposal_id, &true);
+    client.cast_vote(&voter1, &proposal_id, &true);
+    client.cast_vote(&voter1, &proposal_id, &true);
 }
 
 #[test]
@@ -76,8 +87,8 @@ fn test_execute_proposal_success() {
     let proposal_id = client.create_proposal(&voter1, &Symbol::new(&env, "desc"));
 
     // Vote
-    client.vote(&voter1, &proposal_id, &true);
-    client.vote(&voter2, &proposal_id, &true);
+    client.cast_vote(&voter1, &proposal_id, &true);
+    client.cast_vote(&voter2, &proposal_id, &true);
 
     // Fast forward past deadline (1000 + 1000)
     env.ledger().set_timestamp(3000);
@@ -89,7 +100,6 @@ fn test_execute_proposal_success() {
 }
 
 #[test]
-#[should_panic(expected = "Proposal rejected")]
 fn test_execute_proposal_rejected() {
     let env = Env::default();
     env.mock_all_auths();
@@ -100,13 +110,17 @@ fn test_execute_proposal_rejected() {
     let proposal_id = client.create_proposal(&voter1, &Symbol::new(&env, "desc"));
 
     // Vote against
-    client.vote(&voter1, &proposal_id, &false);
-    client.vote(&voter2, &proposal_id, &false);
+    client.cast_vote(&voter1, &proposal_id, &false);
+    client.cast_vote(&voter2, &proposal_id, &false);
 
     // Fast forward
     env.ledger().set_timestamp(3000);
 
+    // Executing a rejected proposal closes it without applying anything
     client.execute_proposal(&admin, &proposal_id);
+    let proposal = client.get_proposal(&proposal_id).unwrap();
+    assert_eq!(proposal.status, Symbol::new(&env, "rejected"));
+    assert!(!proposal.executed);
 }
 
 #[test]
@@ -135,12 +149,627 @@ fn test_common_initialize_and_create_proposal() {
     let proposal_id = contracts.governance.create_proposal(&voter, &Symbol::new(&env, "desc"));
 
     let p = contracts.governance.get_proposal(&proposal_id).unwrap();
-    assert_eq!(p.proposer, voter);
+    assert_eq!(p.creator, voter);
 
     // Complete flow
-    contracts.governance.vote(&voter, &proposal_id, &true);
+    contracts.governance.cast_vote(&voter, &proposal_id, &true);
     env.ledger().set_timestamp(200);
     contracts.governance.execute_proposal(&owner, &proposal_id);
     let p2 = contracts.governance.get_proposal(&proposal_id).unwrap();
     assert!(p2.executed);
 }
+
+#[test]
+fn test_open_proposals_lists_only_open_with_time_remaining() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = GovernanceContractClient::new(&env, &env.register(GovernanceContract, ()));
+    let owner = Address::generate(&env);
+    client.init_governance(&owner, &1000);
+
+    env.ledger().set_timestamp(10_000);
+    let ended = client.create_proposal(&owner, &symbol_short!("ended"));
+    env.ledger().set_timestamp(10_600);
+    let open = client.create_proposal(&owner, &symbol_short!("open"));
+
+    // The first proposal's window closed at 11_000 even though nobody finalized it yet
+    env.ledger().set_timestamp(11_100);
+    assert_eq!(client.open_proposals(&0, &10), vec![&env, (open, 500)]);
+
+    client.finalize_proposal(&ended);
+    assert_eq!(client.open_proposals(&0, &10), vec![&env, (open, 500)]);
+    assert_eq!(client.open_proposals(&1, &10), vec![&env]);
+}
+
+#[test]
+fn test_ended_proposals_pruned_from_active_list_on_create() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(GovernanceContract, ());
+    let client = GovernanceContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    client.init_governance(&owner, &1000);
+
+    env.ledger().set_timestamp(10_000);
+    let mut ended = std::vec::Vec::new();
+    for _ in 0..5 {
+        ended.push(client.create_proposal(&owner, &symbol_short!("ended")));
+    }
+
+    // Nobody finalizes them, but the next proposal drops them from the active list
+    env.ledger().set_timestamp(11_100);
+    let open = client.create_proposal(&owner, &symbol_short!("open"));
+    let active: Vec<u32> = env.as_contract(&contract_id, || {
+        env.storage().persistent().get(&symbol_short!("active")).unwrap()
+    });
+    assert_eq!(active, vec![&env, open]);
+
+    // Pruned proposals can still be finalized
+    for id in ended {
+        client.finalize_proposal(&id);
+    }
+    assert_eq!(client.open_proposals(&0, &10), vec![&env, (open, 1000)]);
+}
+
+// Minimal parameter target standing in for the dispute/refund/pricing contracts.
+#[contract]
+pub struct MockParamTarget;
+
+#[contractimpl]
+impl MockParamTarget {
+    pub fn set_param(env: Env, caller: Address, key: Symbol, value: i128) {
+        caller.require_auth();
+        assert!(value >= 0, "Rejected parameter");
+        env.storage().instance().set(&key, &value);
+    }
+
+    pub fn get_param(env: Env, key: Symbol) -> Option<i128> {
+        env.storage().instance().get(&key)
+    }
+}
+
+fn pass_and_close_voting(env: &Env, client: &GovernanceContractClient, voter: &Address, id: u32) {
+    client.cast_vote(voter, &id, &true);
+    env.ledger().set_timestamp(env.ledger().timestamp() + 1001);
+}
+
+#[test]
+fn test_param_proposal_applies_updates_to_all_targets() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, voter, _) = setup_test(&env);
+
+    let dispute_id = env.register(MockParamTarget, ());
+    let refund_id = env.register(MockParamTarget, ());
+    let dispute = MockParamTargetClient::new(&env, &dispute_id);
+    let refund = MockParamTargetClient::new(&env, &refund_id);
+
+    let updates = vec![
+        &env,
+        ParamUpdate {
+            target: dispute_id.clone(),
+            key: symbol_short!("min_stake"),
+            value: 3000,
+        },
+        ParamUpdate {
+            target: refund_id.clone(),
+            key: symbol_short!("window"),
+            value: 172800,
+        },
+    ];
+    let id = client.create_param_proposal(&voter, &symbol_short!("raise"), &updates);
+    assert_eq!(client.get_param_updates(&id).len(), 2);
+
+    pass_and_close_voting(&env, &client, &voter, id);
+    client.execute_proposal(&owner, &id);
+
+    assert_eq!(dispute.get_param(&symbol_short!("min_stake")), Some(3000));
+    assert_eq!(refund.get_param(&symbol_short!("window")), Some(172800));
+    assert_eq!(
+        client.get_proposal(&id).unwrap().status,
+        Symbol::new(&env, "passed")
+    );
+}
+
+#[test]
+fn test_param_proposal_rolls_back_when_a_target_rejects() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, voter, _) = setup_test(&env);
+
+    let dispute_id = env.register(MockParamTarget, ());
+    let refund_id = env.register(MockParamTarget, ());
+    let dispute = MockParamTargetClient::new(&env, &dispute_id);
+
+    let updates = vec![
+        &env,
+        ParamUpdate {
+            target: dispute_id.clone(),
+            key: symbol_short!("min_stake"),
+            value: 3000,
+        },
+        ParamUpdate {
+            target: refund_id.clone(),
+            key: symbol_short!("window"),
+            value: -1,
+        },
+    ];
+    let id = client.create_param_proposal(&voter, &symbol_short!("raise"), &updates);
+
+    pass_and_close_voting(&env, &client, &voter, id);
+    assert!(client.try_execute_proposal(&owner, &id).is_err());
+
+    // First update was reverted along with the failing one
+    assert_eq!(dispute.get_param(&symbol_short!("min_stake")), None);
+    assert_eq!(
+        client.get_proposal(&id).unwrap().status,
+        Symbol::new(&env, "open")
+    );
+}
+
+#[test]
+fn test_param_proposal_updates_airline_pricing_once_governance_holds_authority() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, voter, _) = setup_test(&env);
+
+    let airline = AirlineContractClient::new(&env, &env.register(AirlineContract, ()));
+    airline.initialize(&owner);
+    airline.initialize_pricing(&owner, &Address::generate(&env), &3_600, &1_000, &5_000);
+
+    let updates = vec![
+        &env,
+        ParamUpdate {
+            target: airline.address.clone(),
+            key: symbol_short!("max_chg"),
+            value: 1_500,
+        },
+    ];
+    let id = client.create_param_proposal(&voter, &symbol_short!("pricing"), &updates);
+    pass_and_close_voting(&env, &client, &voter, id);
+
+    // The airline only takes parameters from its one parameter authority
+    assert!(client.try_execute_proposal(&owner, &id).is_err());
+    assert!(airline
+        .try_set_param_authority(&voter, &client.address)
+        .is_err());
+    airline.set_param_authority(&owner, &client.address);
+    assert_eq!(airline.get_param_authority(), Some(client.address.clone()));
+
+    client.execute_proposal(&owner, &id);
+    assert_eq!(airline.get_pricing_config().unwrap().max_change_bps, 1_500);
+    assert!(airline
+        .try_set_param(&owner, &symbol_short!("max_chg"), &1_000)
+        .is_err());
+}
+
+#[test]
+#[should_panic(expected = "No parameter updates")]
+fn test_param_proposal_requires_updates() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _owner, voter, _) = setup_test(&env);
+
+    client.create_param_proposal(&voter, &symbol_short!("empty"), &vec![&env]);
+}
+
+#[test]
+fn test_upgrade_proposal_forwards_hash_to_proxy() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, voter, _) = setup_test(&env);
+
+    // Governance is the proxy's only signer
+    let proxy_id = env.register(ContractProxy, ());
+    let proxy = ContractProxyClient::new(&env, &proxy_id);
+    proxy.init_proxy(
+        &owner,
+        &BytesN::from_array(&env, &[1u8; 32]),
+        &vec![&env, client.address.clone()],
+        &1,
+    );
+
+    let new_impl = BytesN::from_array(&env, &[2u8; 32]);
+    let id = client.create_upgrade_proposal(&voter, &symbol_short!("upgrade"), &proxy_id, &new_impl);
+    pass_and_close_voting(&env, &client, &voter, id);
+    client.execute_proposal(&owner, &id);
+
+    let forwarded = proxy.get_upgrade_proposal(&1).unwrap();
+    assert_eq!(forwarded.new_implementation, new_impl);
+    assert!(forwarded.approvals.contains(&client.address));
+    assert!(client.get_proposal(&id).unwrap().executed);
+}
+
+#[test]
+fn test_param_proposal_sets_protocol_param_and_marks_executed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, voter, _) = setup_test(&env);
+
+    let updates = vec![
+        &env,
+        ParamUpdate {
+            target: client.address.clone(),
+            key: symbol_short!("fee_bps"),
+            value: 150,
+        },
+    ];
+    let id = client.create_param_proposal(&voter, &symbol_short!("fee"), &updates);
+    assert!(!client.get_proposal(&id).unwrap().executed);
+
+    pass_and_close_voting(&env, &client, &voter, id);
+    client.execute_proposal(&owner, &id);
+
+    assert_eq!(client.get_protocol_param(&symbol_short!("fee_bps")), Some(150));
+    assert!(client.get_proposal(&id).unwrap().executed);
+}
+
+#[test]
+#[should_panic(expected = "Proposal already executed")]
+fn test_proposal_cannot_execute_twice() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, voter, _) = setup_test(&env);
+
+    let updates = vec![
+        &env,
+        ParamUpdate {
+            target: client.address.clone(),
+            key: symbol_short!("fee_bps"),
+            value: 150,
+        },
+    ];
+    let id = client.create_param_proposal(&voter, &symbol_short!("fee"), &updates);
+    pass_and_close_voting(&env, &client, &voter, id);
+    client.execute_proposal(&owner, &id);
+    client.execute_proposal(&owner, &id);
+}
+
+fn delayed_fee_proposal(env: &Env) -> (GovernanceContractClient<'_>, Address, u32) {
+    let (client, owner, voter, _) = setup_test(env);
+    client.set_execution_delay(&owner, &86_400);
+
+    let updates = vec![
+        env,
+        ParamUpdate {
+            target: client.address.clone(),
+            key: symbol_short!("fee_bps"),
+            value: 150,
+        },
+    ];
+    let id = client.create_param_proposal(&voter, &symbol_short!("fee"), &updates);
+    pass_and_close_voting(env, &client, &voter, id);
+    assert_eq!(client.finalize_proposal(&id), Symbol::new(env, "passed"));
+    let proposal = client.get_proposal(&id).unwrap();
+    assert_eq!(proposal.eta, env.ledger().timestamp() + 86_400);
+    (client, owner, id)
+}
+
+#[test]
+#[should_panic(expected = "Timelock active")]
+fn test_execution_blocked_before_eta() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, id) = delayed_fee_proposal(&env);
+
+    env.ledger().set_timestamp(env.ledger().timestamp() + 86_399);
+    client.execute_proposal(&owner, &id);
+}
+
+#[test]
+fn test_execution_allowed_after_eta() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, id) = delayed_fee_proposal(&env);
+    assert_eq!(client.get_protocol_param(&symbol_short!("fee_bps")), None);
+
+    env.ledger().set_timestamp(env.ledger().timestamp() + 86_400);
+    client.execute_proposal(&owner, &id);
+
+    assert_eq!(client.get_protocol_param(&symbol_short!("fee_bps")), Some(150));
+    assert!(client.get_proposal(&id).unwrap().executed);
+}
+
+#[test]
+fn test_changed_vote_counts_only_final_choice() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, voter, _) = setup_test(&env);
+    let other = Address::generate(&env);
+
+    let id = client.create_proposal(&voter, &symbol_short!("flip"));
+    client.cast_vote(&voter, &id, &true);
+    client.cast_vote(&other, &id, &false);
+
+    client.change_vote(&voter, &id, &false);
+    let proposal = client.get_proposal(&id).unwrap();
+    assert_eq!(proposal.yes_votes, 0);
+    assert_eq!(proposal.no_votes, 2);
+    assert!(!client.get_vote(&voter, &id).unwrap().support);
+
+    env.ledger().set_timestamp(env.ledger().timestamp() + 1001);
+    assert!(client.try_change_vote(&voter, &id, &true).is_err());
+    client.execute_proposal(&owner, &id);
+    assert_eq!(
+        client.get_proposal(&id).unwrap().status,
+        Symbol::new(&env, "rejected")
+    );
+}
+
+#[test]
+fn test_votes_weighted_by_token_balance_and_delegation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, voter, _) = setup_test(&env);
+
+    let token_id = env.register(TRQTokenContract, ());
+    let token = TRQTokenContractClient::new(&env, &token_id);
+    token.init_token(&owner, &String::from_str(&env, "Traqora"), &symbol_short!("TRQ"), &7);
+    // Governance snapshots balances at proposal creation
+    token.set_role(&owner, &client.address, &1, &true);
+    client.set_vote_token(&owner, &token_id);
+
+    let whale = Address::generate(&env);
+    let delegator_a = Address::generate(&env);
+    let delegator_b = Address::generate(&env);
+    token.mint(&owner, &voter, &100);
+    token.mint(&owner, &whale, &250);
+    token.mint(&owner, &delegator_a, &30);
+    token.mint(&owner, &delegator_b, &70);
+
+    client.delegate_votes(&delegator_a, &voter);
+    client.delegate_votes(&delegator_b, &voter);
+
+    let id = client.create_proposal(&voter, &symbol_short!("weight"));
+    // 100 own + 100 delegated, not a caller-chosen weight
+    client.cast_vote(&voter, &id, &true);
+    client.cast_vote(&whale, &id, &false);
+    let proposal = client.get_proposal(&id).unwrap();
+    assert_eq!(proposal.yes_votes, 200);
+    assert_eq!(proposal.no_votes, 250);
+    assert_eq!(client.get_vote(&voter, &id).unwrap().power, 200);
+
+    // Delegated power cannot be used a second time
+    assert!(client.try_cast_vote(&delegator_a, &id, &false).is_err());
+    client.undelegate_votes(&delegator_a);
+    assert!(client.try_cast_vote(&delegator_a, &id, &false).is_err());
+
+    env.ledger().set_timestamp(env.ledger().timestamp() + 1001);
+    client.execute_proposal(&owner, &id);
+    assert_eq!(
+        client.get_proposal(&id).unwrap().status,
+        Symbol::new(&env, "rejected")
+    );
+}
+
+#[test]
+fn test_votes_use_balances_at_proposal_creation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, voter, _) = setup_test(&env);
+
+    let token_id = env.register(TRQTokenContract, ());
+    let token = TRQTokenContractClient::new(&env, &token_id);
+    token.init_token(&owner, &String::from_str(&env, "Traqora"), &symbol_short!("TRQ"), &7);
+    client.set_vote_token(&owner, &token_id);
+    token.mint(&owner, &voter, &100);
+
+    // Without the admin role governance can't snapshot, so no proposal can be made
+    assert!(client.try_create_proposal(&voter, &symbol_short!("snap")).is_err());
+    token.set_role(&owner, &client.address, &1, &true);
+    let id = client.create_proposal(&voter, &symbol_short!("snap"));
+
+    // Tokens minted after creation carry no weight on this proposal
+    let latecomer = Address::generate(&env);
+    token.mint(&owner, &latecomer, &1_000);
+    token.mint(&owner, &voter, &900);
+    assert!(client.try_cast_vote(&latecomer, &id, &false).is_err());
+    client.cast_vote(&voter, &id, &true);
+    assert_eq!(client.get_proposal(&id).unwrap().yes_votes, 100);
+
+    // A later proposal sees the new balances
+    let next = client.create_proposal(&voter, &symbol_short!("next"));
+    client.cast_vote(&latecomer, &next, &false);
+    assert_eq!(client.get_proposal(&next).unwrap().no_votes, 1_000);
+}
+
+fn vote_token<'a>(
+    env: &Env,
+    client: &GovernanceContractClient,
+    owner: &Address,
+) -> TRQTokenContractClient<'a> {
+    let token_id = env.register(TRQTokenContract, ());
+    let token = TRQTokenContractClient::new(env, &token_id);
+    token.init_token(owner, &String::from_str(env, "Traqora"), &symbol_short!("TRQ"), &7);
+    token.set_role(owner, &client.address, &1, &true);
+    token
+}
+
+#[test]
+fn test_switching_vote_token_keeps_open_proposals_on_their_snapshot() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, voter, _) = setup_test(&env);
+
+    let old_token = vote_token(&env, &client, &owner);
+    client.set_vote_token(&owner, &old_token.address);
+    old_token.mint(&owner, &voter, &100);
+    let id = client.create_proposal(&voter, &symbol_short!("switch"));
+
+    // The new token's snapshot ids mean nothing for a proposal snapshotted on the old one
+    let new_token = vote_token(&env, &client, &owner);
+    new_token.mint(&owner, &voter, &5);
+    client.set_vote_token(&owner, &new_token.address);
+
+    client.cast_vote(&voter, &id, &true);
+    assert_eq!(client.get_proposal(&id).unwrap().yes_votes, 100);
+
+    let next = client.create_proposal(&voter, &symbol_short!("next"));
+    client.cast_vote(&voter, &next, &true);
+    assert_eq!(client.get_proposal(&next).unwrap().yes_votes, 5);
+}
+
+#[test]
+fn test_proposal_threshold_gates_creation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, voter, _) = setup_test(&env);
+
+    // The threshold is measured in vote token
+    assert!(client.try_set_proposal_threshold(&owner, &50).is_err());
+    let token = vote_token(&env, &client, &owner);
+    client.set_vote_token(&owner, &token.address);
+    assert!(client.try_set_proposal_threshold(&voter, &50).is_err());
+    client.set_proposal_threshold(&owner, &50);
+    assert_eq!(client.get_proposal_threshold(), 50);
+
+    token.mint(&owner, &voter, &49);
+    assert!(client.try_create_proposal(&voter, &symbol_short!("spam")).is_err());
+    assert!(client
+        .try_create_param_proposal(
+            &voter,
+            &symbol_short!("spam"),
+            &vec![
+                &env,
+                ParamUpdate {
+                    target: voter.clone(),
+                    key: symbol_short!("fee"),
+                    value: 1,
+                },
+            ],
+        )
+        .is_err());
+
+    token.mint(&owner, &voter, &1);
+    client.create_proposal(&voter, &symbol_short!("ok"));
+}
+
+#[test]
+#[should_panic(expected = "Too many delegators")]
+fn test_delegatee_accepts_limited_delegators() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _owner, voter, _) = setup_test(&env);
+
+    for _ in 0..50 {
+        client.delegate_votes(&Address::generate(&env), &voter);
+    }
+    client.delegate_votes(&Address::generate(&env), &voter);
+}
+
+#[test]
+fn test_standing_tracks_quorum_and_majority() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, voter, _) = setup_test(&env);
+    client.set_quorum(&owner, &3);
+
+    let id = client.create_proposal(&voter, &symbol_short!("standing"));
+    assert_eq!(client.get_proposal(&id).unwrap().quorum, 3);
+    assert_eq!(client.proposal_standing(&id), symbol_short!("no_quorum"));
+
+    client.cast_vote(&voter, &id, &true);
+    client.cast_vote(&Address::generate(&env), &id, &true);
+    // Majority in favour but still short of quorum
+    assert_eq!(client.proposal_standing(&id), symbol_short!("no_quorum"));
+
+    client.cast_vote(&Address::generate(&env), &id, &false);
+    assert_eq!(client.proposal_standing(&id), symbol_short!("passing"));
+
+    client.cast_vote(&Address::generate(&env), &id, &false);
+    assert_eq!(client.proposal_standing(&id), symbol_short!("failing"));
+}
+
+#[test]
+fn test_proposal_below_quorum_is_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, voter, _) = setup_test(&env);
+    client.set_quorum(&owner, &2);
+
+    let id = client.create_proposal(&voter, &symbol_short!("thin"));
+    pass_and_close_voting(&env, &client, &voter, id);
+    assert_eq!(client.finalize_proposal(&id), Symbol::new(&env, "rejected"));
+}
+
+#[test]
+fn test_whale_power_without_enough_voters_fails_participation_quorum() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, voter, _) = setup_test(&env);
+
+    let token_id = env.register(TRQTokenContract, ());
+    let token = TRQTokenContractClient::new(&env, &token_id);
+    token.init_token(&owner, &String::from_str(&env, "Traqora"), &symbol_short!("TRQ"), &7);
+    // Governance snapshots balances at proposal creation
+    token.set_role(&owner, &client.address, &1, &true);
+    client.set_vote_token(&owner, &token_id);
+    client.set_quorum(&owner, &1_000);
+    client.set_min_voters(&owner, &3);
+    assert_eq!(client.get_min_voters(), 3);
+
+    let whale = Address::generate(&env);
+    token.mint(&owner, &whale, &10_000);
+    token.mint(&owner, &voter, &10);
+
+    let id = client.create_proposal(&voter, &symbol_short!("whale"));
+    client.cast_vote(&whale, &id, &true);
+    client.cast_vote(&voter, &id, &true);
+
+    // Power is far above quorum, but only two addresses voted
+    let proposal = client.get_proposal(&id).unwrap();
+    assert_eq!((proposal.voters, proposal.min_voters), (2, 3));
+    assert_eq!(client.proposal_standing(&id), symbol_short!("few_voter"));
+
+    env.ledger().set_timestamp(env.ledger().timestamp() + 1001);
+    assert_eq!(client.finalize_proposal(&id), Symbol::new(&env, "rejected"));
+}
+
+#[test]
+fn test_legacy_proposal_and_config_migrate_to_current_layout() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, voter, _) = setup_test(&env);
+    // Fresh deployments are already on the current layout
+    assert!(client.try_migrate_legacy_proposals(&owner, &vec![&env]).is_err());
+
+    // Put back the config and an open proposal as stored before v2
+    env.ledger().set_timestamp(5_000);
+    let legacy = LegacyProposal {
+        id: 1,
+        creator: voter.clone(),
+        description: symbol_short!("legacy"),
+        vote_deadline: 6_000,
+        yes_votes: 3,
+        no_votes: 1,
+        status: Symbol::new(&env, "open"),
+    };
+    env.as_contract(&client.address, || {
+        env.storage().instance().set(
+            &symbol_short!("config"),
+            &LegacyGovernanceConfig { voting_period_secs: 1000 },
+        );
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("proposal"), 1u32), &legacy);
+        env.storage().instance().set(&symbol_short!("p_count"), &1u32);
+        VersionedStorage::set_storage_version(&env, &GOVERNANCE_CONTRACT, 1);
+    });
+
+    assert!(client.try_migrate_legacy_proposals(&voter, &vec![&env, 1]).is_err());
+    assert_eq!(client.migrate_legacy_proposals(&owner, &vec![&env, 1]), 1);
+
+    let proposal = client.get_proposal(&1).unwrap();
+    assert_eq!(proposal.yes_votes, 3);
+    assert_eq!(proposal.quorum, 0);
+    assert!(!proposal.executed);
+    assert_eq!(client.open_proposals(&0, &10), vec![&env, (1, 1_000)]);
+    assert_eq!(client.get_min_voters(), 0);
+
+    // The migrated proposal keeps working under the current code
+    client.cast_vote(&owner, &1, &true);
+    assert_eq!(client.get_proposal(&1).unwrap().yes_votes, 4);
+
+    // Migration runs once
+    assert!(client.try_migrate_legacy_proposals(&owner, &vec![&env]).is_err());
+}
@@ -49,9 +49,10 @@ fn test_full_booking_and_loyalty_flow() {
 
     // Loyalty points awarded
     contracts.loyalty.init_loyalty();
+    contracts.loyalty.init_admin(&actors.admin);
     let earned = contracts
         .loyalty
-        .award_points(&actors.passenger, &price, &booking_id);
+        .award_points(&actors.admin, &actors.passenger, &price, &booking_id);
     assert!(earned > 0);
 }
 
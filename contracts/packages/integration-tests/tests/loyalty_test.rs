@@ -1,5 +1,5 @@
-use soroban_sdk::Symbol;
-use loyalty::LoyaltyContract;
+use soroban_sdk::{testutils::Ledger, Symbol};
+use loyalty::{LoyaltyContract, TierConfig};
 
 
 use integration_tests::{generate_actors, new_env, register_contracts};
@@ -23,13 +23,14 @@ fn test_get_or_create_account_and_award_points() {
     let actors = generate_actors(&env);
     let contracts = register_contracts(&env);
     contracts.loyalty.init_loyalty();
+    contracts.loyalty.init_admin(&actors.admin);
 
     let acct = contracts.loyalty.get_or_create_account(&actors.passenger);
     assert_eq!(acct.tier, Symbol::new(&env, "bronze"));
 
     let earned = contracts
         .loyalty
-        .award_points(&actors.passenger, &1000, &12345);
+        .award_points(&actors.admin, &actors.passenger, &1000, &12345);
     assert!(earned >= 1000); // bronze multiplier 1x
 
     let acct2 = contracts.loyalty.get_account(&actors.passenger).unwrap();
@@ -42,10 +43,11 @@ fn test_redeem_points_and_tier_upgrade() {
     let actors = generate_actors(&env);
     let contracts = register_contracts(&env);
     contracts.loyalty.init_loyalty();
+    contracts.loyalty.init_admin(&actors.admin);
 
     // Accumulate points and bookings to reach silver (min_points=1000, min_bookings=5)
     for i in 0..5 {
-        contracts.loyalty.award_points(&actors.passenger, &1000, &i);
+        contracts.loyalty.award_points(&actors.admin, &actors.passenger, &1000, &i);
     }
 
     let acct = contracts.loyalty.get_account(&actors.passenger).unwrap();
@@ -62,3 +64,299 @@ fn test_redeem_points_and_tier_upgrade() {
     let acct2 = contracts.loyalty.get_account(&actors.passenger).unwrap();
     assert!(acct2.total_points >= 0);
 }
+
+#[test]
+fn test_points_to_next_tier_from_bronze() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.loyalty.init_loyalty();
+    contracts.loyalty.init_admin(&actors.admin);
+
+    contracts.loyalty.award_points(&actors.admin, &actors.passenger, &400, &1);
+
+    // Silver requires 1000 points and 5 bookings
+    let (next, points, bookings) = contracts.loyalty.points_to_next_tier(&actors.passenger);
+    assert_eq!(next, Symbol::new(&env, "silver"));
+    assert_eq!(points, 600);
+    assert_eq!(bookings, 4);
+}
+
+#[test]
+fn test_tier_after_booking_previews_upgrade_without_awarding() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.loyalty.init_loyalty();
+    contracts.loyalty.init_admin(&actors.admin);
+
+    for i in 0..4u64 {
+        contracts.loyalty.award_points(&actors.admin, &actors.passenger, &200, &i);
+    }
+
+    // 800 points over 4 bookings; the fifth booking needs 200 more points for silver
+    let bronze = Symbol::new(&env, "bronze");
+    let silver = Symbol::new(&env, "silver");
+    assert_eq!(contracts.loyalty.tier_after_booking(&actors.passenger, &199), bronze);
+    assert_eq!(contracts.loyalty.tier_after_booking(&actors.passenger, &200), silver);
+
+    let account = contracts.loyalty.get_account(&actors.passenger).unwrap();
+    assert_eq!(account.tier, bronze);
+    assert_eq!(account.total_points, 800);
+    assert_eq!(account.lifetime_bookings, 4);
+
+    contracts.loyalty.award_points(&actors.admin, &actors.passenger, &200, &4);
+    assert_eq!(contracts.loyalty.get_account(&actors.passenger).unwrap().tier, silver);
+}
+
+#[test]
+fn test_old_bookings_fall_out_of_tier_window() {
+    let env = new_env();
+    env.ledger().set_timestamp(1_700_000_000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.loyalty.init_loyalty();
+    contracts.loyalty.init_admin(&actors.admin);
+
+    // Five bookings reach silver (min_points=1000, min_bookings=5)
+    for i in 0..5 {
+        contracts.loyalty.award_points(&actors.admin, &actors.passenger, &1000, &i);
+    }
+    assert_eq!(
+        contracts.loyalty.get_account(&actors.passenger).unwrap().tier,
+        Symbol::new(&env, "silver")
+    );
+    assert_eq!(
+        contracts.loyalty.qualifying_activity(&actors.passenger),
+        (5, 5000)
+    );
+
+    // Thirteen months later only the new booking is within the window
+    env.ledger().set_timestamp(1_700_000_000 + 395 * 86_400);
+    contracts.loyalty.award_points(&actors.admin, &actors.passenger, &1000, &5);
+
+    assert_eq!(
+        contracts.loyalty.qualifying_activity(&actors.passenger),
+        (1, 1000)
+    );
+    let acct = contracts.loyalty.get_account(&actors.passenger).unwrap();
+    assert_eq!(acct.tier, Symbol::new(&env, "bronze"));
+    assert_eq!(acct.lifetime_bookings, 6);
+    assert_eq!(acct.lifetime_spent, 6000);
+}
+
+#[test]
+fn test_expired_points_are_deducted_and_tier_recomputed() {
+    let env = new_env();
+    env.ledger().set_timestamp(1_700_000_000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.loyalty.init_loyalty();
+    contracts.loyalty.init_admin(&actors.admin);
+
+    for i in 0..5 {
+        contracts.loyalty.award_points(&actors.admin, &actors.passenger, &1000, &i);
+    }
+    // Spending 1500 points uses up the oldest batch and half of the next
+    contracts.loyalty.redeem_points(&actors.passenger, &1500);
+    assert_eq!(contracts.loyalty.get_points_batches(&actors.passenger).len(), 4);
+
+    // Nothing has expired yet
+    assert_eq!(contracts.loyalty.expire_points(&actors.passenger), 0);
+
+    // A later booking (earned at silver's 1.25x) is not yet due when the first five expire
+    env.ledger().set_timestamp(1_700_000_000 + 425 * 86_400);
+    contracts.loyalty.award_points(&actors.admin, &actors.passenger, &1000, &5);
+
+    env.ledger().set_timestamp(1_700_000_000 + 2 * 365 * 86_400);
+    assert_eq!(contracts.loyalty.expire_points(&actors.passenger), 3500);
+
+    let acct = contracts.loyalty.get_account(&actors.passenger).unwrap();
+    assert_eq!(acct.total_points, 1250);
+    assert_eq!(acct.tier, Symbol::new(&env, "bronze"));
+    assert_eq!(contracts.loyalty.get_points_batches(&actors.passenger).len(), 1);
+}
+
+#[test]
+fn test_redeeming_below_silver_threshold_downgrades_to_bronze() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.loyalty.init_loyalty();
+    contracts.loyalty.init_admin(&actors.admin);
+
+    // Five 200-unit bookings: 1000 points and 5 bookings, exactly silver
+    for i in 0..5 {
+        contracts.loyalty.award_points(&actors.admin, &actors.passenger, &200, &i);
+    }
+    assert_eq!(
+        contracts.loyalty.get_account(&actors.passenger).unwrap().tier,
+        Symbol::new(&env, "silver")
+    );
+
+    contracts.loyalty.redeem_points(&actors.passenger, &100);
+
+    let acct = contracts.loyalty.get_account(&actors.passenger).unwrap();
+    assert_eq!(acct.total_points, 900);
+    assert_eq!(acct.tier, Symbol::new(&env, "bronze"));
+}
+
+#[test]
+fn test_admin_added_diamond_tier_promotes_high_spender() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.loyalty.init_loyalty();
+    contracts.loyalty.init_admin(&actors.admin);
+
+    let diamond = Symbol::new(&env, "diamond");
+    contracts.loyalty.set_tier_config(
+        &actors.admin,
+        &TierConfig {
+            tier: diamond.clone(),
+            min_points: 100_000,
+            min_bookings: 5,
+            points_multiplier: 300,
+            bonus_percentage: 2500,
+        },
+    );
+    assert_eq!(contracts.loyalty.get_tiers().last().unwrap(), diamond);
+
+    for i in 0..5 {
+        contracts.loyalty.award_points(&actors.admin, &actors.passenger, &20_000, &i);
+    }
+    assert_eq!(
+        contracts.loyalty.get_account(&actors.passenger).unwrap().tier,
+        diamond
+    );
+    assert_eq!(contracts.loyalty.get_fee_rebate_bps(&actors.passenger), 2500);
+
+    // Without diamond the account falls back to the best remaining tier it qualifies for
+    contracts.loyalty.remove_tier(&actors.admin, &diamond);
+    assert_eq!(
+        contracts.loyalty.refresh_tier(&actors.passenger),
+        Symbol::new(&env, "silver")
+    );
+    assert!(contracts
+        .loyalty
+        .try_remove_tier(&actors.admin, &Symbol::new(&env, "bronze"))
+        .is_err());
+}
+
+#[test]
+#[should_panic(expected = "Not an admin")]
+fn test_tier_config_requires_admin() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.loyalty.init_loyalty();
+    contracts.loyalty.init_admin(&actors.admin);
+
+    contracts
+        .loyalty
+        .remove_tier(&actors.passenger, &Symbol::new(&env, "gold"));
+}
+
+#[test]
+fn test_manual_adjustments_move_tier_both_ways() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.loyalty.init_loyalty();
+    contracts.loyalty.init_admin(&actors.admin);
+
+    // Five bookings qualify for silver on activity, but 900 points falls short
+    for i in 0..5 {
+        contracts.loyalty.award_points(&actors.admin, &actors.passenger, &180, &i);
+    }
+    assert_eq!(
+        contracts.loyalty.get_account(&actors.passenger).unwrap().tier,
+        Symbol::new(&env, "bronze")
+    );
+
+    contracts.loyalty.adjust_points(
+        &actors.admin,
+        &actors.passenger,
+        &200,
+        &Symbol::new(&env, "goodwill"),
+    );
+    let acct = contracts.loyalty.get_account(&actors.passenger).unwrap();
+    assert_eq!(acct.total_points, 1100);
+    assert_eq!(acct.tier, Symbol::new(&env, "silver"));
+
+    let applied = contracts.loyalty.adjust_points(
+        &actors.admin,
+        &actors.passenger,
+        &-300,
+        &Symbol::new(&env, "correct"),
+    );
+    assert_eq!(applied, -300);
+    let acct = contracts.loyalty.get_account(&actors.passenger).unwrap();
+    assert_eq!(acct.total_points, 800);
+    assert_eq!(acct.tier, Symbol::new(&env, "bronze"));
+}
+
+#[test]
+fn test_redemption_value_partial_then_full_cover() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.loyalty.init_loyalty();
+    contracts.loyalty.init_admin(&actors.admin);
+
+    // 450 points are worth 4 units; the odd 50 aren't spent
+    contracts.loyalty.award_points(&actors.admin, &actors.passenger, &450, &1);
+    assert_eq!(
+        contracts.loyalty.redemption_value(&actors.passenger, &10),
+        (4, 400)
+    );
+
+    contracts.loyalty.award_points(&actors.admin, &actors.passenger, &1_000, &2);
+    assert_eq!(
+        contracts.loyalty.redemption_value(&actors.passenger, &10),
+        (10, 1_000)
+    );
+    assert_eq!(contracts.loyalty.redemption_value(&actors.admin, &10), (0, 0));
+}
+
+#[test]
+fn test_passenger_cannot_mint_own_points() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.loyalty.init_loyalty();
+    contracts.loyalty.init_admin(&actors.admin);
+    contracts
+        .loyalty
+        .set_booking_contract(&actors.admin, &contracts.booking.address);
+
+    assert!(contracts
+        .loyalty
+        .try_award_points(&actors.passenger, &actors.passenger, &1_000, &1)
+        .is_err());
+    assert!(contracts
+        .loyalty
+        .try_accrue_points(
+            &actors.passenger,
+            &actors.passenger,
+            &Symbol::new(&env, "TQ100"),
+            &1_000
+        )
+        .is_err());
+    assert!(contracts.loyalty.get_account(&actors.passenger).is_none());
+
+    // The linked booking contract and admins still credit points
+    contracts.loyalty.accrue_points(
+        &contracts.booking.address,
+        &actors.passenger,
+        &Symbol::new(&env, "TQ100"),
+        &500,
+    );
+    contracts
+        .loyalty
+        .award_points(&actors.admin, &actors.passenger, &1_000, &1);
+    assert_eq!(
+        contracts.loyalty.get_account(&actors.passenger).unwrap().total_points,
+        1_500
+    );
+}
@@ -1,27 +1,54 @@
+use ed25519_dalek::{Signer, SigningKey};
 use soroban_sdk::{
     testutils::{Address as _, Ledger},
-    Address, Bytes, Env, Symbol,
+    token::{StellarAssetClient, TokenClient},
+    xdr::ToXdr,
+    Address, BytesN, Env, Symbol,
 };
 use oracle::{FlightOracle, FlightOracleClient};
 
 
 use integration_tests::{generate_actors, initialize_token, new_env, register_contracts};
 
-fn compute_proof(
+fn provider_key(seed: u8) -> SigningKey {
+    SigningKey::from_bytes(&[seed; 32])
+}
+
+fn public_key(env: &Env, key: &SigningKey) -> BytesN<32> {
+    BytesN::from_array(env, &key.verifying_key().to_bytes())
+}
+
+fn fund_stake(env: &Env, token: &Address, provider: &Address, amount: i128) {
+    StellarAssetClient::new(env, token).mint(provider, &amount);
+}
+
+// The message a provider signs off-chain: XDR of (oracle, flight_number, booking_id, status,
+// timestamp). Naming the oracle keeps a report from being replayed on another deployment.
+fn report_message(
     env: &Env,
-    _flight_number: &Symbol,
+    oracle: &Address,
+    flight_number: &Symbol,
     booking_id: u64,
-    _status: &Symbol,
+    status: &Symbol,
     timestamp: u64,
-) -> soroban_sdk::BytesN<32> {
-    let mut msg = Bytes::new(env);
-    for b in booking_id.to_be_bytes().iter() {
-        msg.push_back(*b);
-    }
-    for b in timestamp.to_be_bytes().iter() {
-        msg.push_back(*b);
-    }
-    env.crypto().keccak256(&msg).into()
+) -> std::vec::Vec<u8> {
+    (oracle.clone(), flight_number.clone(), booking_id, status.clone(), timestamp)
+        .to_xdr(env)
+        .iter()
+        .collect()
+}
+
+fn sign_report(
+    env: &Env,
+    oracle: &Address,
+    key: &SigningKey,
+    flight_number: &Symbol,
+    booking_id: u64,
+    status: &Symbol,
+    timestamp: u64,
+) -> BytesN<64> {
+    let msg = report_message(env, oracle, flight_number, booking_id, status, timestamp);
+    BytesN::from_array(env, &key.sign(&msg).to_bytes())
 }
 
 #[test]
@@ -39,12 +66,15 @@ fn test_oracle_completion_settlement() {
     contracts
         .booking
         .initialize_oracle(&actors.admin, &oracle.address);
-    // Initialize oracle with booking contract address and consensus threshold = 1
-    oracle.initialize(&actors.admin, &1_000i128, &1u32, &contracts.booking.address);
+    // Initialize oracle with booking contract address; one provider's stake reaches consensus
+    let stake_token = env.register_stellar_asset_contract_v2(actors.admin.clone()).address();
+    oracle.initialize(&actors.admin, &1_000i128, &1_000i128, &contracts.booking.address, &3600, &stake_token);
 
     // Register provider
     let provider = Address::generate(&env);
-    oracle.register_oracle_provider(&actors.admin, &provider, &1_000i128);
+    let key = provider_key(1);
+    fund_stake(&env, &stake_token, &provider, 1_000i128);
+    oracle.register_oracle_provider(&actors.admin, &provider, &1_000i128, &public_key(&env, &key));
 
     // Create booking and escrow funds
     let price = 1_000_0000000i128;
@@ -71,8 +101,8 @@ fn test_oracle_completion_settlement() {
     let ts = env.ledger().timestamp();
     let status = Symbol::new(&env, "completed");
     let flight_number = Symbol::new(&env, "TQ300");
-    let proof = compute_proof(&env, &flight_number, booking_id, &status, ts);
-    oracle.submit_flight_status(&provider, &flight_number, &booking_id, &status, &ts, &proof);
+    let signature = sign_report(&env, &oracle.address, &key, &flight_number, booking_id, &status, ts);
+    oracle.submit_flight_status(&provider, &flight_number, &booking_id, &status, &ts, &signature);
 
     oracle.verify_flight_completion(&flight_number, &booking_id);
 
@@ -93,10 +123,13 @@ fn test_oracle_cancellation_refund() {
     contracts
         .booking
         .initialize_oracle(&actors.admin, &oracle.address);
-    oracle.initialize(&actors.admin, &1_000i128, &1u32, &contracts.booking.address);
+    let stake_token = env.register_stellar_asset_contract_v2(actors.admin.clone()).address();
+    oracle.initialize(&actors.admin, &1_000i128, &1_000i128, &contracts.booking.address, &3600, &stake_token);
 
     let provider = Address::generate(&env);
-    oracle.register_oracle_provider(&actors.admin, &provider, &1_000i128);
+    let key = provider_key(1);
+    fund_stake(&env, &stake_token, &provider, 1_000i128);
+    oracle.register_oracle_provider(&actors.admin, &provider, &1_000i128, &public_key(&env, &key));
 
     let price = 500_0000000i128;
     let booking_id = contracts.booking.create_booking(
@@ -121,8 +154,8 @@ fn test_oracle_cancellation_refund() {
     let ts = env.ledger().timestamp();
     let status = Symbol::new(&env, "cancelled");
     let flight_number = Symbol::new(&env, "TQ301");
-    let proof = compute_proof(&env, &flight_number, booking_id, &status, ts);
-    oracle.submit_flight_status(&provider, &flight_number, &booking_id, &status, &ts, &proof);
+    let signature = sign_report(&env, &oracle.address, &key, &flight_number, booking_id, &status, ts);
+    oracle.submit_flight_status(&provider, &flight_number, &booking_id, &status, &ts, &signature);
 
     oracle.verify_airline_cancellation(&flight_number, &booking_id);
 
@@ -144,13 +177,531 @@ fn test_unregistered_provider_cannot_submit() {
     contracts
         .booking
         .initialize_oracle(&actors.admin, &oracle.address);
-    oracle.initialize(&actors.admin, &1_000i128, &1u32, &contracts.booking.address);
+    oracle.initialize(&actors.admin, &1_000i128, &1_000i128, &contracts.booking.address, &3600, &env.register_stellar_asset_contract_v2(actors.admin.clone()).address());
 
     let provider = Address::generate(&env);
     // Not registered
     let ts = env.ledger().timestamp();
     let status = Symbol::new(&env, "completed");
     let flight_number = Symbol::new(&env, "TQ999");
-    let proof = compute_proof(&env, &flight_number, 1u64, &status, ts);
-    oracle.submit_flight_status(&provider, &flight_number, &1u64, &status, &ts, &proof);
+    let signature = sign_report(&env, &oracle.address, &provider_key(1), &flight_number, 1u64, &status, ts);
+    oracle.submit_flight_status(&provider, &flight_number, &1u64, &status, &ts, &signature);
+}
+
+// A booking settled as completed by `honest` (stake 2_000) while `liar` (stake 1_000)
+// reported it cancelled
+struct MinorityReport<'a> {
+    oracle: FlightOracleClient<'a>,
+    admin: Address,
+    honest: Address,
+    liar: Address,
+    flight_number: Symbol,
+    booking_id: u64,
+    stake_token: Address,
+}
+
+fn settle_over_minority_report(env: &Env) -> MinorityReport<'_> {
+    env.ledger().set_timestamp(2_000_000_000);
+    let actors = generate_actors(env);
+    let contracts = register_contracts(env);
+    initialize_token(env, &contracts.token, &actors.admin);
+
+    let oracle_id = env.register(FlightOracle, ());
+    let oracle = FlightOracleClient::new(env, &oracle_id);
+    contracts
+        .booking
+        .initialize_oracle(&actors.admin, &oracle.address);
+    let stake_token = env.register_stellar_asset_contract_v2(actors.admin.clone()).address();
+    oracle.initialize(&actors.admin, &1_000i128, &1_000i128, &contracts.booking.address, &3600, &stake_token);
+    oracle.set_treasury(&actors.admin, &actors.admin);
+
+    let honest = Address::generate(env);
+    let liar = Address::generate(env);
+    let honest_key = provider_key(1);
+    let liar_key = provider_key(2);
+    fund_stake(env, &stake_token, &honest, 2_000i128);
+    oracle.register_oracle_provider(&actors.admin, &honest, &2_000i128, &public_key(env, &honest_key));
+    fund_stake(env, &stake_token, &liar, 1_000i128);
+    oracle.register_oracle_provider(&actors.admin, &liar, &1_000i128, &public_key(env, &liar_key));
+
+    let price = 500_0000000i128;
+    let flight_number = Symbol::new(env, "TQ302");
+    let booking_id = contracts.booking.create_booking(
+        &actors.passenger,
+        &actors.airline,
+        &flight_number,
+        &Symbol::new(env, "JFK"),
+        &Symbol::new(env, "LHR"),
+        &2_000_010_000,
+        &price,
+        &contracts.token.address,
+    );
+    contracts
+        .token
+        .mint(&actors.admin, &actors.passenger, &price);
+    contracts.booking.pay_for_booking(&booking_id);
+
+    let ts = env.ledger().timestamp();
+    let completed = Symbol::new(env, "completed");
+    let cancelled = Symbol::new(env, "cancelled");
+    let honest_sig = sign_report(env, &oracle.address, &honest_key, &flight_number, booking_id, &completed, ts);
+    let liar_sig = sign_report(env, &oracle.address, &liar_key, &flight_number, booking_id, &cancelled, ts);
+    oracle.submit_flight_status(&honest, &flight_number, &booking_id, &completed, &ts, &honest_sig);
+    oracle.submit_flight_status(&liar, &flight_number, &booking_id, &cancelled, &ts, &liar_sig);
+    oracle.verify_flight_completion(&flight_number, &booking_id);
+
+    MinorityReport {
+        oracle,
+        admin: actors.admin,
+        honest,
+        liar,
+        flight_number,
+        booking_id,
+        stake_token,
+    }
+}
+
+#[test]
+fn test_minority_reporter_is_slashed() {
+    let env = new_env();
+    let m = settle_over_minority_report(&env);
+    let cancelled = Symbol::new(&env, "cancelled");
+    let stake = TokenClient::new(&env, &m.stake_token);
+    let treasury_before = stake.balance(&m.admin);
+
+    m.oracle
+        .slash_provider(&m.admin, &m.liar, &m.flight_number, &m.booking_id);
+
+    assert!(m.oracle.get_provider(&m.liar).unwrap().slashed);
+    assert_eq!(stake.balance(&m.admin), treasury_before + 1_000);
+    assert_eq!(stake.balance(&m.oracle.address), 2_000);
+    // Its report no longer counts toward the losing status
+    assert_eq!(m.oracle.get_status_count(&m.flight_number, &m.booking_id, &cancelled), 0);
+    assert_eq!(m.oracle.get_status_stake(&m.flight_number, &m.booking_id, &cancelled), 0);
+
+    let totals = m.oracle.fee_report().totals;
+    assert_eq!(totals.len(), 1);
+    assert_eq!(totals.get(0).unwrap().category, Symbol::new(&env, "slashing"));
+    assert_eq!(totals.get(0).unwrap().amount, 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Did not report minority")]
+fn test_majority_reporter_cannot_be_slashed() {
+    let env = new_env();
+    let m = settle_over_minority_report(&env);
+
+    m.oracle
+        .slash_provider(&m.admin, &m.honest, &m.flight_number, &m.booking_id);
+}
+
+#[test]
+fn test_report_with_invalid_signature_is_rejected() {
+    let env = new_env();
+    env.ledger().set_timestamp(2_000_000_000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+
+    let oracle_id = env.register(FlightOracle, ());
+    let oracle = FlightOracleClient::new(&env, &oracle_id);
+    let stake_token = env.register_stellar_asset_contract_v2(actors.admin.clone()).address();
+    oracle.initialize(&actors.admin, &1_000i128, &1_000i128, &contracts.booking.address, &3600, &stake_token);
+
+    let provider = Address::generate(&env);
+    let key = provider_key(1);
+    fund_stake(&env, &stake_token, &provider, 1_000i128);
+    oracle.register_oracle_provider(&actors.admin, &provider, &1_000i128, &public_key(&env, &key));
+
+    let ts = env.ledger().timestamp();
+    let flight_number = Symbol::new(&env, "TQ303");
+    let completed = Symbol::new(&env, "completed");
+    let cancelled = Symbol::new(&env, "cancelled");
+
+    // Signed by a different key
+    let forged = sign_report(&env, &oracle.address, &provider_key(7), &flight_number, 1u64, &completed, ts);
+    assert!(oracle
+        .try_submit_flight_status(&provider, &flight_number, &1u64, &completed, &ts, &forged)
+        .is_err());
+
+    // Valid signature, but over a different status than the one submitted
+    let signed_cancel = sign_report(&env, &oracle.address, &key, &flight_number, 1u64, &cancelled, ts);
+    assert!(oracle
+        .try_submit_flight_status(&provider, &flight_number, &1u64, &completed, &ts, &signed_cancel)
+        .is_err());
+
+    // Valid signature over the same report, but for another oracle deployment
+    let other_oracle = Address::generate(&env);
+    let replayed = sign_report(&env, &other_oracle, &key, &flight_number, 1u64, &completed, ts);
+    assert!(oracle
+        .try_submit_flight_status(&provider, &flight_number, &1u64, &completed, &ts, &replayed)
+        .is_err());
+
+    assert_eq!(oracle.get_status_count(&flight_number, &1u64, &completed), 0);
+}
+
+#[test]
+fn test_signed_message_binds_the_oracle_contract() {
+    let env = new_env();
+    let (oracle, provider, key) = setup_provider(&env);
+    let ts = env.ledger().timestamp();
+    let flight_number = Symbol::new(&env, "TQ303");
+    let completed = Symbol::new(&env, "completed");
+
+    // The oracle address leads the signed tuple, ahead of the report fields
+    let msg = report_message(&env, &oracle.address, &flight_number, 1, &completed, ts);
+    let without_oracle: std::vec::Vec<u8> = (flight_number.clone(), 1u64, completed.clone(), ts)
+        .to_xdr(&env)
+        .iter()
+        .collect();
+    assert_ne!(msg, without_oracle);
+    let oracle_xdr: std::vec::Vec<u8> = oracle.address.clone().to_xdr(&env).iter().collect();
+    assert!(msg.windows(oracle_xdr.len()).any(|w| w == oracle_xdr.as_slice()));
+
+    let signature = BytesN::from_array(&env, &key.sign(&msg).to_bytes());
+    oracle.submit_flight_status(&provider, &flight_number, &1u64, &completed, &ts, &signature);
+    assert_eq!(oracle.get_status_count(&flight_number, &1u64, &completed), 1);
+}
+
+#[test]
+#[should_panic(expected = "Invalid signature")]
+fn test_forged_signature_panics_with_invalid_signature() {
+    let env = new_env();
+    let (oracle, provider, _key) = setup_provider(&env);
+    let ts = env.ledger().timestamp();
+    let flight_number = Symbol::new(&env, "TQ303");
+    let completed = Symbol::new(&env, "completed");
+
+    let forged = sign_report(&env, &oracle.address, &provider_key(7), &flight_number, 1, &completed, ts);
+    oracle.submit_flight_status(&provider, &flight_number, &1u64, &completed, &ts, &forged);
+}
+
+fn setup_provider(env: &Env) -> (FlightOracleClient<'_>, Address, SigningKey) {
+    env.ledger().set_timestamp(2_000_000_000);
+    let actors = generate_actors(env);
+    let contracts = register_contracts(env);
+
+    let oracle_id = env.register(FlightOracle, ());
+    let oracle = FlightOracleClient::new(env, &oracle_id);
+    let stake_token = env.register_stellar_asset_contract_v2(actors.admin.clone()).address();
+    oracle.initialize(&actors.admin, &1_000i128, &1_000i128, &contracts.booking.address, &3600, &stake_token);
+
+    let provider = Address::generate(env);
+    let key = provider_key(1);
+    fund_stake(env, &stake_token, &provider, 1_000i128);
+    oracle.register_oracle_provider(&actors.admin, &provider, &1_000i128, &public_key(env, &key));
+    (oracle, provider, key)
+}
+
+#[test]
+fn test_recent_report_is_accepted() {
+    let env = new_env();
+    let (oracle, provider, key) = setup_provider(&env);
+
+    let ts = env.ledger().timestamp() - 1800;
+    let status = Symbol::new(&env, "completed");
+    let flight_number = Symbol::new(&env, "TQ304");
+    let signature = sign_report(&env, &oracle.address, &key, &flight_number, 1u64, &status, ts);
+    oracle.submit_flight_status(&provider, &flight_number, &1u64, &status, &ts, &signature);
+
+    assert_eq!(oracle.get_status_count(&flight_number, &1u64, &status), 1);
+}
+
+#[test]
+fn test_provider_cannot_report_twice_on_a_booking() {
+    let env = new_env();
+    let (oracle, provider, key) = setup_provider(&env);
+
+    let ts = env.ledger().timestamp();
+    let flight_number = Symbol::new(&env, "TQ304");
+    let completed = Symbol::new(&env, "completed");
+    let cancelled = Symbol::new(&env, "cancelled");
+    let signature = sign_report(&env, &oracle.address, &key, &flight_number, 1u64, &completed, ts);
+    oracle.submit_flight_status(&provider, &flight_number, &1u64, &completed, &ts, &signature);
+
+    // Neither a repeat nor a later, differently dated report adds stake again
+    assert!(oracle
+        .try_submit_flight_status(&provider, &flight_number, &1u64, &completed, &ts, &signature)
+        .is_err());
+    let later = sign_report(&env, &oracle.address, &key, &flight_number, 1u64, &completed, ts + 1);
+    assert!(oracle
+        .try_submit_flight_status(&provider, &flight_number, &1u64, &completed, &(ts + 1), &later)
+        .is_err());
+    let flipped = sign_report(&env, &oracle.address, &key, &flight_number, 1u64, &cancelled, ts);
+    assert!(oracle
+        .try_submit_flight_status(&provider, &flight_number, &1u64, &cancelled, &ts, &flipped)
+        .is_err());
+
+    assert_eq!(oracle.get_status_count(&flight_number, &1u64, &completed), 1);
+    assert_eq!(oracle.get_status_stake(&flight_number, &1u64, &completed), 1_000);
+    assert_eq!(oracle.get_status_stake(&flight_number, &1u64, &cancelled), 0);
+
+    // Reports on another booking still count
+    let other = sign_report(&env, &oracle.address, &key, &flight_number, 2u64, &completed, ts);
+    oracle.submit_flight_status(&provider, &flight_number, &2u64, &completed, &ts, &other);
+    assert_eq!(oracle.get_status_count(&flight_number, &2u64, &completed), 1);
+}
+
+#[test]
+#[should_panic(expected = "Stale report")]
+fn test_stale_report_is_rejected() {
+    let env = new_env();
+    let (oracle, provider, key) = setup_provider(&env);
+
+    let ts = env.ledger().timestamp() - 3601;
+    let status = Symbol::new(&env, "completed");
+    let flight_number = Symbol::new(&env, "TQ304");
+    let signature = sign_report(&env, &oracle.address, &key, &flight_number, 1u64, &status, ts);
+    oracle.submit_flight_status(&provider, &flight_number, &1u64, &status, &ts, &signature);
+}
+
+#[test]
+fn test_report_within_arrival_grace_is_accepted() {
+    let env = new_env();
+    let (oracle, provider, key) = setup_provider(&env);
+    oracle.set_report_grace_secs(&oracle.get_owner(), &1800);
+
+    let flight_number = Symbol::new(&env, "TQ306");
+    let arrival = env.ledger().timestamp() - 2000;
+    oracle.set_flight_arrival(&oracle.get_owner(), &flight_number, &arrival);
+
+    // Dated 1_500s after arrival, inside the 1_800s grace window
+    let ts = arrival + 1500;
+    let status = Symbol::new(&env, "completed");
+    let signature = sign_report(&env, &oracle.address, &key, &flight_number, 1u64, &status, ts);
+    oracle.submit_flight_status(&provider, &flight_number, &1u64, &status, &ts, &signature);
+
+    assert_eq!(oracle.get_status_count(&flight_number, &1u64, &status), 1);
+}
+
+#[test]
+#[should_panic(expected = "Stale report")]
+fn test_report_beyond_arrival_grace_is_rejected() {
+    let env = new_env();
+    let (oracle, provider, key) = setup_provider(&env);
+    oracle.set_report_grace_secs(&oracle.get_owner(), &1800);
+
+    let flight_number = Symbol::new(&env, "TQ306");
+    let arrival = env.ledger().timestamp() - 2000;
+    oracle.set_flight_arrival(&oracle.get_owner(), &flight_number, &arrival);
+
+    // Recent relative to the ledger, but 1_900s after arrival
+    let ts = arrival + 1900;
+    let status = Symbol::new(&env, "completed");
+    let signature = sign_report(&env, &oracle.address, &key, &flight_number, 1u64, &status, ts);
+    oracle.submit_flight_status(&provider, &flight_number, &1u64, &status, &ts, &signature);
+}
+
+#[test]
+#[should_panic(expected = "Report timestamp in the future")]
+fn test_future_dated_report_is_rejected() {
+    let env = new_env();
+    let (oracle, provider, key) = setup_provider(&env);
+
+    let ts = env.ledger().timestamp() + 3600;
+    let status = Symbol::new(&env, "completed");
+    let flight_number = Symbol::new(&env, "TQ304");
+    let signature = sign_report(&env, &oracle.address, &key, &flight_number, 1u64, &status, ts);
+    oracle.submit_flight_status(&provider, &flight_number, &1u64, &status, &ts, &signature);
+}
+
+#[test]
+fn test_large_provider_outweighs_two_small_providers() {
+    let env = new_env();
+    env.ledger().set_timestamp(2_000_000_000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    let oracle_id = env.register(FlightOracle, ());
+    let oracle = FlightOracleClient::new(&env, &oracle_id);
+    contracts
+        .booking
+        .initialize_oracle(&actors.admin, &oracle.address);
+    let stake_token = env.register_stellar_asset_contract_v2(actors.admin.clone()).address();
+    oracle.initialize(&actors.admin, &1_000i128, &1_000i128, &contracts.booking.address, &3600, &stake_token);
+
+    let small1 = Address::generate(&env);
+    let small2 = Address::generate(&env);
+    let large = Address::generate(&env);
+    let (small1_key, small2_key, large_key) = (provider_key(1), provider_key(2), provider_key(3));
+    fund_stake(&env, &stake_token, &small1, 1_000i128);
+    oracle.register_oracle_provider(&actors.admin, &small1, &1_000i128, &public_key(&env, &small1_key));
+    fund_stake(&env, &stake_token, &small2, 1_000i128);
+    oracle.register_oracle_provider(&actors.admin, &small2, &1_000i128, &public_key(&env, &small2_key));
+    fund_stake(&env, &stake_token, &large, 5_000i128);
+    oracle.register_oracle_provider(&actors.admin, &large, &5_000i128, &public_key(&env, &large_key));
+
+    let price = 500_0000000i128;
+    let booking_id = contracts.booking.create_booking(
+        &actors.passenger,
+        &actors.airline,
+        &Symbol::new(&env, "TQ305"),
+        &Symbol::new(&env, "SFO"),
+        &Symbol::new(&env, "SEA"),
+        &2_000_010_000,
+        &price,
+        &contracts.token.address,
+    );
+    contracts
+        .token
+        .mint(&actors.admin, &actors.passenger, &price);
+    contracts.booking.pay_for_booking(&booking_id);
+
+    let ts = env.ledger().timestamp();
+    let flight_number = Symbol::new(&env, "TQ305");
+    let cancelled = Symbol::new(&env, "cancelled");
+    let completed = Symbol::new(&env, "completed");
+    for (provider, key) in [(&small1, &small1_key), (&small2, &small2_key)] {
+        let signature = sign_report(&env, &oracle.address, key, &flight_number, booking_id, &cancelled, ts);
+        oracle.submit_flight_status(provider, &flight_number, &booking_id, &cancelled, &ts, &signature);
+    }
+    let signature = sign_report(&env, &oracle.address, &large_key, &flight_number, booking_id, &completed, ts);
+    oracle.submit_flight_status(&large, &flight_number, &booking_id, &completed, &ts, &signature);
+
+    assert_eq!(oracle.get_status_count(&flight_number, &booking_id, &cancelled), 2);
+    assert_eq!(oracle.get_status_stake(&flight_number, &booking_id, &cancelled), 2_000);
+    assert_eq!(oracle.get_status_stake(&flight_number, &booking_id, &completed), 5_000);
+
+    // More reports, but less stake behind them
+    assert!(oracle
+        .try_verify_airline_cancellation(&flight_number, &booking_id)
+        .is_err());
+
+    oracle.verify_flight_completion(&flight_number, &booking_id);
+    assert_eq!(contracts.token.balance_of(&actors.airline), price);
+}
+
+#[test]
+fn test_deregistered_provider_withdraws_stake_after_settlement() {
+    let env = new_env();
+    env.ledger().set_timestamp(2_000_000_000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    let oracle_id = env.register(FlightOracle, ());
+    let oracle = FlightOracleClient::new(&env, &oracle_id);
+    contracts
+        .booking
+        .initialize_oracle(&actors.admin, &oracle.address);
+    let stake_token = env.register_stellar_asset_contract_v2(actors.admin.clone()).address();
+    oracle.initialize(&actors.admin, &1_000i128, &1_000i128, &contracts.booking.address, &3600, &stake_token);
+
+    let provider = Address::generate(&env);
+    let key = provider_key(1);
+    fund_stake(&env, &stake_token, &provider, 1_000i128);
+    oracle.register_oracle_provider(&actors.admin, &provider, &1_000i128, &public_key(&env, &key));
+    let stake = TokenClient::new(&env, &stake_token);
+    assert_eq!(stake.balance(&provider), 0);
+    assert_eq!(stake.balance(&oracle_id), 1_000);
+
+    let price = 500_0000000i128;
+    let booking_id = contracts.booking.create_booking(
+        &actors.passenger,
+        &actors.airline,
+        &Symbol::new(&env, "TQ305"),
+        &Symbol::new(&env, "LHR"),
+        &Symbol::new(&env, "JFK"),
+        &(env.ledger().timestamp() + 86400 * 10),
+        &price,
+        &contracts.token.address,
+    );
+    contracts
+        .token
+        .mint(&actors.admin, &actors.passenger, &price);
+    contracts.booking.pay_for_booking(&booking_id);
+
+    let ts = env.ledger().timestamp();
+    let flight_number = Symbol::new(&env, "TQ305");
+    let status = Symbol::new(&env, "completed");
+    let signature = sign_report(&env, &oracle.address, &key, &flight_number, booking_id, &status, ts);
+    oracle.submit_flight_status(&provider, &flight_number, &booking_id, &status, &ts, &signature);
+
+    oracle.deregister_oracle_provider(&actors.admin, &provider);
+    // Its report has not reached settlement yet
+    assert!(oracle.try_withdraw_stake(&provider).is_err());
+
+    oracle.verify_flight_completion(&flight_number, &booking_id);
+    assert_eq!(oracle.withdraw_stake(&provider), 1_000);
+    assert_eq!(stake.balance(&provider), 1_000);
+    assert_eq!(stake.balance(&oracle_id), 0);
+    assert!(oracle.try_withdraw_stake(&provider).is_err());
+}
+
+#[test]
+fn test_unsettled_report_stops_holding_stake_after_settlement_window() {
+    let env = new_env();
+    env.ledger().set_timestamp(2_000_000_000);
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    let oracle = FlightOracleClient::new(&env, &env.register(FlightOracle, ()));
+    let stake_token = env.register_stellar_asset_contract_v2(actors.admin.clone()).address();
+    oracle.initialize(&actors.admin, &1_000i128, &1_000i128, &contracts.booking.address, &3600, &stake_token);
+    let provider = Address::generate(&env);
+    let key = provider_key(1);
+    fund_stake(&env, &stake_token, &provider, 1_000i128);
+    oracle.register_oracle_provider(&actors.admin, &provider, &1_000i128, &public_key(&env, &key));
+
+    let ts = env.ledger().timestamp();
+    let flight_number = Symbol::new(&env, "TQ306");
+    let status = Symbol::new(&env, "completed");
+
+    // The booking never reaches consensus
+    let signature = sign_report(&env, &oracle.address, &key, &flight_number, 1, &status, ts);
+    oracle.submit_flight_status(&provider, &flight_number, &1u64, &status, &ts, &signature);
+    oracle.deregister_oracle_provider(&actors.admin, &provider);
+
+    env.ledger().set_timestamp(ts + 30 * 86_400 - 1);
+    assert!(oracle.try_withdraw_stake(&provider).is_err());
+    env.ledger().set_timestamp(ts + 30 * 86_400);
+    assert_eq!(oracle.withdraw_stake(&provider), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Provider slashed")]
+fn test_slashed_provider_cannot_withdraw_stake() {
+    let env = new_env();
+    let m = settle_over_minority_report(&env);
+
+    m.oracle
+        .slash_provider(&m.admin, &m.liar, &m.flight_number, &m.booking_id);
+    m.oracle.deregister_oracle_provider(&m.admin, &m.liar);
+    m.oracle.withdraw_stake(&m.liar);
+}
+
+#[test]
+fn test_list_providers_paginates_registered_set() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+
+    let oracle_id = env.register(FlightOracle, ());
+    let oracle = FlightOracleClient::new(&env, &oracle_id);
+    let stake_token = env.register_stellar_asset_contract_v2(actors.admin.clone()).address();
+    oracle.initialize(&actors.admin, &1_000i128, &1_000i128, &contracts.booking.address, &3600, &stake_token);
+
+    let mut providers = std::vec::Vec::new();
+    for seed in 1..=4u8 {
+        let provider = Address::generate(&env);
+        let stake = 1_000i128 * seed as i128;
+        fund_stake(&env, &stake_token, &provider, stake);
+        oracle.register_oracle_provider(&actors.admin, &provider, &stake, &public_key(&env, &provider_key(seed)));
+        providers.push(provider);
+    }
+
+    let all = oracle.list_providers(&0, &10);
+    assert_eq!(all.len(), 4);
+    let total_stake: i128 = all.iter().map(|p| p.stake).sum();
+    assert_eq!(total_stake, 10_000);
+
+    let page = oracle.list_providers(&1, &2);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().address, providers[1]);
+    assert_eq!(page.get(1).unwrap().address, providers[2]);
+
+    // Deregistered providers drop out of the list
+    oracle.deregister_oracle_provider(&actors.admin, &providers[0]);
+    let remaining = oracle.list_providers(&0, &10);
+    assert_eq!(remaining.len(), 3);
+    assert_eq!(remaining.get(0).unwrap().address, providers[1]);
 }
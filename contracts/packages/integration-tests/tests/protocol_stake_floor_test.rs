@@ -0,0 +1,122 @@
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Ledger},
+    token, vec, Address, BytesN, Env,
+};
+use dispute::{DisputeContract, DisputeContractClient};
+use governance::{GovernanceContract, GovernanceContractClient, ParamUpdate};
+use oracle::{FlightOracle, FlightOracleClient};
+
+fn setup_registry(env: &Env, owner: &Address) -> GovernanceContractClient<'static> {
+    let id = env.register(GovernanceContract, ());
+    let client = GovernanceContractClient::new(env, &id);
+    client.init_governance(owner, &1000);
+    client
+}
+
+// Passes a proposal setting the protocol-wide minimum stake in the registry itself
+fn set_protocol_min_stake(env: &Env, registry: &GovernanceContractClient, owner: &Address, value: i128) {
+    let voter = Address::generate(env);
+    let updates = vec![
+        env,
+        ParamUpdate {
+            target: registry.address.clone(),
+            key: symbol_short!("min_stake"),
+            value,
+        },
+    ];
+    let id = registry.create_param_proposal(&voter, &symbol_short!("floor"), &updates);
+    registry.cast_vote(&voter, &id, &true);
+    env.ledger().set_timestamp(env.ledger().timestamp() + 1001);
+    registry.execute_proposal(owner, &id);
+}
+
+fn setup_dispute(env: &Env, owner: &Address, registry: &Address) -> DisputeContractClient<'static> {
+    let id = env.register(DisputeContract, ());
+    let client = DisputeContractClient::new(env, &id);
+    let token = env.register_stellar_asset_contract_v2(owner.clone()).address();
+    client.initialize(owner, &2000, &5, &86400, &86400, &86400, &86400, &5000, &2000, &token);
+    client.set_param_registry(owner, registry);
+    client
+}
+
+#[test]
+fn test_protocol_floor_raises_dispute_minimum_stake() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let registry = setup_registry(&env, &owner);
+    let dispute = setup_dispute(&env, &owner, &registry.address);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    let token = dispute.get_config().unwrap().token;
+    token::StellarAssetClient::new(&env, &token).mint(&passenger, &100_000);
+
+    // 20% of 10_000 clears the contract's own minimum
+    dispute.file_dispute(&passenger, &airline, &1, &10_000, &2_000);
+
+    set_protocol_min_stake(&env, &registry, &owner, 5_000);
+    assert_eq!(registry.get_protocol_param(&symbol_short!("min_stake")), Some(5_000));
+
+    assert!(dispute
+        .try_file_dispute(&passenger, &airline, &2, &10_000, &2_000)
+        .is_err());
+    dispute.file_dispute(&passenger, &airline, &2, &10_000, &5_000);
+}
+
+#[test]
+fn test_required_stake_matches_file_dispute() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let registry = setup_registry(&env, &owner);
+    let dispute = setup_dispute(&env, &owner, &registry.address);
+    set_protocol_min_stake(&env, &registry, &owner, 1_500);
+
+    let passenger = Address::generate(&env);
+    let airline = Address::generate(&env);
+    let token = dispute.get_config().unwrap().token;
+    token::StellarAssetClient::new(&env, &token).mint(&passenger, &1_000_000);
+
+    // 20% of the amount, but never below the protocol floor
+    assert_eq!(dispute.required_stake(&5_000), 1_500);
+    assert_eq!(dispute.required_stake(&10_000), 2_000);
+    assert_eq!(dispute.required_stake(&100_000), 20_000);
+
+    for (i, amount) in [5_000i128, 10_000, 100_000].iter().enumerate() {
+        let required = dispute.required_stake(amount);
+        let request_id = i as u64 + 1;
+        assert!(dispute
+            .try_file_dispute(&passenger, &airline, &request_id, amount, &(required - 1))
+            .is_err());
+        dispute.file_dispute(&passenger, &airline, &request_id, amount, &required);
+    }
+}
+
+#[test]
+fn test_protocol_floor_raises_oracle_minimum_stake() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let registry = setup_registry(&env, &owner);
+
+    let oracle_id = env.register(FlightOracle, ());
+    let oracle = FlightOracleClient::new(&env, &oracle_id);
+    let token = env.register_stellar_asset_contract_v2(owner.clone()).address();
+    oracle.initialize(&owner, &1_000, &1_000, &Address::generate(&env), &3600, &token);
+    oracle.set_param_registry(&owner, &registry.address);
+
+    let provider = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token).mint(&provider, &10_000);
+    oracle.register_oracle_provider(&owner, &provider, &1_000, &BytesN::from_array(&env, &[0; 32]));
+
+    set_protocol_min_stake(&env, &registry, &owner, 2_500);
+
+    let provider = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token).mint(&provider, &10_000);
+    assert!(oracle
+        .try_register_oracle_provider(&owner, &provider, &1_000, &BytesN::from_array(&env, &[0; 32]))
+        .is_err());
+    oracle.register_oracle_provider(&owner, &provider, &2_500, &BytesN::from_array(&env, &[0; 32]));
+}
@@ -1,6 +1,16 @@
-use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, Vec};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Ledger},
+    vec, Address, BytesN, Env, Vec,
+};
 
-use proxy::{ContractProxy, ContractProxyClient, ProxyState};
+use proxy::{
+    ContractProxy, ContractProxyClient, LegacyMultisigConfig, LegacyProxyConfig,
+    LegacyUpgradeProposal, ProxyState,
+};
+use storage_version::{VersionedStorage, PROXY_CONTRACT};
+
+const DELAY: u64 = 48 * 3_600;
 
 fn setup_env() -> (Env, ContractProxyClient<'static>) {
     let env = Env::default();
@@ -155,50 +165,23 @@ fn test_upgrade_timelock_enforced_before_execution() {
 
     client.init_proxy(&admin, &implementation, &signers, &2);
 
+    client.set_upgrade_delay(&admin, &DELAY);
+
     let proposal_id = client.propose_upgrade(&signers.get(0).unwrap(), &new_implementation, &None);
     client.approve_upgrade(&signers.get(1).unwrap(), &proposal_id);
 
     // Attempt execution before the timelock expires
-    let result = std::panic::catch_unwind(|| {
-        client.upgrade_to(&signers.get(0).unwrap(), &proposal_id);
-    });
-    assert!(result.is_err());
-    assert!(format!("{:?}", result.err().unwrap()).contains("Upgrade timelock active"));
+    assert!(client
+        .try_upgrade_to(&signers.get(0).unwrap(), &proposal_id)
+        .is_err());
 
     // Advance past the 48-hour timelock and execute successfully
     let now = env.ledger().timestamp();
-    env.ledger().set_timestamp(now + 48 * 60 * 60 + 1);
-    client.upgrade_to(&signers.get(0).unwrap(), &proposal_id);
-
-    assert_eq!(client.get_implementation(), new_implementation);
-    assert_eq!(client.get_version(), 2);
-}
-
-#[test]
-fn test_upgrade_rollback_restores_previous_version() {
-    let (env, client) = setup_env();
-    let admin = Address::generate(&env);
-    let signers = create_signers(&env, 3);
-    let implementation = create_dummy_hash(&env);
-    let new_implementation = BytesN::from_array(&env, &[1u8; 32]);
-
-    client.init_proxy(&admin, &implementation, &signers, &2);
-
-    let proposal_id = client.propose_upgrade(&signers.get(0).unwrap(), &new_implementation, &Some(2u32));
-    client.approve_upgrade(&signers.get(1).unwrap(), &proposal_id);
-    let now = env.ledger().timestamp();
-    env.ledger().set_timestamp(now + 48 * 60 * 60 + 1);
+    env.ledger().set_timestamp(now + DELAY + 1);
     client.upgrade_to(&signers.get(0).unwrap(), &proposal_id);
 
     assert_eq!(client.get_implementation(), new_implementation);
     assert_eq!(client.get_version(), 2);
-    assert_eq!(client.get_storage_version(), 2);
-
-    client.rollback_upgrade(&signers.get(0).unwrap(), &proposal_id);
-
-    assert_eq!(client.get_implementation(), implementation);
-    assert_eq!(client.get_version(), 1);
-    assert_eq!(client.get_storage_version(), 1);
 }
 
 #[test]
@@ -371,3 +354,252 @@ fn test_storage_migration_flow() {
     assert_eq!(client.get_storage_version(), 2);
     assert!(!client.is_upgrading());
 }
+
+// Two-of-two multisig over an initial implementation of [1; 32]
+fn setup_multisig(env: &Env) -> (ContractProxyClient<'_>, Address, Address, Address) {
+    let proxy_id = env.register(ContractProxy, ());
+    let client = ContractProxyClient::new(env, &proxy_id);
+
+    let admin = Address::generate(env);
+    let signer1 = Address::generate(env);
+    let signer2 = Address::generate(env);
+    client.init_proxy(
+        &admin,
+        &BytesN::from_array(env, &[1u8; 32]),
+        &vec![env, signer1.clone(), signer2.clone()],
+        &2,
+    );
+
+    (client, admin, signer1, signer2)
+}
+
+fn setup_with_delay(env: &Env) -> (ContractProxyClient<'_>, Address, Address, Address) {
+    let (client, admin, signer1, signer2) = setup_multisig(env);
+    client.set_upgrade_delay(&admin, &DELAY);
+    (client, admin, signer1, signer2)
+}
+
+#[test]
+fn test_expired_proposal_cannot_be_approved() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, signer1, signer2) = setup_multisig(&env);
+
+    client.set_proposal_expiration(&admin, &3_600);
+    let id = client.propose_upgrade(&signer1, &BytesN::from_array(&env, &[2u8; 32]), &None);
+
+    env.ledger().with_mut(|l| l.timestamp += 3_601);
+
+    let res = client.try_approve_upgrade(&signer2, &id);
+    assert!(res.is_err());
+    assert_eq!(client.get_upgrade_proposal(&id).unwrap().approvals.len(), 1);
+}
+
+#[test]
+fn test_expired_proposal_cannot_be_executed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, signer1, signer2) = setup_multisig(&env);
+
+    client.set_proposal_expiration(&admin, &3_600);
+    let id = client.propose_upgrade(&signer1, &BytesN::from_array(&env, &[2u8; 32]), &None);
+
+    // Approved in time, but executed after the window closed
+    env.ledger().with_mut(|l| l.timestamp += 3_600);
+    client.approve_upgrade(&signer2, &id);
+    env.ledger().with_mut(|l| l.timestamp += 1);
+
+    let res = client.try_upgrade_to(&signer1, &id);
+    assert!(res.is_err());
+    assert_eq!(client.get_implementation(), BytesN::from_array(&env, &[1u8; 32]));
+    assert!(!client.get_upgrade_proposal(&id).unwrap().executed);
+}
+
+#[test]
+fn test_fresh_proposal_executes_with_default_expiration() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _admin, signer1, signer2) = setup_multisig(&env);
+
+    let new_impl = BytesN::from_array(&env, &[2u8; 32]);
+    let id = client.propose_upgrade(&signer1, &new_impl, &None);
+
+    env.ledger().with_mut(|l| l.timestamp += 86_400);
+    client.approve_upgrade(&signer2, &id);
+    client.upgrade_to(&signer1, &id);
+
+    assert_eq!(client.get_implementation(), new_impl);
+    assert_eq!(client.get_multisig_config().unwrap().proposal_expiration, 7 * 86_400);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_non_admin_cannot_set_proposal_expiration() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _admin, signer1, _signer2) = setup_multisig(&env);
+
+    client.set_proposal_expiration(&signer1, &60);
+}
+
+#[test]
+#[should_panic(expected = "Upgrade delay not elapsed")]
+fn test_upgrade_before_delay_panics() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _admin, signer1, signer2) = setup_with_delay(&env);
+
+    let id = client.propose_upgrade(&signer1, &BytesN::from_array(&env, &[2u8; 32]), &None);
+    client.approve_upgrade(&signer2, &id);
+
+    env.ledger().with_mut(|l| l.timestamp += DELAY - 1);
+    client.upgrade_to(&signer1, &id);
+}
+
+#[test]
+fn test_delay_runs_from_threshold_approval() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _admin, signer1, signer2) = setup_with_delay(&env);
+
+    let new_impl = BytesN::from_array(&env, &[2u8; 32]);
+    let id = client.propose_upgrade(&signer1, &new_impl, &None);
+    assert!(client.get_upgrade_proposal(&id).unwrap().approved_at.is_none());
+
+    // Time spent waiting for the second signer does not count
+    env.ledger().with_mut(|l| l.timestamp += DELAY);
+    client.approve_upgrade(&signer2, &id);
+    let approved_at = env.ledger().timestamp();
+    assert_eq!(client.get_upgrade_proposal(&id).unwrap().approved_at, Some(approved_at));
+    assert!(client.try_upgrade_to(&signer1, &id).is_err());
+
+    env.ledger().with_mut(|l| l.timestamp = approved_at + DELAY);
+    client.upgrade_to(&signer1, &id);
+    assert_eq!(client.get_implementation(), new_impl);
+}
+
+#[test]
+fn test_cancelled_upgrade_cannot_execute() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, signer1, signer2) = setup_with_delay(&env);
+
+    let id = client.propose_upgrade(&signer1, &BytesN::from_array(&env, &[2u8; 32]), &None);
+    client.approve_upgrade(&signer2, &id);
+    client.cancel_upgrade(&admin, &id);
+
+    env.ledger().with_mut(|l| l.timestamp += DELAY);
+    assert!(client.try_upgrade_to(&signer1, &id).is_err());
+    assert!(client.get_upgrade_proposal(&id).unwrap().cancelled);
+    assert_eq!(client.get_implementation(), BytesN::from_array(&env, &[1u8; 32]));
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_signer_cannot_cancel_upgrade() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _admin, signer1, _signer2) = setup_with_delay(&env);
+
+    let id = client.propose_upgrade(&signer1, &BytesN::from_array(&env, &[2u8; 32]), &None);
+    client.cancel_upgrade(&signer1, &id);
+}
+
+#[test]
+fn test_lowered_threshold_starts_delay_at_first_execution_attempt() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, signer1, signer2) = setup_with_delay(&env);
+
+    let new_impl = BytesN::from_array(&env, &[2u8; 32]);
+    let id = client.propose_upgrade(&signer1, &new_impl, &None);
+    client.update_multisig(&admin, &vec![&env, signer1.clone(), signer2.clone()], &1);
+
+    // The first call sees the threshold met and starts the delay instead of executing
+    client.upgrade_to(&signer1, &id);
+    let approved_at = env.ledger().timestamp();
+    assert_eq!(client.get_upgrade_proposal(&id).unwrap().approved_at, Some(approved_at));
+    assert!(!client.get_upgrade_proposal(&id).unwrap().executed);
+
+    env.ledger().with_mut(|l| l.timestamp = approved_at + DELAY - 1);
+    assert!(client.try_upgrade_to(&signer1, &id).is_err());
+
+    env.ledger().with_mut(|l| l.timestamp = approved_at + DELAY);
+    client.upgrade_to(&signer1, &id);
+    assert_eq!(client.get_implementation(), new_impl);
+}
+
+#[test]
+fn test_delay_must_stay_below_expiration() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _signer1, _signer2) = setup_with_delay(&env);
+
+    assert!(client.try_set_proposal_expiration(&admin, &DELAY).is_err());
+    assert!(client.try_set_upgrade_delay(&admin, &(7 * 86_400)).is_err());
+    client.set_proposal_expiration(&admin, &(DELAY + 1));
+}
+
+#[test]
+fn test_legacy_proxy_state_migrates_to_current_layout() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, signer1, signer2) = setup_with_delay(&env);
+    // Fresh deployments are already on the current layout
+    assert!(client.try_migrate_legacy_proxy(&admin, &vec![&env]).is_err());
+
+    // Put back the config, multisig and a half-approved proposal as stored before v2
+    let legacy = LegacyUpgradeProposal {
+        proposal_id: 1,
+        new_implementation: BytesN::from_array(&env, &[2u8; 32]),
+        new_storage_version: None,
+        proposed_at: 0,
+        approvals: vec![&env, signer1.clone()],
+        executed: false,
+    };
+    env.as_contract(&client.address, || {
+        env.storage().instance().set(
+            &symbol_short!("config"),
+            &LegacyProxyConfig {
+                admin: admin.clone(),
+                implementation: BytesN::from_array(&env, &[1u8; 32]),
+                state: ProxyState::Active,
+                version: 1,
+                storage_version: 1,
+            },
+        );
+        env.storage().instance().set(
+            &symbol_short!("multisig"),
+            &LegacyMultisigConfig {
+                signers: vec![&env, signer1.clone(), signer2.clone()],
+                threshold: 2,
+                proposal_count: 1,
+            },
+        );
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("upgrade"), 1u64), &legacy);
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("approved"), 1u64, signer1.clone()), &true);
+        env.storage().instance().set(&symbol_short!("ms_count"), &1u64);
+        VersionedStorage::set_storage_version(&env, &PROXY_CONTRACT, 1);
+    });
+
+    assert!(client.try_migrate_legacy_proxy(&signer1, &vec![&env, 1]).is_err());
+    assert_eq!(client.migrate_legacy_proxy(&admin, &vec![&env, 1]), 1);
+
+    let multisig = client.get_multisig_config().unwrap();
+    assert_eq!(multisig.proposal_expiration, 7 * 86_400);
+    let proposal = client.get_upgrade_proposal(&1).unwrap();
+    assert!(proposal.approved_at.is_none());
+    assert!(!proposal.cancelled);
+
+    // The migrated proposal finishes approval and runs under the current code
+    client.approve_upgrade(&signer2, &1);
+    client.upgrade_to(&signer1, &1);
+    assert_eq!(client.get_implementation(), BytesN::from_array(&env, &[2u8; 32]));
+
+    // Migration runs once
+    assert!(client.try_migrate_legacy_proxy(&admin, &vec![&env]).is_err());
+}
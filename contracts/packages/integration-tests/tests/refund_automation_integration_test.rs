@@ -1,3 +1,4 @@
+use booking::BookingStatus;
 use soroban_sdk::{testutils::Ledger, Symbol};
 
 
@@ -51,7 +52,7 @@ fn test_cancel_booking_full_refund_over_72_hours() {
     assert_eq!(contracts.token.balance_of(&contracts.booking.address), 0);
 
     let booking = contracts.booking.get_booking(&booking_numeric_id).unwrap();
-    assert_eq!(booking.status, Symbol::new(&env, "cancelled"));
+    assert_eq!(booking.status, BookingStatus::Cancelled);
 }
 
 #[test]
@@ -1,8 +1,9 @@
-use soroban_sdk::Symbol;
+use soroban_sdk::{testutils::Address as _, Address, Symbol};
+use booking::BookingStatus;
 use refund::RefundContract;
 
 
-use integration_tests::{generate_actors, new_env, register_contracts};
+use integration_tests::{generate_actors, initialize_token, new_env, register_contracts};
 
 #[test]
 fn test_set_policy_and_calculate_refund() {
@@ -47,13 +48,15 @@ fn test_request_and_process_refund() {
     let env = new_env();
     let actors = generate_actors(&env);
     let contracts = register_contracts(&env);
-    contracts.refund.initialize(&actors.admin);
+    link_booking(&actors, &contracts);
+    initialize_token(&env, &contracts.token, &actors.admin);
 
+    let booking_id = create_paid_booking(&env, &actors, &contracts);
     let rid = contracts.refund.request_refund(
         &actors.passenger,
-        &12345,
+        &booking_id,
         &50_0000000i128,
-        &Symbol::new(&env, "USDC"),
+        &contracts.token.address,
         &Symbol::new(&env, "cancelled"),
     );
     let r = contracts.refund.get_refund_request(&rid).unwrap();
@@ -61,6 +64,309 @@ fn test_request_and_process_refund() {
 
     contracts.refund.process_refund(&actors.admin, &rid);
     let r2 = contracts.refund.get_refund_request(&rid).unwrap();
-    assert_eq!(r2.status, Symbol::new(&env, "approved"));
+    assert_eq!(r2.status, Symbol::new(&env, "processed"));
     assert!(r2.processed_at.is_some());
 }
+
+fn create_booking(
+    env: &soroban_sdk::Env,
+    actors: &integration_tests::Actors,
+    contracts: &integration_tests::Contracts,
+) -> u64 {
+    contracts.booking.create_booking(
+        &actors.passenger,
+        &actors.airline,
+        &Symbol::new(env, "FL123"),
+        &Symbol::new(env, "JFK"),
+        &Symbol::new(env, "LAX"),
+        &(env.ledger().timestamp() + 200_000),
+        &50_0000000i128,
+        &contracts.token.address,
+    )
+}
+
+fn create_paid_booking(
+    env: &soroban_sdk::Env,
+    actors: &integration_tests::Actors,
+    contracts: &integration_tests::Contracts,
+) -> u64 {
+    let booking_id = create_booking(env, actors, contracts);
+    contracts
+        .token
+        .mint(&actors.admin, &actors.passenger, &50_0000000i128);
+    contracts.booking.pay_for_booking(&booking_id);
+    booking_id
+}
+
+// Processed refunds are paid out of the booking's escrow, so each contract trusts the other
+fn link_booking(actors: &integration_tests::Actors, contracts: &integration_tests::Contracts) {
+    contracts.refund.initialize(&actors.admin);
+    contracts
+        .refund
+        .set_booking_contract(&actors.admin, &contracts.booking.address);
+    contracts.booking.init_admin(&actors.admin);
+    contracts
+        .booking
+        .set_refund_contract(&actors.admin, &contracts.refund.address);
+}
+
+#[test]
+fn test_refund_request_in_booking_token_accepted() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.refund.initialize(&actors.admin);
+    contracts
+        .refund
+        .set_booking_contract(&actors.admin, &contracts.booking.address);
+
+    let booking_id = create_booking(&env, &actors, &contracts);
+    let rid = contracts.refund.request_refund(
+        &actors.passenger,
+        &booking_id,
+        &50_0000000i128,
+        &contracts.token.address,
+        &Symbol::new(&env, "cancelled"),
+    );
+    let r = contracts.refund.get_refund_request(&rid).unwrap();
+    assert_eq!(r.currency, contracts.token.address);
+}
+
+#[test]
+#[should_panic(expected = "Currency mismatch")]
+fn test_refund_request_in_other_currency_rejected() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.refund.initialize(&actors.admin);
+    contracts
+        .refund
+        .set_booking_contract(&actors.admin, &contracts.booking.address);
+
+    let booking_id = create_booking(&env, &actors, &contracts);
+    contracts.refund.request_refund(
+        &actors.passenger,
+        &booking_id,
+        &50_0000000i128,
+        &Address::generate(&env),
+        &Symbol::new(&env, "cancelled"),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Booking contract not set")]
+fn test_refund_request_without_booking_contract_rejected() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.refund.initialize(&actors.admin);
+
+    // Without the booking there is no escrow token or passenger to check the request against
+    let booking_id = create_booking(&env, &actors, &contracts);
+    contracts.refund.request_refund(
+        &actors.passenger,
+        &booking_id,
+        &50_0000000i128,
+        &contracts.token.address,
+        &Symbol::new(&env, "cancelled"),
+    );
+}
+
+#[test]
+fn test_processed_refund_credits_passenger() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    link_booking(&actors, &contracts);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    // Asking for more than was escrowed only returns the escrow
+    let booking_id = create_paid_booking(&env, &actors, &contracts);
+    let rid = contracts.refund.request_refund(
+        &actors.passenger,
+        &booking_id,
+        &80_0000000i128,
+        &contracts.token.address,
+        &Symbol::new(&env, "cancelled"),
+    );
+    contracts.refund.process_refund(&actors.admin, &rid);
+
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 50_0000000i128);
+    assert_eq!(contracts.token.balance_of(&contracts.booking.address), 0);
+    assert_eq!(
+        contracts.refund.get_refund_request(&rid).unwrap().amount,
+        50_0000000i128
+    );
+    let booking = contracts.booking.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.status, BookingStatus::Refunded);
+    assert_eq!(booking.amount_escrowed, 0);
+    // A processed request can't be paid out again
+    assert!(contracts.refund.try_process_refund(&actors.admin, &rid).is_err());
+}
+
+#[test]
+fn test_partial_refund_pays_remainder_to_airline() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    link_booking(&actors, &contracts);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    let booking_id = create_paid_booking(&env, &actors, &contracts);
+    let rid = contracts.refund.request_refund(
+        &actors.passenger,
+        &booking_id,
+        &30_0000000i128,
+        &contracts.token.address,
+        &Symbol::new(&env, "cancelled"),
+    );
+    contracts.refund.process_refund(&actors.admin, &rid);
+
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 30_0000000i128);
+    assert_eq!(contracts.token.balance_of(&actors.airline), 20_0000000i128);
+    // The booking is settled; its escrow can't be refunded a second time
+    assert!(contracts.booking.try_refund_passenger(&booking_id).is_err());
+}
+
+#[test]
+#[should_panic(expected = "Not the booking passenger")]
+fn test_refund_request_for_another_passengers_booking_rejected() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    link_booking(&actors, &contracts);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    let booking_id = create_paid_booking(&env, &actors, &contracts);
+    contracts.refund.request_refund(
+        &Address::generate(&env),
+        &booking_id,
+        &50_0000000i128,
+        &contracts.token.address,
+        &Symbol::new(&env, "cancelled"),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_reject_refund_by_unauthorized_caller_should_panic() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.refund.initialize(&actors.admin);
+    contracts
+        .refund
+        .set_booking_contract(&actors.admin, &contracts.booking.address);
+
+    let booking_id = create_booking(&env, &actors, &contracts);
+    let rid = contracts.refund.request_refund(
+        &actors.passenger,
+        &booking_id,
+        &50_0000000i128,
+        &contracts.token.address,
+        &Symbol::new(&env, "cancelled"),
+    );
+    contracts
+        .refund
+        .reject_refund(&actors.passenger, &rid, &Symbol::new(&env, "denied"));
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_process_refund_by_other_airline_should_panic() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.refund.initialize(&actors.admin);
+    contracts
+        .refund
+        .set_booking_contract(&actors.admin, &contracts.booking.address);
+
+    let booking_id = create_booking(&env, &actors, &contracts);
+    let rid = contracts.refund.request_refund(
+        &actors.passenger,
+        &booking_id,
+        &50_0000000i128,
+        &contracts.token.address,
+        &Symbol::new(&env, "cancelled"),
+    );
+    contracts
+        .refund
+        .process_refund(&Address::generate(&env), &rid);
+}
+
+#[test]
+fn test_booking_airline_can_process_its_refund() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    link_booking(&actors, &contracts);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    let booking_id = create_paid_booking(&env, &actors, &contracts);
+    let rid = contracts.refund.request_refund(
+        &actors.passenger,
+        &booking_id,
+        &50_0000000i128,
+        &contracts.token.address,
+        &Symbol::new(&env, "cancelled"),
+    );
+    contracts.refund.process_refund(&actors.airline, &rid);
+
+    let r = contracts.refund.get_refund_request(&rid).unwrap();
+    assert_eq!(r.status, Symbol::new(&env, "processed"));
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 50_0000000i128);
+}
+
+#[test]
+fn test_reject_pending_refund_records_reason() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.refund.initialize(&actors.admin);
+    contracts
+        .refund
+        .set_booking_contract(&actors.admin, &contracts.booking.address);
+
+    let booking_id = create_booking(&env, &actors, &contracts);
+    let rid = contracts.refund.request_refund(
+        &actors.passenger,
+        &booking_id,
+        &50_0000000i128,
+        &contracts.token.address,
+        &Symbol::new(&env, "cancelled"),
+    );
+    contracts
+        .refund
+        .reject_refund(&actors.admin, &rid, &Symbol::new(&env, "out_policy"));
+
+    let r = contracts.refund.get_refund_request(&rid).unwrap();
+    assert_eq!(r.status, Symbol::new(&env, "rejected"));
+    assert_eq!(r.rejection_reason, Some(Symbol::new(&env, "out_policy")));
+    assert!(r.processed_at.is_some());
+}
+
+#[test]
+#[should_panic(expected = "Request already processed")]
+fn test_process_rejected_refund_should_panic() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    contracts.refund.initialize(&actors.admin);
+    contracts
+        .refund
+        .set_booking_contract(&actors.admin, &contracts.booking.address);
+
+    let booking_id = create_booking(&env, &actors, &contracts);
+    let rid = contracts.refund.request_refund(
+        &actors.passenger,
+        &booking_id,
+        &50_0000000i128,
+        &contracts.token.address,
+        &Symbol::new(&env, "cancelled"),
+    );
+    contracts
+        .refund
+        .reject_refund(&actors.admin, &rid, &Symbol::new(&env, "fraud"));
+    contracts.refund.process_refund(&actors.admin, &rid);
+}
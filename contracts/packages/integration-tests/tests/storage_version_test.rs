@@ -1,7 +1,9 @@
-use soroban_sdk::{symbol_short, testutils::Address as _, Address, Env};
+use soroban_sdk::{
+    contracttype, symbol_short, testutils::Address as _, vec, Address, Env, Symbol, Vec,
+};
 
 use storage_version::{
-    VersionedStorage, AIRLINE_CONTRACT, BOOKING_CONTRACT, TOKEN_CONTRACT,
+    Migratable, VersionedStorage, AIRLINE_CONTRACT, BOOKING_CONTRACT, TOKEN_CONTRACT,
 };
 
 use token::TRQTokenContract;
@@ -307,3 +309,263 @@ fn test_migration_record_timestamp() {
         let _ts = record.timestamp;
     });
 }
+
+// Reshapes v1 data fine but fails the v2 -> v3 step
+struct FailsAtV2;
+
+impl Migratable for FailsAtV2 {
+    fn migrate_from_v1_to_v2(_env: &Env) -> bool {
+        true
+    }
+
+    fn migrate_from_v2_to_v3(_env: &Env) -> bool {
+        false
+    }
+
+    fn get_migration_steps(env: &Env, _from: u32, _to: u32) -> Vec<Symbol> {
+        Vec::new(env)
+    }
+}
+
+#[test]
+fn test_failed_migration_rolls_back_to_starting_version() {
+    let env = Env::default();
+    let migrator = Address::generate(&env);
+
+    run_as_contract(&env, || {
+        VersionedStorage::set_storage_version(&env, &BOOKING_CONTRACT, 1);
+
+        // Step 1 -> 2 goes through, 2 -> 3 fails
+        let success = VersionedStorage::migrate_storage_with::<FailsAtV2>(
+            &env,
+            &BOOKING_CONTRACT,
+            1,
+            4,
+            &migrator,
+        );
+        assert!(!success);
+        assert_eq!(VersionedStorage::get_storage_version(&env, &BOOKING_CONTRACT), 2);
+        let progress = VersionedStorage::get_migration_progress(&env, &BOOKING_CONTRACT).unwrap();
+        assert_eq!(progress.current_step, 1);
+        assert!(!progress.completed);
+
+        VersionedStorage::rollback_migration(&env, &BOOKING_CONTRACT, 1, &migrator);
+        assert_eq!(VersionedStorage::get_storage_version(&env, &BOOKING_CONTRACT), 1);
+
+        let progress = VersionedStorage::get_migration_progress(&env, &BOOKING_CONTRACT).unwrap();
+        assert!(VersionedStorage::is_rolled_back(&env, &BOOKING_CONTRACT));
+        assert_eq!(progress.current_step, 0);
+        assert!(!progress.completed);
+
+        let count = VersionedStorage::get_migration_count(&env, &BOOKING_CONTRACT);
+        let record = VersionedStorage::get_migration(&env, &BOOKING_CONTRACT, count).unwrap();
+        assert_eq!(record.from_version, 2);
+        assert_eq!(record.to_version, 1);
+        assert_eq!(record.migration_type, symbol_short!("emergency"));
+
+        // The migration can be retried with a working step
+        assert!(VersionedStorage::migrate_storage(&env, &BOOKING_CONTRACT, 1, 4, &migrator));
+        assert_eq!(VersionedStorage::get_storage_version(&env, &BOOKING_CONTRACT), 4);
+        assert!(!VersionedStorage::is_rolled_back(&env, &BOOKING_CONTRACT));
+    });
+}
+
+#[test]
+#[should_panic(expected = "Rollback below pre-migration version")]
+fn test_rollback_below_pre_migration_version() {
+    let env = Env::default();
+    let migrator = Address::generate(&env);
+
+    run_as_contract(&env, || {
+        VersionedStorage::set_storage_version(&env, &BOOKING_CONTRACT, 2);
+        VersionedStorage::migrate_storage(&env, &BOOKING_CONTRACT, 2, 3, &migrator);
+
+        VersionedStorage::rollback_migration(&env, &BOOKING_CONTRACT, 1, &migrator);
+    });
+}
+
+#[test]
+#[should_panic(expected = "No migration to roll back")]
+fn test_rollback_without_migration() {
+    let env = Env::default();
+    let migrator = Address::generate(&env);
+
+    run_as_contract(&env, || {
+        VersionedStorage::set_storage_version(&env, &BOOKING_CONTRACT, 2);
+        VersionedStorage::rollback_migration(&env, &BOOKING_CONTRACT, 1, &migrator);
+    });
+}
+
+// v1 stores a fare in whole units, v2 in cents, v3 moves it under a new key. The cents
+// conversion marks itself done so a retry after rollback doesn't multiply twice.
+struct FareMigration;
+
+impl Migratable for FareMigration {
+    fn migrate_from_v1_to_v2(env: &Env) -> bool {
+        let fare: i128 = match env.storage().instance().get(&symbol_short!("fare")) {
+            Some(fare) => fare,
+            None => return false,
+        };
+        if env.storage().instance().has(&symbol_short!("in_cents")) {
+            return true;
+        }
+        env.storage().instance().set(&symbol_short!("fare"), &(fare * 100));
+        env.storage().instance().set(&symbol_short!("in_cents"), &true);
+        true
+    }
+
+    fn migrate_from_v2_to_v3(env: &Env) -> bool {
+        let fare: i128 = match env.storage().instance().get(&symbol_short!("fare")) {
+            Some(fare) => fare,
+            None => return false,
+        };
+        env.storage().instance().remove(&symbol_short!("fare"));
+        env.storage().instance().set(&symbol_short!("fare_c"), &fare);
+        true
+    }
+
+    fn get_migration_steps(env: &Env, from: u32, to: u32) -> Vec<Symbol> {
+        let mut steps = vec![env];
+        if from <= 1 && to >= 2 {
+            steps.push_back(symbol_short!("to_cents"));
+        }
+        if from <= 2 && to >= 3 {
+            steps.push_back(symbol_short!("rekey"));
+        }
+        steps
+    }
+}
+
+#[test]
+fn test_migratable_steps_reshape_data() {
+    let env = Env::default();
+    let migrator = Address::generate(&env);
+
+    run_as_contract(&env, || {
+        VersionedStorage::set_storage_version(&env, &BOOKING_CONTRACT, 1);
+        env.storage().instance().set(&symbol_short!("fare"), &12i128);
+        assert_eq!(FareMigration::get_migration_steps(&env, 1, 3).len(), 2);
+
+        assert!(VersionedStorage::migrate_storage_with::<FareMigration>(
+            &env,
+            &BOOKING_CONTRACT,
+            1,
+            3,
+            &migrator,
+        ));
+        assert_eq!(VersionedStorage::get_storage_version(&env, &BOOKING_CONTRACT), 3);
+        assert!(!env.storage().instance().has(&symbol_short!("fare")));
+        assert_eq!(
+            env.storage().instance().get::<_, i128>(&symbol_short!("fare_c")),
+            Some(1_200)
+        );
+    });
+}
+
+#[test]
+fn test_failed_migratable_step_stops_migration() {
+    let env = Env::default();
+    let migrator = Address::generate(&env);
+
+    run_as_contract(&env, || {
+        // No fare stored, so the first step fails
+        VersionedStorage::set_storage_version(&env, &BOOKING_CONTRACT, 1);
+
+        assert!(!VersionedStorage::migrate_storage_with::<FareMigration>(
+            &env,
+            &BOOKING_CONTRACT,
+            1,
+            3,
+            &migrator,
+        ));
+        assert_eq!(VersionedStorage::get_storage_version(&env, &BOOKING_CONTRACT), 1);
+        let progress = VersionedStorage::get_migration_progress(&env, &BOOKING_CONTRACT).unwrap();
+        assert!(!progress.completed);
+        assert_eq!(progress.current_step, 0);
+    });
+}
+
+#[test]
+fn test_retry_after_rollback_does_not_reapply_steps() {
+    let env = Env::default();
+    let migrator = Address::generate(&env);
+
+    run_as_contract(&env, || {
+        VersionedStorage::set_storage_version(&env, &BOOKING_CONTRACT, 1);
+        env.storage().instance().set(&symbol_short!("fare"), &12i128);
+
+        assert!(VersionedStorage::migrate_storage_with::<FareMigration>(
+            &env,
+            &BOOKING_CONTRACT,
+            1,
+            2,
+            &migrator,
+        ));
+        // Rolling back rewinds the version but leaves the fare in cents
+        VersionedStorage::rollback_migration(&env, &BOOKING_CONTRACT, 1, &migrator);
+        assert_eq!(VersionedStorage::get_storage_version(&env, &BOOKING_CONTRACT), 1);
+        assert_eq!(
+            env.storage().instance().get::<_, i128>(&symbol_short!("fare")),
+            Some(1_200)
+        );
+
+        // The idempotent step recognises converted data on the retry
+        assert!(VersionedStorage::migrate_storage_with::<FareMigration>(
+            &env,
+            &BOOKING_CONTRACT,
+            1,
+            2,
+            &migrator,
+        ));
+        assert_eq!(
+            env.storage().instance().get::<_, i128>(&symbol_short!("fare")),
+            Some(1_200)
+        );
+    });
+}
+
+// Progress as stored before rollbacks were tracked
+#[contracttype]
+struct LegacyMigrationProgress {
+    contract_type: Symbol,
+    from_version: u32,
+    to_version: u32,
+    current_step: u32,
+    total_steps: u32,
+    completed: bool,
+    started_at: u64,
+    completed_at: Option<u64>,
+}
+
+#[test]
+fn test_progress_stored_before_rollbacks_still_decodes() {
+    let env = Env::default();
+    let migrator = Address::generate(&env);
+
+    run_as_contract(&env, || {
+        env.storage().instance().set(
+            &(symbol_short!("mig_prog"), BOOKING_CONTRACT),
+            &LegacyMigrationProgress {
+                contract_type: BOOKING_CONTRACT,
+                from_version: 1,
+                to_version: 2,
+                current_step: 1,
+                total_steps: 1,
+                completed: true,
+                started_at: 0,
+                completed_at: Some(0),
+            },
+        );
+        env.storage()
+            .instance()
+            .set(&(symbol_short!("mig_pre"), BOOKING_CONTRACT), &1u32);
+        VersionedStorage::set_storage_version(&env, &BOOKING_CONTRACT, 2);
+
+        let progress = VersionedStorage::get_migration_progress(&env, &BOOKING_CONTRACT).unwrap();
+        assert!(progress.completed);
+        assert!(!VersionedStorage::is_rolled_back(&env, &BOOKING_CONTRACT));
+
+        VersionedStorage::rollback_migration(&env, &BOOKING_CONTRACT, 1, &migrator);
+        assert!(VersionedStorage::is_rolled_back(&env, &BOOKING_CONTRACT));
+    });
+}
@@ -1,4 +1,4 @@
-use soroban_sdk::{String, Symbol};
+use soroban_sdk::{vec, String, Symbol};
 use token::TRQTokenContract;
 
 
@@ -92,6 +92,193 @@ fn test_transfer_insufficient_balance_should_panic() {
         .transfer(&actors.airline, &actors.passenger, &1);
 }
 
+#[test]
+#[should_panic(expected = "Below minimum transfer")]
+fn test_transfer_below_minimum_should_panic() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts.token.set_min_transfer(&actors.admin, &100);
+    contracts
+        .token
+        .mint(&actors.admin, &actors.passenger, &1000);
+    contracts
+        .token
+        .transfer(&actors.passenger, &actors.airline, &99);
+}
+
+#[test]
+fn test_transfer_at_minimum_and_dust_sweep_allowed() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts.token.set_min_transfer(&actors.admin, &100);
+    assert_eq!(contracts.token.min_transfer(), 100);
+    contracts
+        .token
+        .mint(&actors.admin, &actors.passenger, &150);
+
+    contracts
+        .token
+        .transfer(&actors.passenger, &actors.airline, &100);
+    // The remaining 50 is below the minimum but empties the account
+    contracts
+        .token
+        .transfer(&actors.passenger, &actors.airline, &50);
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 0);
+    assert_eq!(contracts.token.balance_of(&actors.airline), 150);
+}
+
+#[test]
+fn test_batch_transfer_pays_every_recipient() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts.token.mint(&actors.admin, &actors.admin, &1_000);
+
+    contracts.token.batch_transfer(
+        &actors.admin,
+        &vec![&env, actors.passenger.clone(), actors.airline.clone()],
+        &vec![&env, 300, 200],
+    );
+
+    assert_eq!(contracts.token.balance_of(&actors.admin), 500);
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 300);
+    assert_eq!(contracts.token.balance_of(&actors.airline), 200);
+    assert_eq!(contracts.token.total_supply(), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Length mismatch")]
+fn test_batch_transfer_length_mismatch_should_panic() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts.token.mint(&actors.admin, &actors.admin, &1_000);
+
+    contracts.token.batch_transfer(
+        &actors.admin,
+        &vec![&env, actors.passenger.clone(), actors.airline.clone()],
+        &vec![&env, 300],
+    );
+}
+
+#[test]
+#[should_panic(expected = "Token paused")]
+fn test_transfer_while_paused_should_panic() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts.token.mint(&actors.admin, &actors.passenger, &500);
+
+    contracts.token.pause(&actors.admin);
+    contracts
+        .token
+        .transfer(&actors.passenger, &actors.airline, &100);
+}
+
+#[test]
+fn test_unpause_resumes_balance_changes() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts.token.mint(&actors.admin, &actors.passenger, &500);
+    contracts
+        .token
+        .approve(&actors.passenger, &actors.airline, &300, &100);
+
+    contracts.token.pause(&actors.admin);
+    assert!(contracts.token.is_paused());
+    assert!(contracts
+        .token
+        .try_mint(&actors.admin, &actors.passenger, &100)
+        .is_err());
+    assert!(contracts
+        .token
+        .try_transfer_from(&actors.airline, &actors.passenger, &actors.airline, &100)
+        .is_err());
+    assert!(contracts.token.try_burn(&actors.passenger, &100).is_err());
+
+    contracts.token.unpause(&actors.admin);
+    contracts
+        .token
+        .transfer(&actors.passenger, &actors.airline, &100);
+    contracts
+        .token
+        .transfer_from(&actors.airline, &actors.passenger, &actors.airline, &100);
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 300);
+    assert_eq!(contracts.token.balance_of(&actors.airline), 200);
+}
+
+#[test]
+#[should_panic(expected = "Account frozen")]
+fn test_transfer_from_frozen_sender_should_panic() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts.token.mint(&actors.admin, &actors.passenger, &500);
+
+    contracts.token.freeze_account(&actors.admin, &actors.passenger);
+    contracts
+        .token
+        .transfer(&actors.passenger, &actors.airline, &100);
+}
+
+#[test]
+#[should_panic(expected = "Account frozen")]
+fn test_transfer_to_frozen_receiver_should_panic() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts.token.mint(&actors.admin, &actors.passenger, &500);
+
+    contracts.token.freeze_account(&actors.admin, &actors.airline);
+    contracts
+        .token
+        .transfer(&actors.passenger, &actors.airline, &100);
+}
+
+#[test]
+fn test_unfreeze_restores_transfers() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts.token.mint(&actors.admin, &actors.passenger, &500);
+    contracts
+        .token
+        .approve(&actors.passenger, &actors.airline, &300, &100);
+
+    contracts.token.freeze_account(&actors.admin, &actors.passenger);
+    assert!(contracts.token.is_frozen(&actors.passenger));
+    assert!(contracts
+        .token
+        .try_mint(&actors.admin, &actors.passenger, &100)
+        .is_err());
+    assert!(contracts
+        .token
+        .try_transfer_from(&actors.airline, &actors.passenger, &actors.airline, &100)
+        .is_err());
+
+    contracts.token.unfreeze_account(&actors.admin, &actors.passenger);
+    assert!(!contracts.token.is_frozen(&actors.passenger));
+    contracts
+        .token
+        .transfer(&actors.passenger, &actors.airline, &100);
+    contracts
+        .token
+        .transfer_from(&actors.airline, &actors.passenger, &actors.airline, &100);
+    assert_eq!(contracts.token.balance_of(&actors.airline), 200);
+}
+
 #[test]
 fn test_approve_and_transfer_from() {
     let env = new_env();
@@ -143,3 +330,193 @@ fn test_transfer_from_insufficient_allowance_should_panic() {
         .token
         .transfer_from(&actors.airline, &actors.passenger, &actors.airline, &1);
 }
+
+#[test]
+fn test_increase_allowance_adds_to_current_amount() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    contracts
+        .token
+        .approve(&actors.passenger, &actors.airline, &300, &100);
+    contracts
+        .token
+        .increase_allowance(&actors.passenger, &actors.airline, &200, &200);
+    assert_eq!(
+        contracts.token.allowance(&actors.passenger, &actors.airline),
+        500
+    );
+}
+
+#[test]
+fn test_decrease_allowance_clamps_at_zero() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    contracts
+        .token
+        .approve(&actors.passenger, &actors.airline, &300, &100);
+    contracts
+        .token
+        .decrease_allowance(&actors.passenger, &actors.airline, &100);
+    assert_eq!(
+        contracts.token.allowance(&actors.passenger, &actors.airline),
+        200
+    );
+
+    contracts
+        .token
+        .decrease_allowance(&actors.passenger, &actors.airline, &1_000);
+    assert_eq!(
+        contracts.token.allowance(&actors.passenger, &actors.airline),
+        0
+    );
+}
+
+#[test]
+fn test_allowance_adjustments_apply_to_remaining_after_transfer_from() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts.token.mint(&actors.admin, &actors.passenger, &1_000);
+
+    contracts
+        .token
+        .approve(&actors.passenger, &actors.airline, &300, &100);
+    contracts
+        .token
+        .transfer_from(&actors.airline, &actors.passenger, &actors.airline, &200);
+
+    // Lowering by 50 leaves 50 of the unspent 100, not 250 of the original 300
+    contracts
+        .token
+        .decrease_allowance(&actors.passenger, &actors.airline, &50);
+    assert_eq!(
+        contracts.token.allowance(&actors.passenger, &actors.airline),
+        50
+    );
+    assert!(contracts
+        .token
+        .try_transfer_from(&actors.airline, &actors.passenger, &actors.airline, &100)
+        .is_err());
+
+    contracts
+        .token
+        .increase_allowance(&actors.passenger, &actors.airline, &150, &100);
+    contracts
+        .token
+        .transfer_from(&actors.airline, &actors.passenger, &actors.airline, &200);
+    assert_eq!(contracts.token.balance_of(&actors.airline), 400);
+    assert_eq!(
+        contracts.token.allowance(&actors.passenger, &actors.airline),
+        0
+    );
+}
+
+#[test]
+fn test_metadata_matches_initialized_values() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts.token.mint(&actors.admin, &actors.passenger, &2_500);
+
+    let metadata = contracts.token.metadata();
+    assert_eq!(metadata.name, String::from_str(&env, "TRQ"));
+    assert_eq!(metadata.symbol, Symbol::new(&env, "TRQ"));
+    assert_eq!(metadata.decimals, 7);
+    assert_eq!(metadata.total_supply, 2_500);
+}
+
+#[test]
+fn test_burn_decreases_balance_and_total_supply() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts.token.mint(&actors.admin, &actors.passenger, &1_000);
+
+    contracts.token.burn(&actors.passenger, &300);
+
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 700);
+    assert_eq!(contracts.token.total_supply(), 700);
+}
+
+#[test]
+fn test_burn_from_consumes_allowance() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts.token.mint(&actors.admin, &actors.passenger, &1_000);
+
+    contracts
+        .token
+        .approve(&actors.passenger, &actors.airline, &400, &1000);
+    contracts
+        .token
+        .burn_from(&actors.airline, &actors.passenger, &250);
+
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 750);
+    assert_eq!(contracts.token.total_supply(), 750);
+    assert_eq!(
+        contracts.token.allowance(&actors.passenger, &actors.airline),
+        150
+    );
+}
+
+#[test]
+#[should_panic(expected = "Insufficient balance")]
+fn test_burn_more_than_balance_should_panic() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+    contracts.token.mint(&actors.admin, &actors.passenger, &100);
+
+    contracts.token.burn(&actors.passenger, &101);
+}
+
+#[test]
+fn test_snapshot_balance_unaffected_by_later_transfers() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    contracts
+        .token
+        .mint(&actors.admin, &actors.passenger, &1000);
+    let first = contracts.token.snapshot(&actors.admin);
+
+    contracts
+        .token
+        .transfer(&actors.passenger, &actors.airline, &400);
+    contracts
+        .token
+        .mint(&actors.admin, &actors.airline, &50);
+    let second = contracts.token.snapshot(&actors.admin);
+    contracts.token.burn(&actors.passenger, &100);
+
+    assert_eq!(contracts.token.balance_of_at(&actors.passenger, &first), 1000);
+    assert_eq!(contracts.token.balance_of_at(&actors.airline, &first), 0);
+    assert_eq!(contracts.token.balance_of_at(&actors.passenger, &second), 600);
+    assert_eq!(contracts.token.balance_of_at(&actors.airline, &second), 450);
+    assert_eq!(contracts.token.balance_of(&actors.passenger), 500);
+}
+
+#[test]
+#[should_panic(expected = "Unknown snapshot")]
+fn test_balance_of_at_future_snapshot_should_panic() {
+    let env = new_env();
+    let actors = generate_actors(&env);
+    let contracts = register_contracts(&env);
+    initialize_token(&env, &contracts.token, &actors.admin);
+
+    contracts.token.balance_of_at(&actors.passenger, &1);
+}
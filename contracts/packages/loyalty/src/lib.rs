@@ -1,13 +1,20 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Env, Symbol};
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Env, Symbol, Vec};
+use access::AccessControl;
 
 const MIN_REDEEM_POINTS: i128 = 100;
+// Redemption rate: 100 points = $1
+const POINTS_PER_UNIT: i128 = 100;
+// Trailing window of bookings that count toward tier qualification (12 months)
+const ACTIVITY_WINDOW_SECS: u64 = 365 * 86_400;
+// Earned points expire two years after they are credited
+const POINTS_EXPIRY_SECS: u64 = 2 * 365 * 86_400;
 
 #[contracttype]
 #[derive(Clone)]
 pub struct LoyaltyAccount {
     pub user: Address,
-    pub tier: Symbol, // "bronze", "silver", "gold", "platinum"
+    pub tier: Symbol, // one of the configured tiers, "bronze" to "platinum" by default
     pub total_points: i128,
     pub lifetime_bookings: u64,
     pub lifetime_spent: i128,
@@ -24,6 +31,15 @@ pub struct TierConfig {
     pub bonus_percentage: u32,  // basis points
 }
 
+// A booking counted toward the user's rolling tier activity
+#[contracttype]
+#[derive(Clone)]
+pub struct BookingActivity {
+    pub booking_id: u64,
+    pub amount: i128,
+    pub booked_at: u64,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct PointsTransaction {
@@ -33,6 +49,7 @@ pub struct PointsTransaction {
     pub transaction_type: Symbol, // "earned", "redeemed", "bonus", "expired"
     pub booking_id: Option<u64>,
     pub created_at: u64,
+    pub expires_at: u64,
 }
 
 pub struct LoyaltyStorageKey;
@@ -50,6 +67,62 @@ impl LoyaltyStorageKey {
             .set(&(symbol_short!("account"), user), account);
     }
 
+    pub fn get_booking_points(env: &Env, user: &Address, booking_id: u64) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("bk_pts"), user, booking_id))
+            .unwrap_or(0)
+    }
+
+    pub fn set_booking_points(env: &Env, user: &Address, booking_id: u64, points: i128) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("bk_pts"), user, booking_id), &points);
+    }
+
+    pub fn get_booking_contract(env: &Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("booking_c"))
+    }
+
+    pub fn set_booking_contract(env: &Env, booking_contract: &Address) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("booking_c"), booking_contract);
+    }
+
+    pub fn get_activity(env: &Env, user: &Address) -> Vec<BookingActivity> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("activity"), user))
+            .unwrap_or(Vec::new(env))
+    }
+
+    pub fn set_activity(env: &Env, user: &Address, activity: &Vec<BookingActivity>) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("activity"), user), activity);
+    }
+
+    // Unspent points batches, oldest first; `points` is what is left of each batch
+    pub fn get_points_batches(env: &Env, user: &Address) -> Vec<PointsTransaction> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("pts_batch"), user))
+            .unwrap_or(Vec::new(env))
+    }
+
+    pub fn set_points_batches(env: &Env, user: &Address, batches: &Vec<PointsTransaction>) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("pts_batch"), user), batches);
+    }
+
+    pub fn next_transaction_id(env: &Env) -> u64 {
+        let id: u64 = env.storage().instance().get(&symbol_short!("tx_id")).unwrap_or(1);
+        env.storage().instance().set(&symbol_short!("tx_id"), &(id + 1));
+        id
+    }
+
     pub fn get_tier_config(env: &Env, tier: &Symbol) -> Option<TierConfig> {
         env.storage()
             .persistent()
@@ -61,6 +134,24 @@ impl LoyaltyStorageKey {
             .persistent()
             .set(&(symbol_short!("tier"), tier), config);
     }
+
+    pub fn remove_tier_config(env: &Env, tier: &Symbol) {
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("tier"), tier));
+    }
+
+    // Configured tiers from lowest to highest `min_points`; the first is the base tier
+    pub fn get_tiers(env: &Env) -> Vec<Symbol> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("tiers"))
+            .unwrap_or(Vec::new(env))
+    }
+
+    pub fn set_tiers(env: &Env, tiers: &Vec<Symbol>) {
+        env.storage().instance().set(&symbol_short!("tiers"), tiers);
+    }
 }
 
 #[contract]
@@ -101,9 +192,12 @@ impl LoyaltyContract {
             },
         ];
 
+        let mut order = Vec::new(&env);
         for config in tiers.iter() {
             LoyaltyStorageKey::set_tier_config(&env, &config.tier, config);
+            order.push_back(config.tier.clone());
         }
+        LoyaltyStorageKey::set_tiers(&env, &order);
 
         env.events().publish(
             (symbol_short!("loyalty"), symbol_short!("init")),
@@ -115,6 +209,70 @@ impl LoyaltyContract {
         crate::upgrade_timelock::UpgradeTimelock::init_upgrade_owner(&env, &owner);
     }
 
+    // Owner who can grant the admins that manage tiers
+    pub fn init_admin(env: Env, owner: Address) {
+        AccessControl::init_owner(&env, &owner);
+    }
+
+    // Add a tier or replace an existing one's thresholds and benefits. Tiers are kept
+    // ordered by `min_points`.
+    pub fn set_tier_config(env: Env, admin: Address, config: TierConfig) {
+        AccessControl::require_admin(&env, &admin);
+        assert!(config.min_points >= 0, "Invalid min points");
+
+        let mut tiers = LoyaltyStorageKey::get_tiers(&env);
+        if let Some(i) = tiers.first_index_of(&config.tier) {
+            tiers.remove(i);
+        }
+        let mut position = tiers.len();
+        for (i, tier) in tiers.iter().enumerate() {
+            let existing =
+                LoyaltyStorageKey::get_tier_config(&env, &tier).expect("Tier config not found");
+            if existing.min_points > config.min_points {
+                position = i as u32;
+                break;
+            }
+        }
+        tiers.insert(position, config.tier.clone());
+
+        LoyaltyStorageKey::set_tier_config(&env, &config.tier, &config);
+        LoyaltyStorageKey::set_tiers(&env, &tiers);
+
+        env.events().publish(
+            (symbol_short!("tier"), symbol_short!("set")),
+            (admin, config.tier, config.min_points, config.min_bookings),
+        );
+    }
+
+    // Drop a tier. Accounts holding it move to the tier they qualify for on their next
+    // points change or `refresh_tier`. The base tier can't be removed.
+    pub fn remove_tier(env: Env, admin: Address, tier: Symbol) {
+        AccessControl::require_admin(&env, &admin);
+
+        let mut tiers = LoyaltyStorageKey::get_tiers(&env);
+        let index = tiers.first_index_of(&tier).expect("Tier not found");
+        assert!(index > 0, "Cannot remove base tier");
+        tiers.remove(index);
+
+        LoyaltyStorageKey::remove_tier_config(&env, &tier);
+        LoyaltyStorageKey::set_tiers(&env, &tiers);
+
+        env.events().publish(
+            (symbol_short!("tier"), symbol_short!("removed")),
+            (admin, tier),
+        );
+    }
+
+    pub fn get_tiers(env: Env) -> Vec<Symbol> {
+        LoyaltyStorageKey::get_tiers(&env)
+    }
+
+    fn base_tier(env: &Env) -> Symbol {
+        LoyaltyStorageKey::get_tiers(env)
+            .first()
+            .unwrap_or(symbol_short!("bronze"))
+    }
+
     // Get or create loyalty account
     pub fn get_or_create_account(env: Env, user: Address) -> LoyaltyAccount {
         if let Some(account) = LoyaltyStorageKey::get_account(&env, &user) {
@@ -122,7 +280,7 @@ impl LoyaltyContract {
         } else {
             let new_account = LoyaltyAccount {
                 user: user.clone(),
-                tier: symbol_short!("bronze"),
+                tier: Self::base_tier(&env),
                 total_points: 0,
                 lifetime_bookings: 0,
                 lifetime_spent: 0,
@@ -133,29 +291,39 @@ impl LoyaltyContract {
         }
     }
 
-    // Award points for booking
-    pub fn award_points(env: Env, user: Address, booking_amount: i128, booking_id: u64) -> i128 {
+    // Award points for booking. `caller` must be the linked booking contract or an admin.
+    pub fn award_points(
+        env: Env,
+        caller: Address,
+        user: Address,
+        booking_amount: i128,
+        booking_id: u64,
+    ) -> i128 {
+        Self::require_points_authority(&env, &caller);
         let mut account = Self::get_or_create_account(env.clone(), user.clone());
-
-        let tier_config =
-            LoyaltyStorageKey::get_tier_config(&env, &account.tier).expect("Tier config not found");
-
-        // Base points: 1 point per $1 spent
-        let base_points = booking_amount;
-
-        // Apply tier multiplier
-        let multiplier = tier_config.points_multiplier as i128;
-        let earned_points = base_points * multiplier / 100;
+        let earned_points = Self::points_for(&env, &account.tier, booking_amount);
 
         account.total_points += earned_points;
+        Self::add_batch(&env, &user, earned_points, symbol_short!("earned"), Some(booking_id));
         account.lifetime_bookings += 1;
         account.lifetime_spent += booking_amount;
 
+        let mut activity = Self::recent_activity(&env, &user);
+        activity.push_back(BookingActivity {
+            booking_id,
+            amount: booking_amount,
+            booked_at: env.ledger().timestamp(),
+        });
+        LoyaltyStorageKey::set_activity(&env, &user, &activity);
+
         // Check for tier upgrade
-        Self::check_tier_upgrade(&env, &mut account);
+        Self::check_tier_upgrade(&env, &mut account, activity.len() as u64);
 
         LoyaltyStorageKey::set_account(&env, &user, &account);
 
+        let booking_points = LoyaltyStorageKey::get_booking_points(&env, &user, booking_id);
+        LoyaltyStorageKey::set_booking_points(&env, &user, booking_id, booking_points + earned_points);
+
         env.events().publish(
             (symbol_short!("points"), symbol_short!("earned")),
             (user.clone(), env.ledger().timestamp(), earned_points, booking_id),
@@ -164,13 +332,82 @@ impl LoyaltyContract {
         earned_points
     }
 
-    // Accrue points for a passenger flight.
-    pub fn accrue_points(env: Env, passenger: Address, flight_id: Symbol, amount: i128) -> i128 {
-        passenger.require_auth();
+    // Tier the user would hold after `award_points` for a booking of `booking_amount`,
+    // without recording anything
+    pub fn tier_after_booking(env: Env, user: Address, booking_amount: i128) -> Symbol {
+        let (tier, total_points) = match LoyaltyStorageKey::get_account(&env, &user) {
+            Some(account) => (account.tier, account.total_points),
+            None => (Self::base_tier(&env), 0),
+        };
+        let earned_points = Self::points_for(&env, &tier, booking_amount);
+        let recent_bookings = Self::recent_activity(&env, &user).len() as u64 + 1;
+
+        Self::qualifying_tier(&env, total_points + earned_points, recent_bookings).unwrap_or(tier)
+    }
+
+    // Points a booking earns at `tier`: 1 point per $1 spent, scaled by the tier multiplier
+    fn points_for(env: &Env, tier: &Symbol, booking_amount: i128) -> i128 {
+        // A removed tier earns at the base rate until the account is re-tiered
+        let multiplier = LoyaltyStorageKey::get_tier_config(env, tier)
+            .map(|config| config.points_multiplier)
+            .unwrap_or(100) as i128;
+
+        booking_amount * multiplier / 100
+    }
+
+    // Booking contract allowed to credit and reverse points on its own authority
+    pub fn set_booking_contract(env: Env, admin: Address, booking_contract: Address) {
+        AccessControl::require_admin(&env, &admin);
+        LoyaltyStorageKey::set_booking_contract(&env, &booking_contract);
+    }
+
+    // Reverse the points earned for a booking (e.g. passenger no-show). `caller` must be
+    // the linked booking contract or an admin. Points already spent are not clawed back
+    // below zero.
+    pub fn reverse_points(env: Env, caller: Address, user: Address, booking_id: u64) -> i128 {
+        Self::require_points_authority(&env, &caller);
+
+        let earned = LoyaltyStorageKey::get_booking_points(&env, &user, booking_id);
+        if earned == 0 {
+            return 0;
+        }
+
+        let mut account = LoyaltyStorageKey::get_account(&env, &user).expect("Account not found");
+        let reversed = if account.total_points < earned {
+            account.total_points
+        } else {
+            earned
+        };
+
+        account.total_points -= reversed;
+        Self::deduct_batches(&env, &user, reversed);
+        Self::recompute_tier(&env, &user, &mut account);
+        LoyaltyStorageKey::set_account(&env, &user, &account);
+        LoyaltyStorageKey::set_booking_points(&env, &user, booking_id, 0);
+
+        env.events().publish(
+            (symbol_short!("points"), symbol_short!("reversed")),
+            (user, env.ledger().timestamp(), reversed, booking_id),
+        );
+
+        reversed
+    }
+
+    // Accrue points for a passenger flight. `caller` must be the linked booking contract or
+    // an admin; a passenger can't credit points to themselves.
+    pub fn accrue_points(
+        env: Env,
+        caller: Address,
+        passenger: Address,
+        flight_id: Symbol,
+        amount: i128,
+    ) -> i128 {
+        Self::require_points_authority(&env, &caller);
         assert!(amount > 0, "Invalid points amount");
 
         let mut account = Self::get_or_create_account(env.clone(), passenger.clone());
         account.total_points += amount;
+        Self::add_batch(&env, &passenger, amount, symbol_short!("bonus"), None);
         LoyaltyStorageKey::set_account(&env, &passenger, &account);
 
         env.events().publish(
@@ -181,6 +418,49 @@ impl LoyaltyContract {
         amount
     }
 
+    // Only the linked booking contract or an admin may credit or reverse booking points
+    fn require_points_authority(env: &Env, caller: &Address) {
+        if LoyaltyStorageKey::get_booking_contract(env).as_ref() == Some(caller) {
+            caller.require_auth();
+        } else {
+            AccessControl::require_admin(env, caller);
+        }
+    }
+
+    // Manual correction or goodwill credit by an admin. Credits are recorded as a "bonus"
+    // batch; debits come out of the oldest batches and can't take the balance below zero.
+    // The tier is re-evaluated either way.
+    pub fn adjust_points(
+        env: Env,
+        admin: Address,
+        user: Address,
+        delta: i128,
+        reason: Symbol,
+    ) -> i128 {
+        AccessControl::require_admin(&env, &admin);
+        assert!(delta != 0, "Invalid points amount");
+
+        let mut account = Self::get_or_create_account(env.clone(), user.clone());
+        let applied = if delta > 0 {
+            Self::add_batch(&env, &user, delta, symbol_short!("bonus"), None);
+            delta
+        } else {
+            let debit = (-delta).min(account.total_points);
+            Self::deduct_batches(&env, &user, debit);
+            -debit
+        };
+        account.total_points += applied;
+        Self::recompute_tier(&env, &user, &mut account);
+        LoyaltyStorageKey::set_account(&env, &user, &account);
+
+        env.events().publish(
+            (symbol_short!("points"), symbol_short!("adjusted")),
+            (user, env.ledger().timestamp(), applied, reason),
+        );
+
+        applied
+    }
+
     // Redeem points for discount
     pub fn redeem_points(env: Env, user: Address, points: i128) -> i128 {
         user.require_auth();
@@ -194,10 +474,11 @@ impl LoyaltyContract {
         assert!(account.total_points >= points, "Insufficient points");
         assert!(points > 0, "Invalid points amount");
 
-        // Conversion rate: 100 points = $1
-        let discount = points / 100;
+        let discount = points / POINTS_PER_UNIT;
 
         account.total_points -= points;
+        Self::deduct_batches(&env, &user, points);
+        Self::recompute_tier(&env, &user, &mut account);
         LoyaltyStorageKey::set_account(&env, &user, &account);
 
         env.events().publish(
@@ -208,39 +489,220 @@ impl LoyaltyContract {
         discount
     }
 
-    fn check_tier_upgrade(env: &Env, account: &mut LoyaltyAccount) {
-        let tiers = [
-            symbol_short!("platinum"),
-            symbol_short!("gold"),
-            symbol_short!("silver"),
-            symbol_short!("bronze"),
-        ];
+    // Largest discount the user's balance can take off `booking_price`, and the points it
+    // would consume. Only whole units are redeemed, so leftover points are not spent.
+    pub fn redemption_value(env: Env, user: Address, booking_price: i128) -> (i128, i128) {
+        let balance = LoyaltyStorageKey::get_account(&env, &user)
+            .map(|account| account.total_points)
+            .unwrap_or(0);
+
+        let max_discount = (balance / POINTS_PER_UNIT).min(booking_price.max(0));
+        let points_required = max_discount * POINTS_PER_UNIT;
+        if points_required < MIN_REDEEM_POINTS {
+            return (0, 0);
+        }
+        (max_discount, points_required)
+    }
+
+    // Zero out the user's points batches past their expiry and re-evaluate the tier.
+    // Callable by anyone, e.g. a keeper sweeping inactive accounts.
+    pub fn expire_points(env: Env, user: Address) -> i128 {
+        let now = env.ledger().timestamp();
+        let mut expired = 0i128;
+        let mut remaining = Vec::new(&env);
+        for batch in LoyaltyStorageKey::get_points_batches(&env, &user).iter() {
+            if batch.expires_at <= now {
+                expired += batch.points;
+            } else {
+                remaining.push_back(batch);
+            }
+        }
+        if expired == 0 {
+            return 0;
+        }
+        LoyaltyStorageKey::set_points_batches(&env, &user, &remaining);
+
+        let mut account = LoyaltyStorageKey::get_account(&env, &user).expect("Account not found");
+        let expired = expired.min(account.total_points);
+        account.total_points -= expired;
+        Self::recompute_tier(&env, &user, &mut account);
+        LoyaltyStorageKey::set_account(&env, &user, &account);
+
+        env.events().publish(
+            (symbol_short!("points"), symbol_short!("expired")),
+            (user, now, expired),
+        );
 
-        for tier in tiers.iter() {
+        expired
+    }
+
+    pub fn get_points_batches(env: Env, user: Address) -> Vec<PointsTransaction> {
+        LoyaltyStorageKey::get_points_batches(&env, &user)
+    }
+
+    fn add_batch(
+        env: &Env,
+        user: &Address,
+        points: i128,
+        transaction_type: Symbol,
+        booking_id: Option<u64>,
+    ) {
+        if points <= 0 {
+            return;
+        }
+        let now = env.ledger().timestamp();
+        let mut batches = LoyaltyStorageKey::get_points_batches(env, user);
+        batches.push_back(PointsTransaction {
+            transaction_id: LoyaltyStorageKey::next_transaction_id(env),
+            user: user.clone(),
+            points,
+            transaction_type,
+            booking_id,
+            created_at: now,
+            expires_at: now + POINTS_EXPIRY_SECS,
+        });
+        LoyaltyStorageKey::set_points_batches(env, user, &batches);
+    }
+
+    // Spend points from the oldest batches first, so the points kept are the last to expire
+    fn deduct_batches(env: &Env, user: &Address, points: i128) {
+        let mut left = points;
+        let mut remaining = Vec::new(env);
+        for mut batch in LoyaltyStorageKey::get_points_batches(env, user).iter() {
+            if left >= batch.points {
+                left -= batch.points;
+                continue;
+            }
+            batch.points -= left;
+            left = 0;
+            remaining.push_back(batch);
+        }
+        LoyaltyStorageKey::set_points_batches(env, user, &remaining);
+    }
+
+    // Bookings still inside the activity window; older entries are dropped
+    fn recent_activity(env: &Env, user: &Address) -> Vec<BookingActivity> {
+        let now = env.ledger().timestamp();
+        let mut recent = Vec::new(env);
+        for entry in LoyaltyStorageKey::get_activity(env, user).iter() {
+            if entry.booked_at + ACTIVITY_WINDOW_SECS > now {
+                recent.push_back(entry);
+            }
+        }
+        recent
+    }
+
+    // Bookings and spend within the trailing activity window, used for tier qualification.
+    // Unlike the lifetime totals these fall as old bookings age out.
+    pub fn qualifying_activity(env: Env, user: Address) -> (u64, i128) {
+        let activity = Self::recent_activity(&env, &user);
+        let mut spent = 0i128;
+        for entry in activity.iter() {
+            spent += entry.amount;
+        }
+        (activity.len() as u64, spent)
+    }
+
+    // Re-evaluate a user's tier against their current activity window
+    pub fn refresh_tier(env: Env, user: Address) -> Symbol {
+        let mut account = LoyaltyStorageKey::get_account(&env, &user).expect("Account not found");
+        Self::recompute_tier(&env, &user, &mut account);
+        LoyaltyStorageKey::set_account(&env, &user, &account);
+        account.tier
+    }
+
+    // Prune the activity window and move the tier up or down to match it
+    fn recompute_tier(env: &Env, user: &Address, account: &mut LoyaltyAccount) {
+        let activity = Self::recent_activity(env, user);
+        LoyaltyStorageKey::set_activity(env, user, &activity);
+        Self::check_tier_upgrade(env, account, activity.len() as u64);
+    }
+
+    fn check_tier_upgrade(env: &Env, account: &mut LoyaltyAccount, recent_bookings: u64) {
+        let tier = match Self::qualifying_tier(env, account.total_points, recent_bookings) {
+            Some(tier) => tier,
+            None => return,
+        };
+
+        if account.tier != tier {
+            // Tiers are checked from the top, so anything above the match is lost
+            let tiers = LoyaltyStorageKey::get_tiers(env);
+            let current = tiers.first_index_of(&account.tier).unwrap_or(0);
+            let change = if tiers.first_index_of(&tier).unwrap_or(0) < current {
+                symbol_short!("downgrade")
+            } else {
+                symbol_short!("upgrade")
+            };
+            account.tier = tier.clone();
+            account.tier_updated_at = env.ledger().timestamp();
+
+            env.events().publish(
+                (symbol_short!("tier"), change),
+                (account.user.clone(), env.ledger().timestamp(), tier),
+            );
+        }
+    }
+
+    // Highest configured tier whose point and booking thresholds are both met
+    fn qualifying_tier(env: &Env, total_points: i128, recent_bookings: u64) -> Option<Symbol> {
+        for tier in LoyaltyStorageKey::get_tiers(env).iter().rev() {
             let config =
-                LoyaltyStorageKey::get_tier_config(env, tier).expect("Tier config not found");
-
-            if account.total_points >= config.min_points
-                && account.lifetime_bookings >= config.min_bookings
-            {
-                if account.tier != *tier {
-                    account.tier = tier.clone();
-                    account.tier_updated_at = env.ledger().timestamp();
-
-                    env.events().publish(
-                        (symbol_short!("tier"), symbol_short!("upgrade")),
-                        (account.user.clone(), env.ledger().timestamp(), tier.clone()),
-                    );
-                }
-                break;
+                LoyaltyStorageKey::get_tier_config(env, &tier).expect("Tier config not found");
+
+            if total_points >= config.min_points && recent_bookings >= config.min_bookings {
+                return Some(tier);
             }
         }
+        None
+    }
+
+    fn next_tier(env: &Env, tier: &Symbol) -> Option<Symbol> {
+        let tiers = LoyaltyStorageKey::get_tiers(env);
+        let index = tiers.first_index_of(tier).unwrap_or(0);
+        tiers.get(index + 1)
     }
 
     pub fn get_account(env: Env, user: Address) -> Option<LoyaltyAccount> {
         LoyaltyStorageKey::get_account(&env, &user)
     }
 
+    // Gap between the user's account and the next tier: (next tier, points needed, bookings needed).
+    // Returns ("none", 0, 0) when the user is already at the top tier.
+    pub fn points_to_next_tier(env: Env, user: Address) -> (Symbol, i128, u64) {
+        let (tier, total_points) = match LoyaltyStorageKey::get_account(&env, &user) {
+            Some(account) => (account.tier, account.total_points),
+            None => (Self::base_tier(&env), 0),
+        };
+        let recent_bookings = Self::recent_activity(&env, &user).len() as u64;
+
+        let next = match Self::next_tier(&env, &tier) {
+            Some(next) => next,
+            None => return (symbol_short!("none"), 0, 0),
+        };
+
+        let config =
+            LoyaltyStorageKey::get_tier_config(&env, &next).expect("Tier config not found");
+
+        let points_needed = if config.min_points > total_points {
+            config.min_points - total_points
+        } else {
+            0
+        };
+        let bookings_needed = config.min_bookings.saturating_sub(recent_bookings);
+
+        (next, points_needed, bookings_needed)
+    }
+
+    // Fee rebate for the user's tier, in basis points. Users without an account get none.
+    pub fn get_fee_rebate_bps(env: Env, user: Address) -> u32 {
+        match LoyaltyStorageKey::get_account(&env, &user) {
+            Some(account) => LoyaltyStorageKey::get_tier_config(&env, &account.tier)
+                .map(|config| config.bonus_percentage)
+                .unwrap_or(0),
+            None => 0,
+        }
+    }
+
     pub fn get_tier_benefits(env: Env, tier: Symbol) -> Option<TierConfig> {
         LoyaltyStorageKey::get_tier_config(&env, &tier)
     }
@@ -1,13 +1,29 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, Address, Bytes, BytesN, Env, Symbol, contractclient
+    contract, contractimpl, contracttype, symbol_short, token, xdr::ToXdr, Address, Bytes, BytesN,
+    Env, Symbol, Vec, contractclient
 };
 use access::{AccessControl, Role};
+use ed25519_dalek::{Signature, VerifyingKey};
+
+// How far ahead of the ledger clock a report timestamp may be
+const MAX_REPORT_CLOCK_SKEW_SECS: u64 = 60;
+// How long after a flight's arrival reports are still accepted, unless configured otherwise
+const DEFAULT_REPORT_GRACE_SECS: u64 = 2 * 60 * 60;
+const MAX_PAGE_SIZE: u32 = 50;
+// How long after deregistering a provider's unsettled reports stop holding its stake
+const SETTLEMENT_WINDOW_SECS: u64 = 30 * 24 * 60 * 60;
 
 #[contractclient(name = "BookingClient")]
 pub trait BookingInterface {
     fn oracle_release_payment(env: Env, oracle: Address, booking_id: u64);
-    fn oracle_refund_airline_cancel(env: Env, oracle: Address, booking_id: u64);
+    fn oracle_refund_airline_cancel(env: Env, oracle: Address, booking_id: u64) -> bool;
+}
+
+// Governance parameter registry; `min_stake` is the protocol-wide stake floor in TRQ.
+#[contractclient(name = "ParamRegistryClient")]
+pub trait ParamRegistryInterface {
+    fn get_protocol_param(env: Env, key: Symbol) -> Option<i128>;
 }
 
 
@@ -18,6 +34,7 @@ pub struct OracleProvider {
     pub stake: i128,
     pub registered_at: u64,
     pub slashed: bool,
+    pub public_key: BytesN<32>, // Ed25519 key the provider signs reports with
 }
 
 #[contracttype]
@@ -25,8 +42,15 @@ pub struct OracleProvider {
 pub struct OracleConfig {
     pub admin: Address,
     pub min_stake: i128,
-    pub consensus_threshold: u32,
+    // Accumulated provider stake a status needs before it can settle a booking
+    pub stake_threshold: i128,
     pub booking_contract: Address,
+    // Reports dated more than this many seconds before the ledger time are rejected
+    pub max_report_age_secs: u64,
+    // Token providers stake in; held by this contract until withdrawn
+    pub token: Address,
+    // Reports dated more than this many seconds after a flight's arrival are rejected
+    pub report_grace_secs: u64,
 }
 
 #[contracttype]
@@ -37,7 +61,31 @@ pub struct FlightStatusReport {
     pub status: Symbol,
     pub provider: Address,
     pub timestamp: u64,
-    pub proof: BytesN<32>,
+    pub signature: BytesN<64>,
+}
+
+// Cumulative amount of one fee category paid to the treasury in one token
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeTotal {
+    pub category: Symbol,
+    pub token: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeReport {
+    pub totals: Vec<FeeTotal>,
+}
+
+// Locates a tally a provider has contributed to, so it can be revoked on slash
+#[contracttype]
+#[derive(Clone)]
+pub struct ReportRef {
+    pub flight_number: Symbol,
+    pub booking_id: u64,
+    pub status: Symbol,
 }
 
 pub struct OracleStorage;
@@ -59,6 +107,109 @@ impl OracleStorage {
             .persistent()
             .set(&(symbol_short!("prov"), addr), prov);
     }
+    pub fn remove_provider(env: &Env, addr: &Address) {
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("prov"), addr));
+    }
+    // Registered provider addresses in registration order, for enumeration
+    pub fn get_provider_index(env: &Env) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&symbol_short!("prov_idx"))
+            .unwrap_or(Vec::new(env))
+    }
+    pub fn set_provider_index(env: &Env, index: &Vec<Address>) {
+        env.storage()
+            .persistent()
+            .set(&symbol_short!("prov_idx"), index);
+    }
+    // Deregistered providers keep their record here until the stake is withdrawn
+    pub fn get_exited_provider(env: &Env, addr: &Address) -> Option<OracleProvider> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("exited"), addr))
+    }
+    pub fn set_exited_provider(env: &Env, addr: &Address, prov: &OracleProvider) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("exited"), addr), prov);
+    }
+    pub fn get_exit_time(env: &Env, addr: &Address) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("exit_at"), addr))
+    }
+    pub fn set_exit_time(env: &Env, addr: &Address, ts: u64) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("exit_at"), addr), &ts);
+    }
+    pub fn remove_exit_time(env: &Env, addr: &Address) {
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("exit_at"), addr));
+    }
+    pub fn remove_exited_provider(env: &Env, addr: &Address) {
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("exited"), addr));
+    }
+    // Status a booking settled on through consensus
+    pub fn get_settled_status(env: &Env, flight_number: &Symbol, booking_id: u64) -> Option<Symbol> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("settled"), flight_number, booking_id))
+    }
+    pub fn set_settled_status(env: &Env, flight_number: &Symbol, booking_id: u64, status: &Symbol) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("settled"), flight_number, booking_id), status);
+    }
+    pub fn get_flight_arrival(env: &Env, flight_number: &Symbol) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("arrival"), flight_number))
+    }
+    pub fn set_flight_arrival(env: &Env, flight_number: &Symbol, arrival_time: u64) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("arrival"), flight_number), &arrival_time);
+    }
+    pub fn get_fee_totals(env: &Env) -> Vec<FeeTotal> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("fee_tot"))
+            .unwrap_or(Vec::new(env))
+    }
+    // Add `amount` to the running total for (category, token)
+    pub fn add_fee(env: &Env, category: Symbol, token: &Address, amount: i128) {
+        let mut totals = Self::get_fee_totals(env);
+        let mut found = false;
+        for i in 0..totals.len() {
+            let mut total = totals.get(i).unwrap();
+            if total.category == category && total.token == *token {
+                total.amount += amount;
+                totals.set(i, total);
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            totals.push_back(FeeTotal {
+                category,
+                token: token.clone(),
+                amount,
+            });
+        }
+        env.storage().instance().set(&symbol_short!("fee_tot"), &totals);
+    }
+    pub fn get_treasury(env: &Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("treasury"))
+    }
+    pub fn set_treasury(env: &Env, treasury: &Address) {
+        env.storage().instance().set(&symbol_short!("treasury"), treasury);
+    }
     pub fn status_count(
         env: &Env,
         flight_number: &Symbol,
@@ -77,6 +228,64 @@ impl OracleStorage {
             &(c + 1),
         );
     }
+    pub fn get_param_registry(env: &Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("registry"))
+    }
+    pub fn set_param_registry(env: &Env, registry: &Address) {
+        env.storage().instance().set(&symbol_short!("registry"), registry);
+    }
+    pub fn status_stake(
+        env: &Env,
+        flight_number: &Symbol,
+        booking_id: u64,
+        status: &Symbol,
+    ) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("stk"), flight_number, booking_id, status))
+            .unwrap_or(0i128)
+    }
+    pub fn add_status_stake(
+        env: &Env,
+        flight_number: &Symbol,
+        booking_id: u64,
+        status: &Symbol,
+        stake: i128,
+    ) {
+        let total = Self::status_stake(env, flight_number, booking_id, status);
+        env.storage().persistent().set(
+            &(symbol_short!("stk"), flight_number, booking_id, status),
+            &(total + stake),
+        );
+    }
+    pub fn dec_status_count(env: &Env, flight_number: &Symbol, booking_id: u64, status: &Symbol) {
+        let c = Self::status_count(env, flight_number, booking_id, status);
+        env.storage().persistent().set(
+            &(symbol_short!("cnt"), flight_number, booking_id, status),
+            &c.saturating_sub(1),
+        );
+    }
+    pub fn has_reported(env: &Env, provider: &Address, flight_number: &Symbol, booking_id: u64) -> bool {
+        env.storage()
+            .persistent()
+            .has(&(symbol_short!("reported"), provider, flight_number, booking_id))
+    }
+    pub fn set_reported(env: &Env, provider: &Address, flight_number: &Symbol, booking_id: u64) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("reported"), provider, flight_number, booking_id), &true);
+    }
+    pub fn get_provider_reports(env: &Env, provider: &Address) -> Vec<ReportRef> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("p_reps"), provider))
+            .unwrap_or(Vec::new(env))
+    }
+    pub fn set_provider_reports(env: &Env, provider: &Address, reports: &Vec<ReportRef>) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("p_reps"), provider), reports);
+    }
     pub fn get_report(
         env: &Env,
         flight_number: &Symbol,
@@ -116,8 +325,10 @@ impl FlightOracle {
         env: Env,
         owner: Address,
         min_stake: i128,
-        consensus_threshold: u32,
+        stake_threshold: i128,
         booking_contract: Address,
+        max_report_age_secs: u64,
+        token: Address,
     ) {
         owner.require_auth();
         assert!(
@@ -129,41 +340,298 @@ impl FlightOracle {
         crate::upgrade_timelock::UpgradeTimelock::init_upgrade_owner(&env, &owner);
 
         assert!(min_stake > 0, "Invalid min_stake");
-        assert!(consensus_threshold > 0, "Invalid threshold");
+        assert!(stake_threshold > 0, "Invalid threshold");
+        assert!(max_report_age_secs > 0, "Invalid max report age");
         let cfg = OracleConfig {
             admin: owner.clone(),
             min_stake,
-            consensus_threshold,
+            stake_threshold,
             booking_contract,
+            max_report_age_secs,
+            token,
+            report_grace_secs: DEFAULT_REPORT_GRACE_SECS,
         };
         OracleStorage::set_config(&env, &cfg);
         env.events().publish(
             (symbol_short!("oracle"), symbol_short!("init")),
-            (owner, min_stake, consensus_threshold),
+            (owner, min_stake, stake_threshold),
         );
     }
 
-    pub fn register_oracle_provider(env: Env, admin: Address, provider: Address, stake: i128) {
+    pub fn register_oracle_provider(
+        env: Env,
+        admin: Address,
+        provider: Address,
+        stake: i128,
+        public_key: BytesN<32>,
+    ) {
         AccessControl::require_admin(&env, &admin);
+        // The provider authorizes locking its stake in this contract
+        provider.require_auth();
         let cfg = OracleStorage::get_config(&env).expect("Not initialized");
-        assert!(stake >= cfg.min_stake, "Insufficient stake");
+        assert!(stake >= Self::stake_floor(&env, cfg.min_stake), "Insufficient stake");
         assert!(
-            OracleStorage::get_provider(&env, &provider).is_none(),
+            OracleStorage::get_provider(&env, &provider).is_none()
+                && OracleStorage::get_exited_provider(&env, &provider).is_none(),
             "Already registered"
         );
+        token::Client::new(&env, &cfg.token).transfer(
+            &provider,
+            &env.current_contract_address(),
+            &stake,
+        );
         let prov = OracleProvider {
             address: provider.clone(),
             stake,
             registered_at: env.ledger().timestamp(),
             slashed: false,
+            public_key,
         };
         OracleStorage::set_provider(&env, &provider, &prov);
+        let mut index = OracleStorage::get_provider_index(&env);
+        index.push_back(provider.clone());
+        OracleStorage::set_provider_index(&env, &index);
         env.events().publish(
             (symbol_short!("oracle"), symbol_short!("provider")),
             (provider, stake),
         );
     }
 
+    // Remove a provider. Its reports keep counting, and its stake stays locked
+    // (and slashable) until withdrawn via `withdraw_stake`.
+    pub fn deregister_oracle_provider(env: Env, admin: Address, provider: Address) {
+        AccessControl::require_admin(&env, &admin);
+        let prov = OracleStorage::get_provider(&env, &provider).expect("Provider not registered");
+
+        OracleStorage::remove_provider(&env, &provider);
+        OracleStorage::set_exited_provider(&env, &provider, &prov);
+        OracleStorage::set_exit_time(&env, &provider, env.ledger().timestamp());
+        let mut index = OracleStorage::get_provider_index(&env);
+        if let Some(i) = index.first_index_of(&provider) {
+            index.remove(i);
+        }
+        OracleStorage::set_provider_index(&env, &index);
+
+        env.events().publish(
+            (symbol_short!("oracle"), symbol_short!("dereg")),
+            (provider, prov.stake),
+        );
+    }
+
+    // Return a deregistered provider's stake once every booking it reported on has settled,
+    // or once SETTLEMENT_WINDOW_SECS have passed since it deregistered so a booking that
+    // never reaches consensus can't lock the stake forever. Providers deregistered before
+    // exit times were recorded count the window from their registration.
+    pub fn withdraw_stake(env: Env, provider: Address) -> i128 {
+        provider.require_auth();
+        let cfg = OracleStorage::get_config(&env).expect("Not initialized");
+        let prov =
+            OracleStorage::get_exited_provider(&env, &provider).expect("Provider not deregistered");
+        assert!(!prov.slashed, "Provider slashed");
+
+        let exited_at =
+            OracleStorage::get_exit_time(&env, &provider).unwrap_or(prov.registered_at);
+        let window_passed =
+            env.ledger().timestamp() >= exited_at.saturating_add(SETTLEMENT_WINDOW_SECS);
+        if !window_passed {
+            let reports = OracleStorage::get_provider_reports(&env, &provider);
+            for r in reports.iter() {
+                assert!(
+                    OracleStorage::get_settled_status(&env, &r.flight_number, r.booking_id)
+                        .is_some(),
+                    "Reports pending consensus"
+                );
+            }
+        }
+
+        OracleStorage::remove_exited_provider(&env, &provider);
+        OracleStorage::remove_exit_time(&env, &provider);
+        OracleStorage::set_provider_reports(&env, &provider, &Vec::new(&env));
+        token::Client::new(&env, &cfg.token).transfer(
+            &env.current_contract_address(),
+            &provider,
+            &prov.stake,
+        );
+
+        env.events().publish(
+            (symbol_short!("oracle"), symbol_short!("withdrawn")),
+            (provider, prov.stake),
+        );
+        prov.stake
+    }
+
+    // Governance registry providing the protocol-wide minimum stake
+    pub fn set_param_registry(env: Env, admin: Address, registry: Address) {
+        AccessControl::require_admin(&env, &admin);
+        OracleStorage::set_param_registry(&env, &registry);
+        env.events().publish(
+            (symbol_short!("oracle"), symbol_short!("registry")),
+            (admin, registry),
+        );
+    }
+
+    // The protocol minimum stake applies beneath the oracle's own min_stake
+    fn stake_floor(env: &Env, min_stake: i128) -> i128 {
+        let floor = match OracleStorage::get_param_registry(env) {
+            Some(registry) => ParamRegistryClient::new(env, &registry)
+                .get_protocol_param(&symbol_short!("min_stake"))
+                .unwrap_or(0),
+            None => 0,
+        };
+        min_stake.max(floor)
+    }
+
+    pub fn set_report_grace_secs(env: Env, admin: Address, grace_secs: u64) {
+        AccessControl::require_admin(&env, &admin);
+        let mut cfg = OracleStorage::get_config(&env).expect("Not initialized");
+        cfg.report_grace_secs = grace_secs;
+        OracleStorage::set_config(&env, &cfg);
+        env.events().publish(
+            (symbol_short!("oracle"), symbol_short!("grace")),
+            (admin, grace_secs),
+        );
+    }
+
+    // Scheduled arrival of a flight; reports for it must be dated within the grace period after
+    pub fn set_flight_arrival(env: Env, operator: Address, flight_number: Symbol, arrival_time: u64) {
+        AccessControl::require_operator(&env, &operator);
+        OracleStorage::set_flight_arrival(&env, &flight_number, arrival_time);
+        env.events().publish(
+            (symbol_short!("oracle"), symbol_short!("arrival")),
+            (flight_number, arrival_time),
+        );
+    }
+
+    // Treasury that receives slashed provider stake
+    pub fn set_treasury(env: Env, admin: Address, treasury: Address) {
+        AccessControl::require_admin(&env, &admin);
+        OracleStorage::set_treasury(&env, &treasury);
+        env.events().publish(
+            (symbol_short!("oracle"), symbol_short!("treasury")),
+            (admin, treasury),
+        );
+    }
+
+    // Slash a provider that reported a status other than the one the booking settled on.
+    // Its stake goes to the treasury and its reports stop counting toward consensus.
+    pub fn slash_provider(
+        env: Env,
+        admin: Address,
+        provider: Address,
+        flight_number: Symbol,
+        booking_id: u64,
+    ) {
+        AccessControl::require_admin(&env, &admin);
+        let cfg = OracleStorage::get_config(&env).expect("Not initialized");
+        let treasury = OracleStorage::get_treasury(&env).expect("Treasury not set");
+        let settled = OracleStorage::get_settled_status(&env, &flight_number, booking_id)
+            .expect("No consensus reached");
+
+        let mut reported_minority = false;
+        for r in OracleStorage::get_provider_reports(&env, &provider).iter() {
+            if r.flight_number == flight_number && r.booking_id == booking_id && r.status != settled {
+                reported_minority = true;
+                break;
+            }
+        }
+        assert!(reported_minority, "Did not report minority");
+
+        let registered = OracleStorage::get_provider(&env, &provider);
+        let is_registered = registered.is_some();
+        let mut prov = registered
+            .or_else(|| OracleStorage::get_exited_provider(&env, &provider))
+            .expect("Provider not registered");
+        assert!(!prov.slashed, "Provider slashed");
+
+        prov.slashed = true;
+        if is_registered {
+            OracleStorage::set_provider(&env, &provider, &prov);
+        } else {
+            OracleStorage::set_exited_provider(&env, &provider, &prov);
+        }
+        let revoked = Self::invalidate_provider_reports(&env, &prov);
+        token::Client::new(&env, &cfg.token).transfer(
+            &env.current_contract_address(),
+            &treasury,
+            &prov.stake,
+        );
+        OracleStorage::add_fee(&env, symbol_short!("slashing"), &cfg.token, prov.stake);
+
+        env.events().publish(
+            (symbol_short!("oracle"), symbol_short!("slashed")),
+            (provider, prov.stake, revoked),
+        );
+    }
+
+    fn invalidate_provider_reports(env: &Env, provider: &OracleProvider) -> u32 {
+        let reports = OracleStorage::get_provider_reports(env, &provider.address);
+        for r in reports.iter() {
+            OracleStorage::dec_status_count(env, &r.flight_number, r.booking_id, &r.status);
+            OracleStorage::add_status_stake(
+                env,
+                &r.flight_number,
+                r.booking_id,
+                &r.status,
+                -provider.stake,
+            );
+        }
+        OracleStorage::set_provider_reports(env, &provider.address, &Vec::new(env));
+        reports.len()
+    }
+
+    // Cumulative amounts this contract has paid to the treasury, by category and token
+    pub fn fee_report(env: Env) -> FeeReport {
+        FeeReport {
+            totals: OracleStorage::get_fee_totals(&env),
+        }
+    }
+
+    pub fn get_provider(env: Env, provider: Address) -> Option<OracleProvider> {
+        OracleStorage::get_provider(&env, &provider)
+    }
+
+    // Registered providers in registration order; `limit` is capped at MAX_PAGE_SIZE
+    pub fn list_providers(env: Env, start: u32, limit: u32) -> Vec<OracleProvider> {
+        let limit = if limit > MAX_PAGE_SIZE { MAX_PAGE_SIZE } else { limit };
+        let index = OracleStorage::get_provider_index(&env);
+
+        let mut result = Vec::new(&env);
+        let mut i = start;
+        while i < index.len() && result.len() < limit {
+            if let Some(prov) = OracleStorage::get_provider(&env, &index.get(i).unwrap()) {
+                result.push_back(prov);
+            }
+            i += 1;
+        }
+        result
+    }
+
+    pub fn get_status_count(env: Env, flight_number: Symbol, booking_id: u64, status: Symbol) -> u32 {
+        OracleStorage::status_count(&env, &flight_number, booking_id, &status)
+    }
+
+    pub fn get_status_stake(env: Env, flight_number: Symbol, booking_id: u64, status: Symbol) -> i128 {
+        OracleStorage::status_stake(&env, &flight_number, booking_id, &status)
+    }
+
+    // A status settles once its accumulated stake meets the threshold and outweighs the
+    // stake behind the opposing status.
+    fn require_stake_consensus(
+        env: &Env,
+        cfg: &OracleConfig,
+        flight_number: &Symbol,
+        booking_id: u64,
+        status: &Symbol,
+        opposing: &Symbol,
+    ) {
+        let stake = OracleStorage::status_stake(env, flight_number, booking_id, status);
+        let opposing_stake = OracleStorage::status_stake(env, flight_number, booking_id, opposing);
+        assert!(
+            stake >= cfg.stake_threshold && stake > opposing_stake,
+            "Insufficient consensus"
+        );
+    }
+
     pub fn submit_flight_status(
         env: Env,
         provider: Address,
@@ -171,24 +639,39 @@ impl FlightOracle {
         booking_id: u64,
         status: Symbol,
         timestamp: u64,
-        proof: BytesN<32>,
+        signature: BytesN<64>,
     ) {
         provider.require_auth();
         let prov = OracleStorage::get_provider(&env, &provider).expect("Provider not registered");
         assert!(!prov.slashed, "Provider slashed");
+        // A provider's stake counts once per booking, whatever it reports
+        assert!(
+            !OracleStorage::has_reported(&env, &provider, &flight_number, booking_id),
+            "Already reported"
+        );
 
-        let mut msg = Bytes::new(&env);
-        let booking_bytes = booking_id.to_be_bytes();
-        for b in booking_bytes.iter() {
-            msg.push_back(*b);
-        }
-        let ts_bytes = timestamp.to_be_bytes();
-        for b in ts_bytes.iter() {
-            msg.push_back(*b);
+        let cfg = OracleStorage::get_config(&env).expect("Not initialized");
+        let now = env.ledger().timestamp();
+        assert!(
+            timestamp >= now.saturating_sub(cfg.max_report_age_secs),
+            "Stale report"
+        );
+        assert!(
+            timestamp <= now.saturating_add(MAX_REPORT_CLOCK_SKEW_SECS),
+            "Report timestamp in the future"
+        );
+        if let Some(arrival) = OracleStorage::get_flight_arrival(&env, &flight_number) {
+            assert!(
+                timestamp <= arrival.saturating_add(cfg.report_grace_secs),
+                "Stale report"
+            );
         }
 
-        let computed: BytesN<32> = env.crypto().keccak256(&msg).into();
-        assert!(computed == proof, "Invalid proof");
+        let msg = Self::report_message(&env, &flight_number, booking_id, &status, timestamp);
+        assert!(
+            Self::signature_valid(&prov.public_key, &msg, &signature),
+            "Invalid signature"
+        );
 
         let report = FlightStatusReport {
             flight_number: flight_number.clone(),
@@ -196,10 +679,20 @@ impl FlightOracle {
             status: status.clone(),
             provider: provider.clone(),
             timestamp,
-            proof,
+            signature,
         };
         OracleStorage::add_report(&env, &flight_number, booking_id, &report);
+        OracleStorage::set_reported(&env, &provider, &flight_number, booking_id);
         OracleStorage::inc_status_count(&env, &flight_number, booking_id, &status);
+        OracleStorage::add_status_stake(&env, &flight_number, booking_id, &status, prov.stake);
+
+        let mut reports = OracleStorage::get_provider_reports(&env, &provider);
+        reports.push_back(ReportRef {
+            flight_number: flight_number.clone(),
+            booking_id,
+            status: status.clone(),
+        });
+        OracleStorage::set_provider_reports(&env, &provider, &reports);
 
         env.events().publish(
             (symbol_short!("oracle"), symbol_short!("status")),
@@ -207,16 +700,60 @@ impl FlightOracle {
         );
     }
 
+    // Message a provider signs off-chain: XDR of (oracle, flight_number, booking_id, status,
+    // timestamp), where `oracle` is this contract's address so a signed report can't be
+    // replayed on another oracle deployment
+    fn report_message(
+        env: &Env,
+        flight_number: &Symbol,
+        booking_id: u64,
+        status: &Symbol,
+        timestamp: u64,
+    ) -> Bytes {
+        (
+            env.current_contract_address(),
+            flight_number.clone(),
+            booking_id,
+            status.clone(),
+            timestamp,
+        )
+            .to_xdr(env)
+    }
+
+    // Checked here rather than with `env.crypto().ed25519_verify`, which traps the whole
+    // invocation instead of letting the report be rejected with a reason
+    fn signature_valid(public_key: &BytesN<32>, msg: &Bytes, signature: &BytesN<64>) -> bool {
+        let key = match VerifyingKey::from_bytes(&public_key.to_array()) {
+            Ok(key) => key,
+            Err(_) => return false,
+        };
+        let mut buf = [0u8; 512];
+        let len = msg.len() as usize;
+        if len > buf.len() {
+            return false;
+        }
+        msg.copy_into_slice(&mut buf[..len]);
+        key.verify_strict(&buf[..len], &Signature::from_bytes(&signature.to_array()))
+            .is_ok()
+    }
+
     pub fn verify_flight_completion(env: Env, flight_number: Symbol, booking_id: u64) {
         let cfg = OracleStorage::get_config(&env).expect("Not initialized");
         let status = symbol_short!("completed");
-        let count = OracleStorage::status_count(&env, &flight_number, booking_id, &status);
-        assert!(count >= cfg.consensus_threshold, "Insufficient consensus");
+        Self::require_stake_consensus(
+            &env,
+            &cfg,
+            &flight_number,
+            booking_id,
+            &status,
+            &symbol_short!("cancelled"),
+        );
 
         let booking_client =
             BookingClient::new(&env, &cfg.booking_contract);
         let self_addr = env.current_contract_address();
         booking_client.oracle_release_payment(&self_addr, &booking_id);
+        OracleStorage::set_settled_status(&env, &flight_number, booking_id, &status);
 
         env.events().publish(
             (symbol_short!("oracle"), symbol_short!("settled")),
@@ -227,16 +764,28 @@ impl FlightOracle {
     pub fn verify_airline_cancellation(env: Env, flight_number: Symbol, booking_id: u64) {
         let cfg = OracleStorage::get_config(&env).expect("Not initialized");
         let status = symbol_short!("cancelled");
-        let count = OracleStorage::status_count(&env, &flight_number, booking_id, &status);
-        assert!(count >= cfg.consensus_threshold, "Insufficient consensus");
+        Self::require_stake_consensus(
+            &env,
+            &cfg,
+            &flight_number,
+            booking_id,
+            &status,
+            &symbol_short!("completed"),
+        );
 
         let booking_client =
             BookingClient::new(&env, &cfg.booking_contract);
         let self_addr = env.current_contract_address();
-        booking_client.oracle_refund_airline_cancel(&self_addr, &booking_id);
+        // A failed transfer leaves the booking confirmed so the cancellation can be retried
+        let action = if booking_client.oracle_refund_airline_cancel(&self_addr, &booking_id) {
+            OracleStorage::set_settled_status(&env, &flight_number, booking_id, &status);
+            symbol_short!("refunded")
+        } else {
+            symbol_short!("rf_failed")
+        };
 
         env.events().publish(
-            (symbol_short!("oracle"), symbol_short!("refunded")),
+            (symbol_short!("oracle"), action),
             (booking_id, status),
         );
     }
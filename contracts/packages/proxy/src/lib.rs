@@ -4,11 +4,17 @@ use soroban_sdk::{
     Vec,
 };
 use access::{AccessControl, Role};
+use storage_version::{VersionedStorage, PROXY_CONTRACT};
 
 // Contract meta for version tracking
 contractmeta!(key = "version", val = "1.0.0");
 contractmeta!(key = "contract_type", val = "proxy");
 
+// Upgrade proposals lapse after 7 days unless the admin changes it
+const DEFAULT_PROPOSAL_EXPIRATION: u64 = 7 * 86_400;
+// Storage v2 adds the upgrade delay, proposal expiration and approval/cancel tracking
+const TIMELOCK_STORAGE_VERSION: u32 = 2;
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ProxyState {
@@ -25,6 +31,7 @@ pub struct ProxyConfig {
     pub state: ProxyState,
     pub version: u32,
     pub storage_version: u32,
+    pub upgrade_delay: u64,
 }
 
 #[contracttype]
@@ -33,6 +40,7 @@ pub struct MultisigConfig {
     pub signers: Vec<Address>,
     pub threshold: u32,
     pub proposal_count: u64,
+    pub proposal_expiration: u64,
 }
 
 #[contracttype]
@@ -43,7 +51,9 @@ pub struct UpgradeProposal {
     pub new_storage_version: Option<u32>,
     pub proposed_at: u64,
     pub approvals: Vec<Address>,
+    pub approved_at: Option<u64>,
     pub executed: bool,
+    pub cancelled: bool,
 }
 
 #[contracttype]
@@ -55,6 +65,76 @@ pub struct StorageMigration {
     pub completed: bool,
 }
 
+// Proxy config layout before storage v2, without an upgrade delay
+#[contracttype]
+pub struct LegacyProxyConfig {
+    pub admin: Address,
+    pub implementation: BytesN<32>,
+    pub state: ProxyState,
+    pub version: u32,
+    pub storage_version: u32,
+}
+
+impl LegacyProxyConfig {
+    pub fn upgrade(self) -> ProxyConfig {
+        ProxyConfig {
+            admin: self.admin,
+            implementation: self.implementation,
+            state: self.state,
+            version: self.version,
+            storage_version: self.storage_version,
+            upgrade_delay: 0,
+        }
+    }
+}
+
+// Multisig layout before storage v2, when proposals never expired
+#[contracttype]
+pub struct LegacyMultisigConfig {
+    pub signers: Vec<Address>,
+    pub threshold: u32,
+    pub proposal_count: u64,
+}
+
+impl LegacyMultisigConfig {
+    pub fn upgrade(self) -> MultisigConfig {
+        MultisigConfig {
+            signers: self.signers,
+            threshold: self.threshold,
+            proposal_count: self.proposal_count,
+            proposal_expiration: DEFAULT_PROPOSAL_EXPIRATION,
+        }
+    }
+}
+
+// Proposal layout before storage v2
+#[contracttype]
+pub struct LegacyUpgradeProposal {
+    pub proposal_id: u64,
+    pub new_implementation: BytesN<32>,
+    pub new_storage_version: Option<u32>,
+    pub proposed_at: u64,
+    pub approvals: Vec<Address>,
+    pub executed: bool,
+}
+
+impl LegacyUpgradeProposal {
+    // The threshold time wasn't recorded; `upgrade_to` records it when it first sees the
+    // threshold met, so any delay runs from then
+    pub fn upgrade(self) -> UpgradeProposal {
+        UpgradeProposal {
+            proposal_id: self.proposal_id,
+            new_implementation: self.new_implementation,
+            new_storage_version: self.new_storage_version,
+            proposed_at: self.proposed_at,
+            approvals: self.approvals,
+            approved_at: None,
+            executed: self.executed,
+            cancelled: false,
+        }
+    }
+}
+
 pub struct ProxyStorage;
 
 impl ProxyStorage {
@@ -68,6 +148,10 @@ impl ProxyStorage {
             .set(&symbol_short!("config"), config);
     }
 
+    pub fn get_legacy_config(env: &Env) -> Option<LegacyProxyConfig> {
+        env.storage().instance().get(&symbol_short!("config"))
+    }
+
     pub fn get_multisig(env: &Env) -> Option<MultisigConfig> {
         env.storage().instance().get(&symbol_short!("multisig"))
     }
@@ -78,6 +162,16 @@ impl ProxyStorage {
             .set(&symbol_short!("multisig"), multisig);
     }
 
+    pub fn get_legacy_multisig(env: &Env) -> Option<LegacyMultisigConfig> {
+        env.storage().instance().get(&symbol_short!("multisig"))
+    }
+
+    pub fn get_legacy_proposal(env: &Env, proposal_id: u64) -> Option<LegacyUpgradeProposal> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("upgrade"), proposal_id))
+    }
+
     pub fn get_upgrade_proposal(env: &Env, proposal_id: u64) -> Option<UpgradeProposal> {
         env.storage()
             .persistent()
@@ -168,16 +262,20 @@ impl ContractProxy {
             state: ProxyState::Active,
             version: 1,
             storage_version: 1,
+            upgrade_delay: 0,
         };
 
         let multisig = MultisigConfig {
             signers,
             threshold,
             proposal_count: 0,
+            proposal_expiration: DEFAULT_PROPOSAL_EXPIRATION,
         };
 
         ProxyStorage::set_config(&env, &config);
         ProxyStorage::set_multisig(&env, &multisig);
+        // New deployments start on the current layout
+        VersionedStorage::set_storage_version(&env, &PROXY_CONTRACT, TIMELOCK_STORAGE_VERSION);
 
         env.events().publish(
             (symbol_short!("proxy"), symbol_short!("init")),
@@ -185,6 +283,48 @@ impl ContractProxy {
         );
     }
 
+    // Rewrite the config, multisig and upgrade proposals stored before v2 into the current
+    // layout. Pass every existing proposal id in a single call: the storage version is
+    // bumped afterwards and the migration cannot be run again.
+    pub fn migrate_legacy_proxy(env: Env, admin: Address, proposal_ids: Vec<u64>) -> u32 {
+        admin.require_auth();
+
+        if !AccessControl::has_role(&env, &admin, Role::Admin) {
+            panic!("Unauthorized");
+        }
+        assert!(
+            VersionedStorage::needs_migration(&env, &PROXY_CONTRACT, TIMELOCK_STORAGE_VERSION),
+            "Already migrated"
+        );
+
+        let config = ProxyStorage::get_legacy_config(&env).expect("Not initialized");
+        ProxyStorage::set_config(&env, &config.upgrade());
+        let multisig = ProxyStorage::get_legacy_multisig(&env).expect("Multisig not configured");
+        ProxyStorage::set_multisig(&env, &multisig.upgrade());
+
+        for proposal_id in proposal_ids.iter() {
+            let proposal =
+                ProxyStorage::get_legacy_proposal(&env, proposal_id).expect("Proposal not found");
+            ProxyStorage::set_upgrade_proposal(&env, proposal_id, &proposal.upgrade());
+        }
+
+        let current = VersionedStorage::get_storage_version(&env, &PROXY_CONTRACT);
+        VersionedStorage::migrate_storage(
+            &env,
+            &PROXY_CONTRACT,
+            current,
+            TIMELOCK_STORAGE_VERSION,
+            &admin,
+        );
+
+        env.events().publish(
+            (symbol_short!("proxy"), symbol_short!("migrated")),
+            (admin, env.ledger().timestamp(), proposal_ids.len()),
+        );
+
+        proposal_ids.len()
+    }
+
     pub fn propose_upgrade(
         env: Env,
         proposer: Address,
@@ -205,13 +345,19 @@ impl ContractProxy {
         let mut approvals = Vec::new(&env);
         approvals.push_back(proposer.clone());
 
+        // A single-signer multisig reaches threshold on proposal
+        let now = env.ledger().timestamp();
+        let approved_at = if multisig.threshold <= 1 { Some(now) } else { None };
+
         let proposal = UpgradeProposal {
             proposal_id: proposal_count,
             new_implementation: new_implementation.clone(),
             new_storage_version,
-            proposed_at: env.ledger().timestamp(),
+            proposed_at: now,
             approvals,
+            approved_at,
             executed: false,
+            cancelled: false,
         };
 
         ProxyStorage::set_upgrade_proposal(&env, proposal_count, &proposal);
@@ -238,12 +384,20 @@ impl ContractProxy {
             ProxyStorage::get_upgrade_proposal(&env, proposal_id).expect("Proposal not found");
 
         assert!(!proposal.executed, "Already executed");
+        assert!(!proposal.cancelled, "Proposal cancelled");
+        assert!(
+            env.ledger().timestamp() <= proposal.proposed_at + multisig.proposal_expiration,
+            "Proposal expired"
+        );
         assert!(
             !ProxyStorage::has_approved(&env, proposal_id, &signer),
             "Already approved"
         );
 
         proposal.approvals.push_back(signer.clone());
+        if proposal.approved_at.is_none() && proposal.approvals.len() >= multisig.threshold {
+            proposal.approved_at = Some(env.ledger().timestamp());
+        }
         ProxyStorage::set_upgrade_proposal(&env, proposal_id, &proposal);
         ProxyStorage::record_approval(&env, proposal_id, &signer);
 
@@ -266,6 +420,11 @@ impl ContractProxy {
             ProxyStorage::get_upgrade_proposal(&env, proposal_id).expect("Proposal not found");
 
         assert!(!proposal.executed, "Already executed");
+        assert!(!proposal.cancelled, "Proposal cancelled");
+        assert!(
+            env.ledger().timestamp() <= proposal.proposed_at + multisig.proposal_expiration,
+            "Proposal expired"
+        );
         assert!(
             proposal.approvals.len() >= multisig.threshold,
             "Insufficient approvals"
@@ -273,6 +432,26 @@ impl ContractProxy {
 
         let mut config = ProxyStorage::get_config(&env).expect("Not initialized");
 
+        // Threshold was lowered after the last approval, so it is first met now. Record
+        // that so the delay runs from here rather than restarting on every call.
+        let now = env.ledger().timestamp();
+        if proposal.approved_at.is_none() {
+            proposal.approved_at = Some(now);
+            ProxyStorage::set_upgrade_proposal(&env, proposal_id, &proposal);
+            if config.upgrade_delay > 0 {
+                env.events().publish(
+                    (symbol_short!("upgrade"), symbol_short!("approved")),
+                    (proposal_id, executor),
+                );
+                return;
+            }
+        }
+        let approved_at = proposal.approved_at.unwrap();
+        assert!(
+            now >= approved_at + config.upgrade_delay,
+            "Upgrade delay not elapsed"
+        );
+
         config.state = ProxyState::Upgrading;
         ProxyStorage::set_config(&env, &config);
 
@@ -399,6 +578,74 @@ impl ContractProxy {
         );
     }
 
+    pub fn cancel_upgrade(env: Env, admin: Address, proposal_id: u64) {
+        admin.require_auth();
+
+        if !AccessControl::has_role(&env, &admin, Role::Admin) {
+            panic!("Unauthorized");
+        }
+
+        let mut proposal =
+            ProxyStorage::get_upgrade_proposal(&env, proposal_id).expect("Proposal not found");
+        assert!(!proposal.executed, "Already executed");
+        assert!(!proposal.cancelled, "Proposal cancelled");
+
+        proposal.cancelled = true;
+        ProxyStorage::set_upgrade_proposal(&env, proposal_id, &proposal);
+
+        env.events().publish(
+            (symbol_short!("upgrade"), symbol_short!("cancelled")),
+            (proposal_id, admin),
+        );
+    }
+
+    pub fn set_upgrade_delay(env: Env, admin: Address, upgrade_delay: u64) {
+        admin.require_auth();
+
+        if !AccessControl::has_role(&env, &admin, Role::Admin) {
+            panic!("Unauthorized");
+        }
+
+        // Otherwise every proposal would expire before it could run
+        let multisig = ProxyStorage::get_multisig(&env).expect("Multisig not configured");
+        assert!(
+            upgrade_delay < multisig.proposal_expiration,
+            "Delay must be shorter than expiration"
+        );
+
+        let mut config = ProxyStorage::get_config(&env).expect("Not initialized");
+        config.upgrade_delay = upgrade_delay;
+        ProxyStorage::set_config(&env, &config);
+
+        env.events().publish(
+            (symbol_short!("upgrade"), symbol_short!("delay")),
+            upgrade_delay,
+        );
+    }
+
+    pub fn set_proposal_expiration(env: Env, admin: Address, proposal_expiration: u64) {
+        admin.require_auth();
+
+        if !AccessControl::has_role(&env, &admin, Role::Admin) {
+            panic!("Unauthorized");
+        }
+        assert!(proposal_expiration > 0, "Expiration must be > 0");
+        let config = ProxyStorage::get_config(&env).expect("Not initialized");
+        assert!(
+            config.upgrade_delay < proposal_expiration,
+            "Delay must be shorter than expiration"
+        );
+
+        let mut multisig = ProxyStorage::get_multisig(&env).expect("Multisig not configured");
+        multisig.proposal_expiration = proposal_expiration;
+        ProxyStorage::set_multisig(&env, &multisig);
+
+        env.events().publish(
+            (symbol_short!("multisig"), symbol_short!("expiry")),
+            proposal_expiration,
+        );
+    }
+
     // Role management helpers integrated with shared AccessControl
     pub fn set_role(env: Env, caller: Address, target: Address, role: u32, enabled: bool) {
         let role_enum = match role {
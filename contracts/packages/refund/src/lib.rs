@@ -1,7 +1,23 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Env, Symbol};
+use soroban_sdk::{
+    contract, contractclient, contractimpl, contracttype, symbol_short, Address, Env, Symbol,
+};
 use access::{AccessControl, Role};
 
+#[contractclient(name = "BookingClient")]
+pub trait BookingInterface {
+    fn get_booking_token(env: Env, booking_id: u64) -> Option<Address>;
+    fn get_booking_airline(env: Env, booking_id: u64) -> Option<Address>;
+    fn get_booking_passenger(env: Env, booking_id: u64) -> Option<Address>;
+    fn refund_approved_request(
+        env: Env,
+        refund_contract: Address,
+        booking_id: u64,
+        passenger: Address,
+        amount: i128,
+    ) -> i128;
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct RefundRequest {
@@ -9,11 +25,12 @@ pub struct RefundRequest {
     pub booking_id: u64,
     pub passenger: Address,
     pub amount: i128,
-    pub currency: Symbol,
+    pub currency: Address, // token the refund is paid in
     pub reason: Symbol,
-    pub status: Symbol, // "pending", "approved", "rejected", "processed"
+    pub status: Symbol, // "pending", "rejected", "processed"
     pub created_at: u64,
     pub processed_at: Option<u64>,
+    pub rejection_reason: Option<Symbol>,
 }
 
 #[contracttype]
@@ -51,6 +68,14 @@ impl RefundStorageKey {
             .set(&(symbol_short!("policy"), airline), policy);
     }
 
+    pub fn get_booking_contract(env: &Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("booking"))
+    }
+
+    pub fn set_booking_contract(env: &Env, booking: &Address) {
+        env.storage().instance().set(&symbol_short!("booking"), booking);
+    }
+
     pub fn next_id(env: &Env) -> u64 {
         let id: u64 = env.storage().instance().get(&symbol_short!("next_id")).unwrap_or(1);
         env.storage().instance().set(&symbol_short!("next_id"), &(id + 1));
@@ -68,6 +93,18 @@ impl RefundContract {
         crate::upgrade_timelock::UpgradeTimelock::init_upgrade_owner(&env, &owner);
     }
 
+    // Booking contract refund requests are checked against and paid out of. Required before
+    // any refund can be requested.
+    pub fn set_booking_contract(env: Env, admin: Address, booking: Address) {
+        AccessControl::require_admin(&env, &admin);
+        RefundStorageKey::set_booking_contract(&env, &booking);
+
+        env.events().publish(
+            (symbol_short!("booking"), symbol_short!("set")),
+            (admin, env.ledger().timestamp(), booking),
+        );
+    }
+
     // Set refund policy for airline
     pub fn set_refund_policy(
         env: Env,
@@ -100,11 +137,22 @@ impl RefundContract {
         passenger: Address,
         booking_id: u64,
         amount: i128,
-        currency: Symbol,
+        currency: Address,
         reason: Symbol,
     ) -> u64 {
         passenger.require_auth();
 
+        // Refunds are paid in the token the booking was escrowed in, to its passenger
+        let booking_contract =
+            RefundStorageKey::get_booking_contract(&env).expect("Booking contract not set");
+        let booking = BookingClient::new(&env, &booking_contract);
+        let token = booking.get_booking_token(&booking_id).expect("Booking not found");
+        assert!(currency == token, "Currency mismatch");
+        assert!(
+            booking.get_booking_passenger(&booking_id) == Some(passenger.clone()),
+            "Not the booking passenger"
+        );
+
         let request_id = RefundStorageKey::next_id(&env);
 
         let request = RefundRequest {
@@ -117,6 +165,7 @@ impl RefundContract {
             status: symbol_short!("pending"),
             created_at: env.ledger().timestamp(),
             processed_at: None,
+            rejection_reason: None,
         };
 
         RefundStorageKey::set_request(&env, request_id, &request);
@@ -129,36 +178,44 @@ impl RefundContract {
         request_id
     }
 
-    // Process refund (trigger token transfer)
+    // Process refund, paying the passenger out of the booking's escrow. The amount paid is
+    // capped at what the booking holds and the booking ends up refunded.
     pub fn process_refund(env: Env, admin: Address, request_id: u64) {
-        AccessControl::require_operator(&env, &admin);
-
         let mut request =
             RefundStorageKey::get_request(&env, request_id).expect("Refund request not found");
+        Self::require_refund_authority(&env, &admin, &request);
 
         assert!(
             request.status == symbol_short!("pending"),
             "Request already processed"
         );
 
-        request.status = symbol_short!("approved");
+        let booking_contract =
+            RefundStorageKey::get_booking_contract(&env).expect("Booking contract not set");
+
+        request.status = symbol_short!("processed");
         request.processed_at = Some(env.ledger().timestamp());
+        RefundStorageKey::set_request(&env, request_id, &request);
 
+        request.amount = BookingClient::new(&env, &booking_contract).refund_approved_request(
+            &env.current_contract_address(),
+            &request.booking_id,
+            &request.passenger,
+            &request.amount,
+        );
         RefundStorageKey::set_request(&env, request_id, &request);
 
-        // Emit event for backend to trigger actual token transfer
         env.events().publish(
-            (symbol_short!("refund"), symbol_short!("approved")),
+            (symbol_short!("refund"), symbol_short!("processed")),
             (request.passenger.clone(), env.ledger().timestamp(), request_id, request.booking_id, request.amount),
         );
     }
 
     // Reject a refund request
-    pub fn reject_refund(env: Env, _admin: Address, request_id: u64, reason: Symbol) {
-        // TODO: Check admin authorization
-
+    pub fn reject_refund(env: Env, admin: Address, request_id: u64, reason: Symbol) {
         let mut request =
             RefundStorageKey::get_request(&env, request_id).expect("Refund request not found");
+        Self::require_refund_authority(&env, &admin, &request);
 
         assert!(
             request.status == symbol_short!("pending"),
@@ -167,6 +224,7 @@ impl RefundContract {
 
         request.status = symbol_short!("rejected");
         request.processed_at = Some(env.ledger().timestamp());
+        request.rejection_reason = Some(reason.clone());
 
         RefundStorageKey::set_request(&env, request_id, &request);
 
@@ -176,6 +234,19 @@ impl RefundContract {
         );
     }
 
+    // Operators act on any request; an airline only on refunds for its own bookings
+    fn require_refund_authority(env: &Env, caller: &Address, request: &RefundRequest) {
+        caller.require_auth();
+        if AccessControl::has_role(env, caller, Role::Operator) {
+            return;
+        }
+
+        let airline = RefundStorageKey::get_booking_contract(env).and_then(|booking| {
+            BookingClient::new(env, &booking).get_booking_airline(&request.booking_id)
+        });
+        assert!(airline.as_ref() == Some(caller), "Unauthorized");
+    }
+
     pub fn get_refund_request(env: Env, request_id: u64) -> Option<RefundRequest> {
         RefundStorageKey::get_request(&env, request_id)
     }
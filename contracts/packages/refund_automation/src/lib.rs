@@ -15,8 +15,23 @@ pub struct Booking {
     pub price: i128,
     pub token: Address,
     pub amount_escrowed: i128,
-    pub status: Symbol,
+    pub status: BookingStatus,
     pub created_at: u64,
+    pub points_discount: i128,
+    pub expires_at: u64,
+    pub seat_count: u32,
+}
+
+// Mirrors the booking contract's status enum
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BookingStatus {
+    Pending,
+    Confirmed,
+    Disputed,
+    Completed,
+    Cancelled,
+    Refunded,
 }
 
 #[contractclient(name = "BookingClient")]
@@ -1,5 +1,5 @@
 #![no_std]
-use soroban_sdk::{contracttype, Address, Env};
+use soroban_sdk::{contractclient, contracttype, Address, Env};
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -13,6 +13,13 @@ pub enum Role {
 pub enum DataKey {
     Owner,
     Role(Address, Role),
+    AdminMultisig,
+}
+
+/// The slice of the admin multisig's interface business contracts read.
+#[contractclient(name = "AdminMultisigFlagClient")]
+pub trait AdminMultisigFlag {
+    fn is_emergency_stopped(env: Env) -> bool;
 }
 
 pub struct AccessControl;
@@ -97,3 +104,33 @@ impl AccessControl {
         }
     }
 }
+
+pub struct EmergencyStop;
+
+impl EmergencyStop {
+    /// Link the admin multisig whose emergency stop this contract follows.
+    pub fn set_admin_multisig(env: &Env, multisig: &Address) {
+        env.storage().instance().set(&DataKey::AdminMultisig, multisig);
+    }
+
+    /// Get the linked admin multisig, if any.
+    pub fn get_admin_multisig(env: &Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::AdminMultisig)
+    }
+
+    /// Check whether the linked admin multisig has stopped the protocol.
+    /// Contracts with no multisig linked are never stopped.
+    pub fn is_stopped(env: &Env) -> bool {
+        match Self::get_admin_multisig(env) {
+            Some(multisig) => AdminMultisigFlagClient::new(env, &multisig).is_emergency_stopped(),
+            None => false,
+        }
+    }
+
+    /// Panic while the linked admin multisig has the protocol stopped.
+    pub fn require_not_stopped(env: &Env) {
+        if Self::is_stopped(env) {
+            panic!("System paused");
+        }
+    }
+}
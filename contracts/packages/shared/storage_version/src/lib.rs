@@ -52,12 +52,37 @@ pub const GOVERNANCE_CONTRACT: Symbol = symbol_short!("gov");
 pub const LOYALTY_CONTRACT: Symbol = symbol_short!("loyalty");
 pub const REFUND_CONTRACT: Symbol = symbol_short!("refund");
 pub const TOKEN_CONTRACT: Symbol = symbol_short!("token");
-
-/// Trait for contracts that support storage migration
+pub const PROXY_CONTRACT: Symbol = symbol_short!("proxy");
+
+/// Trait for contracts that support storage migration.
+/// Each step reshapes the contract's data and returns false if it could not.
+///
+/// `rollback_migration` only rewinds the version number; it does not undo data a step
+/// already rewrote. A rolled-back migration is retried by running the same steps again,
+/// so every step must be idempotent: running it on data it has already reshaped must
+/// leave that data unchanged.
 pub trait Migratable {
     fn migrate_from_v1_to_v2(env: &Env) -> bool;
     fn migrate_from_v2_to_v3(env: &Env) -> bool;
-    fn get_migration_steps(from: u32, to: u32) -> Vec<Symbol>;
+    fn get_migration_steps(env: &Env, from: u32, to: u32) -> Vec<Symbol>;
+}
+
+/// Version bumps with no data to reshape, for contracts that rewrite
+/// their records themselves before calling `migrate_storage`
+pub struct VersionOnly;
+
+impl Migratable for VersionOnly {
+    fn migrate_from_v1_to_v2(_env: &Env) -> bool {
+        true
+    }
+
+    fn migrate_from_v2_to_v3(_env: &Env) -> bool {
+        true
+    }
+
+    fn get_migration_steps(env: &Env, _from: u32, _to: u32) -> Vec<Symbol> {
+        Vec::new(env)
+    }
 }
 
 pub struct VersionedStorage;
@@ -78,13 +103,30 @@ impl VersionedStorage {
             .set(&(symbol_short!("strg_ver"), contract_type), &version);
     }
 
-    /// Execute storage migration with progress tracking
+    /// Execute storage migration with progress tracking, bumping versions only
     pub fn migrate_storage(
         env: &Env,
         contract_type: &Symbol,
         from_version: u32,
         to_version: u32,
         migrator: &Address,
+    ) -> bool {
+        Self::migrate_storage_with::<VersionOnly>(
+            env,
+            contract_type,
+            from_version,
+            to_version,
+            migrator,
+        )
+    }
+
+    /// Execute storage migration, running `M`'s step for each version pair
+    pub fn migrate_storage_with<M: Migratable>(
+        env: &Env,
+        contract_type: &Symbol,
+        from_version: u32,
+        to_version: u32,
+        migrator: &Address,
     ) -> bool {
         assert!(from_version < to_version, "Invalid migration direction");
 
@@ -105,19 +147,36 @@ impl VersionedStorage {
         env.storage()
             .instance()
             .set(&(symbol_short!("mig_prog"), contract_type), &progress);
+        env.storage()
+            .instance()
+            .remove(&(symbol_short!("mig_rb"), contract_type));
+        env.storage()
+            .instance()
+            .set(&(symbol_short!("mig_pre"), contract_type), &from_version);
 
         let mut success = true;
         let mut current_v = from_version;
 
         while current_v < to_version && success {
             let next_v = current_v + 1;
-            success = Self::execute_migration_step(env, contract_type, current_v, next_v, migrator);
+            success =
+                Self::execute_migration_step::<M>(env, contract_type, current_v, next_v, migrator);
             if success {
                 current_v = next_v;
                 Self::set_storage_version(env, contract_type, current_v);
             }
         }
 
+        if !success {
+            // Leave progress at the last step that went through so it can be rolled back
+            let mut partial_progress = progress.clone();
+            partial_progress.current_step = current_v - from_version;
+            env.storage().instance().set(
+                &(symbol_short!("mig_prog"), contract_type),
+                &partial_progress,
+            );
+        }
+
         if success {
             let mut completed_progress = progress.clone();
             completed_progress.completed = true;
@@ -143,7 +202,7 @@ impl VersionedStorage {
     }
 
     /// Execute a single migration step
-    fn execute_migration_step(
+    fn execute_migration_step<M: Migratable>(
         env: &Env,
         contract_type: &Symbol,
         from: u32,
@@ -156,7 +215,12 @@ impl VersionedStorage {
             (contract_type.clone(), from, to),
         );
 
-        let result = true;
+        let result = match (from, to) {
+            (1, 2) => M::migrate_from_v1_to_v2(env),
+            (2, 3) => M::migrate_from_v2_to_v3(env),
+            // No data reshaping defined past v3
+            _ => true,
+        };
 
         env.events().publish(
             (symbol_short!("mig"), symbol_short!("step_end")),
@@ -166,6 +230,69 @@ impl VersionedStorage {
         result
     }
 
+    /// Revert a contract's storage version after a failed or bad migration.
+    /// `to_version` must lie between the pre-migration version and the current one.
+    /// Only the version is rewound: data already reshaped by a step stays as it is, and
+    /// is left for the step to recognise when the migration is retried (see `Migratable`).
+    pub fn rollback_migration(
+        env: &Env,
+        contract_type: &Symbol,
+        to_version: u32,
+        migrator: &Address,
+    ) {
+        let pre_version = Self::get_pre_migration_version(env, contract_type)
+            .expect("No migration to roll back");
+        let current = Self::get_storage_version(env, contract_type);
+
+        assert!(to_version < current, "Invalid rollback direction");
+        assert!(to_version >= pre_version, "Rollback below pre-migration version");
+
+        Self::set_storage_version(env, contract_type, to_version);
+
+        if let Some(mut progress) = Self::get_migration_progress(env, contract_type) {
+            progress.current_step = to_version - progress.from_version;
+            progress.completed = false;
+            progress.completed_at = None;
+            env.storage()
+                .instance()
+                .set(&(symbol_short!("mig_prog"), contract_type), &progress);
+        }
+        // Kept apart from `MigrationProgress` so progress records stored before rollbacks
+        // existed still decode
+        env.storage()
+            .instance()
+            .set(&(symbol_short!("mig_rb"), contract_type), &true);
+
+        Self::record_migration(
+            env,
+            contract_type,
+            current,
+            to_version,
+            symbol_short!("emergency"),
+            symbol_short!("rollback"),
+        );
+
+        env.events().publish(
+            (symbol_short!("mig"), symbol_short!("rollback")),
+            (contract_type.clone(), current, to_version, migrator.clone()),
+        );
+    }
+
+    /// Version storage was at when the last migration started
+    pub fn get_pre_migration_version(env: &Env, contract_type: &Symbol) -> Option<u32> {
+        env.storage()
+            .instance()
+            .get(&(symbol_short!("mig_pre"), contract_type))
+    }
+
+    /// Whether the last migration was rolled back and not retried since
+    pub fn is_rolled_back(env: &Env, contract_type: &Symbol) -> bool {
+        env.storage()
+            .instance()
+            .get(&(symbol_short!("mig_rb"), contract_type))
+            .unwrap_or(false)
+    }
+
     /// Get migration progress
     pub fn get_migration_progress(env: &Env, contract_type: &Symbol) -> Option<MigrationProgress> {
         env.storage()
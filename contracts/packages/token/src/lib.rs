@@ -1,8 +1,10 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, Address, Env, String, Symbol,
+    contract, contractimpl, contracttype, symbol_short, Address, Env, String, Symbol, Vec,
 };
-use access::{AccessControl, Role};
+use access::{AccessControl, EmergencyStop, Role};
+
+const MAX_BATCH_SIZE: u32 = 50;
 
 // TRQ Token - Traqora Governance and Loyalty Token
 // This token is used for DAO governance voting and loyalty rewards
@@ -32,12 +34,52 @@ impl TokenStorage {
             .unwrap_or(0)
     }
 
+    // Every balance change goes through here, so the pre-change balance is checkpointed
+    // for the latest snapshot before it is overwritten.
     pub fn set_balance(env: &Env, account: &Address, amount: i128) {
+        let snapshot_id = Self::get_snapshot_id(env);
+        if snapshot_id > 0 {
+            let mut ids = Self::get_snapshot_ids(env, account);
+            if ids.last().is_none_or(|last| last < snapshot_id) {
+                let previous = Self::get_balance(env, account);
+                env.storage()
+                    .persistent()
+                    .set(&(symbol_short!("snap_bal"), account, snapshot_id), &previous);
+                ids.push_back(snapshot_id);
+                env.storage()
+                    .persistent()
+                    .set(&(symbol_short!("snap_ids"), account), &ids);
+            }
+        }
+
         env.storage()
             .persistent()
             .set(&(symbol_short!("balance"), account), &amount);
     }
 
+    pub fn get_snapshot_id(env: &Env) -> u64 {
+        env.storage().instance().get(&symbol_short!("snap_id")).unwrap_or(0)
+    }
+
+    pub fn set_snapshot_id(env: &Env, snapshot_id: u64) {
+        env.storage().instance().set(&symbol_short!("snap_id"), &snapshot_id);
+    }
+
+    // Snapshot ids (ascending) at which `account` has a checkpointed balance
+    pub fn get_snapshot_ids(env: &Env, account: &Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("snap_ids"), account))
+            .unwrap_or(Vec::new(env))
+    }
+
+    pub fn get_snapshot_balance(env: &Env, account: &Address, snapshot_id: u64) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("snap_bal"), account, snapshot_id))
+            .unwrap_or(0)
+    }
+
     pub fn get_allowance(env: &Env, owner: &Address, spender: &Address) -> Option<Allowance> {
         env.storage()
             .temporary()
@@ -60,6 +102,15 @@ impl TokenStorage {
             .set(&symbol_short!("metadata"), metadata);
     }
 
+    // Smallest transfer in base units (i.e. already scaled by decimals); 0 means no floor
+    pub fn get_min_transfer(env: &Env) -> i128 {
+        env.storage().instance().get(&symbol_short!("min_xfer")).unwrap_or(0)
+    }
+
+    pub fn set_min_transfer(env: &Env, amount: i128) {
+        env.storage().instance().set(&symbol_short!("min_xfer"), &amount);
+    }
+
     pub fn get_admin(env: &Env) -> Option<Address> {
         env.storage().instance().get(&symbol_short!("admin"))
     }
@@ -67,6 +118,33 @@ impl TokenStorage {
     pub fn set_admin(env: &Env, admin: &Address) {
         env.storage().instance().set(&symbol_short!("admin"), admin);
     }
+
+    pub fn is_frozen(env: &Env, account: &Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("frozen"), account))
+            .unwrap_or(false)
+    }
+
+    pub fn set_frozen(env: &Env, account: &Address, frozen: bool) {
+        if frozen {
+            env.storage()
+                .persistent()
+                .set(&(symbol_short!("frozen"), account), &true);
+        } else {
+            env.storage()
+                .persistent()
+                .remove(&(symbol_short!("frozen"), account));
+        }
+    }
+
+    pub fn is_paused(env: &Env) -> bool {
+        env.storage().instance().get(&symbol_short!("paused")).unwrap_or(false)
+    }
+
+    pub fn set_paused(env: &Env, paused: bool) {
+        env.storage().instance().set(&symbol_short!("paused"), &paused);
+    }
 }
 
 #[contract]
@@ -98,8 +176,12 @@ impl TRQTokenContract {
     }
 
     pub fn mint(env: Env, admin: Address, to: Address, amount: i128) {
+        EmergencyStop::require_not_stopped(&env);
+        Self::require_not_paused(&env);
+
         AccessControl::require_admin(&env, &admin);
         assert!(amount > 0, "Invalid amount");
+        Self::require_not_frozen(&env, &to);
 
         let current_balance = TokenStorage::get_balance(&env, &to);
         TokenStorage::set_balance(&env, &to, current_balance + amount);
@@ -115,12 +197,18 @@ impl TRQTokenContract {
     }
 
     pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        EmergencyStop::require_not_stopped(&env);
+        Self::require_not_paused(&env);
+
         from.require_auth();
 
         assert!(amount > 0, "Invalid amount");
+        Self::require_not_frozen(&env, &from);
+        Self::require_not_frozen(&env, &to);
 
         let from_balance = TokenStorage::get_balance(&env, &from);
         assert!(from_balance >= amount, "Insufficient balance");
+        Self::check_min_transfer(&env, from_balance, amount);
 
         TokenStorage::set_balance(&env, &from, from_balance - amount);
 
@@ -133,6 +221,147 @@ impl TRQTokenContract {
         );
     }
 
+    // Pays every recipient from one authorization; the balance is checked against the
+    // total up front so the batch either fully succeeds or changes nothing
+    pub fn batch_transfer(env: Env, from: Address, recipients: Vec<Address>, amounts: Vec<i128>) {
+        EmergencyStop::require_not_stopped(&env);
+        Self::require_not_paused(&env);
+
+        from.require_auth();
+
+        assert!(recipients.len() == amounts.len(), "Length mismatch");
+        assert!(!recipients.is_empty(), "Empty batch");
+        assert!(recipients.len() <= MAX_BATCH_SIZE, "Batch too large");
+        Self::require_not_frozen(&env, &from);
+        for to in recipients.iter() {
+            Self::require_not_frozen(&env, &to);
+        }
+
+        let from_balance = TokenStorage::get_balance(&env, &from);
+        let mut total: i128 = 0;
+        for amount in amounts.iter() {
+            assert!(amount > 0, "Invalid amount");
+            Self::check_min_transfer(&env, from_balance, amount);
+            total = total.checked_add(amount).expect("Amount overflow");
+        }
+        assert!(from_balance >= total, "Insufficient balance");
+
+        TokenStorage::set_balance(&env, &from, from_balance - total);
+
+        for (to, amount) in recipients.iter().zip(amounts.iter()) {
+            let to_balance = TokenStorage::get_balance(&env, &to);
+            TokenStorage::set_balance(&env, &to, to_balance + amount);
+
+            env.events().publish(
+                (symbol_short!("transfer"), symbol_short!("success")),
+                (from.clone(), to, amount),
+            );
+        }
+    }
+
+    // Sweeping the whole balance is always allowed so dust can't get stuck
+    fn check_min_transfer(env: &Env, from_balance: i128, amount: i128) {
+        if amount != from_balance {
+            assert!(
+                amount >= TokenStorage::get_min_transfer(env),
+                "Below minimum transfer"
+            );
+        }
+    }
+
+    pub fn set_min_transfer(env: Env, admin: Address, amount: i128) {
+        AccessControl::require_admin(&env, &admin);
+        assert!(amount >= 0, "Invalid amount");
+        TokenStorage::set_min_transfer(&env, amount);
+
+        env.events().publish(
+            (symbol_short!("min_xfer"), symbol_short!("set")),
+            (admin, amount),
+        );
+    }
+
+    pub fn min_transfer(env: Env) -> i128 {
+        TokenStorage::get_min_transfer(&env)
+    }
+
+    // Admin multisig whose emergency stop this contract follows
+    pub fn set_admin_multisig(env: Env, admin: Address, multisig: Address) {
+        AccessControl::require_admin(&env, &admin);
+        EmergencyStop::set_admin_multisig(&env, &multisig);
+
+        env.events().publish(
+            (symbol_short!("token"), symbol_short!("adm_ms")),
+            (admin, multisig),
+        );
+    }
+
+    pub fn is_emergency_stopped(env: Env) -> bool {
+        EmergencyStop::is_stopped(&env)
+    }
+
+    // Halts every balance change (mint, transfers, burns) until unpaused; approvals still work
+    pub fn pause(env: Env, admin: Address) {
+        Self::require_pauser(&env, &admin);
+        TokenStorage::set_paused(&env, true);
+
+        env.events()
+            .publish((symbol_short!("token"), symbol_short!("paused")), admin);
+    }
+
+    pub fn unpause(env: Env, admin: Address) {
+        Self::require_pauser(&env, &admin);
+        TokenStorage::set_paused(&env, false);
+
+        env.events()
+            .publish((symbol_short!("token"), symbol_short!("unpaused")), admin);
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        TokenStorage::is_paused(&env)
+    }
+
+    // The token admin, or the linked admin multisig acting as a contract
+    fn require_pauser(env: &Env, caller: &Address) {
+        if EmergencyStop::get_admin_multisig(env).as_ref() == Some(caller) {
+            caller.require_auth();
+        } else {
+            AccessControl::require_admin(env, caller);
+        }
+    }
+
+    fn require_not_paused(env: &Env) {
+        assert!(!TokenStorage::is_paused(env), "Token paused");
+    }
+
+    // Blocks a single account from sending or receiving without pausing the token
+    pub fn freeze_account(env: Env, admin: Address, account: Address) {
+        AccessControl::require_admin(&env, &admin);
+        TokenStorage::set_frozen(&env, &account, true);
+
+        env.events().publish(
+            (symbol_short!("token"), symbol_short!("frozen")),
+            (admin, account),
+        );
+    }
+
+    pub fn unfreeze_account(env: Env, admin: Address, account: Address) {
+        AccessControl::require_admin(&env, &admin);
+        TokenStorage::set_frozen(&env, &account, false);
+
+        env.events().publish(
+            (symbol_short!("token"), symbol_short!("unfrozen")),
+            (admin, account),
+        );
+    }
+
+    pub fn is_frozen(env: Env, account: Address) -> bool {
+        TokenStorage::is_frozen(&env, &account)
+    }
+
+    fn require_not_frozen(env: &Env, account: &Address) {
+        assert!(!TokenStorage::is_frozen(env, account), "Account frozen");
+    }
+
     pub fn approve(
         env: Env,
         owner: Address,
@@ -155,8 +384,71 @@ impl TRQTokenContract {
         );
     }
 
+    // Adds `delta` to what `allowance()` currently reports, so an expired allowance
+    // restarts from zero; the new expiration replaces the old one
+    pub fn increase_allowance(
+        env: Env,
+        owner: Address,
+        spender: Address,
+        delta: i128,
+        expiration_ledger: u32,
+    ) {
+        owner.require_auth();
+        assert!(delta > 0, "Invalid amount");
+
+        let amount = Self::allowance(env.clone(), owner.clone(), spender.clone())
+            .checked_add(delta)
+            .expect("Allowance overflow");
+        TokenStorage::set_allowance(
+            &env,
+            &owner,
+            &spender,
+            &Allowance {
+                amount,
+                expiration_ledger,
+            },
+        );
+
+        env.events().publish(
+            (symbol_short!("approve"), symbol_short!("increase")),
+            (owner, spender, amount),
+        );
+    }
+
+    // Subtracts `delta` from the current allowance, clamping at zero; expiration is kept
+    pub fn decrease_allowance(env: Env, owner: Address, spender: Address, delta: i128) {
+        owner.require_auth();
+        assert!(delta > 0, "Invalid amount");
+
+        let current = Self::allowance(env.clone(), owner.clone(), spender.clone());
+        let amount = if delta >= current { 0 } else { current - delta };
+        let expiration_ledger = TokenStorage::get_allowance(&env, &owner, &spender)
+            .map(|a| a.expiration_ledger)
+            .unwrap_or(0);
+        TokenStorage::set_allowance(
+            &env,
+            &owner,
+            &spender,
+            &Allowance {
+                amount,
+                expiration_ledger,
+            },
+        );
+
+        env.events().publish(
+            (symbol_short!("approve"), symbol_short!("decrease")),
+            (owner, spender, amount),
+        );
+    }
+
     pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        EmergencyStop::require_not_stopped(&env);
+        Self::require_not_paused(&env);
+
         spender.require_auth();
+        Self::require_not_frozen(&env, &spender);
+        Self::require_not_frozen(&env, &from);
+        Self::require_not_frozen(&env, &to);
 
         let allowance =
             TokenStorage::get_allowance(&env, &from, &spender).expect("No allowance set");
@@ -177,6 +469,7 @@ impl TRQTokenContract {
         // Perform transfer
         let from_balance = TokenStorage::get_balance(&env, &from);
         assert!(from_balance >= amount, "Insufficient balance");
+        Self::check_min_transfer(&env, from_balance, amount);
 
         TokenStorage::set_balance(&env, &from, from_balance - amount);
 
@@ -189,10 +482,85 @@ impl TRQTokenContract {
         );
     }
 
+    pub fn burn(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+
+        Self::burn_balance(&env, &from, amount);
+    }
+
+    pub fn burn_from(env: Env, spender: Address, from: Address, amount: i128) {
+        spender.require_auth();
+
+        let allowance =
+            TokenStorage::get_allowance(&env, &from, &spender).expect("No allowance set");
+
+        assert!(
+            env.ledger().sequence() <= allowance.expiration_ledger,
+            "Allowance expired"
+        );
+        assert!(allowance.amount >= amount, "Insufficient allowance");
+
+        let new_allowance = Allowance {
+            amount: allowance.amount - amount,
+            expiration_ledger: allowance.expiration_ledger,
+        };
+        TokenStorage::set_allowance(&env, &from, &spender, &new_allowance);
+
+        Self::burn_balance(&env, &from, amount);
+    }
+
+    fn burn_balance(env: &Env, from: &Address, amount: i128) {
+        Self::require_not_paused(env);
+        assert!(amount > 0, "Invalid amount");
+
+        let from_balance = TokenStorage::get_balance(env, from);
+        assert!(from_balance >= amount, "Insufficient balance");
+        TokenStorage::set_balance(env, from, from_balance - amount);
+
+        let mut metadata = TokenStorage::get_metadata(env).expect("Not initialized");
+        metadata.total_supply -= amount;
+        TokenStorage::set_metadata(env, &metadata);
+
+        env.events().publish(
+            (symbol_short!("burn"), symbol_short!("success")),
+            (from.clone(), amount),
+        );
+    }
+
     pub fn balance_of(env: Env, account: Address) -> i128 {
         TokenStorage::get_balance(&env, &account)
     }
 
+    // Record a point-in-time view of all balances, e.g. at governance proposal creation.
+    pub fn snapshot(env: Env, admin: Address) -> u64 {
+        AccessControl::require_admin(&env, &admin);
+
+        let snapshot_id = TokenStorage::get_snapshot_id(&env) + 1;
+        TokenStorage::set_snapshot_id(&env, snapshot_id);
+
+        env.events().publish(
+            (symbol_short!("snapshot"), symbol_short!("taken")),
+            (admin, snapshot_id),
+        );
+        snapshot_id
+    }
+
+    // Balance of `account` when `snapshot_id` was taken. The first checkpoint at or after
+    // the snapshot holds it; with none, the balance hasn't changed since.
+    pub fn balance_of_at(env: Env, account: Address, snapshot_id: u64) -> i128 {
+        assert!(
+            snapshot_id > 0 && snapshot_id <= TokenStorage::get_snapshot_id(&env),
+            "Unknown snapshot"
+        );
+
+        for id in TokenStorage::get_snapshot_ids(&env, &account).iter() {
+            if id >= snapshot_id {
+                return TokenStorage::get_snapshot_balance(&env, &account, id);
+            }
+        }
+        TokenStorage::get_balance(&env, &account)
+    }
+
     pub fn allowance(env: Env, owner: Address, spender: Address) -> i128 {
         if let Some(allowance) = TokenStorage::get_allowance(&env, &owner, &spender) {
             if env.ledger().sequence() <= allowance.expiration_ledger {
@@ -205,6 +573,10 @@ impl TRQTokenContract {
         }
     }
 
+    pub fn metadata(env: Env) -> TokenMetadata {
+        TokenStorage::get_metadata(&env).expect("Not initialized")
+    }
+
     pub fn total_supply(env: Env) -> i128 {
         TokenStorage::get_metadata(&env)
             .map(|m| m.total_supply)